@@ -127,7 +127,9 @@ use xcm::latest::prelude::{
 };
 
 use xcm_runtime_apis::{
-	dry_run::{CallDryRunEffects, Error as XcmDryRunApiError, XcmDryRunEffects},
+	dry_run::{
+		CallDryRunEffects, Error as XcmDryRunApiError, TracedDryRunEffects, XcmDryRunEffects,
+	},
 	fees::Error as XcmPaymentApiError,
 };
 
@@ -1186,6 +1188,7 @@ impl pallet_revive::Config for Runtime {
 	type InstantiateOrigin = EnsureSigned<Self::AccountId>;
 	type RuntimeHoldReason = RuntimeHoldReason;
 	type CodeHashLockupDepositPercent = CodeHashLockupDepositPercent;
+	type CodeUpgradeDelay = ConstU32<0>;
 	type ChainId = ConstU64<420_420_421>;
 	type NativeToEthRatio = ConstU32<1_000_000>; // 10^(18 - 12) Eth is 10^18, Native is 10^12.
 	type EthGasEncoder = ();
@@ -1199,7 +1202,10 @@ parameter_types! {
 impl pallet_migrations::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	#[cfg(not(feature = "runtime-benchmarks"))]
-	type Migrations = pallet_revive::migrations::v1::Migration<Runtime>;
+	type Migrations = (
+		pallet_revive::migrations::v1::Migration<Runtime>,
+		pallet_revive::migrations::v2::Migration<Runtime>,
+	);
 	// Benchmarks need mocked migrations to guarantee that they succeed.
 	#[cfg(feature = "runtime-benchmarks")]
 	type Migrations = pallet_migrations::mock_helpers::MockedMigrations;
@@ -1926,6 +1932,7 @@ pallet_revive::impl_runtime_apis_plus_revive!(
 		}
 	}
 
+	#[api_version(3)]
 	impl xcm_runtime_apis::dry_run::DryRunApi<Block, RuntimeCall, RuntimeEvent, OriginCaller> for Runtime {
 		fn dry_run_call(origin: OriginCaller, call: RuntimeCall, result_xcms_version: XcmVersion) -> Result<CallDryRunEffects<RuntimeEvent>, XcmDryRunApiError> {
 			PolkadotXcm::dry_run_call::<Runtime, xcm_config::XcmRouter, OriginCaller, RuntimeCall>(origin, call, result_xcms_version)
@@ -1934,6 +1941,14 @@ pallet_revive::impl_runtime_apis_plus_revive!(
 		fn dry_run_xcm(origin_location: VersionedLocation, xcm: VersionedXcm<RuntimeCall>) -> Result<XcmDryRunEffects<RuntimeEvent>, XcmDryRunApiError> {
 			PolkadotXcm::dry_run_xcm::<Runtime, xcm_config::XcmRouter, RuntimeCall, xcm_config::XcmConfig>(origin_location, xcm)
 		}
+
+		fn dry_run_call_with_trace(origin: OriginCaller, call: RuntimeCall, result_xcms_version: XcmVersion) -> Result<TracedDryRunEffects<CallDryRunEffects<RuntimeEvent>>, XcmDryRunApiError> {
+			PolkadotXcm::dry_run_call_with_trace::<Runtime, xcm_config::XcmRouter, OriginCaller, RuntimeCall>(origin, call, result_xcms_version)
+		}
+
+		fn dry_run_xcm_with_trace(origin_location: VersionedLocation, xcm: VersionedXcm<RuntimeCall>) -> Result<TracedDryRunEffects<XcmDryRunEffects<RuntimeEvent>>, XcmDryRunApiError> {
+			PolkadotXcm::dry_run_xcm_with_trace::<Runtime, xcm_config::XcmRouter, RuntimeCall, xcm_config::XcmConfig>(origin_location, xcm)
+		}
 	}
 
 	impl xcm_runtime_apis::conversions::LocationToAccountApi<Block, AccountId> for Runtime {
@@ -1972,6 +1987,15 @@ pallet_revive::impl_runtime_apis_plus_revive!(
 		}
 	}
 
+	impl xcm_runtime_apis::trapped_assets::TrappedAssetsApi<Block> for Runtime {
+		fn trapped_assets(origin: VersionedLocation) -> Result<
+			Vec<xcm_runtime_apis::trapped_assets::TrappedAssets>,
+			xcm_runtime_apis::trapped_assets::Error
+		> {
+			PolkadotXcm::trapped_assets(origin)
+		}
+	}
+
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentCallApi<Block, Balance, RuntimeCall>
 		for Runtime
 	{
@@ -2447,52 +2471,55 @@ pallet_revive::impl_runtime_apis_plus_revive!(
 				}
 
 				fn worst_case_asset_exchange() -> Result<(XcmAssets, XcmAssets), BenchmarkError> {
+					// The worst case is a swap through the relay token, between two assets without a
+					// pool of their own.
 					let native_asset_location = WestendLocation::get();
-					let native_asset_id = AssetId(native_asset_location.clone());
 					let (account, _) = pallet_xcm_benchmarks::account_and_location::<Runtime>(1);
 					let origin = RuntimeOrigin::signed(account.clone());
-					let asset_location = Location::new(1, [Parachain(2001)]);
-					let asset_id = AssetId(asset_location.clone());
+					let give_asset_location = Location::new(1, [Parachain(2001)]);
+					let want_asset_location = Location::new(1, [Parachain(2002)]);
 
 					assert_ok!(<Balances as fungible::Mutate<_>>::mint_into(
 						&account,
-						ExistentialDeposit::get() + (1_000 * UNITS)
+						ExistentialDeposit::get() + (3_000 * UNITS)
 					));
 
-					assert_ok!(ForeignAssets::force_create(
-						RuntimeOrigin::root(),
-						asset_location.clone().into(),
-						account.clone().into(),
-						true,
-						1,
-					));
-
-					assert_ok!(ForeignAssets::mint(
-						origin.clone(),
-						asset_location.clone().into(),
-						account.clone().into(),
-						3_000 * UNITS,
-					));
-
-					assert_ok!(AssetConversion::create_pool(
-						origin.clone(),
-						native_asset_location.clone().into(),
-						asset_location.clone().into(),
-					));
-
-					assert_ok!(AssetConversion::add_liquidity(
-						origin,
-						native_asset_location.into(),
-						asset_location.into(),
-						1_000 * UNITS,
-						2_000 * UNITS,
-						1,
-						1,
-						account.into(),
-					));
+					for asset_location in [&give_asset_location, &want_asset_location] {
+						assert_ok!(ForeignAssets::force_create(
+							RuntimeOrigin::root(),
+							asset_location.clone().into(),
+							account.clone().into(),
+							true,
+							1,
+						));
+
+						assert_ok!(ForeignAssets::mint(
+							origin.clone(),
+							asset_location.clone().into(),
+							account.clone().into(),
+							3_000 * UNITS,
+						));
+
+						assert_ok!(AssetConversion::create_pool(
+							origin.clone(),
+							native_asset_location.clone().into(),
+							asset_location.clone().into(),
+						));
+
+						assert_ok!(AssetConversion::add_liquidity(
+							origin.clone(),
+							native_asset_location.clone().into(),
+							asset_location.clone().into(),
+							1_000 * UNITS,
+							2_000 * UNITS,
+							1,
+							1,
+							account.clone().into(),
+						));
+					}
 
-					let give_assets: XcmAssets = (native_asset_id, 500 * UNITS).into();
-					let receive_assets: XcmAssets = (asset_id, 660 * UNITS).into();
+					let give_assets: XcmAssets = (AssetId(give_asset_location), 500 * UNITS).into();
+					let receive_assets: XcmAssets = (AssetId(want_asset_location), 300 * UNITS).into();
 
 					Ok((give_assets, receive_assets))
 				}
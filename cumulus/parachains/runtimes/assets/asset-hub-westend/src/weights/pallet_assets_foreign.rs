@@ -627,4 +627,42 @@ impl<T: frame_system::Config> pallet_assets::WeightInfo for WeightInfo<T> {
 			.saturating_add(Weight::from_parts(0, 4211))
 			.saturating_add(T::DbWeight::get().reads(1))
 	}
+	/// Storage: `ForeignAssets::Metadata` (r:1 w:0)
+	/// Proof: `ForeignAssets::Metadata` (`max_values`: None, `max_size`: Some(738), added: 3213, mode: `MaxEncodedLen`)
+	fn metadata() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `245`
+		//  Estimated: `4203`
+		// Minimum execution time: 8_912_000 picoseconds.
+		Weight::from_parts(9_304_000, 0)
+			.saturating_add(Weight::from_parts(0, 4203))
+			.saturating_add(T::DbWeight::get().reads(1))
+	}
+	/// Storage: `ForeignAssets::PermitNonces` (r:1 w:0)
+	/// Proof: `ForeignAssets::PermitNonces` (`max_values`: None, `max_size`: Some(674), added: 3149, mode: `MaxEncodedLen`)
+	fn nonces() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `177`
+		//  Estimated: `4139`
+		// Minimum execution time: 8_203_000 picoseconds.
+		Weight::from_parts(8_581_000, 0)
+			.saturating_add(Weight::from_parts(0, 4139))
+			.saturating_add(T::DbWeight::get().reads(1))
+	}
+	/// Storage: `ForeignAssets::PermitNonces` (r:1 w:1)
+	/// Proof: `ForeignAssets::PermitNonces` (`max_values`: None, `max_size`: Some(674), added: 3149, mode: `MaxEncodedLen`)
+	/// Storage: `ForeignAssets::Asset` (r:1 w:1)
+	/// Proof: `ForeignAssets::Asset` (`max_values`: None, `max_size`: Some(808), added: 3283, mode: `MaxEncodedLen`)
+	/// Storage: `ForeignAssets::Approvals` (r:1 w:1)
+	/// Proof: `ForeignAssets::Approvals` (`max_values`: None, `max_size`: Some(746), added: 3221, mode: `MaxEncodedLen`)
+	fn permit() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `348`
+		//  Estimated: `4273`
+		// Minimum execution time: 42_189_000 picoseconds.
+		Weight::from_parts(43_730_000, 0)
+			.saturating_add(Weight::from_parts(0, 4273))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
 }
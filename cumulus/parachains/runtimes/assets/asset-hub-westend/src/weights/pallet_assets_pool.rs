@@ -619,4 +619,42 @@ impl<T: frame_system::Config> pallet_assets::WeightInfo for WeightInfo<T> {
 			.saturating_add(Weight::from_parts(0, 3613))
 			.saturating_add(T::DbWeight::get().reads(1))
 	}
+	/// Storage: `PoolAssets::Metadata` (r:1 w:0)
+	/// Proof: `PoolAssets::Metadata` (`max_values`: None, `max_size`: Some(140), added: 2615, mode: `MaxEncodedLen`)
+	fn metadata() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `283`
+		//  Estimated: `3605`
+		// Minimum execution time: 8_874_000 picoseconds.
+		Weight::from_parts(9_215_000, 0)
+			.saturating_add(Weight::from_parts(0, 3605))
+			.saturating_add(T::DbWeight::get().reads(1))
+	}
+	/// Storage: `PoolAssets::PermitNonces` (r:1 w:0)
+	/// Proof: `PoolAssets::PermitNonces` (`max_values`: None, `max_size`: Some(76), added: 2551, mode: `MaxEncodedLen`)
+	fn nonces() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `175`
+		//  Estimated: `3541`
+		// Minimum execution time: 8_137_000 picoseconds.
+		Weight::from_parts(8_496_000, 0)
+			.saturating_add(Weight::from_parts(0, 3541))
+			.saturating_add(T::DbWeight::get().reads(1))
+	}
+	/// Storage: `PoolAssets::PermitNonces` (r:1 w:1)
+	/// Proof: `PoolAssets::PermitNonces` (`max_values`: None, `max_size`: Some(76), added: 2551, mode: `MaxEncodedLen`)
+	/// Storage: `PoolAssets::Asset` (r:1 w:1)
+	/// Proof: `PoolAssets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
+	/// Storage: `PoolAssets::Approvals` (r:1 w:1)
+	/// Proof: `PoolAssets::Approvals` (`max_values`: None, `max_size`: Some(148), added: 2623, mode: `MaxEncodedLen`)
+	fn permit() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `386`
+		//  Estimated: `3675`
+		// Minimum execution time: 28_233_000 picoseconds.
+		Weight::from_parts(29_592_000, 0)
+			.saturating_add(Weight::from_parts(0, 3675))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
 }
@@ -56,8 +56,8 @@ use xcm_builder::{
 	FrameTransactionalProcessor, FungibleAdapter, FungiblesAdapter, HashedDescription, IsConcrete,
 	LocalMint, MatchInClassInstances, MatchedConvertedConcreteId, MintLocation,
 	NetworkExportTableItem, NoChecking, OriginToPluralityVoice, ParentAsSuperuser, ParentIsPreset,
-	RelayChainAsNative, SendXcmFeeToAccount, SiblingParachainAsNative, SiblingParachainConvertsVia,
-	SignedAccountId32AsNative, SignedToAccountId32, SingleAssetExchangeAdapter,
+	PathFindingExchangeAdapter, RelayChainAsNative, SendXcmFeeToAccount, SiblingParachainAsNative,
+	SiblingParachainConvertsVia, SignedAccountId32AsNative, SignedToAccountId32,
 	SovereignSignedViaLocation, StartsWith, StartsWithExplicitGlobalConsensus, TakeWeightCredit,
 	TrailingSetTopicAsId, UnpaidRemoteExporter, UsingComponents, WeightInfoBounds,
 	WithComputedOrigin, WithLatestLocationConverter, WithUniqueTopic, XcmFeeManagerFromComponents,
@@ -371,11 +371,18 @@ pub type TrustedTeleporters = (
 /// - Allow origins explicitly authorized by the alias target location.
 pub type TrustedAliasers = (AliasChildLocation, AuthorizedAliasers<Runtime>);
 
+parameter_types! {
+	/// The assets the swaps of the [`PoolAssetsExchanger`] can go through, all the pools are
+	/// paired with the relay token.
+	pub PoolConnectors: alloc::vec::Vec<xcm::latest::Location> = alloc::vec![WestendLocation::get()];
+}
+
 /// Asset converter for pool assets.
-/// Used to convert one asset to another, when there is a pool available between the two.
-/// This type thus allows paying fees with any asset as long as there is a pool between said
+/// Used to convert one asset to another, when there is a pool available between the two or
+/// between each of them and the relay token.
+/// This type thus allows paying fees with any asset as long as there is a swap path between said
 /// asset and the asset required for fee payment.
-pub type PoolAssetsExchanger = SingleAssetExchangeAdapter<
+pub type PoolAssetsExchanger = PathFindingExchangeAdapter<
 	crate::AssetConversion,
 	crate::NativeAndNonPoolAssets,
 	(
@@ -390,6 +397,7 @@ pub type PoolAssetsExchanger = SingleAssetExchangeAdapter<
 			TryConvertInto,
 		>,
 	),
+	PoolConnectors,
 	AccountId,
 >;
 
@@ -286,7 +286,11 @@ where
 		},
 		Some(Subcommand::Key(cmd)) => Ok(cmd.run(&cli)?),
 		None => {
-			let runner = cli.create_runner(&cli.run.normalize())?;
+			// The runtime metrics of the parachain are exported with the `runtime-metrics` feature.
+			let runner = cli.create_runner_with_logger_hook(
+				&cli.run.normalize(),
+				polkadot_node_metrics::collator_logger_hook(),
+			)?;
 			let polkadot_cli =
 				RelayChainCli::<CliConfig>::new(runner.config(), cli.relay_chain_args.iter());
 			let collator_options = cli.run.collator_options();
@@ -0,0 +1,386 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A chain simulated from its runtime and a snapshot of its state.
+
+use crate::{events::EventDecoder, Error, Result, LOG_TARGET};
+use codec::{Decode, Encode, Input};
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+use remote_externalities::{Builder, Mode, OfflineConfig, RemoteExternalities, SnapshotConfig};
+use sc_executor::WasmExecutor;
+use sp_core::{
+	crypto::AccountId32,
+	storage::well_known_keys::CODE,
+	traits::{CallContext, CodeExecutor, RuntimeCode, WrappedRuntimeCode},
+};
+use sp_runtime::{generic, traits::BlakeTwo256, OpaqueExtrinsic};
+use sp_version::RuntimeVersion;
+use std::{fmt, path::PathBuf, str::FromStr};
+use xcm::prelude::*;
+use xcm_runtime_apis::{
+	dry_run::{CallDryRunEffects, Error as DryRunError, XcmDryRunEffects},
+	fees::Error as XcmPaymentApiError,
+};
+
+/// The block type of the chains, only used to load their snapshots.
+type Block = generic::Block<generic::Header<u32, BlakeTwo256>, OpaqueExtrinsic>;
+
+/// The host functions provided to the runtimes.
+type HostFunctions = (
+	sp_io::SubstrateHostFunctions,
+	cumulus_primitives_proof_size_hostfunction::storage_proof_size::HostFunctions,
+);
+
+/// The identifier of a chain of the consensus system.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ChainId {
+	/// The relay chain.
+	Relay,
+	/// The parachain with the given id.
+	Para(u32),
+}
+
+impl ChainId {
+	/// Returns the location of `self`, as seen from the chain `from`.
+	pub fn location_from(self, from: ChainId) -> Location {
+		match (from, self) {
+			(ChainId::Relay, ChainId::Relay) => Location::here(),
+			(ChainId::Relay, ChainId::Para(id)) => Location::new(0, [Parachain(id)]),
+			(ChainId::Para(_), ChainId::Relay) => Location::parent(),
+			(ChainId::Para(from), ChainId::Para(id)) if from == id => Location::here(),
+			(ChainId::Para(_), ChainId::Para(id)) => Location::new(1, [Parachain(id)]),
+		}
+	}
+
+	/// Returns the chain at `location`, as seen from `self`.
+	///
+	/// Returns `None` if `location` is not a chain of the consensus system, such as an account or
+	/// a bridged chain.
+	pub fn resolve(self, location: &Location) -> Option<ChainId> {
+		match (self, location.unpack()) {
+			(_, (0, [])) => Some(self),
+			(ChainId::Relay, (0, [Parachain(id)])) => Some(ChainId::Para(*id)),
+			(ChainId::Para(_), (1, [])) => Some(ChainId::Relay),
+			(ChainId::Para(_), (1, [Parachain(id)])) => Some(ChainId::Para(*id)),
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Display for ChainId {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Relay => write!(f, "relay chain"),
+			Self::Para(id) => write!(f, "parachain {id}"),
+		}
+	}
+}
+
+impl FromStr for ChainId {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"relay" => Ok(Self::Relay),
+			id => id
+				.parse()
+				.map(Self::Para)
+				.map_err(|_| format!("Invalid chain `{s}`, expected `relay` or a parachain id")),
+		}
+	}
+}
+
+/// The runtime of a chain.
+struct Runtime {
+	code: Vec<u8>,
+	hash: Vec<u8>,
+	executor: WasmExecutor<HostFunctions>,
+}
+
+impl Runtime {
+	/// Create a new runtime from its Wasm `code`.
+	fn new(code: Vec<u8>) -> Self {
+		let executor = WasmExecutor::<HostFunctions>::builder()
+			.with_allow_missing_host_functions(true)
+			.build();
+		Self { hash: sp_core::blake2_256(&code).to_vec(), code, executor }
+	}
+
+	/// Call the runtime API `method` with the SCALE encoded `data`, on the state of `ext`.
+	///
+	/// The changes made to the state are kept in `ext`.
+	fn call(
+		&self,
+		ext: &mut RemoteExternalities<Block>,
+		method: &str,
+		data: &[u8],
+	) -> Result<Vec<u8>, String> {
+		let runtime_code = RuntimeCode {
+			code_fetcher: &WrappedRuntimeCode(self.code.as_slice().into()),
+			heap_pages: None,
+			hash: self.hash.clone(),
+		};
+		self.executor
+			.call(&mut ext.ext(), &runtime_code, method, data, CallContext::Offchain)
+			.0
+			.map_err(|error| error.to_string())
+	}
+}
+
+/// A chain simulated from its runtime and a snapshot of its state.
+pub struct Chain {
+	id: ChainId,
+	ext: RemoteExternalities<Block>,
+	runtime: Runtime,
+	events: EventDecoder,
+	/// Whether the runtime implements the `FungiblesApi` of the asset hubs.
+	fungibles_api: bool,
+}
+
+impl Chain {
+	/// Load the chain `id` from the state snapshot at `snapshot`.
+	///
+	/// The runtime at `runtime` is used if given, else the one in the state.
+	pub async fn load(id: ChainId, snapshot: PathBuf, runtime: Option<PathBuf>) -> Result<Self> {
+		log::info!(target: LOG_TARGET, "Loading the {id} from {}", snapshot.display());
+		let mut ext = Builder::<Block>::new()
+			.mode(Mode::Offline(OfflineConfig { state_snapshot: SnapshotConfig::new(snapshot) }))
+			.build()
+			.await
+			.map_err(|error| Error::Snapshot(id, error))?;
+		let code = match runtime {
+			Some(path) => std::fs::read(&path).map_err(|error| Error::Io(path, error))?,
+			None => ext
+				.execute_with(|| sp_io::storage::get(CODE))
+				.ok_or(Error::MissingRuntime(id))?
+				.to_vec(),
+		};
+
+		let runtime = Runtime::new(code);
+		let call = |ext: &mut _, method, data: &[u8]| {
+			runtime.call(ext, method, data).map_err(|error| Error::Call {
+				chain: id,
+				method,
+				error,
+			})
+		};
+		let decode_error = |method, error| Error::Decode { chain: id, method, error };
+
+		let version = call(&mut ext, "Core_version", &[])?;
+		let version = RuntimeVersion::decode(&mut &version[..])
+			.map_err(|error| decode_error("Core_version", error))?;
+		let fungibles_api = version.has_api_with(&sp_core::blake2_64(b"FungiblesApi"), |_| true);
+
+		let metadata = call(&mut ext, "Metadata_metadata_at_version", &15u32.encode())?;
+		let metadata = Option::<Vec<u8>>::decode(&mut &metadata[..])
+			.map_err(|error| decode_error("Metadata_metadata_at_version", error))?
+			.ok_or(Error::UnsupportedMetadata(id))?;
+		let RuntimeMetadata::V15(metadata) = RuntimeMetadataPrefixed::decode(&mut &metadata[..])
+			.map_err(|error| decode_error("Metadata_metadata_at_version", error))?
+			.1
+		else {
+			return Err(Error::UnsupportedMetadata(id))
+		};
+
+		log::info!(
+			target: LOG_TARGET,
+			"Loaded the {id}, running {} version {}",
+			version.spec_name,
+			version.spec_version,
+		);
+		Ok(Self { id, ext, runtime, events: EventDecoder::new(metadata), fungibles_api })
+	}
+
+	/// Returns the identifier of the chain.
+	pub fn id(&self) -> ChainId {
+		self.id
+	}
+
+	/// Dry-run `call` dispatched from `origin`, the SCALE encoded `RuntimeCall` and `OriginCaller`
+	/// of the runtime.
+	///
+	/// The forwarded messages are returned in `xcm_version`.
+	pub fn dry_run_call(
+		&mut self,
+		origin: &[u8],
+		call: &[u8],
+		xcm_version: XcmVersion,
+	) -> Result<CallDryRunEffects<String>> {
+		const METHOD: &str = "DryRunApi_dry_run_call";
+		let result = self.call(METHOD, &[origin, call, &xcm_version.encode()].concat())?;
+		self.decode_dry_run(METHOD, &result, |input, events| {
+			Ok(CallDryRunEffects {
+				execution_result: Decode::decode(input)?,
+				emitted_events: events.decode_events(input)?,
+				local_xcm: Decode::decode(input)?,
+				forwarded_xcms: Decode::decode(input)?,
+			})
+		})
+	}
+
+	/// Dry-run `xcm` from `origin`.
+	pub fn dry_run_xcm(
+		&mut self,
+		origin: VersionedLocation,
+		xcm: VersionedXcm<()>,
+	) -> Result<XcmDryRunEffects<String>> {
+		const METHOD: &str = "DryRunApi_dry_run_xcm";
+		// `VersionedXcm<()>` is encoded like the `VersionedXcm<RuntimeCall>` of the runtime.
+		let result = self.call(METHOD, &(origin, xcm).encode())?;
+		self.decode_dry_run(METHOD, &result, |input, events| {
+			Ok(XcmDryRunEffects {
+				execution_result: Decode::decode(input)?,
+				emitted_events: events.decode_events(input)?,
+				forwarded_xcms: Decode::decode(input)?,
+			})
+		})
+	}
+
+	/// Returns the fee charged for executing `xcm`, in the asset `asset_id`.
+	pub fn execution_fee(&mut self, xcm: VersionedXcm<()>, asset_id: AssetId) -> Result<u128> {
+		let weight: Weight = self
+			.call_api::<_, XcmPaymentApiError>("XcmPaymentApi_query_xcm_weight", &xcm.encode())?;
+		self.call_api::<_, XcmPaymentApiError>(
+			"XcmPaymentApi_query_weight_to_asset_fee",
+			&(weight, VersionedAssetId::from(asset_id)).encode(),
+		)
+	}
+
+	/// Returns the fees charged for delivering `xcm` to `destination`.
+	pub fn delivery_fees(
+		&mut self,
+		destination: VersionedLocation,
+		xcm: VersionedXcm<()>,
+	) -> Result<VersionedAssets> {
+		self.call_api::<_, XcmPaymentApiError>(
+			"XcmPaymentApi_query_delivery_fees",
+			&(destination, xcm).encode(),
+		)
+	}
+
+	/// Returns the balances of `account`.
+	///
+	/// The balances are queried from the `FungiblesApi` if the runtime implements it, else the
+	/// native balance of the account is read from `frame_system`.
+	pub fn balances(&mut self, account: &AccountId32) -> Result<Assets> {
+		if self.fungibles_api {
+			const METHOD: &str = "FungiblesApi_query_account_balances";
+			// The error is the index of a `FungiblesAccessError` variant.
+			let balances: VersionedAssets = self.call_api::<_, u8>(METHOD, &account.encode())?;
+			return balances.try_into().map_err(|_| Error::Api {
+				chain: self.id,
+				method: METHOD,
+				error: "Unsupported XCM version".into(),
+			})
+		}
+
+		let key = [
+			sp_core::twox_128(b"System").as_slice(),
+			&sp_core::twox_128(b"Account"),
+			&sp_core::blake2_128(account.as_ref()),
+			account.as_ref(),
+		]
+		.concat();
+		let free = match self.ext.execute_with(|| sp_io::storage::get(&key)) {
+			// The nonce and reference counts of the account, followed by its free balance.
+			Some(info) =>
+				<(u32, u32, u32, u32, u128)>::decode(&mut &info[..])
+					.map_err(|error| Error::Decode {
+						chain: self.id,
+						method: "System::Account",
+						error,
+					})?
+					.4,
+			None => 0,
+		};
+		Ok(vec![(Here, free).into()].into())
+	}
+
+	/// Call the runtime API `method` with the SCALE encoded `data`, returning the encoded result.
+	fn call(&mut self, method: &'static str, data: &[u8]) -> Result<Vec<u8>> {
+		self.runtime.call(&mut self.ext, method, data).map_err(|error| Error::Call {
+			chain: self.id,
+			method,
+			error,
+		})
+	}
+
+	/// Call the runtime API `method`, which returns a `Result<R, E>`.
+	fn call_api<R: Decode, E: Decode + fmt::Debug>(
+		&mut self,
+		method: &'static str,
+		data: &[u8],
+	) -> Result<R> {
+		let result = self.call(method, data)?;
+		Result::<R, E>::decode(&mut &result[..])
+			.map_err(|error| Error::Decode { chain: self.id, method, error })?
+			.map_err(|error| Error::Api { chain: self.id, method, error: format!("{error:?}") })
+	}
+
+	/// Decode the `result` of the dry-run `method`, using `decode_effects` to decode its effects.
+	fn decode_dry_run<T>(
+		&self,
+		method: &'static str,
+		mut result: &[u8],
+		decode_effects: impl FnOnce(&mut &[u8], &EventDecoder) -> Result<T, codec::Error>,
+	) -> Result<T> {
+		let input = &mut result;
+		let effects = match input.read_byte() {
+			Ok(0) => decode_effects(input, &self.events).map(Ok),
+			Ok(1) => DryRunError::decode(input).map(Err),
+			Ok(_) => Err("Invalid result variant".into()),
+			Err(error) => Err(error),
+		};
+		effects
+			.map_err(|error| Error::Decode { chain: self.id, method, error })?
+			.map_err(|error| Error::Api { chain: self.id, method, error: format!("{error:?}") })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn chain_locations_are_resolved() {
+		let chains = [ChainId::Relay, ChainId::Para(1000), ChainId::Para(2000)];
+		for from in chains {
+			for to in chains {
+				assert_eq!(from.resolve(&to.location_from(from)), Some(to));
+			}
+		}
+
+		assert_eq!(
+			ChainId::Para(1000).location_from(ChainId::Para(2000)),
+			(Parent, Parachain(1000)).into()
+		);
+		assert_eq!(ChainId::Relay.resolve(&Parachain(1000).into()), Some(ChainId::Para(1000)));
+		assert_eq!(ChainId::Relay.resolve(&Parent.into()), None);
+		assert_eq!(ChainId::Para(1000).resolve(&(Parent, Parent).into()), None);
+		assert_eq!(
+			ChainId::Para(1000)
+				.resolve(&Junction::AccountId32 { network: None, id: [0; 32] }.into()),
+			None
+		);
+	}
+
+	#[test]
+	fn chain_ids_are_parsed() {
+		assert_eq!("relay".parse(), Ok(ChainId::Relay));
+		assert_eq!("1000".parse(), Ok(ChainId::Para(1000)));
+		assert!("asset-hub".parse::<ChainId>().is_err());
+	}
+}
@@ -0,0 +1,178 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decoding of the events emitted by a runtime, using its metadata.
+
+use codec::{Compact, Decode, Input};
+use frame_metadata::v15::RuntimeMetadataV15;
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive};
+use std::collections::BTreeMap;
+
+/// Decodes the events of a runtime into their names, such as `Balances::Transfer`.
+///
+/// The fields of the events are skipped, as they can not be decoded without the types of the
+/// runtime.
+pub(crate) struct EventDecoder {
+	/// The types of the runtime.
+	types: PortableRegistry,
+	/// The name and the event type of the pallets, by index.
+	pallets: BTreeMap<u8, (String, u32)>,
+}
+
+impl EventDecoder {
+	/// Create a new decoder of the events of the runtime described by `metadata`.
+	pub fn new(metadata: RuntimeMetadataV15) -> Self {
+		let pallets = metadata
+			.pallets
+			.into_iter()
+			.filter_map(|pallet| Some((pallet.index, (pallet.name, pallet.event?.ty.id))))
+			.collect();
+		Self { types: metadata.types, pallets }
+	}
+
+	/// Decode a vector of events.
+	pub fn decode_events(&self, input: &mut &[u8]) -> Result<Vec<String>, codec::Error> {
+		let Compact(len) = Compact::<u32>::decode(input)?;
+		(0..len).map(|_| self.decode_event(input)).collect()
+	}
+
+	/// Decode a single event, prefixed by the index of the pallet emitting it.
+	fn decode_event(&self, input: &mut &[u8]) -> Result<String, codec::Error> {
+		let index = input.read_byte()?;
+		let (pallet, ty) = self.pallets.get(&index).ok_or("Unknown pallet index")?;
+		let name = self.skip_variant(*ty, input)?;
+		Ok(format!("{pallet}::{name}"))
+	}
+
+	/// Skip a value of the variant type `ty`, returning the name of its variant.
+	fn skip_variant<'a>(&'a self, ty: u32, input: &mut &[u8]) -> Result<&'a str, codec::Error> {
+		let TypeDef::Variant(def) = &self.resolve(ty)?.type_def else {
+			return Err("Expected a variant type".into())
+		};
+		let index = input.read_byte()?;
+		let variant = def
+			.variants
+			.iter()
+			.find(|variant| variant.index == index)
+			.ok_or("Unknown variant index")?;
+		self.skip_fields(&variant.fields, input)?;
+		Ok(&variant.name)
+	}
+
+	/// Skip the values of `fields`.
+	fn skip_fields(
+		&self,
+		fields: &[Field<PortableForm>],
+		input: &mut &[u8],
+	) -> Result<(), codec::Error> {
+		fields.iter().try_for_each(|field| self.skip(field.ty.id, input))
+	}
+
+	/// Skip a value of type `ty`.
+	fn skip(&self, ty: u32, input: &mut &[u8]) -> Result<(), codec::Error> {
+		match &self.resolve(ty)?.type_def {
+			TypeDef::Composite(def) => self.skip_fields(&def.fields, input),
+			TypeDef::Variant(_) => self.skip_variant(ty, input).map(drop),
+			TypeDef::Sequence(def) => {
+				let Compact(len) = Compact::<u32>::decode(input)?;
+				(0..len).try_for_each(|_| self.skip(def.type_param.id, input))
+			},
+			TypeDef::Array(def) =>
+				(0..def.len).try_for_each(|_| self.skip(def.type_param.id, input)),
+			TypeDef::Tuple(def) => def.fields.iter().try_for_each(|ty| self.skip(ty.id, input)),
+			TypeDef::Primitive(def) => skip_primitive(def, input),
+			// The length of a compact value only depends on its first byte.
+			TypeDef::Compact(_) => Compact::<u128>::skip(input),
+			TypeDef::BitSequence(_) => Err("Bit sequences are not supported".into()),
+		}
+	}
+
+	/// Returns the type with the given id.
+	fn resolve(&self, ty: u32) -> Result<&scale_info::Type<PortableForm>, codec::Error> {
+		self.types.resolve(ty).ok_or_else(|| "Unknown type".into())
+	}
+}
+
+/// Skip a value of the primitive type `def`.
+fn skip_primitive(def: &TypeDefPrimitive, input: &mut &[u8]) -> Result<(), codec::Error> {
+	let len = match def {
+		TypeDefPrimitive::Str => return String::skip(input),
+		TypeDefPrimitive::Bool | TypeDefPrimitive::U8 | TypeDefPrimitive::I8 => 1,
+		TypeDefPrimitive::U16 | TypeDefPrimitive::I16 => 2,
+		TypeDefPrimitive::Char | TypeDefPrimitive::U32 | TypeDefPrimitive::I32 => 4,
+		TypeDefPrimitive::U64 | TypeDefPrimitive::I64 => 8,
+		TypeDefPrimitive::U128 | TypeDefPrimitive::I128 => 16,
+		TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => 32,
+	};
+	*input = input.get(len..).ok_or("Not enough data to skip")?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use scale_info::{meta_type, Registry, TypeInfo};
+
+	#[derive(Encode, TypeInfo)]
+	struct Transfer {
+		from: [u8; 32],
+		amount: Compact<u128>,
+	}
+
+	#[derive(Encode, TypeInfo)]
+	enum Event {
+		#[codec(index = 2)]
+		Transferred(Transfer, Vec<(u32, Option<String>)>),
+		#[codec(index = 5)]
+		Burned { who: u64, amount: u128 },
+	}
+
+	fn decoder() -> EventDecoder {
+		let mut registry = Registry::new();
+		let ty = registry.register_type(&meta_type::<Event>()).id;
+		EventDecoder { types: registry.into(), pallets: [(7, ("Balances".into(), ty))].into() }
+	}
+
+	#[test]
+	fn decode_events_works() {
+		let events = vec![
+			(
+				7u8,
+				Event::Transferred(
+					Transfer { from: [1; 32], amount: Compact(u128::MAX) },
+					vec![(1, None), (2, Some("two".into()))],
+				),
+			),
+			(7u8, Event::Burned { who: 3, amount: 4 }),
+		];
+		let bytes = (events, true).encode();
+
+		let input = &mut &bytes[..];
+		assert_eq!(
+			decoder().decode_events(input).unwrap(),
+			vec!["Balances::Transferred", "Balances::Burned"]
+		);
+		// The data following the events is left untouched.
+		assert!(bool::decode(input).unwrap());
+	}
+
+	#[test]
+	fn decode_events_fails_for_unknown_pallets() {
+		let bytes = vec![(8u8, Event::Burned { who: 3, amount: 4 })].encode();
+		assert!(decoder().decode_events(&mut &bytes[..]).is_err());
+	}
+}
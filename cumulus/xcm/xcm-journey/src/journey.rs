@@ -0,0 +1,347 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Following the messages of a journey from chain to chain.
+
+use crate::{Chain, ChainId, Error, Result, LOG_TARGET};
+use frame_support::dispatch::DispatchResultWithPostInfo;
+use sp_core::crypto::AccountId32;
+use std::{
+	collections::{BTreeMap, VecDeque},
+	fmt,
+};
+use xcm::prelude::*;
+
+/// The default maximum number of hops of a journey.
+const DEFAULT_MAX_HOPS: usize = 16;
+
+/// How a journey starts.
+pub enum Start {
+	/// Dispatch a call.
+	Call {
+		/// The SCALE encoded `OriginCaller` of the runtime dispatching the call.
+		origin: Vec<u8>,
+		/// The SCALE encoded `RuntimeCall` of the runtime.
+		call: Vec<u8>,
+	},
+	/// Execute an XCM program.
+	Xcm {
+		/// The location executing the program.
+		origin: Location,
+		/// The program.
+		xcm: VersionedXcm<()>,
+	},
+}
+
+/// The outcome of a hop.
+#[derive(Debug)]
+pub enum HopOutcome {
+	/// The result of the call starting the journey.
+	Call(DispatchResultWithPostInfo),
+	/// The outcome of an XCM program.
+	Xcm(Outcome),
+}
+
+impl HopOutcome {
+	/// Whether the hop succeeded.
+	pub fn is_success(&self) -> bool {
+		match self {
+			Self::Call(result) => result.is_ok(),
+			Self::Xcm(outcome) => matches!(outcome, Outcome::Complete { .. }),
+		}
+	}
+}
+
+/// A message forwarded by a hop.
+#[derive(Debug)]
+pub struct Forwarded {
+	/// The destination of the message, as seen from the chain sending it.
+	pub destination: VersionedLocation,
+	/// The chain at `destination`, `None` if it is not a chain of the consensus system, such as a
+	/// bridged chain.
+	pub chain: Option<ChainId>,
+	/// The message.
+	pub message: VersionedXcm<()>,
+	/// The fees charged for delivering the message, if they could be queried.
+	pub delivery_fees: Option<VersionedAssets>,
+	/// Whether the message was followed, its destination being part of the simulation.
+	pub followed: bool,
+}
+
+/// A hop of a journey, the execution of a program on a chain.
+#[derive(Debug)]
+pub struct Hop {
+	/// The chain executing the program.
+	pub chain: ChainId,
+	/// The origin of the XCM program, as seen from `chain`, `None` for the call starting the
+	/// journey.
+	pub origin: Option<Location>,
+	/// The outcome of the program.
+	pub outcome: HopOutcome,
+	/// The names of the events emitted by the program.
+	pub events: Vec<String>,
+	/// The fee charged for executing the XCM program, in the asset it pays its execution with.
+	///
+	/// `None` for the call starting the journey, whose fees depend on the way it is submitted.
+	pub execution_fee: Option<Asset>,
+	/// The messages forwarded by the program.
+	pub forwarded: Vec<Forwarded>,
+}
+
+/// The balances of an account on a chain, before and after a journey.
+#[derive(Debug)]
+pub struct Balance {
+	/// The chain holding the balances.
+	pub chain: ChainId,
+	/// The account.
+	pub account: AccountId32,
+	/// The balances before the journey.
+	pub before: Assets,
+	/// The balances after the journey.
+	pub after: Assets,
+}
+
+/// The report of a journey.
+#[derive(Debug)]
+pub struct Journey {
+	/// The hops of the journey, in the order they were executed.
+	pub hops: Vec<Hop>,
+	/// The balances of the accounts of interest, on every chain.
+	pub balances: Vec<Balance>,
+}
+
+impl Journey {
+	/// Whether every hop of the journey succeeded.
+	pub fn is_success(&self) -> bool {
+		self.hops.iter().all(|hop| hop.outcome.is_success())
+	}
+}
+
+/// Simulates journeys across a set of chains.
+pub struct Simulator {
+	chains: BTreeMap<ChainId, Chain>,
+	xcm_version: XcmVersion,
+	max_hops: usize,
+}
+
+impl Simulator {
+	/// Create a new simulator of `chains`.
+	pub fn new(chains: impl IntoIterator<Item = Chain>) -> Self {
+		let chains = chains.into_iter().map(|chain| (chain.id(), chain)).collect();
+		Self { chains, xcm_version: XCM_VERSION, max_hops: DEFAULT_MAX_HOPS }
+	}
+
+	/// Set the XCM version of the messages forwarded by the calls starting the journeys.
+	pub fn with_xcm_version(mut self, xcm_version: XcmVersion) -> Self {
+		self.xcm_version = xcm_version;
+		self
+	}
+
+	/// Set the maximum number of hops of a journey.
+	pub fn with_max_hops(mut self, max_hops: usize) -> Self {
+		self.max_hops = max_hops;
+		self
+	}
+
+	/// Run a journey starting on the chain `from`, reporting the balances of `accounts` on every
+	/// chain.
+	///
+	/// The changes made by the journey are kept, later journeys start from the resulting state.
+	pub fn run(
+		&mut self,
+		from: ChainId,
+		start: Start,
+		accounts: &[AccountId32],
+	) -> Result<Journey> {
+		let before = self.balances(accounts)?;
+
+		let chain = self.chains.get_mut(&from).ok_or(Error::UnknownChain(from))?;
+		let mut hop = match start {
+			Start::Call { origin, call } => {
+				let effects = chain.dry_run_call(&origin, &call, self.xcm_version)?;
+				Hop {
+					chain: from,
+					origin: None,
+					outcome: HopOutcome::Call(effects.execution_result),
+					events: effects.emitted_events,
+					execution_fee: None,
+					forwarded: forwarded(chain, effects.forwarded_xcms),
+				}
+			},
+			Start::Xcm { origin, xcm } => execute_xcm(chain, origin, xcm)?,
+		};
+
+		let mut queue = VecDeque::from(self.follow(&mut hop));
+		let mut hops = vec![hop];
+		while let Some((to, origin, message)) = queue.pop_front() {
+			if hops.len() >= self.max_hops {
+				return Err(Error::TooManyHops(self.max_hops))
+			}
+			log::debug!(target: LOG_TARGET, "Executing a message from {origin:?} on the {to}");
+			let chain = self.chains.get_mut(&to).ok_or(Error::UnknownChain(to))?;
+			let mut hop = execute_xcm(chain, origin, message)?;
+			queue.extend(self.follow(&mut hop));
+			hops.push(hop);
+		}
+
+		let after = self.balances(accounts)?;
+		let balances = before
+			.into_iter()
+			.zip(after)
+			.map(|((chain, account, before), (_, _, after))| Balance {
+				chain,
+				account,
+				before,
+				after,
+			})
+			.collect();
+		Ok(Journey { hops, balances })
+	}
+
+	/// Returns the messages forwarded by `hop` to the simulated chains, marking them as followed.
+	///
+	/// The messages are returned along with their destination and their origin, as seen from the
+	/// destination.
+	fn follow(&self, hop: &mut Hop) -> Vec<(ChainId, Location, VersionedXcm<()>)> {
+		hop.forwarded
+			.iter_mut()
+			.filter_map(|forwarded| {
+				let Some(to) = forwarded.chain.filter(|to| self.chains.contains_key(to)) else {
+					log::warn!(
+						target: LOG_TARGET,
+						"Not following the message sent to {:?}, which is not simulated",
+						forwarded.destination,
+					);
+					return None
+				};
+				forwarded.followed = true;
+				Some((to, hop.chain.location_from(to), forwarded.message.clone()))
+			})
+			.collect()
+	}
+
+	/// Returns the balances of `accounts` on every chain.
+	fn balances(
+		&mut self,
+		accounts: &[AccountId32],
+	) -> Result<Vec<(ChainId, AccountId32, Assets)>> {
+		let mut balances = Vec::new();
+		for chain in self.chains.values_mut() {
+			for account in accounts {
+				balances.push((chain.id(), account.clone(), chain.balances(account)?));
+			}
+		}
+		Ok(balances)
+	}
+}
+
+/// Execute `xcm` from `origin` on `chain`.
+fn execute_xcm(chain: &mut Chain, origin: Location, xcm: VersionedXcm<()>) -> Result<Hop> {
+	let execution_fee = execution_fee(chain, &xcm);
+	// The origin is given in the version of the message, which the runtime is known to support.
+	let versioned_origin = VersionedLocation::from(origin.clone())
+		.into_version(xcm.identify_version())
+		.unwrap_or_else(|()| origin.clone().into());
+	let effects = chain.dry_run_xcm(versioned_origin, xcm)?;
+	Ok(Hop {
+		chain: chain.id(),
+		origin: Some(origin),
+		outcome: HopOutcome::Xcm(effects.execution_result),
+		events: effects.emitted_events,
+		execution_fee,
+		forwarded: forwarded(chain, effects.forwarded_xcms),
+	})
+}
+
+/// Returns the fee charged by `chain` for executing `xcm`, in the asset it pays its execution with.
+///
+/// Returns `None` if `xcm` does not pay for its execution, or if the fee could not be queried.
+fn execution_fee(chain: &mut Chain, xcm: &VersionedXcm<()>) -> Option<Asset> {
+	let program = Xcm::<()>::try_from(xcm.clone()).ok()?;
+	let asset_id = program.0.iter().find_map(|instruction| match instruction {
+		BuyExecution { fees, .. } => Some(fees.id.clone()),
+		PayFees { asset } => Some(asset.id.clone()),
+		_ => None,
+	})?;
+	chain
+		.execution_fee(xcm.clone(), asset_id.clone())
+		.inspect_err(|error| log::warn!(target: LOG_TARGET, "{error}"))
+		.ok()
+		.map(|fee| (asset_id, fee).into())
+}
+
+/// Returns the messages forwarded by `chain`, along with their delivery fees.
+fn forwarded(
+	chain: &mut Chain,
+	forwarded_xcms: Vec<(VersionedLocation, Vec<VersionedXcm<()>>)>,
+) -> Vec<Forwarded> {
+	let mut forwarded = Vec::new();
+	for (destination, messages) in forwarded_xcms {
+		let to = Location::try_from(destination.clone())
+			.ok()
+			.and_then(|location| chain.id().resolve(&location));
+		for message in messages {
+			let delivery_fees = chain
+				.delivery_fees(destination.clone(), message.clone())
+				.inspect_err(|error| log::warn!(target: LOG_TARGET, "{error}"))
+				.ok();
+			forwarded.push(Forwarded {
+				destination: destination.clone(),
+				chain: to,
+				message,
+				delivery_fees,
+				followed: false,
+			});
+		}
+	}
+	forwarded
+}
+
+impl fmt::Display for Journey {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for (index, hop) in self.hops.iter().enumerate() {
+			match &hop.origin {
+				Some(origin) => writeln!(f, "#{index} {}, message from {origin:?}", hop.chain)?,
+				None => writeln!(f, "#{index} {}, call", hop.chain)?,
+			}
+			match &hop.outcome {
+				HopOutcome::Call(result) => writeln!(f, "  Result: {result:?}")?,
+				HopOutcome::Xcm(outcome) => writeln!(f, "  Outcome: {outcome:?}")?,
+			}
+			if let Some(fee) = &hop.execution_fee {
+				writeln!(f, "  Execution fee: {fee:?}")?;
+			}
+			writeln!(f, "  Events: {}", hop.events.join(", "))?;
+			for forwarded in &hop.forwarded {
+				let status = if forwarded.followed { "followed" } else { "not followed" };
+				writeln!(f, "  Forwarded to {:?} ({status})", forwarded.destination)?;
+				if let Some(fees) = &forwarded.delivery_fees {
+					writeln!(f, "    Delivery fees: {fees:?}")?;
+				}
+			}
+		}
+
+		if !self.balances.is_empty() {
+			writeln!(f, "Balances")?;
+		}
+		for balance in &self.balances {
+			writeln!(f, "  {} on the {}", balance.account, balance.chain)?;
+			writeln!(f, "    Before: {:?}", balance.before)?;
+			writeln!(f, "    After: {:?}", balance.after)?;
+		}
+		Ok(())
+	}
+}
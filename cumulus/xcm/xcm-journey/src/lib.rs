@@ -0,0 +1,78 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Offline simulation of XCM journeys across several chains.
+//!
+//! The chains are loaded from their runtime and a snapshot of their state, as created by
+//! [`remote_externalities`]. A journey starts with the dry-run of a call or of an XCM program on
+//! one of them, then every message it forwards is dry-run on its destination, hop by hop, through
+//! the [`DryRunApi`](xcm_runtime_apis::dry_run::DryRunApi). The changes made by every hop are kept,
+//! so the final balances of the accounts reflect the whole journey.
+//!
+//! ```ignore
+//! let relay = Chain::load(ChainId::Relay, "westend.snap".into(), None).await?;
+//! let asset_hub = Chain::load(ChainId::Para(1000), "asset-hub.snap".into(), None).await?;
+//! let journey = Simulator::new([relay, asset_hub])
+//! 	.run(ChainId::Para(1000), Start::Call { origin, call }, &[account])?;
+//! println!("{journey}");
+//! ```
+
+mod chain;
+mod events;
+mod journey;
+
+pub use chain::{Chain, ChainId};
+pub use journey::{Balance, Forwarded, Hop, HopOutcome, Journey, Simulator, Start};
+
+use std::path::PathBuf;
+
+/// The log target of this crate.
+const LOG_TARGET: &str = "xcm::journey";
+
+/// The errors of the simulator.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// A file could not be read.
+	#[error("Failed to read {0}: {1}")]
+	Io(PathBuf, std::io::Error),
+	/// The state snapshot of a chain could not be loaded.
+	#[error("Failed to load the state snapshot of the {0}: {1}")]
+	Snapshot(ChainId, &'static str),
+	/// No runtime was given for a chain, and none is in its state.
+	#[error("No runtime found in the state of the {0}")]
+	MissingRuntime(ChainId),
+	/// The runtime of a chain does not provide the metadata V15.
+	#[error("The runtime of the {0} does not provide the metadata V15")]
+	UnsupportedMetadata(ChainId),
+	/// The journey involves a chain that was not loaded.
+	#[error("The {0} was not loaded")]
+	UnknownChain(ChainId),
+	/// The runtime failed to execute a runtime API call.
+	#[error("Failed to call `{method}` on the {chain}: {error}")]
+	Call { chain: ChainId, method: &'static str, error: String },
+	/// The result of a runtime API call could not be decoded.
+	#[error("Failed to decode the result of `{method}` on the {chain}: {error}")]
+	Decode { chain: ChainId, method: &'static str, error: codec::Error },
+	/// A runtime API returned an error.
+	#[error("`{method}` returned an error on the {chain}: {error}")]
+	Api { chain: ChainId, method: &'static str, error: String },
+	/// The journey did not end after the maximum number of hops.
+	#[error("The journey did not end after {0} hops")]
+	TooManyHops(usize),
+}
+
+/// The result type of the simulator.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -0,0 +1,153 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Command line interface of the XCM journey simulator.
+
+use clap::{ArgGroup, Parser};
+use codec::Decode;
+use sp_core::crypto::AccountId32;
+use std::{path::PathBuf, process::ExitCode, str::FromStr};
+use xcm::prelude::*;
+use xcm_journey::{Chain, ChainId, Simulator, Start};
+
+/// Follows an XCM journey across several chains, entirely offline.
+///
+/// The journey starts with a call or an XCM program dry-run on one chain. Every message it
+/// forwards is then dry-run on its destination, hop by hop. The outcome and fees of every hop are
+/// reported, along with the balances of the given accounts before and after the journey.
+#[derive(Debug, Parser)]
+#[command(version, group(ArgGroup::new("start").required(true).args(["call", "xcm"])))]
+struct Cli {
+	/// A chain of the simulation, as `<relay|PARA_ID>=<SNAPSHOT>[,<RUNTIME>]`.
+	///
+	/// The state snapshot is created by `try-runtime create-snapshot`. The runtime of the snapshot
+	/// is used, unless the path of another Wasm runtime is given.
+	#[arg(long = "chain", required = true)]
+	chains: Vec<ChainArg>,
+
+	/// The chain the journey starts on.
+	#[arg(long)]
+	from: ChainId,
+
+	/// The SCALE encoded `OriginCaller` dispatching `--call`.
+	///
+	/// A signed origin is usually encoded as `0x0000` followed by the account id.
+	#[arg(long, value_parser = parse_hex, requires = "call")]
+	origin: Option<Vec<u8>>,
+
+	/// The SCALE encoded `RuntimeCall` starting the journey.
+	#[arg(long, value_parser = parse_hex, requires = "origin")]
+	call: Option<Vec<u8>>,
+
+	/// The SCALE encoded `VersionedLocation` executing `--xcm`.
+	#[arg(long, value_parser = parse_hex, requires = "xcm")]
+	origin_location: Option<Vec<u8>>,
+
+	/// The SCALE encoded `VersionedXcm` starting the journey.
+	#[arg(long, value_parser = parse_hex, requires = "origin_location")]
+	xcm: Option<Vec<u8>>,
+
+	/// An account whose balances are reported, as a hex or SS58 encoded 32 bytes account id.
+	#[arg(long = "account")]
+	accounts: Vec<AccountId32>,
+
+	/// The XCM version of the messages forwarded by `--call`.
+	#[arg(long, default_value_t = XCM_VERSION)]
+	xcm_version: XcmVersion,
+
+	/// The maximum number of hops of the journey.
+	#[arg(long, default_value_t = 16)]
+	max_hops: usize,
+}
+
+/// A chain of the simulation.
+#[derive(Clone, Debug)]
+struct ChainArg {
+	id: ChainId,
+	snapshot: PathBuf,
+	runtime: Option<PathBuf>,
+}
+
+impl FromStr for ChainArg {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (id, paths) = s
+			.split_once('=')
+			.ok_or_else(|| format!("Invalid chain `{s}`, expected `<relay|PARA_ID>=<SNAPSHOT>`"))?;
+		let (snapshot, runtime) = match paths.split_once(',') {
+			Some((snapshot, runtime)) => (snapshot, Some(runtime.into())),
+			None => (paths, None),
+		};
+		Ok(Self { id: id.parse()?, snapshot: snapshot.into(), runtime })
+	}
+}
+
+/// Parse hex encoded bytes.
+fn parse_hex(s: &str) -> Result<Vec<u8>, String> {
+	array_bytes::hex2bytes(s).map_err(|error| format!("Invalid hex: {error:?}"))
+}
+
+/// Decode a SCALE encoded argument.
+fn decode<T: Decode>(name: &str, bytes: &[u8]) -> Result<T, String> {
+	T::decode(&mut &bytes[..]).map_err(|error| format!("Invalid `{name}`: {error}"))
+}
+
+async fn run(cli: Cli) -> Result<bool, String> {
+	let start = match (cli.origin, cli.call, cli.origin_location, cli.xcm) {
+		(Some(origin), Some(call), _, _) => Start::Call { origin, call },
+		(_, _, Some(origin), Some(xcm)) => Start::Xcm {
+			origin: decode::<VersionedLocation>("--origin-location", &origin)?
+				.try_into()
+				.map_err(|()| "Unsupported version of `--origin-location`")?,
+			xcm: decode("--xcm", &xcm)?,
+		},
+		_ => unreachable!("Either `--call` or `--xcm` is required; qed"),
+	};
+
+	let mut chains = Vec::new();
+	for ChainArg { id, snapshot, runtime } in cli.chains {
+		chains.push(Chain::load(id, snapshot, runtime).await.map_err(|error| error.to_string())?);
+	}
+
+	let journey = Simulator::new(chains)
+		.with_xcm_version(cli.xcm_version)
+		.with_max_hops(cli.max_hops)
+		.run(cli.from, start, &cli.accounts)
+		.map_err(|error| error.to_string())?;
+	print!("{journey}");
+	Ok(journey.is_success())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+	tracing_subscriber::fmt()
+		.with_env_filter(
+			tracing_subscriber::EnvFilter::try_from_default_env()
+				.unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+		)
+		.with_writer(std::io::stderr)
+		.init();
+
+	match run(Cli::parse()).await {
+		Ok(true) => ExitCode::SUCCESS,
+		Ok(false) => ExitCode::from(2),
+		Err(error) => {
+			eprintln!("Error: {error}");
+			ExitCode::FAILURE
+		},
+	}
+}
@@ -34,7 +34,7 @@ pub use self::metronome::Metronome;
 #[cfg(feature = "runtime-metrics")]
 pub mod runtime;
 #[cfg(feature = "runtime-metrics")]
-pub use self::runtime::logger_hook;
+pub use self::runtime::{collator_logger_hook, logger_hook};
 
 /// Export a dummy logger hook when the `runtime-metrics` feature is not enabled.
 #[cfg(not(feature = "runtime-metrics"))]
@@ -42,6 +42,13 @@ pub fn logger_hook() -> impl FnOnce(&mut sc_cli::LoggerBuilder, &sc_service::Con
 	|_logger_builder, _config| {}
 }
 
+/// Export a dummy collator logger hook when the `runtime-metrics` feature is not enabled.
+#[cfg(not(feature = "runtime-metrics"))]
+pub fn collator_logger_hook() -> impl FnOnce(&mut sc_cli::LoggerBuilder, &sc_service::Configuration)
+{
+	|_logger_builder, _config| {}
+}
+
 /// This module reexports Prometheus types and defines the [`Metrics`](metrics::Metrics) trait.
 pub mod metrics {
 	/// Reexport Substrate Prometheus types.
@@ -40,6 +40,7 @@ use std::{
 	sync::{Arc, Mutex, MutexGuard},
 };
 mod parachain;
+mod xcm;
 
 /// Holds the registered Prometheus metric collections.
 #[derive(Clone, Default)]
@@ -221,6 +222,23 @@ pub fn logger_hook() -> impl FnOnce(&mut sc_cli::LoggerBuilder, &sc_service::Con
 		let registry = config.prometheus_registry().cloned().unwrap();
 		let metrics_provider = RuntimeMetricsProvider::new(registry);
 		parachain::register_metrics(&metrics_provider);
+		xcm::register_metrics(&metrics_provider);
+		logger_builder.with_custom_profiling(Box::new(metrics_provider));
+	}
+}
+
+/// Returns the custom profiling closure of the collators that we'll apply to the `LoggerBuilder`.
+///
+/// Unlike [`logger_hook`], the metrics of the relay chain runtime are not registered.
+pub fn collator_logger_hook(
+) -> impl FnOnce(&mut sc_cli::LoggerBuilder, &sc_service::Configuration) -> () {
+	|logger_builder, config| {
+		if config.prometheus_registry().is_none() {
+			return
+		}
+		let registry = config.prometheus_registry().cloned().unwrap();
+		let metrics_provider = RuntimeMetricsProvider::new(registry);
+		xcm::register_metrics(&metrics_provider);
 		logger_builder.with_custom_profiling(Box::new(metrics_provider));
 	}
 }
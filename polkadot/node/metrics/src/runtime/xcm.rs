@@ -0,0 +1,28 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Client side declaration and registration of the XCM Prometheus metrics.
+//!
+//! The metrics are published by the runtimes of the relay chain and of the parachains, so they are
+//! registered by both the relay chain nodes and the collators.
+
+use crate::runtime::RuntimeMetricsProvider;
+use polkadot_primitives::metric_definitions::XCM_RATE_LIMITER_THROTTLED_MESSAGES;
+
+/// Register the XCM runtime metrics.
+pub fn register_metrics(runtime_metrics_provider: &RuntimeMetricsProvider) {
+	runtime_metrics_provider.register_countervec(XCM_RATE_LIMITER_THROTTLED_MESSAGES);
+}
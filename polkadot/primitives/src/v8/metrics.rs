@@ -172,6 +172,14 @@ pub mod metric_definitions {
 			labels: &["validity"],
 		};
 
+	/// Counts the number of XCM messages throttled by the rate limiter. Use the `messages` and
+	/// `weight` labels of `quota` to differentiate between the quotas the origins exhausted.
+	pub const XCM_RATE_LIMITER_THROTTLED_MESSAGES: CounterVecDefinition = CounterVecDefinition {
+		name: "polkadot_xcm_rate_limiter_throttled_messages",
+		description: "Counts the number of XCM messages throttled by the rate limiter.",
+		labels: &["quota"],
+	};
+
 	/// Measures how much time does it take to verify a single validator signature of a dispute
 	/// statement
 	pub const PARACHAIN_VERIFY_DISPUTE_SIGNATURE: HistogramDefinition =
@@ -34,6 +34,7 @@ pub mod traits;
 
 #[cfg(feature = "try-runtime")]
 pub mod try_runtime;
+pub mod xcm_rate_limiter;
 pub mod xcm_sender;
 
 #[cfg(test)]
@@ -0,0 +1,42 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime metrics of the XCM rate limiter.
+//!
+//! The metrics are only published with the `runtime-metrics` feature, and exported by the relay
+//! chain nodes and the collators.
+
+use pallet_xcm_rate_limiter::{ExhaustedQuota, OnThrottled};
+use polkadot_primitives::metric_definitions::XCM_RATE_LIMITER_THROTTLED_MESSAGES;
+use polkadot_runtime_metrics::CounterVec;
+use xcm::latest::Location;
+
+const THROTTLED_MESSAGES: CounterVec = CounterVec::new(XCM_RATE_LIMITER_THROTTLED_MESSAGES);
+
+/// Counts the messages throttled by `pallet_xcm_rate_limiter`, labelled by the quota their origin
+/// exhausted.
+///
+/// To be used as the `OnThrottled` handler of the pallet.
+pub struct ThrottledMessagesMetric;
+impl OnThrottled for ThrottledMessagesMetric {
+	fn on_throttled(_: &Location, quota: ExhaustedQuota) {
+		let quota = match quota {
+			ExhaustedQuota::Messages => "messages",
+			ExhaustedQuota::Weight => "weight",
+		};
+		THROTTLED_MESSAGES.with_label_values(&[quota]).inc();
+	}
+}
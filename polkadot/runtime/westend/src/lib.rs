@@ -121,7 +121,9 @@ use xcm::{
 };
 use xcm_builder::PayOverXcm;
 use xcm_runtime_apis::{
-	dry_run::{CallDryRunEffects, Error as XcmDryRunApiError, XcmDryRunEffects},
+	dry_run::{
+		CallDryRunEffects, Error as XcmDryRunApiError, TracedDryRunEffects, XcmDryRunEffects,
+	},
 	fees::Error as XcmPaymentApiError,
 };
 
@@ -2719,6 +2721,7 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	#[api_version(3)]
 	impl xcm_runtime_apis::dry_run::DryRunApi<Block, RuntimeCall, RuntimeEvent, OriginCaller> for Runtime {
 		fn dry_run_call(origin: OriginCaller, call: RuntimeCall, result_xcms_version: XcmVersion) -> Result<CallDryRunEffects<RuntimeEvent>, XcmDryRunApiError> {
 			XcmPallet::dry_run_call::<Runtime, xcm_config::XcmRouter, OriginCaller, RuntimeCall>(origin, call, result_xcms_version)
@@ -2727,6 +2730,14 @@ sp_api::impl_runtime_apis! {
 		fn dry_run_xcm(origin_location: VersionedLocation, xcm: VersionedXcm<RuntimeCall>) -> Result<XcmDryRunEffects<RuntimeEvent>, XcmDryRunApiError> {
 			XcmPallet::dry_run_xcm::<Runtime, xcm_config::XcmRouter, RuntimeCall, xcm_config::XcmConfig>(origin_location, xcm)
 		}
+
+		fn dry_run_call_with_trace(origin: OriginCaller, call: RuntimeCall, result_xcms_version: XcmVersion) -> Result<TracedDryRunEffects<CallDryRunEffects<RuntimeEvent>>, XcmDryRunApiError> {
+			XcmPallet::dry_run_call_with_trace::<Runtime, xcm_config::XcmRouter, OriginCaller, RuntimeCall>(origin, call, result_xcms_version)
+		}
+
+		fn dry_run_xcm_with_trace(origin_location: VersionedLocation, xcm: VersionedXcm<RuntimeCall>) -> Result<TracedDryRunEffects<XcmDryRunEffects<RuntimeEvent>>, XcmDryRunApiError> {
+			XcmPallet::dry_run_xcm_with_trace::<Runtime, xcm_config::XcmRouter, RuntimeCall, xcm_config::XcmConfig>(origin_location, xcm)
+		}
 	}
 
 	impl xcm_runtime_apis::conversions::LocationToAccountApi<Block, AccountId> for Runtime {
@@ -2741,6 +2752,15 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	impl xcm_runtime_apis::trapped_assets::TrappedAssetsApi<Block> for Runtime {
+		fn trapped_assets(origin: VersionedLocation) -> Result<
+			Vec<xcm_runtime_apis::trapped_assets::TrappedAssets>,
+			xcm_runtime_apis::trapped_assets::Error
+		> {
+			XcmPallet::trapped_assets(origin)
+		}
+	}
+
 	impl pallet_nomination_pools_runtime_api::NominationPoolsApi<
 		Block,
 		AccountId,
@@ -0,0 +1,94 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Benchmarks for the rate limiter pallet.
+
+use super::*;
+use frame_benchmarking::v2::*;
+use sp_runtime::traits::Zero;
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	const QUOTA: Quota = Quota { max_messages: 10, max_weight: Weight::from_parts(1_000, 1_000) };
+
+	#[benchmark]
+	fn set_quota() -> Result<(), BenchmarkError> {
+		let origin =
+			T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let location = Location::new(1, [Parachain(1000)]);
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, Box::new(location.clone().into()), Some(QUOTA));
+
+		assert_eq!(Quotas::<T>::get(VersionedLocation::from(location)), Some(QUOTA));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_default_quota() -> Result<(), BenchmarkError> {
+		let origin =
+			T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, Some(QUOTA));
+
+		assert_eq!(DefaultQuota::<T>::get(), Some(QUOTA));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn consume_quota() {
+		// The worst case: the location exhausts its own quota.
+		let location = Location::new(1, [Parachain(1000)]);
+		let key = VersionedLocation::from(location.clone());
+		Quotas::<T>::insert(&key, Quota { max_messages: 1, max_weight: Weight::MAX });
+		Usages::<T>::insert(&key, Usage::new(Zero::zero()));
+
+		#[block]
+		{
+			assert!(!Pallet::<T>::is_exhausted(&location));
+			Pallet::<T>::consume(&location, Weight::zero());
+		}
+
+		assert!(Pallet::<T>::is_exhausted(&location));
+	}
+
+	#[benchmark]
+	fn on_idle_base() {
+		#[block]
+		{
+			Pallet::<T>::on_idle(Zero::zero(), Weight::MAX);
+		}
+	}
+
+	#[benchmark]
+	fn prune_usage() {
+		let key = VersionedLocation::from(Location::new(1, [Parachain(1000)]));
+		Usages::<T>::insert(&key, Usage::new(Zero::zero()));
+		frame_system::Pallet::<T>::set_block_number(T::Period::get().max(One::one()));
+
+		#[block]
+		{
+			Pallet::<T>::prune_usages(&mut WeightMeter::new());
+		}
+
+		assert!(Usages::<T>::get(&key).is_none());
+	}
+
+	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
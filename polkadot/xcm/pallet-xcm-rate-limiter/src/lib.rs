@@ -0,0 +1,379 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet to cap the XCM messages processed from each origin.
+//!
+//! The pallet stores the quotas of messages and weight each origin may consume per period,
+//! configured by the `AdminOrigin`, and counts the messages processed in the current period. It
+//! implements [`RateLimits`] so that it can back the [`xcm_builder::RateLimitedProcessor`] of the
+//! message queue, or the [`xcm_builder::RateLimitedBarrier`] of the XCM executor together with
+//! the [`xcm_builder::RateLimitedWeightBounds`] weigher:
+//!
+//! ```ignore
+//! type MessageProcessor = RateLimitedProcessor<
+//! 	xcm_builder::ProcessXcmMessage<AggregateMessageOrigin, XcmExecutor<XcmConfig>, RuntimeCall>,
+//! 	XcmRateLimiter,
+//! >;
+//! ```
+//!
+//! Origins without a quota of their own are subject to the default quota, if any. Once an origin
+//! exhausted its quota, a [`Event::QuotaExhausted`] event is emitted and its messages wait in their
+//! queue until the next period, or are rejected by the barrier. Each throttled message is reported
+//! to [`Config::OnThrottled`], e.g. to count it in a metric. The usages of the past periods are
+//! pruned when the chain is idle.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use frame_support::{pallet_prelude::*, traits::EnsureOrigin, weights::WeightMeter};
+use frame_system::pallet_prelude::*;
+pub use pallet::*;
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{One, Saturating},
+	RuntimeDebug,
+};
+use xcm::prelude::*;
+use xcm_builder::RateLimits;
+
+const LOG_TARGET: &str = "xcm::rate-limiter";
+
+pub trait WeightInfo {
+	fn set_quota() -> Weight;
+	fn set_default_quota() -> Weight;
+	fn consume_quota() -> Weight;
+	fn on_idle_base() -> Weight;
+	fn prune_usage() -> Weight;
+}
+
+/// Test weight functions.
+pub struct TestWeightInfo;
+impl WeightInfo for TestWeightInfo {
+	fn set_quota() -> Weight {
+		Weight::from_parts(100_000_000, 0)
+	}
+
+	fn set_default_quota() -> Weight {
+		Weight::from_parts(100_000_000, 0)
+	}
+
+	fn consume_quota() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+
+	fn on_idle_base() -> Weight {
+		Weight::from_parts(1_000_000, 0)
+	}
+
+	fn prune_usage() -> Weight {
+		Weight::from_parts(1_000_000, 0)
+	}
+}
+
+/// The messages and weight an origin may consume per period.
+#[derive(
+	Clone,
+	Copy,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Eq,
+	PartialEq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct Quota {
+	/// The maximum number of messages.
+	pub max_messages: u32,
+	/// The maximum weight used by all the messages.
+	pub max_weight: Weight,
+}
+
+/// The messages processed from an origin in a period.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct Usage<BlockNumber> {
+	/// The first block of the period.
+	pub period_start: BlockNumber,
+	/// The number of messages processed.
+	pub messages: u32,
+	/// The weight used by the messages processed.
+	pub weight: Weight,
+}
+
+impl<BlockNumber> Usage<BlockNumber> {
+	fn new(period_start: BlockNumber) -> Self {
+		Self { period_start, messages: 0, weight: Weight::zero() }
+	}
+
+	fn exhausts(&self, quota: &Quota) -> bool {
+		self.messages >= quota.max_messages || self.weight.any_gte(quota.max_weight)
+	}
+
+	/// Returns the part of `quota` this usage exhausts, provided that it exhausts it.
+	fn exhausted_quota(&self, quota: &Quota) -> ExhaustedQuota {
+		if self.messages >= quota.max_messages {
+			ExhaustedQuota::Messages
+		} else {
+			ExhaustedQuota::Weight
+		}
+	}
+}
+
+/// The part of its quota an origin exhausted.
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub enum ExhaustedQuota {
+	/// The maximum number of messages.
+	Messages,
+	/// The maximum weight used by all the messages.
+	Weight,
+}
+
+/// Handler of the messages throttled because their origin exhausted its quota.
+pub trait OnThrottled {
+	/// A message of `origin` was throttled because `quota` is exhausted.
+	fn on_throttled(origin: &Location, quota: ExhaustedQuota);
+}
+
+impl OnThrottled for () {
+	fn on_throttled(_: &Location, _: ExhaustedQuota) {}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		#[allow(deprecated)]
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The origin allowed to configure the quotas.
+		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The number of blocks the quotas apply to, `1` for quotas per block.
+		///
+		/// The periods are aligned on the multiples of this number, e.g. an era.
+		#[pallet::constant]
+		type Period: Get<BlockNumberFor<Self>>;
+
+		/// Notified of the messages throttled by the quotas, e.g. to count them in a metric.
+		type OnThrottled: OnThrottled;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The quota of a location was set, or removed if `None`.
+		QuotaSet { location: Location, quota: Option<Quota> },
+		/// The default quota was set, or removed if `None`.
+		DefaultQuotaSet { quota: Option<Quota> },
+		/// A location exhausted its quota, its messages wait until the end of the period.
+		QuotaExhausted { location: Location, period_start: BlockNumberFor<T> },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The location can not be converted to the latest version.
+		BadLocation,
+	}
+
+	/// The quota of the locations without a quota of their own.
+	#[pallet::storage]
+	pub type DefaultQuota<T: Config> = StorageValue<_, Quota, OptionQuery>;
+
+	/// The quotas of the locations, in the latest version.
+	#[pallet::storage]
+	pub type Quotas<T: Config> = StorageMap<_, Blake2_128Concat, VersionedLocation, Quota>;
+
+	/// The messages processed from the locations with a quota, in the latest version.
+	///
+	/// Entries of past periods are overwritten by the next message of their location, or pruned
+	/// when the chain is idle.
+	#[pallet::storage]
+	pub type Usages<T: Config> =
+		StorageMap<_, Blake2_128Concat, VersionedLocation, Usage<BlockNumberFor<T>>>;
+
+	/// The first block of the last period whose past usages were all pruned.
+	#[pallet::storage]
+	pub type LastPrunedPeriod<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
+	/// The last location whose usage was visited by the ongoing pruning, if any.
+	#[pallet::storage]
+	pub type PruneCursor<T: Config> = StorageValue<_, VersionedLocation, OptionQuery>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_idle(_: BlockNumberFor<T>, limit: Weight) -> Weight {
+			let mut meter = WeightMeter::with_limit(limit);
+			if meter.try_consume(T::WeightInfo::on_idle_base()).is_err() {
+				return Weight::zero()
+			}
+			Self::prune_usages(&mut meter);
+			meter.consumed()
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set the quota of `location`, or remove it if `quota` is `None`.
+		///
+		/// - `origin`: Must be an origin specified by AdminOrigin.
+		/// - `location`: The origin of the messages the quota applies to.
+		/// - `quota`: The quota, `None` to apply the default quota instead.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::set_quota())]
+		pub fn set_quota(
+			origin: OriginFor<T>,
+			location: Box<VersionedLocation>,
+			quota: Option<Quota>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let location: Location = (*location).try_into().map_err(|()| {
+				tracing::debug!(
+					target: LOG_TARGET,
+					"Failed to convert versioned location",
+				);
+				Error::<T>::BadLocation
+			})?;
+			let key = VersionedLocation::from(location.clone());
+			match quota {
+				Some(quota) => Quotas::<T>::insert(&key, quota),
+				None => Quotas::<T>::remove(&key),
+			}
+			// The usage is counted again against the new quota.
+			Usages::<T>::remove(&key);
+			Self::deposit_event(Event::QuotaSet { location, quota });
+			Ok(())
+		}
+
+		/// Set the default quota, or remove it if `quota` is `None`.
+		///
+		/// - `origin`: Must be an origin specified by AdminOrigin.
+		/// - `quota`: The quota of the locations without a quota of their own, `None` to not limit
+		///   them.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::set_default_quota())]
+		pub fn set_default_quota(origin: OriginFor<T>, quota: Option<Quota>) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			DefaultQuota::<T>::set(quota);
+			Self::deposit_event(Event::DefaultQuotaSet { quota });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Returns the first block of the current period.
+	fn period_start() -> BlockNumberFor<T> {
+		let now = frame_system::Pallet::<T>::block_number();
+		let period = T::Period::get().max(One::one());
+		now.saturating_sub(now % period)
+	}
+
+	/// Returns the quota of `key` and its usage in the current period, if it has a quota.
+	fn quota_and_usage(key: &VersionedLocation) -> Option<(Quota, Usage<BlockNumberFor<T>>)> {
+		let quota = Quotas::<T>::get(key).or_else(DefaultQuota::<T>::get)?;
+		let period_start = Self::period_start();
+		let usage = Usages::<T>::get(key)
+			.filter(|usage| usage.period_start == period_start)
+			.unwrap_or_else(|| Usage::new(period_start));
+		Some((quota, usage))
+	}
+
+	/// Removes the usages of the past periods, as long as `meter` allows.
+	///
+	/// The pruning goes over all the usages once per period, and resumes from where it stopped
+	/// when `meter` ran out.
+	pub(crate) fn prune_usages(meter: &mut WeightMeter) {
+		let period_start = Self::period_start();
+		let mut cursor = PruneCursor::<T>::take();
+		if cursor.is_none() && LastPrunedPeriod::<T>::get() == period_start {
+			return
+		}
+
+		let mut usages = match &cursor {
+			Some(location) => Usages::<T>::iter_from(Usages::<T>::hashed_key_for(location)),
+			None => Usages::<T>::iter(),
+		};
+		while meter.try_consume(T::WeightInfo::prune_usage()).is_ok() {
+			let Some((location, usage)) = usages.next() else {
+				LastPrunedPeriod::<T>::put(period_start);
+				return
+			};
+			if usage.period_start < period_start {
+				Usages::<T>::remove(&location);
+			}
+			cursor = Some(location);
+		}
+		if let Some(location) = cursor {
+			PruneCursor::<T>::put(location);
+		}
+	}
+}
+
+impl<T: Config> RateLimits for Pallet<T> {
+	fn is_exhausted(origin: &Location) -> bool {
+		let key = VersionedLocation::from(origin.clone());
+		let Some((quota, usage)) = Self::quota_and_usage(&key) else { return false };
+		let exhausted = usage.exhausts(&quota);
+		if exhausted {
+			T::OnThrottled::on_throttled(origin, usage.exhausted_quota(&quota));
+		}
+		exhausted
+	}
+
+	fn consume(origin: &Location, weight: Weight) {
+		let key = VersionedLocation::from(origin.clone());
+		let Some((quota, mut usage)) = Self::quota_and_usage(&key) else { return };
+
+		usage.messages.saturating_inc();
+		usage.weight.saturating_accrue(weight);
+		if usage.exhausts(&quota) {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?origin, ?usage, ?quota,
+				"Quota exhausted",
+			);
+			Self::deposit_event(Event::QuotaExhausted {
+				location: origin.clone(),
+				period_start: usage.period_start,
+			});
+		}
+		Usages::<T>::insert(&key, usage);
+	}
+
+	fn weight() -> Weight {
+		T::WeightInfo::consume_quota()
+	}
+}
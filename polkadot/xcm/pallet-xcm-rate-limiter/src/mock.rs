@@ -0,0 +1,65 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Test environment for the rate limiter pallet.
+
+use crate::{self as pallet_xcm_rate_limiter, ExhaustedQuota, OnThrottled};
+use frame_support::{construct_runtime, derive_impl, parameter_types};
+use frame_system::EnsureRoot;
+use sp_runtime::BuildStorage;
+use xcm::prelude::*;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+construct_runtime!(
+	pub enum Test {
+		System: frame_system,
+		XcmRateLimiter: pallet_xcm_rate_limiter,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+}
+
+parameter_types! {
+	pub const Period: u64 = 10;
+	pub static Throttled: Vec<(Location, ExhaustedQuota)> = vec![];
+}
+
+/// Records the throttled messages in [`Throttled`].
+pub struct RecordThrottled;
+impl OnThrottled for RecordThrottled {
+	fn on_throttled(origin: &Location, quota: ExhaustedQuota) {
+		Throttled::mutate(|throttled| throttled.push((origin.clone(), quota)));
+	}
+}
+
+impl pallet_xcm_rate_limiter::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type AdminOrigin = EnsureRoot<u64>;
+	type Period = Period;
+	type OnThrottled = RecordThrottled;
+	type WeightInfo = pallet_xcm_rate_limiter::TestWeightInfo;
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,175 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tests for the rate limiter pallet.
+
+use crate::{
+	mock::*, DefaultQuota, Event, ExhaustedQuota, LastPrunedPeriod, PruneCursor, Quota, Quotas,
+	Usage, Usages, WeightInfo,
+};
+use frame_support::{assert_noop, assert_ok, traits::Hooks, weights::WeightMeter};
+use sp_runtime::{traits::Zero, DispatchError};
+use xcm::prelude::*;
+use xcm_builder::RateLimits;
+
+const QUOTA: Quota = Quota { max_messages: 2, max_weight: Weight::from_parts(100, 100) };
+
+fn sibling(id: u32) -> Location {
+	Location::new(1, [Parachain(id)])
+}
+
+/// Processes a message from `origin` using `weight`, if its quota is not exhausted.
+fn process(origin: &Location, weight: u64) -> bool {
+	if XcmRateLimiter::is_exhausted(origin) {
+		return false
+	}
+	XcmRateLimiter::consume(origin, Weight::from_parts(weight, weight));
+	true
+}
+
+#[test]
+fn quotas_are_set_by_admin_origin() {
+	new_test_ext().execute_with(|| {
+		let location = Box::new(VersionedLocation::from(sibling(1000)));
+		assert_noop!(
+			XcmRateLimiter::set_quota(RuntimeOrigin::signed(1), location.clone(), Some(QUOTA)),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			XcmRateLimiter::set_default_quota(RuntimeOrigin::signed(1), Some(QUOTA)),
+			DispatchError::BadOrigin,
+		);
+
+		assert_ok!(XcmRateLimiter::set_quota(RuntimeOrigin::root(), location.clone(), Some(QUOTA)));
+		assert_eq!(Quotas::<Test>::get(VersionedLocation::from(sibling(1000))), Some(QUOTA));
+		System::assert_last_event(
+			Event::QuotaSet { location: sibling(1000), quota: Some(QUOTA) }.into(),
+		);
+
+		assert_ok!(XcmRateLimiter::set_quota(RuntimeOrigin::root(), location, None));
+		assert_eq!(Quotas::<Test>::get(VersionedLocation::from(sibling(1000))), None);
+
+		assert_ok!(XcmRateLimiter::set_default_quota(RuntimeOrigin::root(), Some(QUOTA)));
+		assert_eq!(DefaultQuota::<Test>::get(), Some(QUOTA));
+		System::assert_last_event(Event::DefaultQuotaSet { quota: Some(QUOTA) }.into());
+	});
+}
+
+#[test]
+fn locations_without_quota_are_not_limited() {
+	new_test_ext().execute_with(|| {
+		for _ in 0..10 {
+			assert!(process(&sibling(1000), 1_000));
+		}
+		assert_eq!(Usages::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn locations_exhausting_their_quota_wait_for_the_next_period() {
+	new_test_ext().execute_with(|| {
+		let location = Box::new(VersionedLocation::from(sibling(1000)));
+		assert_ok!(XcmRateLimiter::set_quota(RuntimeOrigin::root(), location, Some(QUOTA)));
+
+		assert!(process(&sibling(1000), 10));
+		System::reset_events();
+		assert!(process(&sibling(1000), 10));
+		// The quota of messages is exhausted.
+		System::assert_last_event(
+			Event::QuotaExhausted { location: sibling(1000), period_start: 0 }.into(),
+		);
+		assert!(Throttled::get().is_empty());
+		assert!(!process(&sibling(1000), 10));
+		assert_eq!(Throttled::get(), vec![(sibling(1000), ExhaustedQuota::Messages)]);
+
+		// Other locations are not limited.
+		assert!(process(&sibling(2000), 10));
+
+		// The quota is renewed in the next period.
+		System::set_block_number(10);
+		assert!(process(&sibling(1000), 10));
+		let usage = Usages::<Test>::get(VersionedLocation::from(sibling(1000))).unwrap();
+		assert_eq!((usage.period_start, usage.messages), (10, 1));
+	});
+}
+
+#[test]
+fn locations_exhausting_their_weight_quota_wait_for_the_next_period() {
+	new_test_ext().execute_with(|| {
+		let location = Box::new(VersionedLocation::from(sibling(1000)));
+		assert_ok!(XcmRateLimiter::set_quota(RuntimeOrigin::root(), location, Some(QUOTA)));
+
+		// The message used more weight than the quota, the next ones wait.
+		assert!(process(&sibling(1000), 120));
+		assert!(!process(&sibling(1000), 1));
+		assert_eq!(Throttled::get(), vec![(sibling(1000), ExhaustedQuota::Weight)]);
+		let usage = Usages::<Test>::get(VersionedLocation::from(sibling(1000))).unwrap();
+		assert_eq!((usage.messages, usage.weight), (1, Weight::from_parts(120, 120)));
+	});
+}
+
+#[test]
+fn default_quota_applies_to_locations_without_quota() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(XcmRateLimiter::set_default_quota(RuntimeOrigin::root(), Some(QUOTA)));
+		let unlimited = Quota { max_messages: u32::MAX, max_weight: Weight::MAX };
+		let location = Box::new(VersionedLocation::from(sibling(1000)));
+		assert_ok!(XcmRateLimiter::set_quota(RuntimeOrigin::root(), location, Some(unlimited)));
+
+		for _ in 0..3 {
+			assert!(process(&sibling(1000), 10));
+		}
+		assert!(process(&sibling(2000), 10));
+		assert!(process(&sibling(2000), 10));
+		assert!(!process(&sibling(2000), 10));
+	});
+}
+
+#[test]
+fn usages_of_past_periods_are_pruned_when_idle() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(XcmRateLimiter::set_default_quota(RuntimeOrigin::root(), Some(QUOTA)));
+		for id in 0..4 {
+			assert!(process(&sibling(id), 10));
+		}
+		System::set_block_number(10);
+		assert!(process(&sibling(0), 10));
+
+		// Not enough weight to prune all the usages, the pruning resumes in the next block.
+		let weight = <Test as crate::Config>::WeightInfo::on_idle_base() +
+			<Test as crate::Config>::WeightInfo::prune_usage() * 2;
+		assert_eq!(XcmRateLimiter::on_idle(10, weight), weight);
+		assert!(PruneCursor::<Test>::get().is_some());
+		assert_eq!(LastPrunedPeriod::<Test>::get(), 0);
+
+		XcmRateLimiter::on_idle(11, Weight::MAX);
+		assert!(PruneCursor::<Test>::get().is_none());
+		assert_eq!(LastPrunedPeriod::<Test>::get(), 10);
+		// Only the usage of the current period is left.
+		assert_eq!(
+			Usages::<Test>::iter().collect::<Vec<_>>(),
+			vec![(
+				VersionedLocation::from(sibling(0)),
+				Usage { period_start: 10, messages: 1, weight: Weight::from_parts(10, 10) },
+			)],
+		);
+
+		// Nothing left to prune until the next period.
+		let mut meter = WeightMeter::new();
+		XcmRateLimiter::prune_usages(&mut meter);
+		assert!(meter.consumed().is_zero());
+	});
+}
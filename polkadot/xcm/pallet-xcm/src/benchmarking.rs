@@ -579,6 +579,8 @@ mod benchmarks {
 		let claim_location = T::ExecuteXcmOrigin::try_origin(claim_origin.clone().into())
 			.map_err(|_| BenchmarkError::Override(BenchmarkResult::from_weight(Weight::MAX)))?;
 		let asset: Asset = T::get_asset();
+		// The worst case is when the trap is indexed and its entry must be removed.
+		AssetTrapIndexEnabled::<T>::set(true);
 		// Trap assets for claiming later
 		crate::Pallet::<T>::drop_assets(
 			&claim_location,
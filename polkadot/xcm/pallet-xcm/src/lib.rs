@@ -65,6 +65,7 @@ use xcm_builder::{
 	QueryControllerWeightInfo, SendController, SendControllerWeightInfo,
 };
 use xcm_executor::{
+	trace::InstructionTrace,
 	traits::{
 		AssetTransferError, CheckSuspension, ClaimAssets, ConvertLocation, ConvertOrigin,
 		DropAssets, EventEmitter, FeeManager, FeeReason, MatchesFungible, OnResponse, Properties,
@@ -75,8 +76,11 @@ use xcm_executor::{
 };
 use xcm_runtime_apis::{
 	authorized_aliases::{Error as AuthorizedAliasersApiError, OriginAliaser},
-	dry_run::{CallDryRunEffects, Error as XcmDryRunApiError, XcmDryRunEffects},
+	dry_run::{
+		CallDryRunEffects, Error as XcmDryRunApiError, TracedDryRunEffects, XcmDryRunEffects,
+	},
 	fees::Error as XcmPaymentApiError,
+	trapped_assets::{Error as TrappedAssetsApiError, TrappedAssets},
 	trusted_query::Error as TrustedQueryApiError,
 };
 
@@ -737,6 +741,9 @@ pub mod pallet {
 		/// instruction that caused the error.
 		#[codec(index = 28)]
 		LocalExecutionIncompleteWithError { index: InstructionIndex, error: ExecutionError },
+		/// No assets trapped by the origin were found with the given hash.
+		#[codec(index = 29)]
+		TrappedAssetsNotFound,
 	}
 
 	impl<T: Config> From<SendError> for Error<T> {
@@ -908,6 +915,17 @@ pub mod pallet {
 		}
 	}
 
+	/// The record of assets trapped by an origin, see [`TrappedAssetsOf`].
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct TrappedAssetsRecord<BlockNumber> {
+		/// The trapped assets, in the version they were trapped with.
+		pub assets: VersionedAssets,
+		/// The block in which the assets were last trapped.
+		pub block_number: BlockNumber,
+		/// The topic of the message that last trapped the assets, or its id if it had none.
+		pub topic: XcmHash,
+	}
+
 	/// Fungible assets which we know are locked on a remote chain.
 	#[pallet::storage]
 	pub(super) type RemoteLockedFungibles<T: Config> = StorageNMap<
@@ -966,6 +984,35 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	/// Whether the asset traps are indexed by origin in [`TrappedAssetsOf`].
+	///
+	/// The value is absent if the index was never enabled, in which case there is no entry of
+	/// [`TrappedAssetsOf`] to clean up when claiming assets.
+	#[pallet::storage]
+	pub(super) type AssetTrapIndexEnabled<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// The trapped assets, by origin, see [`AssetTraps`].
+	///
+	/// Keys are the origin, in the latest version, and the hash of the trap. Only the assets
+	/// trapped while [`AssetTrapIndexEnabled`] is set are indexed, up to
+	/// [`MAX_TRAPPED_ASSETS_PER_ORIGIN`] traps per origin. The entries are removed once all the
+	/// assets of the trap were claimed.
+	#[pallet::storage]
+	pub(super) type TrappedAssetsOf<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		VersionedLocation,
+		Identity,
+		H256,
+		TrappedAssetsRecord<BlockNumberFor<T>>,
+		OptionQuery,
+	>;
+
+	/// The number of entries of each origin in [`TrappedAssetsOf`].
+	#[pallet::storage]
+	pub(super) type TrappedAssetsCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, VersionedLocation, u32, ValueQuery>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
 		#[serde(skip)]
@@ -1471,7 +1518,7 @@ pub mod pallet {
 			weight_limit: WeightLimit,
 		) -> DispatchResult {
 			let origin = T::ExecuteXcmOrigin::ensure_origin(origin)?;
-			let dest = (*dest).try_into().map_err(|()| {
+			let dest: Location = (*dest).try_into().map_err(|()| {
 				tracing::debug!(
 					target: "xcm::pallet_xcm::transfer_assets",
 					"Failed to convert destination VersionedLocation",
@@ -1497,33 +1544,15 @@ pub mod pallet {
 				?origin, ?dest, ?beneficiary, ?assets, ?fee_asset_item, ?weight_limit,
 			);
 
-			ensure!(assets.len() <= MAX_ASSETS_FOR_TRANSFER, Error::<T>::TooManyAssets);
-			let assets = assets.into_inner();
-			let fee_asset_item = fee_asset_item as usize;
-			// Find transfer types for fee and non-fee assets.
-			let (fees_transfer_type, assets_transfer_type) =
-				Self::find_fee_and_assets_transfer_types(&assets, fee_asset_item, &dest)?;
-
-			// We check for network native asset reserve transfers in preparation for the Asset Hub
-			// Migration. This check will be removed after the migration and the determined
-			// reserve location adjusted accordingly. For more information, see https://github.com/paritytech/polkadot-sdk/issues/9054.
-			Self::ensure_network_asset_reserve_transfer_allowed(
-				&assets,
-				fee_asset_item,
-				&assets_transfer_type,
-				&fees_transfer_type,
-			)?;
-
-			Self::do_transfer_assets(
-				origin,
-				dest,
-				Either::Left(beneficiary),
-				assets,
-				assets_transfer_type,
-				fee_asset_item,
-				fees_transfer_type,
+			let (local_xcm, remote_xcm) = Self::transfer_assets_programs(
+				origin.clone(),
+				dest.clone(),
+				beneficiary,
+				assets.into_inner(),
+				fee_asset_item as usize,
 				weight_limit,
-			)
+			)?;
+			Self::execute_xcm_transfer(origin, dest, local_xcm, remote_xcm)
 		}
 
 		/// Claims assets trapped on this pallet because of leftover assets during XCM execution.
@@ -1874,12 +1903,62 @@ pub mod pallet {
 				Err(Error::<T>::AliasNotFound.into())
 			}
 		}
+
+		/// Start or stop indexing the trapped assets by origin.
+		///
+		/// The index allows to list the assets trapped by an origin, see
+		/// [`Pallet::trapped_assets`], and to claim them with [`Pallet::claim_trapped_assets`].
+		/// Stopping the indexing keeps the existing entries.
+		///
+		/// - `origin`: Must be an origin specified by AdminOrigin.
+		/// - `enabled`: `true` to index the assets trapped from now on, `false` to stop.
+		#[pallet::call_index(17)]
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn force_asset_trap_index(origin: OriginFor<T>, enabled: bool) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			AssetTrapIndexEnabled::<T>::set(enabled);
+			Ok(())
+		}
+
+		/// Claims assets trapped by the caller, identified by the hash of their trap.
+		///
+		/// Like [`Pallet::claim_assets`], but the trapped assets are found in the index of the
+		/// trapped assets, see [`Pallet::force_asset_trap_index`].
+		///
+		/// - `origin`: The origin that trapped the assets.
+		/// - `hash`: The hash of the trap, as in the `AssetsTrapped` event.
+		/// - `beneficiary`: The location/account where the claimed assets will be deposited.
+		#[pallet::call_index(18)]
+		#[pallet::weight(T::WeightInfo::claim_assets().saturating_add(T::DbWeight::get().reads(1)))]
+		pub fn claim_trapped_assets(
+			origin: OriginFor<T>,
+			hash: H256,
+			beneficiary: Box<VersionedLocation>,
+		) -> DispatchResult {
+			let origin_location = T::ExecuteXcmOrigin::ensure_origin(origin.clone())?;
+			let record =
+				TrappedAssetsOf::<T>::get(VersionedLocation::from(origin_location.clone()), hash)
+					.ok_or_else(|| {
+					tracing::debug!(
+						target: "xcm::pallet_xcm::claim_trapped_assets",
+						?origin_location, ?hash, "No trapped assets found",
+					);
+					Error::<T>::TrappedAssetsNotFound
+				})?;
+			Self::claim_assets(origin, Box::new(record.assets), beneficiary)
+		}
 	}
 }
 
 /// The maximum number of distinct assets allowed to be transferred in a single helper extrinsic.
 const MAX_ASSETS_FOR_TRANSFER: usize = 2;
 
+/// The maximum number of traps indexed per origin in [`TrappedAssetsOf`].
+///
+/// The further traps of an origin are not indexed, they can still be claimed with
+/// [`Pallet::claim_assets`].
+pub const MAX_TRAPPED_ASSETS_PER_ORIGIN: u32 = 32;
+
 /// Specify how assets used for fees are handled during asset transfers.
 #[derive(Clone, PartialEq)]
 enum FeesHandling<T: Config> {
@@ -2161,16 +2240,78 @@ impl<T: Config> Pallet<T> {
 		Self::execute_xcm_transfer(origin_location, dest, local_xcm, remote_xcm)
 	}
 
+	/// Build the local and remote XCM programs of [`Pallet::transfer_assets`], without executing
+	/// them.
+	///
+	/// The local program is executed by `origin`, the remote one, if any, is sent to `dest`.
+	pub fn transfer_assets_programs(
+		origin: Location,
+		dest: Location,
+		beneficiary: Location,
+		assets: Vec<Asset>,
+		fee_asset_item: usize,
+		weight_limit: WeightLimit,
+	) -> Result<(Xcm<<T as Config>::RuntimeCall>, Option<Xcm<()>>), DispatchError> {
+		ensure!(assets.len() <= MAX_ASSETS_FOR_TRANSFER, Error::<T>::TooManyAssets);
+		// Find transfer types for fee and non-fee assets.
+		let (fees_transfer_type, assets_transfer_type) =
+			Self::find_fee_and_assets_transfer_types(&assets, fee_asset_item, &dest)?;
+
+		// We check for network native asset reserve transfers in preparation for the Asset Hub
+		// Migration. This check will be removed after the migration and the determined
+		// reserve location adjusted accordingly. For more information, see https://github.com/paritytech/polkadot-sdk/issues/9054.
+		Self::ensure_network_asset_reserve_transfer_allowed(
+			&assets,
+			fee_asset_item,
+			&assets_transfer_type,
+			&fees_transfer_type,
+		)?;
+
+		Self::build_transfer_assets_programs(
+			origin,
+			dest,
+			Either::Left(beneficiary),
+			assets,
+			assets_transfer_type,
+			fee_asset_item,
+			fees_transfer_type,
+			weight_limit,
+		)
+	}
+
 	fn do_transfer_assets(
 		origin: Location,
 		dest: Location,
 		beneficiary: Either<Location, Xcm<()>>,
-		mut assets: Vec<Asset>,
+		assets: Vec<Asset>,
 		assets_transfer_type: TransferType,
 		fee_asset_index: usize,
 		fees_transfer_type: TransferType,
 		weight_limit: WeightLimit,
 	) -> DispatchResult {
+		let (local_xcm, remote_xcm) = Self::build_transfer_assets_programs(
+			origin.clone(),
+			dest.clone(),
+			beneficiary,
+			assets,
+			assets_transfer_type,
+			fee_asset_index,
+			fees_transfer_type,
+			weight_limit,
+		)?;
+		Self::execute_xcm_transfer(origin, dest, local_xcm, remote_xcm)
+	}
+
+	fn build_transfer_assets_programs(
+		origin: Location,
+		dest: Location,
+		beneficiary: Either<Location, Xcm<()>>,
+		mut assets: Vec<Asset>,
+		assets_transfer_type: TransferType,
+		fee_asset_index: usize,
+		fees_transfer_type: TransferType,
+		weight_limit: WeightLimit,
+	) -> Result<(Xcm<<T as Config>::RuntimeCall>, Option<Xcm<()>>), DispatchError> {
 		// local and remote XCM programs to potentially handle fees separately
 		let fees = if fees_transfer_type == assets_transfer_type {
 			let fees = assets.get(fee_asset_index).ok_or(Error::<T>::Empty)?.clone();
@@ -2215,16 +2356,16 @@ impl<T: Config> Pallet<T> {
 			FeesHandling::Separate { local_xcm, remote_xcm }
 		};
 
-		let (local_xcm, remote_xcm) = Self::build_xcm_transfer_type(
-			origin.clone(),
-			dest.clone(),
+		Self::build_xcm_transfer_type(
+			origin,
+			dest,
 			beneficiary,
 			assets,
 			assets_transfer_type,
 			fees,
 			weight_limit,
-		)?;
-		Self::execute_xcm_transfer(origin, dest, local_xcm, remote_xcm)
+		)
+		.map_err(Into::into)
 	}
 
 	fn build_xcm_transfer_type(
@@ -3149,6 +3290,85 @@ impl<T: Config> Pallet<T> {
 		Ok(XcmDryRunEffects { forwarded_xcms, emitted_events: events, execution_result: result })
 	}
 
+	/// Dry-runs `call` with the given `origin`, like [`Self::dry_run_call`], tracing the XCM
+	/// instructions executed.
+	///
+	/// Meant to be used in the `xcm_runtime_apis::dry_run::DryRunApi` runtime API.
+	pub fn dry_run_call_with_trace<Runtime, Router, OriginCaller, RuntimeCall>(
+		origin: OriginCaller,
+		call: RuntimeCall,
+		result_xcms_version: XcmVersion,
+	) -> Result<
+		TracedDryRunEffects<CallDryRunEffects<<Runtime as frame_system::Config>::RuntimeEvent>>,
+		XcmDryRunApiError,
+	>
+	where
+		Runtime: crate::Config,
+		Router: InspectMessageQueues,
+		RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo>,
+		<RuntimeCall as Dispatchable>::RuntimeOrigin: From<OriginCaller>,
+	{
+		let (effects, trace) = xcm_executor::trace::record(|| {
+			Self::dry_run_call::<Runtime, Router, OriginCaller, RuntimeCall>(
+				origin,
+				call,
+				result_xcms_version,
+			)
+		});
+		Ok(TracedDryRunEffects {
+			effects: effects?,
+			trace: Self::convert_trace(result_xcms_version, trace)?,
+		})
+	}
+
+	/// Dry-runs `xcm` with the given `origin_location`, like [`Self::dry_run_xcm`], tracing the
+	/// instructions executed.
+	///
+	/// Meant to be used in the `xcm_runtime_apis::dry_run::DryRunApi` runtime API.
+	pub fn dry_run_xcm_with_trace<
+		Runtime,
+		Router,
+		RuntimeCall: Decode + GetDispatchInfo,
+		XcmConfig,
+	>(
+		origin_location: VersionedLocation,
+		xcm: VersionedXcm<RuntimeCall>,
+	) -> Result<
+		TracedDryRunEffects<XcmDryRunEffects<<Runtime as frame_system::Config>::RuntimeEvent>>,
+		XcmDryRunApiError,
+	>
+	where
+		Runtime: frame_system::Config,
+		Router: InspectMessageQueues,
+		XcmConfig: xcm_executor::Config<RuntimeCall = RuntimeCall>,
+	{
+		let xcm_version = xcm.identify_version();
+		let (effects, trace) = xcm_executor::trace::record(|| {
+			Self::dry_run_xcm::<Runtime, Router, RuntimeCall, XcmConfig>(origin_location, xcm)
+		});
+		Ok(TracedDryRunEffects {
+			effects: effects?,
+			trace: Self::convert_trace(xcm_version, trace)?,
+		})
+	}
+
+	fn convert_trace(
+		xcm_version: XcmVersion,
+		trace: Vec<InstructionTrace>,
+	) -> Result<Vec<InstructionTrace>, XcmDryRunApiError> {
+		trace
+			.into_iter()
+			.map(|instruction| instruction.into_version(xcm_version))
+			.collect::<Result<Vec<_>, ()>>()
+			.map_err(|()| {
+				tracing::debug!(
+					target: "xcm::pallet_xcm::convert_trace",
+					"Failed to convert the trace to requested version",
+				);
+				XcmDryRunApiError::VersionedConversionFailed
+			})
+	}
+
 	fn convert_xcms(
 		xcm_version: XcmVersion,
 		xcms: Vec<VersionedXcm<()>>,
@@ -3376,6 +3596,73 @@ impl<T: Config> Pallet<T> {
 			.unwrap_or_default())
 	}
 
+	/// Returns the assets trapped by `origin`, see [`TrappedAssetsOf`].
+	///
+	/// `origin` must be the exact location the assets were trapped from.
+	pub fn trapped_assets(
+		origin: VersionedLocation,
+	) -> Result<Vec<TrappedAssets>, TrappedAssetsApiError> {
+		// storage entries are always latest version
+		let origin: VersionedLocation = origin.into_version(XCM_VERSION).map_err(|e| {
+			tracing::debug!(
+				target: "xcm::pallet_xcm::trapped_assets",
+				?e, "Failed to convert versioned location",
+			);
+			TrappedAssetsApiError::LocationVersionConversionFailed
+		})?;
+		Ok(TrappedAssetsOf::<T>::iter_prefix(&origin)
+			.map(|(hash, record)| TrappedAssets {
+				hash,
+				assets: record.assets,
+				count: AssetTraps::<T>::get(hash),
+				block_number: record.block_number.saturated_into(),
+				topic: record.topic,
+			})
+			.collect())
+	}
+
+	/// Records the trap `hash` of `origin` in [`TrappedAssetsOf`].
+	///
+	/// The trap is not indexed if `origin` already has [`MAX_TRAPPED_ASSETS_PER_ORIGIN`] traps
+	/// indexed.
+	fn index_trapped_assets(
+		origin: &Location,
+		hash: H256,
+		assets: &VersionedAssets,
+		context: &XcmContext,
+	) {
+		let key = VersionedLocation::from(origin.clone());
+		let record = TrappedAssetsRecord {
+			assets: assets.clone(),
+			block_number: frame_system::Pallet::<T>::current_block_number(),
+			topic: context.topic_or_message_id(),
+		};
+		TrappedAssetsOf::<T>::mutate(&key, hash, |entry| {
+			if entry.is_none() {
+				let count = TrappedAssetsCount::<T>::get(&key);
+				if count >= MAX_TRAPPED_ASSETS_PER_ORIGIN {
+					tracing::debug!(
+						target: "xcm::pallet_xcm::drop_assets",
+						?origin, ?hash, "Too many trapped assets indexed, not indexing the trap",
+					);
+					return
+				}
+				TrappedAssetsCount::<T>::insert(&key, count + 1);
+			}
+			*entry = Some(record);
+		});
+	}
+
+	/// Removes the trap `hash` of `origin` from [`TrappedAssetsOf`], if it is indexed.
+	fn unindex_trapped_assets(origin: &Location, hash: H256) {
+		let key = VersionedLocation::from(origin.clone());
+		if TrappedAssetsOf::<T>::take(&key, hash).is_some() {
+			TrappedAssetsCount::<T>::mutate_exists(&key, |count| {
+				*count = count.and_then(|count| count.checked_sub(1)).filter(|count| *count > 0);
+			});
+		}
+	}
+
 	/// Given an `origin` and a `target`, returns if the `origin` location was added by `target` as
 	/// an authorized aliaser.
 	///
@@ -3874,18 +4161,26 @@ impl<T: Config> VersionChangeNotifier for Pallet<T> {
 }
 
 impl<T: Config> DropAssets for Pallet<T> {
-	fn drop_assets(origin: &Location, assets: AssetsInHolding, _context: &XcmContext) -> Weight {
+	fn drop_assets(origin: &Location, assets: AssetsInHolding, context: &XcmContext) -> Weight {
 		if assets.is_empty() {
 			return Weight::zero()
 		}
 		let versioned = VersionedAssets::from(Assets::from(assets));
 		let hash = BlakeTwo256::hash_of(&(&origin, &versioned));
 		AssetTraps::<T>::mutate(hash, |n| *n += 1);
+		let indexed = AssetTrapIndexEnabled::<T>::get();
+		if indexed {
+			Self::index_trapped_assets(origin, hash, &versioned, context);
+		}
 		Self::deposit_event(Event::AssetsTrapped {
 			hash,
 			origin: origin.clone(),
 			assets: versioned,
 		});
+		if indexed {
+			// The index flag, the entry and the count of the origin.
+			return T::DbWeight::get().reads_writes(3, 2)
+		}
 		// TODO #3735: Put the real weight in there.
 		Weight::zero()
 	}
@@ -3911,7 +4206,12 @@ impl<T: Config> ClaimAssets for Pallet<T> {
 		let hash = BlakeTwo256::hash_of(&(origin.clone(), versioned.clone()));
 		match AssetTraps::<T>::get(hash) {
 			0 => return false,
-			1 => AssetTraps::<T>::remove(hash),
+			1 => {
+				AssetTraps::<T>::remove(hash);
+				if AssetTrapIndexEnabled::<T>::exists() {
+					Self::unindex_trapped_assets(origin, hash);
+				}
+			},
 			n => AssetTraps::<T>::insert(hash, n - 1),
 		}
 		Self::deposit_event(Event::AssetsClaimed {
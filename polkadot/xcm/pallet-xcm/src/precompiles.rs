@@ -13,20 +13,32 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::{Config, VersionedLocation, VersionedXcm, Weight, WeightInfo};
-use alloc::vec::Vec;
-use codec::{DecodeAll, DecodeLimit};
+use crate::{
+	Config, VersionedAssetId, VersionedAssets, VersionedLocation, VersionedXcm, Weight, WeightInfo,
+};
+use alloc::{boxed::Box, vec::Vec};
+use codec::{DecodeAll, DecodeLimit, Encode};
 use core::{fmt, marker::PhantomData, num::NonZero};
+use frame_support::{
+	dispatch::DispatchResultWithPostInfo,
+	storage::{with_transaction, TransactionOutcome},
+	traits::EnsureOrigin,
+};
 use pallet_revive::{
 	precompiles::{
-		alloy::{self, sol_types::SolValue},
+		alloy::{
+			self,
+			primitives::{Address, FixedBytes, U256},
+			sol_types::{sol_data, SolType, SolValue},
+		},
 		AddressMatcher, Error, Ext, Precompile,
 	},
 	DispatchInfo, Origin,
 };
+use sp_runtime::{traits::Zero, DispatchError};
 use tracing::error;
 use xcm::{v5, IdentifyVersion, MAX_XCM_DECODE_DEPTH};
-use xcm_executor::traits::WeightBounds;
+use xcm_executor::traits::{TransferType, WeightBounds};
 
 alloy::sol!("src/precompiles/IXcm.sol");
 use IXcm::IXcmCalls;
@@ -47,6 +59,148 @@ fn ensure_xcm_version<V: IdentifyVersion>(input: &V) -> Result<(), Error> {
 	Ok(())
 }
 
+/// Convert an ABI junction to an XCM junction.
+fn junction(junction: &IXcm::Junction) -> Result<v5::Junction, Error> {
+	use IXcm::JunctionType;
+	let data = &junction.data[..];
+	let invalid = |error: alloy::sol_types::Error| revert(&error, "XCM: Invalid junction data");
+	Ok(match junction.kind {
+		JunctionType::Parachain => v5::Junction::Parachain(u32::abi_decode(data).map_err(invalid)?),
+		JunctionType::AccountId32 => v5::Junction::AccountId32 {
+			network: None,
+			id: FixedBytes::<32>::abi_decode(data).map_err(invalid)?.0,
+		},
+		JunctionType::AccountIndex64 => v5::Junction::AccountIndex64 {
+			network: None,
+			index: u64::abi_decode(data).map_err(invalid)?,
+		},
+		JunctionType::AccountKey20 => v5::Junction::AccountKey20 {
+			network: None,
+			key: Address::abi_decode(data).map_err(invalid)?.0 .0,
+		},
+		JunctionType::PalletInstance =>
+			v5::Junction::PalletInstance(sol_data::Uint::<8>::abi_decode(data).map_err(invalid)?),
+		JunctionType::GeneralIndex =>
+			v5::Junction::GeneralIndex(u128::abi_decode(data).map_err(invalid)?),
+		JunctionType::GeneralKey => {
+			if data.len() > 32 {
+				return Err(Error::Revert("XCM: General key longer than 32 bytes".into()));
+			}
+			let mut key = [0u8; 32];
+			key[..data.len()].copy_from_slice(data);
+			v5::Junction::GeneralKey { length: data.len() as u8, data: key }
+		},
+		JunctionType::OnlyChild => v5::Junction::OnlyChild,
+		JunctionType::Plurality => {
+			let (id, part) = <(v5::BodyId, v5::BodyPart)>::decode_all(&mut &data[..])
+				.map_err(|error| revert(&error, "XCM: Invalid plurality"))?;
+			v5::Junction::Plurality { id, part }
+		},
+		JunctionType::GlobalConsensus => v5::Junction::GlobalConsensus(
+			v5::NetworkId::decode_all(&mut &data[..])
+				.map_err(|error| revert(&error, "XCM: Invalid network"))?,
+		),
+		_ => return Err(Error::Revert("XCM: Invalid junction type".into())),
+	})
+}
+
+/// Convert an XCM junction to an ABI junction.
+fn abi_junction(junction: &v5::Junction) -> Result<IXcm::Junction, Error> {
+	use IXcm::JunctionType;
+	let (kind, data) = match junction {
+		v5::Junction::Parachain(id) => (JunctionType::Parachain, id.abi_encode()),
+		v5::Junction::AccountId32 { network: None, id } =>
+			(JunctionType::AccountId32, FixedBytes(*id).abi_encode()),
+		v5::Junction::AccountIndex64 { network: None, index } =>
+			(JunctionType::AccountIndex64, index.abi_encode()),
+		v5::Junction::AccountKey20 { network: None, key } =>
+			(JunctionType::AccountKey20, Address::from(*key).abi_encode()),
+		v5::Junction::PalletInstance(index) =>
+			(JunctionType::PalletInstance, sol_data::Uint::<8>::abi_encode(index)),
+		v5::Junction::GeneralIndex(index) => (JunctionType::GeneralIndex, index.abi_encode()),
+		v5::Junction::GeneralKey { length, data } =>
+			(JunctionType::GeneralKey, data[..(*length as usize).min(32)].to_vec()),
+		v5::Junction::OnlyChild => (JunctionType::OnlyChild, Vec::new()),
+		v5::Junction::Plurality { id, part } => (JunctionType::Plurality, (id, part).encode()),
+		v5::Junction::GlobalConsensus(network) => (JunctionType::GlobalConsensus, network.encode()),
+		_ => return Err(revert(junction, "XCM: Junction can't be represented in the ABI")),
+	};
+	Ok(IXcm::Junction { kind, data: data.into() })
+}
+
+/// Convert an ABI location to an XCM location.
+fn location(location: &IXcm::Location) -> Result<v5::Location, Error> {
+	let mut result = v5::Location::new(location.parents, v5::Junctions::Here);
+	for item in &location.interior {
+		result
+			.push_interior(junction(item)?)
+			.map_err(|error| revert(&error, "XCM: Too many junctions"))?;
+	}
+	Ok(result)
+}
+
+/// Convert an XCM location to an ABI location.
+fn abi_location(location: &v5::Location) -> Result<IXcm::Location, Error> {
+	Ok(IXcm::Location {
+		parents: location.parent_count(),
+		interior: location.interior().iter().map(abi_junction).collect::<Result<_, _>>()?,
+	})
+}
+
+/// Convert ABI assets to XCM assets.
+fn assets(assets: &[IXcm::Asset]) -> Result<v5::Assets, Error> {
+	let assets = assets
+		.iter()
+		.map(|asset| {
+			let amount = u128::try_from(asset.amount)
+				.map_err(|error| revert(&error, "XCM: Asset amount too large"))?;
+			Ok(v5::Asset {
+				id: v5::AssetId(location(&asset.id)?),
+				fun: v5::Fungibility::Fungible(amount),
+			})
+		})
+		.collect::<Result<Vec<_>, Error>>()?;
+	v5::Assets::from_sorted_and_deduplicated(assets)
+		.map_err(|error| revert(&error, "XCM: Assets must be sorted and deduplicated"))
+}
+
+/// Convert XCM fungible assets to ABI assets.
+fn abi_assets(assets: &v5::Assets) -> Result<Vec<IXcm::Asset>, Error> {
+	assets
+		.inner()
+		.iter()
+		.map(|asset| match asset.fun {
+			v5::Fungibility::Fungible(amount) =>
+				Ok(IXcm::Asset { id: abi_location(&asset.id.0)?, amount: U256::from(amount) }),
+			v5::Fungibility::NonFungible(_) =>
+				Err(revert(asset, "XCM: Non fungible assets are not supported")),
+		})
+		.collect()
+}
+
+/// Convert an ABI weight limit, where a zero weight is unlimited.
+fn weight_limit(weight: &IXcm::Weight) -> v5::WeightLimit {
+	match Weight::from_parts(weight.refTime, weight.proofSize) {
+		weight if weight.is_zero() => v5::WeightLimit::Unlimited,
+		weight => v5::WeightLimit::Limited(weight),
+	}
+}
+
+/// Convert an ABI transfer type, using `reserve` for remote reserve transfers.
+fn transfer_type(
+	transfer_type: IXcm::TransferType,
+	reserve: &IXcm::Location,
+) -> Result<TransferType, Error> {
+	Ok(match transfer_type {
+		IXcm::TransferType::Teleport => TransferType::Teleport,
+		IXcm::TransferType::LocalReserve => TransferType::LocalReserve,
+		IXcm::TransferType::DestinationReserve => TransferType::DestinationReserve,
+		IXcm::TransferType::RemoteReserve =>
+			TransferType::RemoteReserve(VersionedLocation::from(location(reserve)?)),
+		_ => return Err(Error::Revert("XCM: Invalid transfer type".into())),
+	})
+}
+
 pub struct XcmPrecompile<T>(PhantomData<T>);
 
 impl<Runtime> Precompile for XcmPrecompile<Runtime>
@@ -164,6 +318,197 @@ where
 
 				Ok(final_weight.abi_encode())
 			},
+			IXcmCalls::transferAssets(IXcm::transferAssetsCall {
+				destination,
+				beneficiary,
+				assets: transferred_assets,
+				feeAssetItem,
+				weightLimit,
+			}) => {
+				let weight_to_charge = <Runtime as Config>::WeightInfo::transfer_assets();
+				let charged_amount = env.charge(weight_to_charge)?;
+
+				let result: DispatchResultWithPostInfo = crate::Pallet::<Runtime>::transfer_assets(
+					frame_origin,
+					Box::new(location(destination)?.into()),
+					Box::new(location(beneficiary)?.into()),
+					Box::new(assets(transferred_assets)?.into()),
+					*feeAssetItem,
+					weight_limit(weightLimit),
+				)
+				.map(Into::into)
+				.map_err(Into::into);
+
+				let pre = DispatchInfo {
+					call_weight: weight_to_charge,
+					extension_weight: Weight::zero(),
+					..Default::default()
+				};
+
+				// Adjust gas using actual weight or fallback to initially charged weight
+				let actual_weight = frame_support::dispatch::extract_actual_weight(&result, &pre);
+				env.adjust_gas(charged_amount, actual_weight);
+
+				result
+					.map(|_| Vec::new())
+					.map_err(|error| revert(&error, "XCM transferAssets failed"))
+			},
+			IXcmCalls::transferAssetsUsingTypeAndThen(
+				IXcm::transferAssetsUsingTypeAndThenCall {
+					destination,
+					assets: transferred_assets,
+					assetsTransferType,
+					assetsReserve,
+					remoteFeesId,
+					feesTransferType,
+					feesReserve,
+					customXcmOnDestination,
+					weightLimit,
+				},
+			) => {
+				let weight_to_charge = <Runtime as Config>::WeightInfo::transfer_assets();
+				let charged_amount = env.charge(weight_to_charge)?;
+
+				let custom_xcm = VersionedXcm::<()>::decode_all_with_depth_limit(
+					MAX_XCM_DECODE_DEPTH,
+					&mut &customXcmOnDestination[..],
+				)
+				.map_err(|error| {
+					revert(&error, "XCM transferAssetsUsingTypeAndThen: Invalid message format")
+				})?;
+
+				ensure_xcm_version(&custom_xcm)?;
+
+				let result: DispatchResultWithPostInfo =
+					crate::Pallet::<Runtime>::transfer_assets_using_type_and_then(
+						frame_origin,
+						Box::new(location(destination)?.into()),
+						Box::new(assets(transferred_assets)?.into()),
+						Box::new(transfer_type(*assetsTransferType, assetsReserve)?),
+						Box::new(VersionedAssetId::from(v5::AssetId(location(remoteFeesId)?))),
+						Box::new(transfer_type(*feesTransferType, feesReserve)?),
+						Box::new(custom_xcm),
+						weight_limit(weightLimit),
+					)
+					.map(Into::into)
+					.map_err(Into::into);
+
+				let pre = DispatchInfo {
+					call_weight: weight_to_charge,
+					extension_weight: Weight::zero(),
+					..Default::default()
+				};
+
+				// Adjust gas using actual weight or fallback to initially charged weight
+				let actual_weight = frame_support::dispatch::extract_actual_weight(&result, &pre);
+				env.adjust_gas(charged_amount, actual_weight);
+
+				result
+					.map(|_| Vec::new())
+					.map_err(|error| revert(&error, "XCM transferAssetsUsingTypeAndThen failed"))
+			},
+			IXcmCalls::quoteDeliveryFees(IXcm::quoteDeliveryFeesCall { destination, message }) => {
+				// Validating the message is bounded by the cost of sending it.
+				let _ = env.charge(<Runtime as Config>::WeightInfo::send())?;
+
+				let message = VersionedXcm::<()>::decode_all_with_depth_limit(
+					MAX_XCM_DECODE_DEPTH,
+					&mut &message[..],
+				)
+				.map_err(|error| revert(&error, "XCM quoteDeliveryFees: Invalid message format"))?;
+
+				ensure_xcm_version(&message)?;
+
+				let fees = crate::Pallet::<Runtime>::query_delivery_fees(
+					location(destination)?.into(),
+					message,
+				)
+				.map_err(|error| revert(&error, "XCM quoteDeliveryFees: Unroutable message"))?;
+				let fees = v5::Assets::try_from(fees).map_err(|error| {
+					revert(&error, "XCM quoteDeliveryFees: Conversion to Assets failed")
+				})?;
+
+				Ok(abi_assets(&fees)?.abi_encode())
+			},
+			IXcmCalls::dryRunTransferAssets(IXcm::dryRunTransferAssetsCall {
+				destination,
+				beneficiary,
+				assets: transferred_assets,
+				feeAssetItem,
+				weightLimit,
+			}) => {
+				let weight_to_charge = <Runtime as Config>::WeightInfo::transfer_assets()
+					.saturating_add(<Runtime as Config>::WeightInfo::send());
+				let _ = env.charge(weight_to_charge)?;
+
+				let destination = location(destination)?;
+				let beneficiary = location(beneficiary)?;
+				let transferred_assets = assets(transferred_assets)?;
+				let weight_limit = weight_limit(weightLimit);
+
+				let origin_location =
+					<Runtime as Config>::ExecuteXcmOrigin::ensure_origin(frame_origin.clone())
+						.map_err(|error| revert(&error, "XCM dryRunTransferAssets: Bad origin"))?;
+				let (mut local_xcm, remote_xcm) =
+					crate::Pallet::<Runtime>::transfer_assets_programs(
+						origin_location,
+						destination.clone(),
+						beneficiary.clone(),
+						transferred_assets.clone().into_inner(),
+						*feeAssetItem as usize,
+						weight_limit.clone(),
+					)
+					.map_err(|error| {
+						revert(&error, "XCM dryRunTransferAssets: Invalid transfer")
+					})?;
+
+				let local_weight = <<Runtime>::Weigher>::weight(&mut local_xcm, Weight::MAX)
+					.map_err(|error| {
+						revert(&error, "XCM dryRunTransferAssets: Failed to calculate weight")
+					})?;
+
+				let (delivery_fees, remote_message) = match remote_xcm {
+					Some(remote_xcm) => {
+						let remote_message = VersionedXcm::<()>::from(remote_xcm);
+						let fees = crate::Pallet::<Runtime>::query_delivery_fees(
+							destination.clone().into(),
+							remote_message.clone(),
+						)
+						.map_err(|error| {
+							revert(&error, "XCM dryRunTransferAssets: Unroutable message")
+						})?;
+						let fees = v5::Assets::try_from(fees).map_err(|error| {
+							revert(&error, "XCM dryRunTransferAssets: Conversion to Assets failed")
+						})?;
+						(abi_assets(&fees)?, remote_message.encode())
+					},
+					None => (Vec::new(), Vec::new()),
+				};
+
+				let success = with_transaction(|| {
+					let result = crate::Pallet::<Runtime>::transfer_assets(
+						frame_origin,
+						Box::new(destination.into()),
+						Box::new(beneficiary.into()),
+						Box::new(VersionedAssets::from(transferred_assets)),
+						*feeAssetItem,
+						weight_limit,
+					);
+					TransactionOutcome::Rollback(Ok::<_, DispatchError>(result.is_ok()))
+				})
+				.unwrap_or_default();
+
+				let effects = IXcm::TransferEffects {
+					success,
+					localWeight: IXcm::Weight {
+						refTime: local_weight.ref_time(),
+						proofSize: local_weight.proof_size(),
+					},
+					deliveryFees: delivery_fees,
+					remoteMessage: remote_message.into(),
+				};
+				Ok(effects.abi_encode())
+			},
 		}
 	}
 }
@@ -172,7 +517,10 @@ where
 mod test {
 	use crate::{
 		mock::*,
-		precompiles::IXcm::{self, weighMessageCall},
+		precompiles::{
+			abi_assets, abi_location,
+			IXcm::{self, weighMessageCall},
+		},
 		VersionedLocation, VersionedXcm,
 	};
 	use frame_support::traits::Currency;
@@ -854,4 +1202,232 @@ mod test {
 			assert!(result.did_revert());
 		});
 	}
+
+	#[test]
+	fn transfer_assets_precompile_works() {
+		let balances = vec![
+			(ALICE, CUSTOM_INITIAL_BALANCE),
+			(ParaId::from(OTHER_PARA_ID).into_account_truncating(), CUSTOM_INITIAL_BALANCE),
+		];
+		new_test_ext_with_balances(balances).execute_with(|| {
+			let xcm_precompile_addr = H160::from(
+				hex::const_decode_to_array(b"00000000000000000000000000000000000A0000").unwrap(),
+			);
+
+			let destination: Location = Parachain(OTHER_PARA_ID).into();
+			let beneficiary: Location = AccountId32 { network: None, id: BOB.into() }.into();
+			let assets: Assets = (Here, SEND_AMOUNT).into();
+
+			let xcm_transfer_params = IXcm::transferAssetsCall {
+				destination: abi_location(&destination).unwrap(),
+				beneficiary: abi_location(&beneficiary).unwrap(),
+				assets: abi_assets(&assets).unwrap(),
+				feeAssetItem: 0,
+				weightLimit: IXcm::Weight { refTime: 0, proofSize: 0 },
+			};
+			let call = IXcm::IXcmCalls::transferAssets(xcm_transfer_params);
+			let encoded_call = call.abi_encode();
+
+			let result = pallet_revive::Pallet::<Test>::bare_call(
+				RuntimeOrigin::signed(ALICE),
+				xcm_precompile_addr,
+				U256::zero(),
+				Weight::MAX,
+				DepositLimit::UnsafeOnlyForDryRun,
+				encoded_call,
+			);
+			let return_value = match result.result {
+				Ok(value) => value,
+				Err(err) => panic!("XcmTransferAssetsPrecompile call failed with error: {err:?}"),
+			};
+			assert!(!return_value.did_revert());
+
+			let para_account: AccountId = ParaId::from(OTHER_PARA_ID).into_account_truncating();
+			assert_eq!(Balances::total_balance(&ALICE), CUSTOM_INITIAL_BALANCE - SEND_AMOUNT);
+			assert_eq!(
+				Balances::total_balance(&para_account),
+				CUSTOM_INITIAL_BALANCE + SEND_AMOUNT
+			);
+			assert_eq!(
+				sent_xcm(),
+				vec![(
+					destination,
+					Xcm(vec![
+						ReserveAssetDeposited((Parent, SEND_AMOUNT).into()),
+						ClearOrigin,
+						BuyExecution {
+							fees: (Parent, SEND_AMOUNT).into(),
+							weight_limit: Unlimited
+						},
+						DepositAsset { assets: AllCounted(1).into(), beneficiary },
+					]),
+				)]
+			);
+		});
+	}
+
+	#[test]
+	fn transfer_assets_using_type_and_then_precompile_works() {
+		use codec::Encode;
+
+		let balances = vec![
+			(ALICE, CUSTOM_INITIAL_BALANCE),
+			(ParaId::from(OTHER_PARA_ID).into_account_truncating(), CUSTOM_INITIAL_BALANCE),
+		];
+		new_test_ext_with_balances(balances).execute_with(|| {
+			let xcm_precompile_addr = H160::from(
+				hex::const_decode_to_array(b"00000000000000000000000000000000000A0000").unwrap(),
+			);
+
+			let destination: Location = Parachain(OTHER_PARA_ID).into();
+			let beneficiary: Location = AccountId32 { network: None, id: BOB.into() }.into();
+			let assets: Assets = (Here, SEND_AMOUNT).into();
+			let custom_xcm_on_dest = Xcm::<()>(vec![DepositAsset {
+				assets: AllCounted(1).into(),
+				beneficiary: beneficiary.clone(),
+			}]);
+
+			let xcm_transfer_params = IXcm::transferAssetsUsingTypeAndThenCall {
+				destination: abi_location(&destination).unwrap(),
+				assets: abi_assets(&assets).unwrap(),
+				assetsTransferType: IXcm::TransferType::LocalReserve,
+				assetsReserve: abi_location(&Here.into_location()).unwrap(),
+				remoteFeesId: abi_location(&Here.into_location()).unwrap(),
+				feesTransferType: IXcm::TransferType::LocalReserve,
+				feesReserve: abi_location(&Here.into_location()).unwrap(),
+				customXcmOnDestination: VersionedXcm::from(custom_xcm_on_dest.clone())
+					.encode()
+					.into(),
+				weightLimit: IXcm::Weight { refTime: 0, proofSize: 0 },
+			};
+			let call = IXcm::IXcmCalls::transferAssetsUsingTypeAndThen(xcm_transfer_params);
+			let encoded_call = call.abi_encode();
+
+			let result = pallet_revive::Pallet::<Test>::bare_call(
+				RuntimeOrigin::signed(ALICE),
+				xcm_precompile_addr,
+				U256::zero(),
+				Weight::MAX,
+				DepositLimit::UnsafeOnlyForDryRun,
+				encoded_call,
+			);
+			let return_value = match result.result {
+				Ok(value) => value,
+				Err(err) => panic!("XcmTransferAssetsPrecompile call failed with error: {err:?}"),
+			};
+			assert!(!return_value.did_revert());
+
+			let para_account: AccountId = ParaId::from(OTHER_PARA_ID).into_account_truncating();
+			assert_eq!(Balances::total_balance(&ALICE), CUSTOM_INITIAL_BALANCE - SEND_AMOUNT);
+			assert_eq!(
+				Balances::total_balance(&para_account),
+				CUSTOM_INITIAL_BALANCE + SEND_AMOUNT
+			);
+			assert_eq!(sent_xcm().len(), 1);
+		});
+	}
+
+	#[test]
+	fn quote_delivery_fees_precompile_works() {
+		use codec::Encode;
+
+		new_test_ext_with_balances(vec![(ALICE, CUSTOM_INITIAL_BALANCE)]).execute_with(|| {
+			let xcm_precompile_addr = H160::from(
+				hex::const_decode_to_array(b"00000000000000000000000000000000000A0000").unwrap(),
+			);
+
+			let message: VersionedXcm<()> = VersionedXcm::from(Xcm::<()>(vec![ClearOrigin]));
+			let quote_params = IXcm::quoteDeliveryFeesCall {
+				destination: abi_location(&Para3000Location::get()).unwrap(),
+				message: message.encode().into(),
+			};
+			let call = IXcm::IXcmCalls::quoteDeliveryFees(quote_params);
+			let encoded_call = call.abi_encode();
+
+			let result = pallet_revive::Pallet::<Test>::bare_call(
+				RuntimeOrigin::signed(ALICE),
+				xcm_precompile_addr,
+				U256::zero(),
+				Weight::MAX,
+				DepositLimit::UnsafeOnlyForDryRun,
+				encoded_call,
+			);
+			let return_value = match result.result {
+				Ok(value) => value,
+				Err(err) =>
+					panic!("XcmQuoteDeliveryFeesPrecompile call failed with error: {err:?}"),
+			};
+			assert!(!return_value.did_revert());
+
+			let fees = Vec::<IXcm::Asset>::abi_decode(&return_value.data[..])
+				.expect("XcmQuoteDeliveryFeesPrecompile Failed to decode fees");
+			assert_eq!(
+				fees.abi_encode(),
+				abi_assets(&Para3000PaymentAssets::get()).unwrap().abi_encode()
+			);
+		});
+	}
+
+	#[test]
+	fn dry_run_transfer_assets_precompile_does_not_change_state() {
+		use codec::Encode;
+
+		let balances = vec![
+			(ALICE, CUSTOM_INITIAL_BALANCE),
+			(ParaId::from(OTHER_PARA_ID).into_account_truncating(), CUSTOM_INITIAL_BALANCE),
+		];
+		new_test_ext_with_balances(balances).execute_with(|| {
+			let xcm_precompile_addr = H160::from(
+				hex::const_decode_to_array(b"00000000000000000000000000000000000A0000").unwrap(),
+			);
+
+			let destination: Location = Parachain(OTHER_PARA_ID).into();
+			let beneficiary: Location = AccountId32 { network: None, id: BOB.into() }.into();
+			let assets: Assets = (Here, SEND_AMOUNT).into();
+
+			let dry_run_params = IXcm::dryRunTransferAssetsCall {
+				destination: abi_location(&destination).unwrap(),
+				beneficiary: abi_location(&beneficiary).unwrap(),
+				assets: abi_assets(&assets).unwrap(),
+				feeAssetItem: 0,
+				weightLimit: IXcm::Weight { refTime: 0, proofSize: 0 },
+			};
+			let call = IXcm::IXcmCalls::dryRunTransferAssets(dry_run_params);
+			let encoded_call = call.abi_encode();
+
+			let result = pallet_revive::Pallet::<Test>::bare_call(
+				RuntimeOrigin::signed(ALICE),
+				xcm_precompile_addr,
+				U256::zero(),
+				Weight::MAX,
+				DepositLimit::UnsafeOnlyForDryRun,
+				encoded_call,
+			);
+			let return_value = match result.result {
+				Ok(value) => value,
+				Err(err) => panic!("XcmDryRunPrecompile call failed with error: {err:?}"),
+			};
+			assert!(!return_value.did_revert());
+
+			let effects = IXcm::TransferEffects::abi_decode(&return_value.data[..])
+				.expect("XcmDryRunPrecompile Failed to decode effects");
+			assert!(effects.success);
+			assert_eq!(
+				Weight::from_parts(effects.localWeight.refTime, effects.localWeight.proofSize),
+				BaseXcmWeight::get()
+			);
+			assert!(effects.deliveryFees.is_empty());
+			let remote_message = VersionedXcm::from(Xcm::<()>(vec![
+				ReserveAssetDeposited((Parent, SEND_AMOUNT).into()),
+				ClearOrigin,
+				BuyExecution { fees: (Parent, SEND_AMOUNT).into(), weight_limit: Unlimited },
+				DepositAsset { assets: AllCounted(1).into(), beneficiary },
+			]));
+			assert_eq!(effects.remoteMessage.to_vec(), remote_message.encode());
+
+			// Nothing was transferred. The mock router keeps the sent messages outside of the
+			// storage, so unlike the routers of a runtime it isn't rolled back.
+			assert_eq!(Balances::total_balance(&ALICE), CUSTOM_INITIAL_BALANCE);
+		});
+	}
 }
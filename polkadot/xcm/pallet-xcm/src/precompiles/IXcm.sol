@@ -5,9 +5,9 @@ pragma solidity ^0.8.20;
 address constant XCM_PRECOMPILE_ADDRESS = address(0xA0000);
 
 /// @title XCM Precompile Interface
-/// @notice A low-level interface for interacting with `pallet_xcm`.
+/// @notice An interface for interacting with `pallet_xcm`.
 /// It forwards calls directly to the corresponding dispatchable functions,
-/// providing access to XCM execution and message passing.
+/// providing access to XCM execution, message passing and asset transfers.
 /// @dev Documentation:
 /// @dev - XCM: https://docs.polkadot.com/develop/interoperability
 /// @dev - SCALE codec: https://docs.polkadot.com/polkadot-protocol/parachain-basics/data-encoding
@@ -21,6 +21,80 @@ interface IXcm {
         uint64 proofSize;
     }
 
+    /// @notice The kind of a `Junction`.
+    enum JunctionType {
+        Parachain,
+        AccountId32,
+        AccountIndex64,
+        AccountKey20,
+        PalletInstance,
+        GeneralIndex,
+        GeneralKey,
+        OnlyChild,
+        Plurality,
+        GlobalConsensus
+    }
+
+    /// @notice A single item in the path of a `Location`.
+    /// @dev The `data` of each kind of junction is:
+    /// @dev - `Parachain`: `abi.encode(uint32 id)`.
+    /// @dev - `AccountId32`: `abi.encode(bytes32 id)`.
+    /// @dev - `AccountIndex64`: `abi.encode(uint64 index)`.
+    /// @dev - `AccountKey20`: `abi.encode(address key)`.
+    /// @dev - `PalletInstance`: `abi.encode(uint8 index)`.
+    /// @dev - `GeneralIndex`: `abi.encode(uint128 index)`.
+    /// @dev - `GeneralKey`: the key itself, at most 32 bytes.
+    /// @dev - `OnlyChild`: empty.
+    /// @dev - `Plurality`: the SCALE-encoded `BodyId` and `BodyPart`.
+    /// @dev - `GlobalConsensus`: the SCALE-encoded `NetworkId`.
+    /// @dev Account junctions are always relative to the current network.
+    struct Junction {
+        /// @custom:property The kind of the junction.
+        JunctionType kind;
+        /// @custom:property The encoded fields of the junction.
+        bytes data;
+    }
+
+    /// @notice A relative path between consensus systems, see XCM `Location`.
+    struct Location {
+        /// @custom:property The number of parent junctions at the beginning of the path.
+        uint8 parents;
+        /// @custom:property The junctions of the path, from the outermost to the innermost.
+        Junction[] interior;
+    }
+
+    /// @notice An amount of a fungible asset.
+    struct Asset {
+        /// @custom:property The location identifying the asset.
+        Location id;
+        /// @custom:property The amount of the asset.
+        uint256 amount;
+    }
+
+    /// @notice How assets are transferred to their destination, see `pallet_xcm::TransferType`.
+    enum TransferType {
+        /// @dev Teleport the assets to the destination.
+        Teleport,
+        /// @dev Reserve-transfer the assets, using the current chain as reserve.
+        LocalReserve,
+        /// @dev Reserve-transfer the assets, using the destination as reserve.
+        DestinationReserve,
+        /// @dev Reserve-transfer the assets, using a remote chain as reserve.
+        RemoteReserve
+    }
+
+    /// @notice The effects of a dry-run asset transfer.
+    struct TransferEffects {
+        /// @custom:property Whether the transfer succeeded.
+        bool success;
+        /// @custom:property The weight of the XCM program executed locally.
+        Weight localWeight;
+        /// @custom:property The fees charged for delivering the XCM program sent to the destination.
+        Asset[] deliveryFees;
+        /// @custom:property The SCALE-encoded Versioned XCM sent to the destination, if any.
+        bytes remoteMessage;
+    }
+
     /// @notice Executes an XCM message locally on the current chain with the caller's origin.
     /// @dev Internally calls `pallet_xcm::execute`.
     /// @param message A SCALE-encoded Versioned XCM message.
@@ -38,4 +112,64 @@ interface IXcm {
     /// @param message SCALE-encoded Versioned XCM message to analyze.
     /// @return weight Struct containing estimated `refTime` and `proofSize`.
     function weighMessage(bytes calldata message) external view returns (Weight memory weight);
+
+    /// @notice Transfers assets to another chain, deriving how each asset is transferred from the
+    /// reserve and teleport configuration of the current chain.
+    /// @dev Internally calls `pallet_xcm::transfer_assets`.
+    /// @param destination The destination chain.
+    /// @param beneficiary The beneficiary of the assets, relative to `destination`.
+    /// @param assets The assets to transfer, including the fees paid on the destination.
+    /// @param feeAssetItem The index in `assets` of the asset used to pay the fees.
+    /// @param weightLimit The weight bought on the destination, a zero weight being unlimited.
+    function transferAssets(
+        Location calldata destination,
+        Location calldata beneficiary,
+        Asset[] calldata assets,
+        uint32 feeAssetItem,
+        Weight calldata weightLimit
+    ) external;
+
+    /// @notice Transfers assets to another chain with explicit transfer types, and executes a custom
+    /// XCM on the destination.
+    /// @dev Internally calls `pallet_xcm::transfer_assets_using_type_and_then`.
+    /// @param destination The destination chain.
+    /// @param assets The assets to transfer, including the fees paid on the destination.
+    /// @param assetsTransferType How `assets` are transferred.
+    /// @param assetsReserve The reserve of `assets`, only used for `TransferType.RemoteReserve`.
+    /// @param remoteFeesId The asset of `assets` used to pay the fees.
+    /// @param feesTransferType How the fees are transferred.
+    /// @param feesReserve The reserve of the fees, only used for `TransferType.RemoteReserve`.
+    /// @param customXcmOnDestination SCALE-encoded Versioned XCM executed on the destination.
+    /// @param weightLimit The weight bought on the destination, a zero weight being unlimited.
+    function transferAssetsUsingTypeAndThen(
+        Location calldata destination,
+        Asset[] calldata assets,
+        TransferType assetsTransferType,
+        Location calldata assetsReserve,
+        Location calldata remoteFeesId,
+        TransferType feesTransferType,
+        Location calldata feesReserve,
+        bytes calldata customXcmOnDestination,
+        Weight calldata weightLimit
+    ) external;
+
+    /// @notice Quotes the fees charged for delivering an XCM message to another chain.
+    /// @param destination The destination chain.
+    /// @param message SCALE-encoded Versioned XCM message.
+    /// @return fees The assets charged to the sender.
+    function quoteDeliveryFees(Location calldata destination, bytes calldata message)
+        external
+        view
+        returns (Asset[] memory fees);
+
+    /// @notice Dry-runs `transferAssets`, reverting all of its state changes.
+    /// @dev Reverts if the parameters are invalid.
+    /// @return effects The outcome of the transfer, its local weight and its delivery fees.
+    function dryRunTransferAssets(
+        Location calldata destination,
+        Location calldata beneficiary,
+        Asset[] calldata assets,
+        uint32 feeAssetItem,
+        Weight calldata weightLimit
+    ) external returns (TransferEffects memory effects);
 }
@@ -26,8 +26,9 @@ use crate::{
 	xcm_helpers::find_xcm_sent_message_id,
 	AssetTraps, AuthorizedAliasers, Config, CurrentMigration, Error, ExecuteControllerWeightInfo,
 	LatestVersionedLocation, MaxAuthorizedAliases, Pallet, Queries, QueryStatus, RecordedXcm,
-	RemoteLockedFungibleRecord, ShouldRecordXcm, VersionDiscoveryQueue, VersionMigrationStage,
-	VersionNotifiers, VersionNotifyTargets, WeightInfo,
+	RemoteLockedFungibleRecord, ShouldRecordXcm, TrappedAssetsCount, VersionDiscoveryQueue,
+	VersionMigrationStage, VersionNotifiers, VersionNotifyTargets, WeightInfo,
+	MAX_TRAPPED_ASSETS_PER_ORIGIN,
 };
 use bounded_collections::BoundedVec;
 use frame_support::{
@@ -38,12 +39,14 @@ use frame_support::{
 use polkadot_parachain_primitives::primitives::Id as ParaId;
 use sp_runtime::{
 	traits::{AccountIdConversion, BlakeTwo256, BlockNumberProvider, Hash},
-	SaturatedConversion, TokenError,
+	DispatchError, SaturatedConversion, TokenError,
 };
 use xcm::{latest::QueryResponseInfo, prelude::*};
 use xcm_builder::AllowKnownQueryResponses;
 use xcm_executor::{
-	traits::{Properties, QueryHandler, QueryResponseStatus, ShouldExecute},
+	traits::{
+		ClaimAssets, DropAssets, Properties, QueryHandler, QueryResponseStatus, ShouldExecute,
+	},
 	XcmExecutor,
 };
 use xcm_simulator::fake_message_hash;
@@ -718,6 +721,119 @@ fn claim_assets_works() {
 	});
 }
 
+/// Test the opt-in index of trapped assets, queried by origin and claimed by hash.
+#[test]
+fn trapped_assets_index_works() {
+	let balances = vec![(ALICE, INITIAL_BALANCE)];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		let trapping_program =
+			Xcm::<RuntimeCall>::builder_unsafe().withdraw_asset((Here, SEND_AMOUNT)).build();
+		let source: Location = Junction::AccountId32 { network: None, id: ALICE.into() }.into();
+		let versioned_assets = VersionedAssets::from(Assets::from((Here, SEND_AMOUNT)));
+		let hash = BlakeTwo256::hash_of(&(source.clone(), versioned_assets.clone()));
+		let beneficiary = Box::new(VersionedLocation::from(source.clone()));
+
+		// Assets trapped while the index is disabled are not indexed.
+		assert_ok!(XcmPallet::execute(
+			RuntimeOrigin::signed(ALICE),
+			Box::new(VersionedXcm::from(trapping_program.clone())),
+			BaseXcmWeight::get() * 2,
+		));
+		assert_eq!(XcmPallet::trapped_assets(source.clone().into()), Ok(vec![]));
+		assert_noop!(
+			XcmPallet::claim_trapped_assets(
+				RuntimeOrigin::signed(ALICE),
+				hash,
+				beneficiary.clone()
+			),
+			Error::<Test>::TrappedAssetsNotFound,
+		);
+
+		// Only the admin origin can enable the index.
+		assert_noop!(
+			XcmPallet::force_asset_trap_index(RuntimeOrigin::signed(ALICE), true),
+			DispatchError::BadOrigin,
+		);
+		assert_ok!(XcmPallet::force_asset_trap_index(RuntimeOrigin::root(), true));
+
+		System::set_block_number(2);
+		assert_ok!(XcmPallet::execute(
+			RuntimeOrigin::signed(ALICE),
+			Box::new(VersionedXcm::from(trapping_program)),
+			BaseXcmWeight::get() * 2,
+		));
+		assert_eq!(Balances::total_balance(&ALICE), INITIAL_BALANCE - 2 * SEND_AMOUNT);
+
+		// The same assets were trapped twice, the index keeps the last trap.
+		let trapped = XcmPallet::trapped_assets(source.clone().into()).unwrap();
+		assert_eq!(trapped.len(), 1);
+		assert_eq!(trapped[0].hash, hash);
+		assert_eq!(trapped[0].assets, versioned_assets);
+		assert_eq!(trapped[0].count, 2);
+		assert_eq!(trapped[0].block_number, 2);
+
+		// Claim them with the helper extrinsic, the index entry is kept while some remain.
+		assert_ok!(XcmPallet::claim_trapped_assets(
+			RuntimeOrigin::signed(ALICE),
+			hash,
+			beneficiary.clone(),
+		));
+		assert_eq!(XcmPallet::trapped_assets(source.clone().into()).unwrap()[0].count, 1);
+		assert_ok!(XcmPallet::claim_trapped_assets(
+			RuntimeOrigin::signed(ALICE),
+			hash,
+			beneficiary.clone(),
+		));
+		assert_eq!(Balances::total_balance(&ALICE), INITIAL_BALANCE);
+		assert_eq!(XcmPallet::trapped_assets(source.into()), Ok(vec![]));
+		assert_eq!(AssetTraps::<Test>::iter().collect::<Vec<_>>(), vec![]);
+		assert_noop!(
+			XcmPallet::claim_trapped_assets(RuntimeOrigin::signed(ALICE), hash, beneficiary),
+			Error::<Test>::TrappedAssetsNotFound,
+		);
+	});
+}
+
+/// Test that the traps indexed per origin are bounded.
+#[test]
+fn trapped_assets_index_is_bounded() {
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		assert_ok!(XcmPallet::force_asset_trap_index(RuntimeOrigin::root(), true));
+		let source: Location = Junction::AccountId32 { network: None, id: ALICE.into() }.into();
+		let context = XcmContext { origin: None, message_id: [0u8; 32], topic: None };
+		let trap = |amount: u128| {
+			XcmPallet::drop_assets(&source, Asset::from((Here, amount)).into(), &context);
+		};
+
+		for amount in 1..=MAX_TRAPPED_ASSETS_PER_ORIGIN as u128 + 1 {
+			trap(amount);
+		}
+		// The last trap is not indexed, but it can still be claimed.
+		let indexed = XcmPallet::trapped_assets(source.clone().into()).unwrap();
+		assert_eq!(indexed.len(), MAX_TRAPPED_ASSETS_PER_ORIGIN as usize);
+		assert_eq!(AssetTraps::<Test>::iter().count(), MAX_TRAPPED_ASSETS_PER_ORIGIN as usize + 1);
+
+		// Trapping the same assets again keeps a single entry.
+		trap(1);
+		assert_eq!(
+			TrappedAssetsCount::<Test>::get(VersionedLocation::from(source.clone())),
+			MAX_TRAPPED_ASSETS_PER_ORIGIN,
+		);
+
+		// Claiming all the assets of a trap makes room for a new one.
+		let assets: Assets = (Here, 2u128).into();
+		assert!(<XcmPallet as ClaimAssets>::claim_assets(
+			&source,
+			&Location::here(),
+			&assets,
+			&context
+		));
+		trap(MAX_TRAPPED_ASSETS_PER_ORIGIN as u128 + 2);
+		let indexed = XcmPallet::trapped_assets(source.clone().into()).unwrap();
+		assert_eq!(indexed.len(), MAX_TRAPPED_ASSETS_PER_ORIGIN as usize);
+	});
+}
+
 /// Test failure to complete execution reverts intermediate side-effects.
 ///
 /// XCM program will withdraw and deposit some assets, then fail execution of a further withdraw.
@@ -18,5 +18,8 @@
 //!
 //! E.g. types that implement the [`xcm_executor::traits::AssetExchange`] trait.
 
+mod path_finding_adapter;
+pub use path_finding_adapter::PathFindingExchangeAdapter;
+
 mod single_asset_adapter;
 pub use single_asset_adapter::SingleAssetExchangeAdapter;
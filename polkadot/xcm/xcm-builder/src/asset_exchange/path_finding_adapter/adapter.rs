@@ -0,0 +1,318 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Path finding asset exchange adapter.
+
+extern crate alloc;
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+use frame_support::{
+	ensure,
+	traits::{tokens::fungibles, Get},
+};
+use pallet_asset_conversion::{QuotePrice, SwapCredit};
+use xcm::prelude::*;
+use xcm_executor::{
+	traits::{AssetExchange, MatchesFungibles},
+	AssetsInHolding,
+};
+
+/// An adapter from [`pallet_asset_conversion::SwapCredit`] and
+/// [`pallet_asset_conversion::QuotePrice`] to [`xcm_executor::traits::AssetExchange`], swapping
+/// through several pools when there is no pool between the two assets.
+///
+/// The swap paths go through the assets of `Connectors`, which should be the assets most pools
+/// are paired with, such as the native asset. The paths are bounded by the
+/// [`SwapCredit::max_path_len`] of `AssetConversion`, each path is quoted pool by pool and the
+/// one with the best price is swapped at once, so the whole path is either swapped or not at all.
+///
+/// Like the [`SingleAssetExchangeAdapter`](crate::SingleAssetExchangeAdapter), this adapter takes
+/// just one fungible asset in `give` and allows only one fungible asset in `want`.
+pub struct PathFindingExchangeAdapter<AssetConversion, Fungibles, Matcher, Connectors, AccountId>(
+	PhantomData<(AssetConversion, Fungibles, Matcher, Connectors, AccountId)>,
+);
+
+impl<AssetConversion, Fungibles, Matcher, Connectors, AccountId>
+	PathFindingExchangeAdapter<AssetConversion, Fungibles, Matcher, Connectors, AccountId>
+where
+	AssetConversion: SwapCredit<
+			AccountId,
+			Balance = u128,
+			AssetKind = Fungibles::AssetId,
+			Credit = fungibles::Credit<AccountId, Fungibles>,
+		> + QuotePrice<Balance = u128, AssetKind = Fungibles::AssetId>,
+	Fungibles: fungibles::Balanced<AccountId, Balance = u128>,
+	Connectors: Get<Vec<Fungibles::AssetId>>,
+{
+	/// Returns the swap paths from `give` to `want`, shortest first.
+	fn candidate_paths(
+		give: &Fungibles::AssetId,
+		want: &Fungibles::AssetId,
+	) -> Vec<Vec<Fungibles::AssetId>> {
+		let max_path_len = <AssetConversion as SwapCredit<AccountId>>::max_path_len() as usize;
+		let connectors: Vec<_> = Connectors::get()
+			.into_iter()
+			.filter(|connector| connector != give && connector != want)
+			.collect();
+
+		let mut paths = Vec::new();
+		// The paths from `give` to which `want` is yet to be appended.
+		let mut partial_paths = vec![vec![give.clone()]];
+		while !partial_paths.is_empty() {
+			let mut next_partial_paths = Vec::new();
+			for partial_path in partial_paths {
+				if partial_path.len() >= max_path_len {
+					continue;
+				}
+				if partial_path.len() + 1 < max_path_len {
+					for connector in &connectors {
+						if !partial_path.contains(connector) {
+							let mut next_partial_path = partial_path.clone();
+							next_partial_path.push(connector.clone());
+							next_partial_paths.push(next_partial_path);
+						}
+					}
+				}
+				let mut path = partial_path;
+				path.push(want.clone());
+				paths.push(path);
+			}
+			partial_paths = next_partial_paths;
+		}
+		paths
+	}
+
+	/// Quotes the amount of `path[last]` resulting from swapping exactly `amount_in` of
+	/// `path[0]`.
+	fn quote_exact_in(path: &[Fungibles::AssetId], amount_in: u128) -> Option<u128> {
+		path.windows(2).try_fold(amount_in, |amount, pair| {
+			<AssetConversion as QuotePrice>::quote_price_exact_tokens_for_tokens(
+				pair[0].clone(),
+				pair[1].clone(),
+				amount,
+				true, // Include fee.
+			)
+		})
+	}
+
+	/// Quotes the amount of `path[0]` required to obtain exactly `amount_out` of `path[last]`.
+	fn quote_exact_out(path: &[Fungibles::AssetId], amount_out: u128) -> Option<u128> {
+		path.windows(2).rev().try_fold(amount_out, |amount, pair| {
+			<AssetConversion as QuotePrice>::quote_price_tokens_for_exact_tokens(
+				pair[0].clone(),
+				pair[1].clone(),
+				amount,
+				true, // Include fee.
+			)
+		})
+	}
+
+	/// Returns the path with the best price along with its quote.
+	///
+	/// If `maximal`, that is the path resulting in the most of `want` for exactly `amount` of
+	/// `give`, otherwise the path requiring the least of `give` for exactly `amount` of `want`.
+	fn best_path(
+		give: &Fungibles::AssetId,
+		want: &Fungibles::AssetId,
+		amount: u128,
+		maximal: bool,
+	) -> Option<(Vec<Fungibles::AssetId>, u128)> {
+		let mut best: Option<(Vec<Fungibles::AssetId>, u128)> = None;
+		for path in Self::candidate_paths(give, want) {
+			let quote = if maximal {
+				Self::quote_exact_in(&path, amount)
+			} else {
+				Self::quote_exact_out(&path, amount)
+			};
+			let Some(quote) = quote else { continue };
+			let is_better = match &best {
+				None => true,
+				Some((_, best_quote)) if maximal => quote > *best_quote,
+				Some((_, best_quote)) => quote < *best_quote,
+			};
+			if is_better {
+				best = Some((path, quote));
+			}
+		}
+		best
+	}
+}
+
+impl<AssetConversion, Fungibles, Matcher, Connectors, AccountId> AssetExchange
+	for PathFindingExchangeAdapter<AssetConversion, Fungibles, Matcher, Connectors, AccountId>
+where
+	AssetConversion: SwapCredit<
+			AccountId,
+			Balance = u128,
+			AssetKind = Fungibles::AssetId,
+			Credit = fungibles::Credit<AccountId, Fungibles>,
+		> + QuotePrice<Balance = u128, AssetKind = Fungibles::AssetId>,
+	Fungibles: fungibles::Balanced<AccountId, Balance = u128>,
+	Matcher: MatchesFungibles<Fungibles::AssetId, Fungibles::Balance>,
+	Connectors: Get<Vec<Fungibles::AssetId>>,
+{
+	fn exchange_asset(
+		_: Option<&Location>,
+		give: AssetsInHolding,
+		want: &Assets,
+		maximal: bool,
+	) -> Result<AssetsInHolding, AssetsInHolding> {
+		let mut give_iter = give.fungible_assets_iter();
+		let give_asset = give_iter.next().ok_or_else(|| {
+			tracing::trace!(
+				target: "xcm::PathFindingExchangeAdapter::exchange_asset",
+				?give, "No fungible asset was in `give`.",
+			);
+			give.clone()
+		})?;
+		ensure!(give_iter.next().is_none(), give.clone()); // We only support 1 asset in `give`.
+		ensure!(give.non_fungible_assets_iter().next().is_none(), give.clone()); // We don't allow non-fungible assets.
+		ensure!(want.len() == 1, give.clone()); // We only support 1 asset in `want`.
+		let want_asset = want.get(0).ok_or_else(|| give.clone())?;
+		let (give_asset_id, give_amount) =
+			Matcher::matches_fungibles(&give_asset).map_err(|error| {
+				tracing::trace!(
+					target: "xcm::PathFindingExchangeAdapter::exchange_asset",
+					?give_asset,
+					?error,
+					"Could not map XCM asset give to FRAME asset.",
+				);
+				give.clone()
+			})?;
+		let (want_asset_id, want_amount) =
+			Matcher::matches_fungibles(&want_asset).map_err(|error| {
+				tracing::trace!(
+					target: "xcm::PathFindingExchangeAdapter::exchange_asset",
+					?want_asset,
+					?error,
+					"Could not map XCM asset want to FRAME asset.",
+				);
+				give.clone()
+			})?;
+
+		let amount = if maximal { give_amount } else { want_amount };
+		let (path, _) = Self::best_path(&give_asset_id, &want_asset_id, amount, maximal)
+			.ok_or_else(|| {
+				tracing::debug!(
+					target: "xcm::PathFindingExchangeAdapter::exchange_asset",
+					?give_asset_id,
+					?want_asset_id,
+					"No swap path found",
+				);
+				give.clone()
+			})?;
+
+		// We have to do this to convert the XCM assets into credit the pools can use.
+		let credit_in = Fungibles::issue(give_asset_id, give_amount);
+
+		// Do the swap, along the whole path at once.
+		let (credit_out, maybe_credit_change) = if maximal {
+			// If `maximal`, then we swap exactly `credit_in` to get as much of `want_asset_id` as
+			// we can, with a minimum of `want_amount`.
+			let credit_out = <AssetConversion as SwapCredit<_>>::swap_exact_tokens_for_tokens(
+				path,
+				credit_in,
+				Some(want_amount),
+			)
+			.map_err(|(credit_in, error)| {
+				tracing::debug!(
+					target: "xcm::PathFindingExchangeAdapter::exchange_asset",
+					?error,
+					"Could not perform the swap",
+				);
+				drop(credit_in);
+				give.clone()
+			})?;
+
+			// We don't have leftover assets if exchange was maximal.
+			(credit_out, None)
+		} else {
+			// If `minimal`, then we swap as little of `credit_in` as we can to get exactly
+			// `want_amount` of `want_asset_id`.
+			let (credit_out, credit_change) =
+				<AssetConversion as SwapCredit<_>>::swap_tokens_for_exact_tokens(
+					path,
+					credit_in,
+					want_amount,
+				)
+				.map_err(|(credit_in, error)| {
+					tracing::debug!(
+						target: "xcm::PathFindingExchangeAdapter::exchange_asset",
+						?error,
+						"Could not perform the swap",
+					);
+					drop(credit_in);
+					give.clone()
+				})?;
+
+			(credit_out, if credit_change.peek() > 0 { Some(credit_change) } else { None })
+		};
+
+		// We create an `AssetsInHolding` instance by putting in the resulting asset
+		// of the exchange.
+		let resulting_asset: Asset = (want_asset.id.clone(), credit_out.peek()).into();
+		let mut result: AssetsInHolding = resulting_asset.into();
+
+		// If we have some leftover assets from the exchange, also put them in the result.
+		if let Some(credit_change) = maybe_credit_change {
+			let leftover_asset: Asset = (give_asset.id.clone(), credit_change.peek()).into();
+			result.subsume(leftover_asset);
+		}
+
+		Ok(result)
+	}
+
+	fn quote_exchange_price(give: &Assets, want: &Assets, maximal: bool) -> Option<Assets> {
+		if give.len() != 1 || want.len() != 1 {
+			return None;
+		} // We only support 1 asset in `give` or `want`.
+		let give_asset = give.get(0)?;
+		let want_asset = want.get(0)?;
+		// We first match both XCM assets to the asset ID types `AssetConversion` can handle.
+		let (give_asset_id, give_amount) = Matcher::matches_fungibles(give_asset)
+			.map_err(|error| {
+				tracing::trace!(
+					target: "xcm::PathFindingExchangeAdapter::quote_exchange_price",
+					?give_asset,
+					?error,
+					"Could not map XCM asset to FRAME asset.",
+				);
+			})
+			.ok()?;
+		let (want_asset_id, want_amount) = Matcher::matches_fungibles(want_asset)
+			.map_err(|error| {
+				tracing::trace!(
+					target: "xcm::PathFindingExchangeAdapter::quote_exchange_price",
+					?want_asset,
+					?error,
+					"Could not map XCM asset to FRAME asset.",
+				);
+			})
+			.ok()?;
+		// We quote the price along the best path.
+		if maximal {
+			// The amount of `want` resulting from swapping `give`.
+			let (_, resulting_want) =
+				Self::best_path(&give_asset_id, &want_asset_id, give_amount, true)?;
+			Some((want_asset.id.clone(), resulting_want).into())
+		} else {
+			// The `give` amount required to obtain `want`.
+			let (_, necessary_give) =
+				Self::best_path(&give_asset_id, &want_asset_id, want_amount, false)?;
+			Some((give_asset.id.clone(), necessary_give).into())
+		}
+	}
+}
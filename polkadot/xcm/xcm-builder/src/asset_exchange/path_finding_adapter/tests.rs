@@ -0,0 +1,187 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tests for the [`PathFindingExchangeAdapter`] type.
+
+use super::super::single_asset_adapter::mock::*;
+use xcm::prelude::*;
+use xcm_executor::{traits::AssetExchange, AssetsInHolding};
+
+// ========== Happy path ==========
+
+/// Scenario:
+/// The custom asset 1 is swapped for the custom asset 2, there is no pool between them so the
+/// swap goes through the native token.
+#[test]
+fn maximal_exchange_through_connector() {
+	new_test_ext().execute_with(|| {
+		let assets = PathFindingExchanger::exchange_asset(
+			None,
+			vec![([PalletInstance(2), GeneralIndex(1)], 10_000_000).into()].into(),
+			&vec![([PalletInstance(2), GeneralIndex(2)], 1).into()].into(),
+			true, // Maximal
+		)
+		.unwrap();
+		let amount = get_amount_from_first_fungible(&assets);
+		// `10_000_000` of the asset 1 give `4_533_054` of the native token, which give `9_338_067`
+		// of the asset 2.
+		assert_eq!(amount, 9_338_067);
+	});
+}
+
+#[test]
+fn minimal_exchange_through_connector() {
+	new_test_ext().execute_with(|| {
+		let assets = PathFindingExchanger::exchange_asset(
+			None,
+			vec![([PalletInstance(2), GeneralIndex(1)], 10_000_000).into()].into(),
+			&vec![([PalletInstance(2), GeneralIndex(2)], 5_000_000).into()].into(),
+			false, // Minimal
+		)
+		.unwrap();
+		let (first_amount, second_amount) = get_amount_from_fungibles(&assets);
+		// The leftover of the asset 1, `4_192_308` of it were swapped.
+		assert_eq!(first_amount, 5_807_692);
+		assert_eq!(second_amount, 5_000_000);
+	});
+}
+
+#[test]
+fn direct_exchange_is_preferred() {
+	new_test_ext().execute_with(|| {
+		// Same result as with the `SingleAssetExchangeAdapter`.
+		let assets = PathFindingExchanger::exchange_asset(
+			None,
+			vec![([PalletInstance(2), GeneralIndex(1)], 10_000_000).into()].into(),
+			&vec![(Here, 2_000_000).into()].into(),
+			true, // Maximal
+		)
+		.unwrap();
+		let amount = get_amount_from_first_fungible(&assets);
+		assert_eq!(amount, 4_533_054);
+	});
+}
+
+#[test]
+fn maximal_quote_through_connector() {
+	new_test_ext().execute_with(|| {
+		let assets = quote(
+			&([PalletInstance(2), GeneralIndex(1)], 10_000_000).into(),
+			&([PalletInstance(2), GeneralIndex(2)], 1).into(),
+			true,
+		)
+		.unwrap();
+		let amount = get_amount_from_first_fungible(&assets.into());
+		// The amount of the asset 2 resulting from swapping all `10_000_000` of the asset 1.
+		assert_eq!(amount, 9_338_067);
+	});
+}
+
+#[test]
+fn minimal_quote_through_connector() {
+	new_test_ext().execute_with(|| {
+		let assets = quote(
+			&([PalletInstance(2), GeneralIndex(1)], 10_000_000).into(),
+			&([PalletInstance(2), GeneralIndex(2)], 5_000_000).into(),
+			false,
+		)
+		.unwrap();
+		let amount = get_amount_from_first_fungible(&assets.into());
+		// The amount of the asset 1 needed to get `5_000_000` of the asset 2.
+		assert_eq!(amount, 4_192_308);
+	});
+}
+
+// ========== Unhappy path ==========
+
+#[test]
+fn no_path() {
+	new_test_ext().execute_with(|| {
+		let nonexistent_asset_id = 1000;
+		assert!(PathFindingExchanger::exchange_asset(
+			None,
+			vec![([PalletInstance(2), GeneralIndex(1)], 10_000_000).into()].into(),
+			&vec![([PalletInstance(2), GeneralIndex(nonexistent_asset_id)], 1).into()].into(),
+			true, // Maximal
+		)
+		.is_err());
+		assert!(quote(
+			&([PalletInstance(2), GeneralIndex(1)], 10_000_000).into(),
+			&([PalletInstance(2), GeneralIndex(nonexistent_asset_id)], 1).into(),
+			true,
+		)
+		.is_none());
+	});
+}
+
+#[test]
+fn exchange_through_connector_fails() {
+	new_test_ext().execute_with(|| {
+		assert!(PathFindingExchanger::exchange_asset(
+			None,
+			vec![([PalletInstance(2), GeneralIndex(1)], 10_000_000).into()].into(),
+			// We're asking for more than the asset 1 can give...
+			&vec![([PalletInstance(2), GeneralIndex(2)], 10_000_000).into()].into(),
+			true, // Maximal
+		)
+		.is_err());
+	});
+}
+
+#[test]
+fn more_than_one_asset_in_give() {
+	new_test_ext().execute_with(|| {
+		assert!(PathFindingExchanger::exchange_asset(
+			None,
+			vec![([PalletInstance(2), GeneralIndex(1)], 1).into(), (Here, 2).into()].into(),
+			&vec![([PalletInstance(2), GeneralIndex(2)], 1).into()].into(),
+			true
+		)
+		.is_err());
+	});
+}
+
+// ========== Helper functions ==========
+
+fn get_amount_from_first_fungible(assets: &AssetsInHolding) -> u128 {
+	let mut fungibles_iter = assets.fungible_assets_iter();
+	let first_fungible = fungibles_iter.next().unwrap();
+	let Fungible(amount) = first_fungible.fun else {
+		unreachable!("Asset should be fungible");
+	};
+	amount
+}
+
+fn get_amount_from_fungibles(assets: &AssetsInHolding) -> (u128, u128) {
+	let mut fungibles_iter = assets.fungible_assets_iter();
+	let first_fungible = fungibles_iter.next().unwrap();
+	let Fungible(first_amount) = first_fungible.fun else {
+		unreachable!("Asset should be fungible");
+	};
+	let second_fungible = fungibles_iter.next().unwrap();
+	let Fungible(second_amount) = second_fungible.fun else {
+		unreachable!("Asset should be fungible");
+	};
+	(first_amount, second_amount)
+}
+
+fn quote(asset_1: &Asset, asset_2: &Asset, maximal: bool) -> Option<Assets> {
+	PathFindingExchanger::quote_exchange_price(
+		&asset_1.clone().into(),
+		&asset_2.clone().into(),
+		maximal,
+	)
+}
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-//! Mock to test [`SingleAssetExchangeAdapter`].
+//! Mock to test [`SingleAssetExchangeAdapter`] and [`PathFindingExchangeAdapter`].
 
 use core::marker::PhantomData;
 use frame_support::{
@@ -36,7 +36,10 @@ use sp_runtime::{
 use xcm::prelude::*;
 use xcm_executor::{traits::ConvertLocation, XcmExecutor};
 
-use crate::{FungibleAdapter, IsConcrete, MatchedConvertedConcreteId, StartsWith};
+use crate::{
+	FungibleAdapter, IsConcrete, MatchedConvertedConcreteId, PathFindingExchangeAdapter,
+	SingleAssetExchangeAdapter, StartsWith,
+};
 
 pub type Block = frame_system::mocking::MockBlock<Runtime>;
 pub type AccountId = u64;
@@ -204,16 +207,26 @@ impl MaybeEquivalence<Location, NativeOrWithId<u32>> for LocationToAssetId {
 	}
 }
 
-pub type PoolAssetsExchanger = crate::SingleAssetExchangeAdapter<
+pub type PoolAssetsMatcher = MatchedConvertedConcreteId<
+	NativeOrWithId<u32>,
+	Balance,
+	(StartsWith<TrustBackedAssetsPalletLocation>, Equals<HereLocation>),
+	LocationToAssetId,
+	TryConvertInto,
+>;
+
+pub type PoolAssetsExchanger =
+	SingleAssetExchangeAdapter<AssetConversion, NativeAndAssets, PoolAssetsMatcher, AccountId>;
+
+parameter_types! {
+	pub PoolConnectors: Vec<NativeOrWithId<u32>> = vec![NativeOrWithId::Native];
+}
+
+pub type PathFindingExchanger = PathFindingExchangeAdapter<
 	AssetConversion,
 	NativeAndAssets,
-	MatchedConvertedConcreteId<
-		NativeOrWithId<u32>,
-		Balance,
-		(StartsWith<TrustBackedAssetsPalletLocation>, Equals<HereLocation>),
-		LocationToAssetId,
-		TryConvertInto,
-	>,
+	PoolAssetsMatcher,
+	PoolConnectors,
 	AccountId,
 >;
 
@@ -359,6 +372,8 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 		System::set_block_number(1);
 		assert_ok!(AssetsPallet::force_create(RuntimeOrigin::root(), 1, owner, false, 1,));
 		assert_ok!(AssetsPallet::mint_into(1, &owner, INITIAL_BALANCE,));
+		assert_ok!(AssetsPallet::force_create(RuntimeOrigin::root(), 2, owner, false, 1,));
+		assert_ok!(AssetsPallet::mint_into(2, &owner, INITIAL_BALANCE,));
 		assert_ok!(AssetConversion::create_pool(
 			RuntimeOrigin::signed(owner),
 			Box::new(NativeOrWithId::Native),
@@ -374,6 +389,23 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 			0,
 			owner,
 		));
+		// There is no pool between the custom assets 1 and 2, they can only be swapped through
+		// the native token.
+		assert_ok!(AssetConversion::create_pool(
+			RuntimeOrigin::signed(owner),
+			Box::new(NativeOrWithId::Native),
+			Box::new(NativeOrWithId::WithId(2)),
+		));
+		assert_ok!(AssetConversion::add_liquidity(
+			RuntimeOrigin::signed(owner),
+			Box::new(NativeOrWithId::Native),
+			Box::new(NativeOrWithId::WithId(2)),
+			10_000_000,
+			30_000_000,
+			0,
+			0,
+			owner,
+		));
 	});
 	ext
 }
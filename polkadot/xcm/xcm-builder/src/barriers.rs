@@ -16,7 +16,7 @@
 
 //! Various implementations for `ShouldExecute`.
 
-use crate::{CreateMatcher, MatchXcm};
+use crate::{CreateMatcher, MatchXcm, RateLimits};
 use core::{cell::Cell, marker::PhantomData, ops::ControlFlow, result::Result};
 use frame_support::{
 	ensure,
@@ -279,6 +279,49 @@ where
 	}
 }
 
+/// Barrier condition that caps the messages an origin may execute with the quotas of `Limits`.
+///
+/// The quota of the origin is consumed by the `max_weight` of the messages allowed by `Inner`.
+/// The messages of an origin which exhausted its quota are rejected, which drops them when they
+/// come from a message queue. Use [`crate::RateLimitedProcessor`] to keep them in their queue
+/// instead.
+///
+/// The weight of `Limits` is not included in the weight of the messages, so the executor must
+/// weigh them with [`crate::RateLimitedWeightBounds`] to charge it.
+pub struct RateLimitedBarrier<Inner, Limits>(PhantomData<(Inner, Limits)>);
+impl<Inner, Limits> ShouldExecute for RateLimitedBarrier<Inner, Limits>
+where
+	Inner: ShouldExecute,
+	Limits: RateLimits,
+{
+	fn should_execute<Call>(
+		origin: &Location,
+		instructions: &mut [Instruction<Call>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		tracing::trace!(
+			target: "xcm::barriers",
+			?origin,
+			?instructions,
+			?max_weight,
+			?properties,
+			"RateLimitedBarrier"
+		);
+		Inner::should_execute(origin, instructions, max_weight, properties)?;
+		if Limits::is_exhausted(origin) {
+			tracing::trace!(
+				target: "xcm::barriers",
+				?origin,
+				"RateLimitedBarrier: quota exhausted",
+			);
+			return Err(ProcessMessageError::Unsupported)
+		}
+		Limits::consume(origin, max_weight);
+		Ok(())
+	}
+}
+
 /// Allows execution from any origin that is contained in `T` (i.e. `T::Contains(origin)`).
 ///
 /// Use only for executions from completely trusted origins, from which no permissionless messages
@@ -38,15 +38,15 @@ pub use asset_conversion::{
 };
 
 mod asset_exchange;
-pub use asset_exchange::SingleAssetExchangeAdapter;
+pub use asset_exchange::{PathFindingExchangeAdapter, SingleAssetExchangeAdapter};
 
 mod barriers;
 pub use barriers::{
 	AllowExplicitUnpaidExecutionFrom, AllowHrmpNotificationsFromRelayChain,
 	AllowKnownQueryResponses, AllowSubscriptionsFrom, AllowTopLevelPaidExecutionFrom,
 	AllowUnpaidExecutionFrom, DenyRecursively, DenyReserveTransferToRelayChain, DenyThenTry,
-	IsChildSystemParachain, IsParentsOnly, IsSiblingSystemParachain, RespectSuspension,
-	TakeWeightCredit, TrailingSetTopicAsId, WithComputedOrigin,
+	IsChildSystemParachain, IsParentsOnly, IsSiblingSystemParachain, RateLimitedBarrier,
+	RespectSuspension, TakeWeightCredit, TrailingSetTopicAsId, WithComputedOrigin,
 };
 
 mod controller;
@@ -127,7 +127,7 @@ mod pay;
 pub use pay::{FixedLocation, LocatableAssetId, PayAccountId32OnChainOverXcm, PayOverXcm};
 
 mod process_xcm_message;
-pub use process_xcm_message::ProcessXcmMessage;
+pub use process_xcm_message::{ProcessXcmMessage, RateLimitedProcessor, RateLimits};
 
 mod routing;
 pub use routing::{
@@ -148,5 +148,6 @@ pub use universal_exports::{
 
 mod weight;
 pub use weight::{
-	FixedRateOfFungible, FixedWeightBounds, TakeRevenue, UsingComponents, WeightInfoBounds,
+	FixedRateOfFungible, FixedWeightBounds, RateLimitedWeightBounds, TakeRevenue, UsingComponents,
+	WeightInfoBounds,
 };
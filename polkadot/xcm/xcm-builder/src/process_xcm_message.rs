@@ -128,13 +128,90 @@ impl<
 	}
 }
 
+/// Trait to account for the messages processed from an origin against its quota.
+///
+/// Used by [`RateLimitedProcessor`] and [`crate::RateLimitedBarrier`] to cap the number of
+/// messages and the weight an origin may consume in a given period.
+pub trait RateLimits {
+	/// Returns whether `origin` exhausted its quota, its messages must wait for it to be renewed.
+	fn is_exhausted(origin: &Location) -> bool;
+
+	/// Consumes the quota of `origin` for one message which used `weight`.
+	fn consume(origin: &Location, weight: Weight);
+
+	/// The weight of calling both [`Self::is_exhausted`] and [`Self::consume`].
+	fn weight() -> Weight;
+}
+
+impl RateLimits for () {
+	fn is_exhausted(_: &Location) -> bool {
+		false
+	}
+
+	fn consume(_: &Location, _: Weight) {}
+
+	fn weight() -> Weight {
+		Weight::zero()
+	}
+}
+
+/// A message processor that caps the messages processed by `Processor` with the quotas of
+/// `Limits`.
+///
+/// The messages of an origin which exhausted its quota are not processed but yielded with
+/// [`ProcessMessageError::Yield`], so that they stay in their queue until the quota is renewed.
+/// The quota of the origin is consumed by the weight its messages actually used.
+pub struct RateLimitedProcessor<Processor, Limits>(PhantomData<(Processor, Limits)>);
+impl<Processor, Limits> ProcessMessage for RateLimitedProcessor<Processor, Limits>
+where
+	Processor: ProcessMessage,
+	Processor::Origin: Into<Location> + Clone,
+	Limits: RateLimits,
+{
+	type Origin = Processor::Origin;
+
+	fn process_message(
+		message: &[u8],
+		origin: Self::Origin,
+		meter: &mut WeightMeter,
+		id: &mut XcmHash,
+	) -> Result<bool, ProcessMessageError> {
+		let required = Limits::weight();
+		if meter.try_consume(required).is_err() {
+			return Err(ProcessMessageError::Overweight(required))
+		}
+		let location = origin.clone().into();
+		if Limits::is_exhausted(&location) {
+			tracing::trace!(
+				target: LOG_TARGET,
+				?location,
+				"Quota exhausted, yielding the message",
+			);
+			return Err(ProcessMessageError::Yield)
+		}
+
+		let consumed = meter.consumed();
+		let result =
+			Processor::process_message(message, origin, meter, id).map_err(
+				|error| match error {
+					// The message needs the weight of the limits on top of its own.
+					ProcessMessageError::Overweight(weight) =>
+						ProcessMessageError::Overweight(weight.saturating_add(required)),
+					error => error,
+				},
+			)?;
+		Limits::consume(&location, meter.consumed().saturating_sub(consumed));
+		Ok(result)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 	use alloc::vec;
 	use codec::Encode;
 	use frame_support::{
-		assert_err, assert_ok,
+		assert_err, assert_ok, parameter_types,
 		traits::{ProcessMessageError, ProcessMessageError::*},
 	};
 	use polkadot_test_runtime::*;
@@ -240,6 +317,87 @@ mod tests {
 		});
 	}
 
+	parameter_types! {
+		pub static RemainingMessages: u32 = 0;
+		pub static ConsumedWeight: Weight = Weight::zero();
+	}
+
+	/// Allows the number of messages in [`RemainingMessages`], whatever their origin.
+	struct TestRateLimits;
+	impl RateLimits for TestRateLimits {
+		fn is_exhausted(_: &Location) -> bool {
+			RemainingMessages::get() == 0
+		}
+
+		fn consume(_: &Location, weight: Weight) {
+			RemainingMessages::mutate(|remaining| *remaining -= 1);
+			ConsumedWeight::mutate(|consumed| *consumed += weight);
+		}
+
+		fn weight() -> Weight {
+			Weight::from_parts(10, 10)
+		}
+	}
+
+	#[test]
+	fn rate_limited_process_message_yields_when_quota_exhausted() {
+		type LimitedProcessor = RateLimitedProcessor<Processor, TestRateLimits>;
+
+		sp_io::TestExternalities::default().execute_with(|| {
+			RemainingMessages::set(2);
+			let meter = &mut WeightMeter::new();
+
+			// Failing messages consume the quota too.
+			let msg = &v5_xcm(false).encode()[..];
+			assert_eq!(
+				LimitedProcessor::process_message(msg, ORIGIN, meter, &mut [0; 32]),
+				Ok(false)
+			);
+			let msg = &v5_xcm(true).encode()[..];
+			assert_eq!(
+				LimitedProcessor::process_message(msg, ORIGIN, meter, &mut [0; 32]),
+				Ok(true)
+			);
+			assert_eq!(RemainingMessages::get(), 0);
+			assert_eq!(meter.consumed(), ConsumedWeight::get() + TestRateLimits::weight() * 2);
+
+			// The quota is exhausted, the message is kept in its queue instead of being executed.
+			let consumed = meter.consumed();
+			assert_err!(LimitedProcessor::process_message(msg, ORIGIN, meter, &mut [0; 32]), Yield);
+			assert_eq!(meter.consumed(), consumed + TestRateLimits::weight());
+
+			// The message is executed once the quota is renewed.
+			RemainingMessages::set(1);
+			assert_eq!(
+				LimitedProcessor::process_message(msg, ORIGIN, meter, &mut [0; 32]),
+				Ok(true)
+			);
+		});
+	}
+
+	#[test]
+	fn rate_limited_process_message_overweight_includes_limits() {
+		type LimitedProcessor = RateLimitedProcessor<Processor, TestRateLimits>;
+
+		sp_io::TestExternalities::default().execute_with(|| {
+			RemainingMessages::set(1);
+			let msg = &v5_xcm(true).encode()[..];
+			let required: Weight = 1000.into();
+
+			// The message needs the weight of the limits on top of its own.
+			let meter = &mut WeightMeter::with_limit(required);
+			assert_err!(
+				LimitedProcessor::process_message(msg, ORIGIN, meter, &mut [0; 32]),
+				Overweight(required + TestRateLimits::weight())
+			);
+			assert_eq!(RemainingMessages::get(), 1);
+
+			let meter = &mut WeightMeter::with_limit(required + TestRateLimits::weight());
+			assert_ok!(LimitedProcessor::process_message(msg, ORIGIN, meter, &mut [0; 32]));
+			assert_eq!(RemainingMessages::get(), 0);
+		});
+	}
+
 	fn v3_xcm(success: bool) -> VersionedXcm<RuntimeCall> {
 		let instr = if success {
 			v3::Instruction::<RuntimeCall>::ClearOrigin
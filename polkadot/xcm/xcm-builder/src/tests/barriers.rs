@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
+use frame_support::parameter_types;
 use std::marker::PhantomData;
 use xcm_executor::traits::Properties;
 
@@ -741,6 +742,68 @@ fn suspension_should_work() {
 	assert_eq!(r, Ok(()));
 }
 
+parameter_types! {
+	pub static RemainingMessages: u32 = 0;
+	pub static ConsumedWeight: Weight = Weight::zero();
+}
+
+/// Allows the number of messages in [`RemainingMessages`], whatever their origin.
+struct TestRateLimits;
+impl RateLimits for TestRateLimits {
+	fn is_exhausted(_: &Location) -> bool {
+		RemainingMessages::get() == 0
+	}
+
+	fn consume(_: &Location, weight: Weight) {
+		RemainingMessages::mutate(|remaining| *remaining -= 1);
+		ConsumedWeight::mutate(|consumed| *consumed += weight);
+	}
+
+	fn weight() -> Weight {
+		Weight::zero()
+	}
+}
+
+#[test]
+fn rate_limited_barrier_should_work() {
+	RemainingMessages::set(1);
+	AllowUnpaidFrom::set(vec![Parent.into()]);
+	type Barrier =
+		RateLimitedBarrier<AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>, TestRateLimits>;
+
+	// The messages rejected by the inner barrier don't consume the quota.
+	let mut message =
+		Xcm::<()>(vec![TransferAsset { assets: (Parent, 100).into(), beneficiary: Here.into() }]);
+	let r = Barrier::should_execute(
+		&Parachain(1).into(),
+		message.inner_mut(),
+		Weight::from_parts(10, 10),
+		&mut props(Weight::zero()),
+	);
+	assert_eq!(r, Err(ProcessMessageError::Unsupported));
+	assert_eq!(RemainingMessages::get(), 1);
+
+	// The quota is consumed by the maximum weight of the message.
+	let r = Barrier::should_execute(
+		&Parent.into(),
+		message.inner_mut(),
+		Weight::from_parts(10, 10),
+		&mut props(Weight::zero()),
+	);
+	assert_eq!(r, Ok(()));
+	assert_eq!(RemainingMessages::get(), 0);
+	assert_eq!(ConsumedWeight::get(), Weight::from_parts(10, 10));
+
+	// The quota is exhausted, the message is rejected.
+	let r = Barrier::should_execute(
+		&Parent.into(),
+		message.inner_mut(),
+		Weight::from_parts(10, 10),
+		&mut props(Weight::zero()),
+	);
+	assert_eq!(r, Err(ProcessMessageError::Unsupported));
+}
+
 #[test]
 fn allow_subscriptions_from_should_work() {
 	// allow only parent
@@ -251,6 +251,47 @@ fn weigher_weight_limit_correctly_accounts_for_nested_instructions() {
 	);
 }
 
+#[test]
+fn rate_limited_weight_bounds_should_add_the_weight_of_the_limits() {
+	struct Limits;
+	impl RateLimits for Limits {
+		fn is_exhausted(_: &Location) -> bool {
+			false
+		}
+
+		fn consume(_: &Location, _: Weight) {}
+
+		fn weight() -> Weight {
+			Weight::from_parts(5, 5)
+		}
+	}
+	type Weigher = RateLimitedWeightBounds<<TestConfig as Config>::Weigher, Limits>;
+
+	MaxInstructions::set(10);
+	let mut message = Xcm(vec![ClearOrigin; 2]);
+	assert_eq!(
+		Weigher::weight(&mut message, Weight::MAX),
+		Ok(UnitWeightCost::get() * 2 + Weight::from_parts(5, 5))
+	);
+	assert_eq!(Weigher::instr_weight(&mut ClearOrigin), Ok(UnitWeightCost::get()));
+
+	// The weight of the limits counts towards the weight limit.
+	assert_eq!(
+		Weigher::weight(&mut message, UnitWeightCost::get() * 2),
+		Err(InstructionError {
+			index: 1,
+			error: XcmError::WeightLimitReached(UnitWeightCost::get() * 2)
+		})
+	);
+	assert_eq!(
+		Weigher::weight(&mut Xcm(vec![]), Weight::from_parts(4, 4)),
+		Err(InstructionError {
+			index: 0,
+			error: XcmError::WeightLimitReached(Weight::from_parts(5, 5))
+		})
+	);
+}
+
 #[test]
 fn weight_trader_tuple_should_work() {
 	let para_1: Location = Parachain(1).into();
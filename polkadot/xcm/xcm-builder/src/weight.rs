@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
+use crate::RateLimits;
 use codec::Decode;
 use core::{marker::PhantomData, result::Result};
 use frame_support::{
@@ -69,6 +70,28 @@ impl<T: Get<Weight>, C: Decode + GetDispatchInfo, M: Get<u32>> WeightBounds<C>
 	}
 }
 
+/// Weighs messages with `Bounds` and adds the weight of `Limits` to them.
+///
+/// Use it as the weigher of an executor whose barrier includes a [`crate::RateLimitedBarrier`], so
+/// that the storage accesses of its quotas are charged with the messages.
+pub struct RateLimitedWeightBounds<Bounds, Limits>(PhantomData<(Bounds, Limits)>);
+impl<C, Bounds: WeightBounds<C>, Limits: RateLimits> WeightBounds<C>
+	for RateLimitedWeightBounds<Bounds, Limits>
+{
+	fn weight(message: &mut Xcm<C>, weight_limit: Weight) -> Result<Weight, InstructionError> {
+		let limits_weight = Limits::weight();
+		let weight = Bounds::weight(message, weight_limit.saturating_sub(limits_weight))?
+			.saturating_add(limits_weight);
+		if weight.any_gt(weight_limit) {
+			return Err(InstructionError { index: 0, error: XcmError::WeightLimitReached(weight) })
+		}
+		Ok(weight)
+	}
+	fn instr_weight(instruction: &mut Instruction<C>) -> Result<Weight, XcmError> {
+		Bounds::instr_weight(instruction)
+	}
+}
+
 impl<T: Get<Weight>, C: Decode + GetDispatchInfo, M> FixedWeightBounds<T, C, M> {
 	fn weight_with_limit(
 		message: &mut Xcm<C>,
@@ -46,6 +46,8 @@ mod assets;
 pub use assets::AssetsInHolding;
 mod config;
 pub use config::Config;
+pub mod trace;
+use trace::{AssetsDelta, InstructionTrace, Program};
 
 #[cfg(test)]
 mod tests;
@@ -100,6 +102,8 @@ pub struct XcmExecutor<Config: config::Config> {
 	message_weight: Weight,
 	asset_claimer: Option<Location>,
 	already_paid_fees: bool,
+	/// The program being processed, see [`trace`].
+	program: Program,
 	_config: PhantomData<Config>,
 }
 
@@ -381,6 +385,7 @@ impl<Config: config::Config> XcmExecutor<Config> {
 			message_weight: Weight::zero(),
 			asset_claimer: None,
 			already_paid_fees: false,
+			program: Program::Message,
 			_config: PhantomData,
 		}
 	}
@@ -494,6 +499,7 @@ impl<Config: config::Config> XcmExecutor<Config> {
 		let mut r = Xcm::<Config::RuntimeCall>(vec![]);
 		core::mem::swap(&mut self.error_handler, &mut r);
 		self.error_handler_weight = Weight::zero();
+		self.program = Program::ErrorHandler;
 		r
 	}
 
@@ -509,6 +515,7 @@ impl<Config: config::Config> XcmExecutor<Config> {
 		let mut r = Xcm::<Config::RuntimeCall>(vec![]);
 		core::mem::swap(&mut self.appendix, &mut r);
 		self.appendix_weight = Weight::zero();
+		self.program = Program::Appendix;
 		r
 	}
 
@@ -871,7 +878,11 @@ impl<Config: config::Config> XcmExecutor<Config> {
 							});
 						}
 
-						self.process_instruction(instr)
+						if trace::is_recording() {
+							self.process_and_trace_instruction(i as u32, instr)
+						} else {
+							self.process_instruction(instr)
+						}
 					});
 					if let Err(error) = inst_res {
 						tracing::debug!(
@@ -899,6 +910,35 @@ impl<Config: config::Config> XcmExecutor<Config> {
 		result
 	}
 
+	/// Process a single XCM instruction and record its trace, see [`trace::record`].
+	fn process_and_trace_instruction(
+		&mut self,
+		index: u32,
+		instr: Instruction<Config::RuntimeCall>,
+	) -> Result<(), XcmError> {
+		// The top-level instructions are processed with a recursion count of 2.
+		let depth = recursion_count::with(|count| count.saturating_sub(2)).unwrap_or_default();
+		let origin = self.context.origin.clone().map(Into::into);
+		let weight = Config::Weigher::instr_weight(&mut instr.clone()).unwrap_or_default();
+		let (holding, fees, total_surplus) =
+			(self.holding.clone(), self.fees.clone(), self.total_surplus);
+
+		let result = self.process_instruction(instr);
+
+		let surplus = self.total_surplus.saturating_sub(total_surplus);
+		trace::push(InstructionTrace {
+			program: self.program,
+			index,
+			depth,
+			origin,
+			holding: AssetsDelta::between(&holding, &self.holding),
+			fees: AssetsDelta::between(&fees, &self.fees),
+			weight_used: weight.saturating_sub(surplus),
+			error: result.err(),
+		});
+		result
+	}
+
 	/// Process a single XCM instruction, mutating the state of the XCM virtual machine.
 	fn process_instruction(
 		&mut self,
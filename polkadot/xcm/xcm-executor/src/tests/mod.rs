@@ -24,3 +24,4 @@ mod initiate_transfer;
 mod mock;
 mod pay_fees;
 mod set_asset_claimer;
+mod trace;
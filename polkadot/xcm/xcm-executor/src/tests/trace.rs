@@ -0,0 +1,106 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Unit tests related to the tracing of the executed instructions.
+
+use codec::Encode;
+use xcm::prelude::*;
+
+use super::mock::*;
+use crate::{
+	trace::{self, AssetsDelta, Program},
+	XcmExecutor,
+};
+
+#[test]
+fn instructions_are_traced() {
+	let sender = Location::new(0, [AccountId32 { id: [0; 32], network: None }]);
+
+	// Make sure the user has enough funds to withdraw.
+	add_asset(sender.clone(), (Here, 100u128));
+
+	// Build xcm.
+	let xcm = Xcm::<TestCall>::builder_unsafe()
+		.withdraw_asset((Here, 100u128))
+		.pay_fees((Here, 10u128))
+		.set_error_handler(Xcm(vec![ClearOrigin]))
+		.trap(7u64)
+		.build();
+
+	let mut hash = xcm.using_encoded(sp_io::hashing::blake2_256);
+	let (outcome, traces) = trace::record(|| {
+		XcmExecutor::<XcmConfig>::prepare_and_execute(
+			sender.clone(),
+			xcm,
+			&mut hash,
+			Weight::MAX,
+			Weight::zero(),
+		)
+	});
+	assert!(matches!(outcome, Outcome::Incomplete { .. }));
+
+	let steps = traces
+		.iter()
+		.map(|trace| (trace.program, trace.index, trace.depth, trace.error))
+		.collect::<Vec<_>>();
+	assert_eq!(
+		steps,
+		vec![
+			(Program::Message, 0, 0, None),
+			(Program::Message, 1, 0, None),
+			(Program::Message, 2, 0, None),
+			(Program::Message, 3, 0, Some(XcmError::Trap(7))),
+			(Program::ErrorHandler, 0, 0, None),
+		]
+	);
+	assert!(traces.iter().all(|trace| trace.origin == Some(sender.clone().into())));
+	assert!(traces.iter().all(|trace| trace.weight_used == Weight::from_parts(2, 2)));
+
+	// The withdrawn assets are moved to the holding register...
+	assert_eq!(
+		traces[0].holding,
+		AssetsDelta { added: (Here, 100u128).into(), removed: Assets::new().into() }
+	);
+	// ...and the fees are taken from it.
+	assert_eq!(traces[1].holding.removed, (Here, 10u128).into());
+	assert_eq!(traces[1].fees.removed, Assets::new().into());
+	assert_eq!(traces[2].holding, AssetsDelta::default());
+
+	// The traces can be converted to older versions.
+	let trace = traces[0].clone().into_version(4).unwrap();
+	assert_eq!(trace.origin, Some(VersionedLocation::V4(sender.try_into().unwrap())));
+	assert_eq!(
+		trace.holding.added,
+		VersionedAssets::V4((xcm::v4::Junctions::Here, 100u128).into())
+	);
+}
+
+#[test]
+fn instructions_are_not_traced_by_default() {
+	let sender = Location::new(0, [AccountId32 { id: [0; 32], network: None }]);
+	let xcm = Xcm::<TestCall>::builder_unsafe().clear_origin().build();
+
+	let mut hash = xcm.using_encoded(sp_io::hashing::blake2_256);
+	let outcome = XcmExecutor::<XcmConfig>::prepare_and_execute(
+		sender,
+		xcm,
+		&mut hash,
+		Weight::MAX,
+		Weight::zero(),
+	);
+	assert!(matches!(outcome, Outcome::Complete { .. }));
+	assert!(!trace::is_recording());
+}
@@ -0,0 +1,150 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tracing of the instructions executed by the [`XcmExecutor`](crate::XcmExecutor).
+//!
+//! Meant for dry-running only, the instructions are not traced unless [`record`] is used. The
+//! traces are returned by the dry-run runtime API, so their locations and assets are versioned.
+
+use crate::AssetsInHolding;
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_weights::Weight;
+use xcm::{
+	latest::{Asset, Assets},
+	v5::Error as XcmError,
+	IntoVersion, Version as XcmVersion, VersionedAssets, VersionedLocation,
+};
+
+environmental::environmental!(recorder: Vec<InstructionTrace>);
+
+/// The program an instruction is part of.
+#[derive(Copy, Clone, Debug, Default, Encode, Decode, Eq, PartialEq, TypeInfo)]
+pub enum Program {
+	/// The executed message.
+	#[default]
+	Message,
+	/// The error handler, executed after an instruction failed.
+	ErrorHandler,
+	/// The appendix, executed last.
+	Appendix,
+}
+
+/// The assets added to and removed from a register by an instruction.
+#[derive(Clone, Debug, Encode, Decode, Eq, PartialEq, TypeInfo)]
+pub struct AssetsDelta {
+	/// The assets added to the register.
+	pub added: VersionedAssets,
+	/// The assets removed from the register.
+	pub removed: VersionedAssets,
+}
+
+impl Default for AssetsDelta {
+	fn default() -> Self {
+		Self { added: Assets::new().into(), removed: Assets::new().into() }
+	}
+}
+
+impl AssetsDelta {
+	/// Compute the delta of a register from its content `before` and `after` an instruction.
+	pub(crate) fn between(before: &AssetsInHolding, after: &AssetsInHolding) -> Self {
+		let mut added = Vec::<Asset>::new();
+		let mut removed = Vec::<Asset>::new();
+
+		for (id, &amount) in &after.fungible {
+			let previous = before.fungible.get(id).copied().unwrap_or_default();
+			if amount > previous {
+				added.push((id.clone(), amount - previous).into());
+			}
+		}
+		for (id, &amount) in &before.fungible {
+			let current = after.fungible.get(id).copied().unwrap_or_default();
+			if amount > current {
+				removed.push((id.clone(), amount - current).into());
+			}
+		}
+		for (id, instance) in after.non_fungible.difference(&before.non_fungible) {
+			added.push((id.clone(), *instance).into());
+		}
+		for (id, instance) in before.non_fungible.difference(&after.non_fungible) {
+			removed.push((id.clone(), *instance).into());
+		}
+
+		Self { added: Assets::from(added).into(), removed: Assets::from(removed).into() }
+	}
+
+	/// Convert the assets to the given XCM `version`.
+	pub fn into_version(self, version: XcmVersion) -> Result<Self, ()> {
+		Ok(Self {
+			added: self.added.into_version(version)?,
+			removed: self.removed.into_version(version)?,
+		})
+	}
+}
+
+/// The trace of an executed instruction.
+#[derive(Clone, Debug, Encode, Decode, Eq, PartialEq, TypeInfo)]
+pub struct InstructionTrace {
+	/// The program the instruction is part of.
+	pub program: Program,
+	/// The index of the instruction in its program.
+	pub index: u32,
+	/// The number of executions the instruction is nested in, such as the execution of a message
+	/// by a `Transact` instruction.
+	pub depth: u8,
+	/// The origin the instruction was executed with.
+	pub origin: Option<VersionedLocation>,
+	/// The changes of the holding register.
+	pub holding: AssetsDelta,
+	/// The changes of the fees register.
+	pub fees: AssetsDelta,
+	/// The weight used by the instruction.
+	pub weight_used: Weight,
+	/// The error of the instruction, if it failed.
+	///
+	/// Errors are not versioned, so they are always the ones of XCM v5.
+	pub error: Option<XcmError>,
+}
+
+impl InstructionTrace {
+	/// Convert the origin and the assets of the trace to the given XCM `version`.
+	pub fn into_version(self, version: XcmVersion) -> Result<Self, ()> {
+		Ok(Self {
+			origin: self.origin.map(|origin| origin.into_version(version)).transpose()?,
+			holding: self.holding.into_version(version)?,
+			fees: self.fees.into_version(version)?,
+			..self
+		})
+	}
+}
+
+/// Execute `f`, recording the traces of the instructions executed.
+pub fn record<R>(f: impl FnOnce() -> R) -> (R, Vec<InstructionTrace>) {
+	let mut traces = Vec::new();
+	let result = recorder::using(&mut traces, f);
+	(result, traces)
+}
+
+/// Whether the executed instructions are recorded.
+pub(crate) fn is_recording() -> bool {
+	recorder::with(|_| ()).is_some()
+}
+
+/// Record the trace of an executed instruction.
+pub(crate) fn push(trace: InstructionTrace) {
+	recorder::with(|traces| traces.push(trace));
+}
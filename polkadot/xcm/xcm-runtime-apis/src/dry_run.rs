@@ -22,6 +22,7 @@ use alloc::vec::Vec;
 use codec::{Decode, Encode};
 use frame_support::pallet_prelude::{DispatchResultWithPostInfo, TypeInfo};
 use xcm::prelude::*;
+use xcm_executor::trace::InstructionTrace;
 
 /// Effects of dry-running an extrinsic.
 #[derive(Encode, Decode, Debug, TypeInfo)]
@@ -47,6 +48,16 @@ pub struct XcmDryRunEffects<Event> {
 	pub forwarded_xcms: Vec<(VersionedLocation, Vec<VersionedXcm<()>>)>,
 }
 
+/// Effects of a dry-run along with the trace of the XCM instructions it executed.
+#[derive(Encode, Decode, Debug, TypeInfo)]
+pub struct TracedDryRunEffects<Effects> {
+	/// The effects of the dry-run.
+	pub effects: Effects,
+	/// The trace of every XCM instruction executed, in order, including the ones of nested
+	/// programs.
+	pub trace: Vec<InstructionTrace>,
+}
+
 sp_api::decl_runtime_apis! {
 	/// API for dry-running extrinsics and XCM programs to get the programs that need to be passed to the fees API.
 	///
@@ -73,6 +84,14 @@ sp_api::decl_runtime_apis! {
 
 		/// Dry run XCM program
 		fn dry_run_xcm(origin_location: VersionedLocation, xcm: VersionedXcm<Call>) -> Result<XcmDryRunEffects<Event>, Error>;
+
+		/// Dry run call, tracing the XCM instructions executed.
+		#[api_version(3)]
+		fn dry_run_call_with_trace(origin: OriginCaller, call: Call, result_xcms_version: XcmVersion) -> Result<TracedDryRunEffects<CallDryRunEffects<Event>>, Error>;
+
+		/// Dry run XCM program, tracing the instructions executed.
+		#[api_version(3)]
+		fn dry_run_xcm_with_trace(origin_location: VersionedLocation, xcm: VersionedXcm<Call>) -> Result<TracedDryRunEffects<XcmDryRunEffects<Event>>, Error>;
 	}
 }
 
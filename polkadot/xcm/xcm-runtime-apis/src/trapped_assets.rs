@@ -0,0 +1,56 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Contains runtime APIs for querying the assets trapped by XCM programs.
+
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
+use frame_support::pallet_prelude::TypeInfo;
+use sp_core::H256;
+use xcm::{latest::XcmHash, VersionedAssets, VersionedLocation};
+
+/// Assets trapped by an origin, which can be claimed back with a `ClaimAsset` instruction.
+#[derive(Clone, Debug, Encode, Decode, Eq, PartialEq, TypeInfo)]
+pub struct TrappedAssets {
+	/// The hash identifying the trap, as in the `AssetsTrapped` event.
+	pub hash: H256,
+	/// The trapped assets, in the version they were trapped with.
+	pub assets: VersionedAssets,
+	/// The number of times the assets were trapped, each of them can be claimed once.
+	pub count: u32,
+	/// The block number the assets were last trapped at.
+	pub block_number: u64,
+	/// The topic of the message that last trapped the assets, or its id if it had none.
+	pub topic: XcmHash,
+}
+
+sp_api::decl_runtime_apis! {
+	/// API for querying the assets trapped by XCM programs.
+	pub trait TrappedAssetsApi {
+		/// Returns the assets trapped by `origin`.
+		///
+		/// Only the assets trapped while the index of the trapped assets was enabled are returned.
+		fn trapped_assets(origin: VersionedLocation) -> Result<Vec<TrappedAssets>, Error>;
+	}
+}
+
+/// `TrappedAssetsApi` Runtime APIs errors.
+#[derive(Copy, Clone, Encode, Decode, Eq, PartialEq, Debug, TypeInfo)]
+pub enum Error {
+	/// Converting a location from one version to another failed.
+	#[codec(index = 0)]
+	LocationVersionConversionFailed,
+}
@@ -20,8 +20,9 @@ use frame_support::sp_runtime::testing::H256;
 use frame_system::RawOrigin;
 use sp_api::ProvideRuntimeApi;
 use xcm::prelude::*;
+use xcm_executor::trace::Program;
 use xcm_runtime_apis::{
-	dry_run::{CallDryRunEffects, DryRunApi},
+	dry_run::{CallDryRunEffects, DryRunApi, TracedDryRunEffects},
 	fees::XcmPaymentApi,
 };
 
@@ -443,6 +444,66 @@ fn dry_run_xcm_versions() {
 	}
 }
 
+#[test]
+fn dry_run_xcm_with_trace_works() {
+	sp_tracing::init_for_tests();
+	let who = 1; // AccountId = u64.
+	let origin = Location::new(0, [AccountIndex64 { index: who, network: None }]);
+	let amount = 100u128;
+	let xcm = Xcm::<RuntimeCall>::builder_unsafe()
+		.withdraw_asset((Here, amount))
+		.clear_origin()
+		.buy_execution((Here, amount), Unlimited)
+		.deposit_asset(AllCounted(1), AccountIndex64 { index: 2, network: None })
+		.build();
+	let balances = vec![(who, amount + ExistentialDeposit::get())];
+	new_test_ext_with_balances(balances.clone()).execute_with(|| {
+		let client = TestClient;
+		let runtime_api = client.runtime_api();
+		let TracedDryRunEffects { effects, trace } = runtime_api
+			.dry_run_xcm_with_trace(
+				H256::zero(),
+				VersionedLocation::from(origin.clone()),
+				VersionedXcm::from(xcm.clone()),
+			)
+			.unwrap()
+			.unwrap();
+		assert!(matches!(effects.execution_result, Outcome::Complete { .. }));
+
+		let steps = trace
+			.iter()
+			.map(|step| (step.program, step.index, step.origin.clone(), step.error))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			steps,
+			vec![
+				(Program::Message, 0, Some(origin.clone().into()), None),
+				(Program::Message, 1, Some(origin.clone().into()), None),
+				(Program::Message, 2, None, None),
+				(Program::Message, 3, None, None),
+			]
+		);
+		assert_eq!(trace[0].holding.added, (Here, amount).into());
+		assert_eq!(Assets::try_from(trace[3].holding.removed.clone()).unwrap().len(), 1);
+	});
+
+	// The trace uses the version of the dry-run program.
+	new_test_ext_with_balances(balances).execute_with(|| {
+		let client = TestClient;
+		let runtime_api = client.runtime_api();
+		let TracedDryRunEffects { trace, .. } = runtime_api
+			.dry_run_xcm_with_trace(
+				H256::zero(),
+				VersionedLocation::from(origin.clone()),
+				VersionedXcm::from(xcm).into_version(4).unwrap(),
+			)
+			.unwrap()
+			.unwrap();
+		assert!(matches!(trace[0].origin, Some(VersionedLocation::V4(_))));
+		assert!(matches!(trace[0].holding.added, VersionedAssets::V4(_)));
+	});
+}
+
 #[test]
 fn calling_payment_api_with_a_lower_version_works() {
 	let transfer_amount = 100u128;
@@ -45,7 +45,10 @@ use xcm_executor::{
 
 use xcm_runtime_apis::{
 	conversions::{Error as LocationToAccountApiError, LocationToAccountApi},
-	dry_run::{CallDryRunEffects, DryRunApi, Error as XcmDryRunApiError, XcmDryRunEffects},
+	dry_run::{
+		CallDryRunEffects, DryRunApi, Error as XcmDryRunApiError, TracedDryRunEffects,
+		XcmDryRunEffects,
+	},
 	fees::{Error as XcmPaymentApiError, XcmPaymentApi},
 	trusted_query::{Error as TrustedQueryApiError, TrustedQueryApi},
 };
@@ -519,5 +522,17 @@ sp_api::mock_impl_runtime_apis! {
 		fn dry_run_xcm(origin_location: VersionedLocation, xcm: VersionedXcm<RuntimeCall>) -> Result<XcmDryRunEffects<RuntimeEvent>, XcmDryRunApiError> {
 			pallet_xcm::Pallet::<TestRuntime>::dry_run_xcm::<TestRuntime, XcmRouter, RuntimeCall, XcmConfig>(origin_location, xcm)
 		}
+
+		fn dry_run_call_with_trace(
+			origin: OriginCaller,
+			call: RuntimeCall,
+			result_xcms_version: XcmVersion,
+		) -> Result<TracedDryRunEffects<CallDryRunEffects<RuntimeEvent>>, XcmDryRunApiError> {
+			pallet_xcm::Pallet::<TestRuntime>::dry_run_call_with_trace::<TestRuntime, XcmRouter, OriginCaller, RuntimeCall>(origin, call, result_xcms_version)
+		}
+
+		fn dry_run_xcm_with_trace(origin_location: VersionedLocation, xcm: VersionedXcm<RuntimeCall>) -> Result<TracedDryRunEffects<XcmDryRunEffects<RuntimeEvent>>, XcmDryRunApiError> {
+			pallet_xcm::Pallet::<TestRuntime>::dry_run_xcm_with_trace::<TestRuntime, XcmRouter, RuntimeCall, XcmConfig>(origin_location, xcm)
+		}
 	}
 }
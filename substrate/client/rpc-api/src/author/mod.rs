@@ -59,6 +59,11 @@ pub trait AuthorApi<Hash, BlockHash> {
 	#[method(name = "author_pendingExtrinsics")]
 	fn pending_extrinsics(&self) -> Result<Vec<Bytes>, Error>;
 
+	/// Returns all extrinsics that are not ready for inclusion yet, e.g. because they depend on a
+	/// transaction with a lower nonce that is not in the pool.
+	#[method(name = "author_futureExtrinsics", with_extensions)]
+	fn future_extrinsics(&self) -> Result<Vec<Bytes>, Error>;
+
 	/// Remove given extrinsic from the pool and temporarily ban it to prevent reimporting.
 	#[method(name = "author_removeExtrinsic", with_extensions)]
 	async fn remove_extrinsic(
@@ -153,6 +153,11 @@ where
 		Ok(self.pool.ready().map(|tx| tx.data().encode().into()).collect())
 	}
 
+	fn future_extrinsics(&self, ext: &Extensions) -> Result<Vec<Bytes>> {
+		check_if_safe(ext)?;
+		Ok(self.pool.futures().into_iter().map(|tx| tx.data().encode().into()).collect())
+	}
+
 	async fn remove_extrinsic(
 		&self,
 		ext: &Extensions,
@@ -182,6 +182,32 @@ async fn author_should_return_pending_extrinsics() {
 	assert_eq!(pending, vec![xt_bytes]);
 }
 
+#[tokio::test]
+async fn author_should_return_future_extrinsics() {
+	let setup = TestSetup::default();
+	let api = setup.to_rpc();
+
+	let ready_bytes: Bytes = uxt(Sr25519Keyring::Alice, 0).encode().into();
+	let future_bytes: Bytes = uxt(Sr25519Keyring::Alice, 2).encode().into();
+	for xt in [&ready_bytes, &future_bytes] {
+		api.call::<_, H256>("author_submitExtrinsic", [to_hex(xt, true)]).await.unwrap();
+	}
+
+	let pending: Vec<Bytes> =
+		api.call("author_pendingExtrinsics", EmptyParams::new()).await.unwrap();
+	assert_eq!(pending, vec![ready_bytes]);
+	let future: Vec<Bytes> = api.call("author_futureExtrinsics", EmptyParams::new()).await.unwrap();
+	assert_eq!(future, vec![future_bytes]);
+
+	// The future extrinsics are only exposed to unsafe calls.
+	let mut api = setup.to_rpc();
+	api.extensions_mut().insert(DenyUnsafe::Yes);
+	assert!(api
+		.call::<_, Vec<Bytes>>("author_futureExtrinsics", EmptyParams::new())
+		.await
+		.is_err());
+}
+
 #[tokio::test]
 async fn author_should_remove_extrinsics() {
 	const METHOD: &'static str = "author_removeExtrinsic";
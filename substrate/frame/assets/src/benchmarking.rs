@@ -595,5 +595,47 @@ benchmarks_instance_pallet! {
 		assert_eq!(amount, 100u32.into());
 	}
 
+	metadata {
+		let (asset_id, caller, _) = create_default_asset::<T, I>(true);
+		T::Currency::make_free_balance_be(&caller, DepositBalanceOf::<T, I>::max_value());
+		let dummy = vec![0u8; T::StringLimit::get() as usize];
+		let origin = SystemOrigin::Signed(caller.clone()).into();
+		Assets::<T, I>::set_metadata(origin, asset_id.clone(), dummy.clone(), dummy, 12)?;
+		let metadata;
+	}: {
+		metadata = Metadata::<T, I>::get(asset_id.into());
+	} verify {
+		assert_eq!(metadata.decimals, 12);
+	}
+
+	nonces {
+		let (asset_id, caller, _) = create_default_asset::<T, I>(true);
+		PermitNonces::<T, I>::insert(asset_id.clone().into(), &caller, 1);
+		let nonce;
+	}: {
+		nonce = PermitNonces::<T, I>::get(asset_id.into(), &caller);
+	} verify {
+		assert_eq!(nonce, 1);
+	}
+
+	// The storage accesses of an EIP-2612 `permit` of the assets precompile. The hashing and
+	// signature recovery are charged separately by the precompile.
+	permit {
+		let (asset_id, caller, _) = create_default_minted_asset::<T, I>(true, 100u32.into());
+		T::Currency::make_free_balance_be(&caller, DepositBalanceOf::<T, I>::max_value());
+
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+		let amount = 100u32.into();
+		let asset_id: T::AssetId = asset_id.into();
+	}: {
+		let nonce = PermitNonces::<T, I>::get(asset_id.clone(), &caller);
+		Assets::<T, I>::use_permit_nonce(asset_id.clone(), &caller, nonce)?;
+		Assets::<T, I>::do_approve_transfer(asset_id.clone(), &caller, &delegate, amount)?;
+	} verify {
+		assert_eq!(PermitNonces::<T, I>::get(asset_id.clone(), &caller), 1);
+		assert!(!T::Currency::reserved_balance(&caller).is_zero());
+		assert_last_event::<T, I>(Event::ApprovedTransfer { asset_id, source: caller, delegate, amount }.into());
+	}
+
 	impl_benchmark_test_suite!(Assets, crate::mock::new_test_ext(), crate::mock::Test)
 }
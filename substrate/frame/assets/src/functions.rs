@@ -861,13 +861,17 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 
 	/// Destroy approvals associated with a given asset up to the max (T::RemoveItemsLimit).
 	///
+	/// The permit nonces of the asset are removed once all approvals are destroyed and count
+	/// towards the same limit.
+	///
 	/// Each call emits the `Event::DestroyedApprovals` event
-	/// Returns the number of destroyed approvals.
+	/// Returns the number of destroyed approvals and permit nonces.
 	pub(super) fn do_destroy_approvals(
 		id: T::AssetId,
 		max_items: u32,
 	) -> Result<u32, DispatchError> {
 		let mut removed_approvals = 0;
+		let mut removed_nonces = 0;
 		Asset::<T, I>::try_mutate_exists(
 			id.clone(),
 			|maybe_details| -> Result<(), DispatchError> {
@@ -884,6 +888,16 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 						break
 					}
 				}
+				// The deposits of the permit nonces are returned once all approvals are gone.
+				if removed_approvals < max_items {
+					for (owner, _) in PermitNonces::<T, I>::drain_prefix(&id) {
+						T::Currency::unreserve(&owner, T::ApprovalDeposit::get());
+						removed_nonces = removed_nonces.saturating_add(1);
+						if removed_approvals.saturating_add(removed_nonces) >= max_items {
+							break
+						}
+					}
+				}
 				Self::deposit_event(Event::ApprovalsDestroyed {
 					asset_id: id,
 					approvals_destroyed: removed_approvals as u32,
@@ -892,7 +906,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 				Ok(())
 			},
 		)?;
-		Ok(removed_approvals)
+		Ok(removed_approvals.saturating_add(removed_nonces))
 	}
 
 	/// Complete destroying an asset and unreserve the deposit.
@@ -904,6 +918,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 			ensure!(details.status == AssetStatus::Destroying, Error::<T, I>::IncorrectStatus);
 			ensure!(details.accounts == 0, Error::<T, I>::InUse);
 			ensure!(details.approvals == 0, Error::<T, I>::InUse);
+			ensure!(PermitNonces::<T, I>::iter_prefix(&id).next().is_none(), Error::<T, I>::InUse);
 			ensure!(T::CallbackHandle::destroyed(&id).is_ok(), Error::<T, I>::CallbackFailed);
 
 			let metadata = Metadata::<T, I>::take(&id);
@@ -917,6 +932,43 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		})
 	}
 
+	/// Uses the EIP-2612 permit `nonce` of `owner` for asset `id`.
+	///
+	/// The first permit of `owner` reserves `T::ApprovalDeposit` from it for its nonce, which is
+	/// only returned once the asset is destroyed.
+	pub(super) fn use_permit_nonce(
+		id: T::AssetId,
+		owner: &T::AccountId,
+		nonce: u64,
+	) -> DispatchResult {
+		if !PermitNonces::<T, I>::contains_key(&id, owner) {
+			T::Currency::reserve(owner, T::ApprovalDeposit::get())?;
+		}
+		PermitNonces::<T, I>::insert(id, owner, nonce.saturating_add(1));
+		Ok(())
+	}
+
+	/// Sets the approval from `owner` to spend `amount` of asset `id` tokens by `delegate`.
+	///
+	/// Unlike [`Self::do_approve_transfer`], an existing approval is replaced instead of being
+	/// added to.
+	pub(super) fn do_set_approval(
+		id: T::AssetId,
+		owner: &T::AccountId,
+		delegate: &T::AccountId,
+		amount: T::Balance,
+	) -> DispatchResult {
+		if let Some(approval) = Approvals::<T, I>::take((id.clone(), owner, delegate)) {
+			T::Currency::unreserve(owner, approval.deposit);
+			Asset::<T, I>::mutate(&id, |maybe_details| {
+				if let Some(details) = maybe_details {
+					details.approvals.saturating_dec();
+				}
+			});
+		}
+		Self::do_approve_transfer(id, owner, delegate, amount)
+	}
+
 	/// Creates an approval from `owner` to spend `amount` of asset `id` tokens by 'delegate'
 	/// while reserving `T::ApprovalDeposit` from owner
 	///
@@ -385,7 +385,8 @@ pub mod pallet {
 		#[pallet::no_default_bounds]
 		type MetadataDepositPerByte: Get<DepositBalanceOf<Self, I>>;
 
-		/// The amount of funds that must be reserved when creating a new approval.
+		/// The amount of funds that must be reserved when creating a new approval, or the permit
+		/// nonce of an account.
 		#[pallet::constant]
 		#[pallet::no_default_bounds]
 		type ApprovalDeposit: Get<DepositBalanceOf<Self, I>>;
@@ -465,6 +466,22 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	#[pallet::storage]
+	/// The nonces of the EIP-2612 permits signed by an account for an asset.
+	/// First key is the asset ID, second key is the owner.
+	///
+	/// The first permit of an account reserves `ApprovalDeposit` from it, which is returned when
+	/// the nonce is removed by `destroy_approvals`.
+	pub type PermitNonces<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AssetId,
+		Blake2_128Concat,
+		T::AccountId,
+		u64,
+		ValueQuery,
+	>;
+
 	/// The asset ID enforced for the next asset creation, if any present. Otherwise, this storage
 	/// item has no effect.
 	///
@@ -15,17 +15,22 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{weights::WeightInfo, Call, Config, PhantomData, TransferFlags};
-use alloc::vec::Vec;
+use crate::{
+	weights::WeightInfo, Call, Config, Metadata, PermitNonces, PhantomData, TransferFlags,
+};
+use alloc::{string::String, vec::Vec};
 use ethereum_standards::{
+	IERC20Metadata::{self, IERC20MetadataCalls},
+	IERC20Permit::{self, IERC20PermitCalls},
 	IERC20,
 	IERC20::{IERC20Calls, IERC20Events},
 };
+use frame_support::traits::Get;
 use pallet_revive::precompiles::{
 	alloy::{
 		self,
 		primitives::IntoLogData,
-		sol_types::{Revert, SolCall},
+		sol_types::{self, Revert, SolCall, SolInterface, SolValue},
 	},
 	AddressMapper, AddressMatcher, Error, Ext, Precompile, RuntimeCosts, H160, H256,
 };
@@ -66,6 +71,95 @@ impl<const P: u16> AssetPrecompileConfig for InlineIdConfig<P> {
 	type AssetIdExtractor = InlineAssetIdExtractor;
 }
 
+/// The calls of the interfaces implemented by the [`ERC20`] precompile.
+///
+/// The `sol!` macro does not merge inherited interfaces, so the calls of [`IERC20`],
+/// [`IERC20Metadata`] and [`IERC20Permit`] are combined here.
+pub enum ERC20Calls {
+	IERC20(IERC20Calls),
+	IERC20Metadata(IERC20MetadataCalls),
+	IERC20Permit(IERC20PermitCalls),
+}
+
+impl SolInterface for ERC20Calls {
+	const NAME: &'static str = "ERC20Calls";
+	const MIN_DATA_LENGTH: usize = {
+		let (a, b, c) = (
+			IERC20Calls::MIN_DATA_LENGTH,
+			IERC20MetadataCalls::MIN_DATA_LENGTH,
+			IERC20PermitCalls::MIN_DATA_LENGTH,
+		);
+		let min = if a < b { a } else { b };
+		if min < c {
+			min
+		} else {
+			c
+		}
+	};
+	const COUNT: usize = IERC20Calls::COUNT + IERC20MetadataCalls::COUNT + IERC20PermitCalls::COUNT;
+
+	fn selector(&self) -> [u8; 4] {
+		match self {
+			Self::IERC20(call) => call.selector(),
+			Self::IERC20Metadata(call) => call.selector(),
+			Self::IERC20Permit(call) => call.selector(),
+		}
+	}
+
+	fn selector_at(i: usize) -> Option<[u8; 4]> {
+		if i < IERC20Calls::COUNT {
+			return IERC20Calls::selector_at(i)
+		}
+		let i = i - IERC20Calls::COUNT;
+		if i < IERC20MetadataCalls::COUNT {
+			return IERC20MetadataCalls::selector_at(i)
+		}
+		IERC20PermitCalls::selector_at(i - IERC20MetadataCalls::COUNT)
+	}
+
+	fn valid_selector(selector: [u8; 4]) -> bool {
+		IERC20Calls::valid_selector(selector) ||
+			IERC20MetadataCalls::valid_selector(selector) ||
+			IERC20PermitCalls::valid_selector(selector)
+	}
+
+	fn abi_decode_raw(selector: [u8; 4], data: &[u8]) -> sol_types::Result<Self> {
+		if IERC20Calls::valid_selector(selector) {
+			IERC20Calls::abi_decode_raw(selector, data).map(Self::IERC20)
+		} else if IERC20MetadataCalls::valid_selector(selector) {
+			IERC20MetadataCalls::abi_decode_raw(selector, data).map(Self::IERC20Metadata)
+		} else {
+			IERC20PermitCalls::abi_decode_raw(selector, data).map(Self::IERC20Permit)
+		}
+	}
+
+	fn abi_decode_raw_validate(selector: [u8; 4], data: &[u8]) -> sol_types::Result<Self> {
+		if IERC20Calls::valid_selector(selector) {
+			IERC20Calls::abi_decode_raw_validate(selector, data).map(Self::IERC20)
+		} else if IERC20MetadataCalls::valid_selector(selector) {
+			IERC20MetadataCalls::abi_decode_raw_validate(selector, data).map(Self::IERC20Metadata)
+		} else {
+			IERC20PermitCalls::abi_decode_raw_validate(selector, data).map(Self::IERC20Permit)
+		}
+	}
+
+	fn abi_encoded_size(&self) -> usize {
+		match self {
+			Self::IERC20(call) => call.abi_encoded_size(),
+			Self::IERC20Metadata(call) => call.abi_encoded_size(),
+			Self::IERC20Permit(call) => call.abi_encoded_size(),
+		}
+	}
+
+	fn abi_encode_raw(&self, out: &mut Vec<u8>) {
+		match self {
+			Self::IERC20(call) => call.abi_encode_raw(out),
+			Self::IERC20Metadata(call) => call.abi_encode_raw(out),
+			Self::IERC20Permit(call) => call.abi_encode_raw(out),
+		}
+	}
+}
+
 /// An ERC20 precompile.
 pub struct ERC20<Runtime, PrecompileConfig, Instance = ()> {
 	_phantom: PhantomData<(Runtime, PrecompileConfig, Instance)>,
@@ -85,7 +179,7 @@ where
 	alloy::primitives::U256: TryFrom<<Runtime as Config<Instance>>::Balance>,
 {
 	type T = Runtime;
-	type Interface = IERC20::IERC20Calls;
+	type Interface = ERC20Calls;
 	const MATCHER: AddressMatcher = PrecompileConfig::MATCHER;
 	const HAS_CONTRACT_INFO: bool = false;
 
@@ -97,18 +191,52 @@ where
 		let asset_id = PrecompileConfig::AssetIdExtractor::asset_id_from_address(address)?.into();
 
 		match input {
-			IERC20Calls::transfer(call) => Self::transfer(asset_id, call, env),
-			IERC20Calls::totalSupply(_) => Self::total_supply(asset_id, env),
-			IERC20Calls::balanceOf(call) => Self::balance_of(asset_id, call, env),
-			IERC20Calls::allowance(call) => Self::allowance(asset_id, call, env),
-			IERC20Calls::approve(call) => Self::approve(asset_id, call, env),
-			IERC20Calls::transferFrom(call) => Self::transfer_from(asset_id, call, env),
+			ERC20Calls::IERC20(call) => match call {
+				IERC20Calls::transfer(call) => Self::transfer(asset_id, call, env),
+				IERC20Calls::totalSupply(_) => Self::total_supply(asset_id, env),
+				IERC20Calls::balanceOf(call) => Self::balance_of(asset_id, call, env),
+				IERC20Calls::allowance(call) => Self::allowance(asset_id, call, env),
+				IERC20Calls::approve(call) => Self::approve(asset_id, call, env),
+				IERC20Calls::transferFrom(call) => Self::transfer_from(asset_id, call, env),
+			},
+			ERC20Calls::IERC20Metadata(call) => match call {
+				IERC20MetadataCalls::name(_) => Self::name(asset_id, env),
+				IERC20MetadataCalls::symbol(_) => Self::symbol(asset_id, env),
+				IERC20MetadataCalls::decimals(_) => Self::decimals(asset_id, env),
+			},
+			ERC20Calls::IERC20Permit(call) => match call {
+				IERC20PermitCalls::permit(call) => Self::permit(asset_id, address, call, env),
+				IERC20PermitCalls::nonces(call) => Self::nonces(asset_id, call, env),
+				IERC20PermitCalls::DOMAIN_SEPARATOR(_) =>
+					Self::domain_separator_call(asset_id, address, env),
+			},
 		}
 	}
 }
 
 const ERR_INVALID_CALLER: &str = "Invalid caller";
 const ERR_BALANCE_CONVERSION_FAILED: &str = "Balance conversion failed";
+const ERR_PERMIT_EXPIRED: &str = "Permit expired";
+const ERR_INVALID_SIGNATURE: &str = "Invalid signature";
+
+/// The EIP-712 type of the domain of the permits.
+const EIP712_DOMAIN_TYPE: &[u8] =
+	b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+
+/// The EIP-712 type of a permit.
+const PERMIT_TYPE: &[u8] =
+	b"Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)";
+
+/// The version of the EIP-712 domain of the permits.
+const PERMIT_VERSION: &[u8] = b"1";
+
+/// Half of the order of the secp256k1 curve, the upper bound of the `s` value of a signature.
+const SECP256K1_HALF_N: [u8; 32] = match alloy::hex::const_decode_to_array(
+	b"7fffffffffffffffffffffffffffffff5d576e7357a4501ddfe92f46681b20a0",
+) {
+	Ok(n) => n,
+	Err(_) => panic!("valid hex; qed"),
+};
 
 impl<Runtime, PrecompileConfig, Instance: 'static> ERC20<Runtime, PrecompileConfig, Instance>
 where
@@ -305,6 +433,171 @@ where
 
 		return Ok(IERC20::transferFromCall::abi_encode_returns(&true));
 	}
+
+	/// Execute the name call.
+	fn name(
+		asset_id: <Runtime as Config<Instance>>::AssetId,
+		env: &mut impl Ext<T = Runtime>,
+	) -> Result<Vec<u8>, Error> {
+		env.charge(<Runtime as Config<Instance>>::WeightInfo::metadata())?;
+		let name = Metadata::<Runtime, Instance>::get(asset_id).name;
+		let name = String::from_utf8_lossy(&name).into_owned();
+		return Ok(IERC20Metadata::nameCall::abi_encode_returns(&name));
+	}
+
+	/// Execute the symbol call.
+	fn symbol(
+		asset_id: <Runtime as Config<Instance>>::AssetId,
+		env: &mut impl Ext<T = Runtime>,
+	) -> Result<Vec<u8>, Error> {
+		env.charge(<Runtime as Config<Instance>>::WeightInfo::metadata())?;
+		let symbol = Metadata::<Runtime, Instance>::get(asset_id).symbol;
+		let symbol = String::from_utf8_lossy(&symbol).into_owned();
+		return Ok(IERC20Metadata::symbolCall::abi_encode_returns(&symbol));
+	}
+
+	/// Execute the decimals call.
+	fn decimals(
+		asset_id: <Runtime as Config<Instance>>::AssetId,
+		env: &mut impl Ext<T = Runtime>,
+	) -> Result<Vec<u8>, Error> {
+		env.charge(<Runtime as Config<Instance>>::WeightInfo::metadata())?;
+		let decimals = Metadata::<Runtime, Instance>::get(asset_id).decimals;
+		return Ok(IERC20Metadata::decimalsCall::abi_encode_returns(&decimals));
+	}
+
+	/// Execute the nonces call.
+	fn nonces(
+		asset_id: <Runtime as Config<Instance>>::AssetId,
+		call: &IERC20Permit::noncesCall,
+		env: &mut impl Ext<T = Runtime>,
+	) -> Result<Vec<u8>, Error> {
+		env.charge(<Runtime as Config<Instance>>::WeightInfo::nonces())?;
+		let owner = call.owner.into_array().into();
+		let owner = <Runtime as pallet_revive::Config>::AddressMapper::to_account_id(&owner);
+		let nonce = PermitNonces::<Runtime, Instance>::get(asset_id, owner);
+		return Ok(IERC20Permit::noncesCall::abi_encode_returns(&alloy::primitives::U256::from(
+			nonce,
+		)));
+	}
+
+	/// Execute the DOMAIN_SEPARATOR call.
+	fn domain_separator_call(
+		asset_id: <Runtime as Config<Instance>>::AssetId,
+		address: &[u8; 20],
+		env: &mut impl Ext<T = Runtime>,
+	) -> Result<Vec<u8>, Error> {
+		let separator = Self::domain_separator(asset_id, address, env)?.into();
+		return Ok(IERC20Permit::DOMAIN_SEPARATORCall::abi_encode_returns(&separator));
+	}
+
+	/// Hash `data` with keccak-256, charging the cost of the hash.
+	fn keccak_256(env: &mut impl Ext<T = Runtime>, data: &[u8]) -> Result<[u8; 32], Error> {
+		env.gas_meter_mut().charge(RuntimeCosts::HashKeccak256(data.len() as u32))?;
+		Ok(sp_io::hashing::keccak_256(data))
+	}
+
+	/// Compute the EIP-712 domain separator of the asset precompile at `address`.
+	fn domain_separator(
+		asset_id: <Runtime as Config<Instance>>::AssetId,
+		address: &[u8; 20],
+		env: &mut impl Ext<T = Runtime>,
+	) -> Result<[u8; 32], Error> {
+		env.charge(<Runtime as Config<Instance>>::WeightInfo::metadata())?;
+		let name = Metadata::<Runtime, Instance>::get(asset_id).name;
+
+		let type_hash = Self::keccak_256(env, EIP712_DOMAIN_TYPE)?;
+		let name_hash = Self::keccak_256(env, &name)?;
+		let version_hash = Self::keccak_256(env, PERMIT_VERSION)?;
+		let chain_id = <Runtime as pallet_revive::Config>::ChainId::get();
+		let domain = (
+			alloy::primitives::B256::from(type_hash),
+			alloy::primitives::B256::from(name_hash),
+			alloy::primitives::B256::from(version_hash),
+			alloy::primitives::U256::from(chain_id),
+			alloy::primitives::Address::from(*address),
+		)
+			.abi_encode();
+
+		Self::keccak_256(env, &domain)
+	}
+
+	/// Execute the permit call.
+	fn permit(
+		asset_id: <Runtime as Config<Instance>>::AssetId,
+		address: &[u8; 20],
+		call: &IERC20Permit::permitCall,
+		env: &mut impl Ext<T = Runtime>,
+	) -> Result<Vec<u8>, Error> {
+		env.charge(<Runtime as Config<Instance>>::WeightInfo::permit())?;
+
+		let now = alloy::primitives::U256::from_be_bytes(env.now().to_big_endian());
+		if call.deadline < now {
+			return Err(Error::Revert(Revert { reason: ERR_PERMIT_EXPIRED.into() }));
+		}
+
+		let owner = <Runtime as pallet_revive::Config>::AddressMapper::to_account_id(
+			&call.owner.into_array().into(),
+		);
+		let spender = <Runtime as pallet_revive::Config>::AddressMapper::to_account_id(
+			&call.spender.into_array().into(),
+		);
+		let nonce = PermitNonces::<Runtime, Instance>::get(asset_id.clone(), &owner);
+
+		let domain_separator = Self::domain_separator(asset_id.clone(), address, env)?;
+		let type_hash = Self::keccak_256(env, PERMIT_TYPE)?;
+		let permit = (
+			alloy::primitives::B256::from(type_hash),
+			call.owner,
+			call.spender,
+			call.value,
+			alloy::primitives::U256::from(nonce),
+			call.deadline,
+		)
+			.abi_encode();
+		let struct_hash = Self::keccak_256(env, &permit)?;
+		let digest =
+			Self::keccak_256(env, &[&b"\x19\x01"[..], &domain_separator, &struct_hash].concat())?;
+
+		// Only accept signatures in the lower half of the curve order, as `ecrecover` accepts
+		// both `s` and `n - s` for the same signer.
+		if ![27, 28].contains(&call.v) || call.s.0 > SECP256K1_HALF_N {
+			return Err(Error::Revert(Revert { reason: ERR_INVALID_SIGNATURE.into() }));
+		}
+		let mut signature = [0u8; 65];
+		signature[..32].copy_from_slice(&call.r.0);
+		signature[32..64].copy_from_slice(&call.s.0);
+		signature[64] = call.v;
+
+		env.gas_meter_mut().charge(RuntimeCosts::EcdsaRecovery)?;
+		env.gas_meter_mut().charge(RuntimeCosts::EcdsaToEthAddress)?;
+		let signer = env
+			.ecdsa_recover(&signature, &digest)
+			.and_then(|public| env.ecdsa_to_eth_address(&public))
+			.map_err(|_| Error::Revert(Revert { reason: ERR_INVALID_SIGNATURE.into() }))?;
+		if call.owner.is_zero() || signer != call.owner.into_array() {
+			return Err(Error::Revert(Revert { reason: ERR_INVALID_SIGNATURE.into() }));
+		}
+
+		crate::Pallet::<Runtime, Instance>::use_permit_nonce(asset_id.clone(), &owner, nonce)?;
+		crate::Pallet::<Runtime, Instance>::do_set_approval(
+			asset_id,
+			&owner,
+			&spender,
+			Self::to_balance(call.value)?,
+		)?;
+
+		Self::deposit_event(
+			env,
+			IERC20Events::Approval(IERC20::Approval {
+				owner: call.owner,
+				spender: call.spender,
+				value: call.value,
+			}),
+		)?;
+
+		return Ok(Vec::new());
+	}
 }
 
 #[cfg(test)]
@@ -547,4 +840,172 @@ mod test {
 			);
 		});
 	}
+
+	#[test]
+	fn metadata_works() {
+		new_test_ext().execute_with(|| {
+			let asset_id = 0u32;
+			let asset_addr =
+				hex::const_decode_to_array(b"0000000000000000000000000000000001200000").unwrap();
+
+			Balances::make_free_balance_be(&1, 100);
+			assert_ok!(Assets::force_create(RuntimeOrigin::root(), asset_id, 1, true, 1));
+			assert_ok!(Assets::set_metadata(
+				RuntimeOrigin::signed(1),
+				asset_id,
+				b"Token".to_vec(),
+				b"TKN".to_vec(),
+				10
+			));
+
+			let call = |data: Vec<u8>| {
+				pallet_revive::Pallet::<Test>::bare_call(
+					RuntimeOrigin::signed(1),
+					H160::from(asset_addr),
+					0u32.into(),
+					Weight::MAX,
+					DepositLimit::UnsafeOnlyForDryRun,
+					data,
+				)
+				.result
+				.unwrap()
+				.data
+			};
+
+			let data = call(IERC20Metadata::nameCall {}.abi_encode());
+			assert_eq!(IERC20Metadata::nameCall::abi_decode_returns(&data).unwrap(), "Token");
+
+			let data = call(IERC20Metadata::symbolCall {}.abi_encode());
+			assert_eq!(IERC20Metadata::symbolCall::abi_decode_returns(&data).unwrap(), "TKN");
+
+			let data = call(IERC20Metadata::decimalsCall {}.abi_encode());
+			assert_eq!(IERC20Metadata::decimalsCall::abi_decode_returns(&data).unwrap(), 10);
+		});
+	}
+
+	#[test]
+	fn permit_works() {
+		use frame_support::{crypto::ecdsa::ECDSAExt, traits::fungibles::approvals::Inspect};
+		use sp_core::{ecdsa, Pair};
+		use sp_io::hashing::keccak_256;
+
+		new_test_ext().execute_with(|| {
+			let asset_id = 0u32;
+			let asset_addr = H160::from(
+				hex::const_decode_to_array(b"0000000000000000000000000000000001200000").unwrap(),
+			);
+
+			let pair = ecdsa::Pair::from_seed(&[7u8; 32]);
+			let owner_addr = H160::from(pair.public().to_eth_address().unwrap());
+			let owner = <Test as pallet_revive::Config>::AddressMapper::to_account_id(&owner_addr);
+			let spender = 2;
+			let spender_addr = <Test as pallet_revive::Config>::AddressMapper::to_address(&spender);
+
+			Balances::make_free_balance_be(&owner, 100);
+			Balances::make_free_balance_be(&spender, 100);
+			assert_ok!(Assets::force_create(RuntimeOrigin::root(), asset_id, owner, true, 1));
+			assert_ok!(Assets::mint(RuntimeOrigin::signed(owner), asset_id, owner, 100));
+			assert_ok!(Assets::set_metadata(
+				RuntimeOrigin::signed(owner),
+				asset_id,
+				b"Token".to_vec(),
+				b"TKN".to_vec(),
+				10
+			));
+
+			let call = |data: Vec<u8>| {
+				pallet_revive::Pallet::<Test>::bare_call(
+					RuntimeOrigin::signed(spender),
+					asset_addr,
+					0u32.into(),
+					Weight::MAX,
+					DepositLimit::UnsafeOnlyForDryRun,
+					data,
+				)
+				.result
+				.unwrap()
+			};
+
+			let domain_separator = keccak_256(
+				&(
+					alloy::primitives::B256::from(keccak_256(EIP712_DOMAIN_TYPE)),
+					alloy::primitives::B256::from(keccak_256(b"Token")),
+					alloy::primitives::B256::from(keccak_256(b"1")),
+					U256::from(<<Test as pallet_revive::Config>::ChainId as Get<u64>>::get()),
+					alloy::primitives::Address::from(asset_addr.0),
+				)
+					.abi_encode(),
+			);
+			let data = call(IERC20Permit::DOMAIN_SEPARATORCall {}.abi_encode()).data;
+			let ret = IERC20Permit::DOMAIN_SEPARATORCall::abi_decode_returns(&data).unwrap();
+			assert_eq!(ret.0, domain_separator);
+
+			let data =
+				call(IERC20Permit::noncesCall { owner: owner_addr.0.into() }.abi_encode()).data;
+			assert_eq!(IERC20Permit::noncesCall::abi_decode_returns(&data).unwrap(), U256::ZERO);
+
+			let struct_hash = keccak_256(
+				&(
+					alloy::primitives::B256::from(keccak_256(PERMIT_TYPE)),
+					alloy::primitives::Address::from(owner_addr.0),
+					alloy::primitives::Address::from(spender_addr.0),
+					U256::from(25),
+					U256::ZERO,
+					U256::MAX,
+				)
+					.abi_encode(),
+			);
+			let digest = keccak_256(&[&b"\x19\x01"[..], &domain_separator, &struct_hash].concat());
+			let signature = pair.sign_prehashed(&digest);
+			let signature: &[u8] = signature.as_ref();
+
+			let permit = IERC20Permit::permitCall {
+				owner: owner_addr.0.into(),
+				spender: spender_addr.0.into(),
+				value: U256::from(25),
+				deadline: U256::MAX,
+				v: signature[64] + 27,
+				r: alloy::primitives::B256::from_slice(&signature[..32]),
+				s: alloy::primitives::B256::from_slice(&signature[32..64]),
+			};
+			// The permit replaces the existing allowance.
+			assert_ok!(Assets::approve_transfer(
+				RuntimeOrigin::signed(owner),
+				asset_id,
+				spender,
+				10
+			));
+			let reserved = Balances::reserved_balance(&owner);
+			assert!(!call(permit.abi_encode()).did_revert());
+
+			assert_contract_event(
+				asset_addr,
+				IERC20Events::Approval(IERC20::Approval {
+					owner: owner_addr.0.into(),
+					spender: spender_addr.0.into(),
+					value: U256::from(25),
+				}),
+			);
+			assert_eq!(Assets::allowance(asset_id, &owner, &spender), 25);
+			// The owner pays a deposit for its nonce.
+			let approval_deposit: u64 = <Test as Config>::ApprovalDeposit::get();
+			assert_eq!(Balances::reserved_balance(&owner), reserved + approval_deposit);
+
+			let data =
+				call(IERC20Permit::noncesCall { owner: owner_addr.0.into() }.abi_encode()).data;
+			assert_eq!(IERC20Permit::noncesCall::abi_decode_returns(&data).unwrap(), U256::from(1));
+
+			// The signature can not be replayed once the nonce has been used.
+			assert!(call(permit.abi_encode()).did_revert());
+			assert_eq!(Assets::allowance(asset_id, &owner, &spender), 25);
+
+			// Destroying the asset removes the nonce and returns its deposit.
+			assert_ok!(Assets::start_destroy(RuntimeOrigin::signed(owner), asset_id));
+			assert_ok!(Assets::destroy_accounts(RuntimeOrigin::signed(owner), asset_id));
+			assert_ok!(Assets::destroy_approvals(RuntimeOrigin::signed(owner), asset_id));
+			assert_ok!(Assets::finish_destroy(RuntimeOrigin::signed(owner), asset_id));
+			assert!(!PermitNonces::<Test>::contains_key(asset_id, &owner));
+			assert_eq!(Balances::reserved_balance(&owner), 0);
+		});
+	}
 }
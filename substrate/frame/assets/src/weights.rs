@@ -107,6 +107,9 @@ pub trait WeightInfo {
 	fn total_issuance() -> Weight;
 	fn balance() -> Weight;
 	fn allowance() -> Weight;
+	fn metadata() -> Weight;
+	fn nonces() -> Weight;
+	fn permit() -> Weight;
 }
 
 /// Weights for `pallet_assets` using the Substrate node and recommended hardware.
@@ -601,6 +604,43 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 		Weight::from_parts(11_882_000, 3613)
 			.saturating_add(T::DbWeight::get().reads(1_u64))
 	}
+	/// Storage: `Assets::Metadata` (r:1 w:0)
+	/// Proof: `Assets::Metadata` (`max_values`: None, `max_size`: Some(140), added: 2615, mode: `MaxEncodedLen`)
+	fn metadata() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `331`
+		//  Estimated: `3605`
+		// Minimum execution time: 9_112_000 picoseconds.
+		Weight::from_parts(9_486_000, 3605)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+	}
+	/// Storage: `Assets::PermitNonces` (r:1 w:0)
+	/// Proof: `Assets::PermitNonces` (`max_values`: None, `max_size`: Some(76), added: 2551, mode: `MaxEncodedLen`)
+	fn nonces() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `243`
+		//  Estimated: `3541`
+		// Minimum execution time: 8_417_000 picoseconds.
+		Weight::from_parts(8_803_000, 3541)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+	}
+	/// Storage: `Assets::PermitNonces` (r:1 w:1)
+	/// Proof: `Assets::PermitNonces` (`max_values`: None, `max_size`: Some(76), added: 2551, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::Asset` (r:1 w:1)
+	/// Proof: `Assets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::Approvals` (r:1 w:1)
+	/// Proof: `Assets::Approvals` (`max_values`: None, `max_size`: Some(148), added: 2623, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	fn permit() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `490`
+		//  Estimated: `3675`
+		// Minimum execution time: 44_557_000 picoseconds.
+		Weight::from_parts(46_020_000, 3675)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
 }
 
 // For backwards compatibility and tests.
@@ -1094,4 +1134,41 @@ impl WeightInfo for () {
 		Weight::from_parts(11_882_000, 3613)
 			.saturating_add(RocksDbWeight::get().reads(1_u64))
 	}
+	/// Storage: `Assets::Metadata` (r:1 w:0)
+	/// Proof: `Assets::Metadata` (`max_values`: None, `max_size`: Some(140), added: 2615, mode: `MaxEncodedLen`)
+	fn metadata() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `331`
+		//  Estimated: `3605`
+		// Minimum execution time: 9_112_000 picoseconds.
+		Weight::from_parts(9_486_000, 3605)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+	}
+	/// Storage: `Assets::PermitNonces` (r:1 w:0)
+	/// Proof: `Assets::PermitNonces` (`max_values`: None, `max_size`: Some(76), added: 2551, mode: `MaxEncodedLen`)
+	fn nonces() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `243`
+		//  Estimated: `3541`
+		// Minimum execution time: 8_417_000 picoseconds.
+		Weight::from_parts(8_803_000, 3541)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+	}
+	/// Storage: `Assets::PermitNonces` (r:1 w:1)
+	/// Proof: `Assets::PermitNonces` (`max_values`: None, `max_size`: Some(76), added: 2551, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::Asset` (r:1 w:1)
+	/// Proof: `Assets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
+	/// Storage: `Assets::Approvals` (r:1 w:1)
+	/// Proof: `Assets::Approvals` (`max_values`: None, `max_size`: Some(148), added: 2623, mode: `MaxEncodedLen`)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	fn permit() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `490`
+		//  Estimated: `3675`
+		// Minimum execution time: 44_557_000 picoseconds.
+		Weight::from_parts(46_020_000, 3675)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
 }
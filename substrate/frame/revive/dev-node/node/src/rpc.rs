@@ -22,27 +22,39 @@
 
 #![warn(missing_docs)]
 
+mod dev;
+pub use dev::{Dev, DevApiServer, DevState, StateWrites};
+
+use futures::channel::mpsc;
 use jsonrpsee::RpcModule;
 use polkadot_sdk::{
+	sc_client_api::Backend,
+	sc_consensus_manual_seal::EngineCommand,
 	sc_transaction_pool_api::TransactionPool,
 	sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata},
 	*,
 };
-use revive_dev_runtime::{AccountId, Nonce, OpaqueBlock};
+use revive_dev_runtime::{AccountId, Hash, Nonce, OpaqueBlock};
 use std::sync::Arc;
 
 /// Full client dependencies.
-pub struct FullDeps<C, P> {
+pub struct FullDeps<C, P, B> {
 	/// The client instance to use.
 	pub client: Arc<C>,
 	/// Transaction pool instance.
 	pub pool: Arc<P>,
+	/// The backend instance to use.
+	pub backend: Arc<B>,
+	/// The state shared with the block authoring task.
+	pub dev_state: DevState,
+	/// Channel to send commands to the block authoring task.
+	pub command_sink: mpsc::Sender<EngineCommand<Hash>>,
 }
 
 #[docify::export]
 /// Instantiate all full RPC extensions.
-pub fn create_full<C, P>(
-	deps: FullDeps<C, P>,
+pub fn create_full<C, P, B>(
+	deps: FullDeps<C, P, B>,
 ) -> Result<RpcModule<()>, Box<dyn std::error::Error + Send + Sync>>
 where
 	C: Send
@@ -55,12 +67,14 @@ where
 	C::Api: sp_block_builder::BlockBuilder<OpaqueBlock>,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<OpaqueBlock, AccountId, Nonce>,
 	P: TransactionPool + 'static,
+	B: Backend<OpaqueBlock> + Send + Sync + 'static,
 {
 	use polkadot_sdk::substrate_frame_rpc_system::{System, SystemApiServer};
 	let mut module = RpcModule::new(());
-	let FullDeps { client, pool } = deps;
+	let FullDeps { client, pool, backend, dev_state, command_sink } = deps;
 
 	module.merge(System::new(client.clone(), pool.clone()).into_rpc())?;
+	module.merge(Dev::new(client, pool, backend, dev_state, command_sink).into_rpc())?;
 
 	Ok(module)
 }
@@ -0,0 +1,293 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Foundry and Hardhat style RPC methods controlling the development chain.
+//!
+//! State writes are applied by an inherent of the dev pallet, in a block sealed right away, and
+//! snapshots are reverted by reverting the blocks of the client. The blocks sealed by these
+//! methods are not finalized, so that they can be reverted.
+
+use futures::{
+	channel::{mpsc, oneshot},
+	SinkExt,
+};
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	proc_macros::rpc,
+	types::{error::ErrorCode, ErrorObjectOwned},
+};
+use polkadot_sdk::{
+	pallet_revive::{
+		evm::{Bytes, StateOverride},
+		H160, H256, U256,
+	},
+	sc_client_api::Backend,
+	sc_consensus_manual_seal::EngineCommand,
+	sc_transaction_pool_api::{InPoolTransaction, TransactionPool, TxInvalidityReportMap},
+	sp_blockchain::HeaderBackend,
+	sp_inherents::{InherentData, InherentIdentifier},
+	*,
+};
+use revive_dev_runtime::{
+	dev::{StateWrite, INHERENT_IDENTIFIER},
+	BlockNumber, Hash, OpaqueBlock,
+};
+use std::{
+	collections::BTreeMap,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc, Mutex,
+	},
+};
+
+/// The state shared by the dev RPC methods and the block authoring task.
+#[derive(Clone, Default)]
+pub struct DevState {
+	/// The state writes to apply in the next block.
+	pending_writes: Arc<Mutex<Vec<StateWrite>>>,
+	/// The offset added to the system time, in seconds.
+	time_offset: Arc<AtomicU64>,
+}
+
+impl DevState {
+	/// Create the inherent data providers of the next block.
+	///
+	/// Takes the pending state writes, so that they are only applied once.
+	pub fn inherent_data_providers(&self) -> (sp_timestamp::InherentDataProvider, StateWrites) {
+		let offset = self.time_offset.load(Ordering::Relaxed).saturating_mul(1000);
+		let timestamp = sp_timestamp::Timestamp::current() + offset;
+		let writes = std::mem::take(&mut *self.pending_writes.lock().expect("not poisoned; qed"));
+		(sp_timestamp::InherentDataProvider::new(timestamp), StateWrites(writes))
+	}
+}
+
+/// Provides the state writes applied by the dev pallet.
+pub struct StateWrites(Vec<StateWrite>);
+
+#[async_trait]
+impl sp_inherents::InherentDataProvider for StateWrites {
+	async fn provide_inherent_data(
+		&self,
+		inherent_data: &mut InherentData,
+	) -> Result<(), sp_inherents::Error> {
+		inherent_data.put_data(INHERENT_IDENTIFIER, &self.0)
+	}
+
+	async fn try_handle_error(
+		&self,
+		_identifier: &InherentIdentifier,
+		_error: &[u8],
+	) -> Option<Result<(), sp_inherents::Error>> {
+		None
+	}
+}
+
+/// Dev RPC methods.
+#[rpc(server)]
+pub trait DevApi {
+	/// Seal a new block.
+	#[method(name = "evm_mine")]
+	async fn mine(&self) -> RpcResult<String>;
+
+	/// Shift the timestamp of the next blocks by `seconds`.
+	///
+	/// Returns the total time shift, in seconds.
+	#[method(name = "evm_increaseTime")]
+	fn increase_time(&self, seconds: u64) -> RpcResult<u64>;
+
+	/// Take a snapshot of the current best block, returning its id.
+	#[method(name = "evm_snapshot")]
+	fn snapshot(&self) -> RpcResult<U256>;
+
+	/// Revert the chain to the snapshot with the given id.
+	///
+	/// The snapshot and the ones taken after it can not be reverted to anymore, and the
+	/// transactions of the pool are dropped.
+	#[method(name = "evm_revert")]
+	async fn revert(&self, id: U256) -> RpcResult<bool>;
+
+	/// Set the balance of an account.
+	#[method(name = "anvil_setBalance")]
+	async fn set_balance(&self, address: H160, balance: U256) -> RpcResult<()>;
+
+	/// Set the code of an account.
+	#[method(name = "anvil_setCode")]
+	async fn set_code(&self, address: H160, code: Bytes) -> RpcResult<()>;
+
+	/// Set a storage slot of a contract.
+	#[method(name = "anvil_setStorageAt")]
+	async fn set_storage_at(&self, address: H160, slot: U256, value: H256) -> RpcResult<bool>;
+
+	/// Accept the transactions sent on behalf of an account without a valid signature.
+	#[method(name = "anvil_impersonateAccount")]
+	async fn impersonate_account(&self, address: H160) -> RpcResult<()>;
+
+	/// Stop impersonating an account.
+	#[method(name = "anvil_stopImpersonatingAccount")]
+	async fn stop_impersonating_account(&self, address: H160) -> RpcResult<()>;
+}
+
+/// Implements the [`DevApiServer`] RPC trait.
+pub struct Dev<C, P, B> {
+	client: Arc<C>,
+	pool: Arc<P>,
+	backend: Arc<B>,
+	state: DevState,
+	commands: mpsc::Sender<EngineCommand<Hash>>,
+	/// The snapshots taken, by id.
+	snapshots: Mutex<BTreeMap<u64, BlockNumber>>,
+	/// The id of the next snapshot.
+	next_snapshot: AtomicU64,
+}
+
+impl<C, P, B> Dev<C, P, B> {
+	/// Create a new [`Dev`] instance.
+	pub fn new(
+		client: Arc<C>,
+		pool: Arc<P>,
+		backend: Arc<B>,
+		state: DevState,
+		commands: mpsc::Sender<EngineCommand<Hash>>,
+	) -> Self {
+		Self {
+			client,
+			pool,
+			backend,
+			state,
+			commands,
+			snapshots: Default::default(),
+			next_snapshot: AtomicU64::new(1),
+		}
+	}
+
+	/// Seal a new block and wait for it to be imported.
+	async fn seal(&self) -> RpcResult<()> {
+		let (sender, receiver) = oneshot::channel();
+		self.commands
+			.clone()
+			.send(EngineCommand::SealNewBlock {
+				create_empty: true,
+				finalize: false,
+				parent_hash: None,
+				sender: Some(sender),
+			})
+			.await
+			.map_err(|_| internal_error("Block authoring is disabled"))?;
+
+		receiver
+			.await
+			.map_err(|_| internal_error("Block authoring is disabled"))?
+			.map_err(|err| internal_error(err.to_string()))?;
+		Ok(())
+	}
+
+	/// Apply `write` in a new block.
+	async fn write(&self, write: StateWrite) -> RpcResult<()> {
+		self.state.pending_writes.lock().expect("not poisoned; qed").push(write);
+		self.seal().await
+	}
+
+	/// Apply `state_override` to the account at `address` in a new block.
+	async fn write_override(&self, address: H160, state_override: StateOverride) -> RpcResult<()> {
+		self.write(StateWrite::Override(address, state_override)).await
+	}
+}
+
+#[async_trait]
+impl<C, P, B> DevApiServer for Dev<C, P, B>
+where
+	C: HeaderBackend<OpaqueBlock> + Send + Sync + 'static,
+	P: TransactionPool + 'static,
+	B: Backend<OpaqueBlock> + Send + Sync + 'static,
+{
+	async fn mine(&self) -> RpcResult<String> {
+		self.seal().await?;
+		Ok("0x0".into())
+	}
+
+	fn increase_time(&self, seconds: u64) -> RpcResult<u64> {
+		let offset = self.state.time_offset.fetch_add(seconds, Ordering::Relaxed);
+		Ok(offset.saturating_add(seconds))
+	}
+
+	fn snapshot(&self) -> RpcResult<U256> {
+		let id = self.next_snapshot.fetch_add(1, Ordering::Relaxed);
+		let best_number = self.client.info().best_number;
+		self.snapshots.lock().expect("not poisoned; qed").insert(id, best_number);
+		Ok(id.into())
+	}
+
+	async fn revert(&self, id: U256) -> RpcResult<bool> {
+		let Ok(id) = u64::try_from(id) else { return Ok(false) };
+		let number = {
+			let mut snapshots = self.snapshots.lock().expect("not poisoned; qed");
+			let Some(number) = snapshots.get(&id).copied() else { return Ok(false) };
+			snapshots.split_off(&id);
+			number
+		};
+
+		let info = self.client.info();
+		if number < info.finalized_number {
+			return Err(internal_error("Can not revert finalized blocks"));
+		}
+		self.backend
+			.revert(info.best_number.saturating_sub(number), false)
+			.map_err(|err| internal_error(err.to_string()))?;
+
+		// The transactions of the pool were validated against the reverted blocks.
+		let transactions: TxInvalidityReportMap<_> = self
+			.pool
+			.ready()
+			.map(|tx| tx.hash().clone())
+			.chain(self.pool.futures().into_iter().map(|tx| tx.hash().clone()))
+			.map(|hash| (hash, None))
+			.collect();
+		self.pool.report_invalid(None, transactions).await;
+		Ok(true)
+	}
+
+	async fn set_balance(&self, address: H160, balance: U256) -> RpcResult<()> {
+		let state_override = StateOverride { balance: Some(balance), ..Default::default() };
+		self.write_override(address, state_override).await
+	}
+
+	async fn set_code(&self, address: H160, code: Bytes) -> RpcResult<()> {
+		let state_override = StateOverride { code: Some(code), ..Default::default() };
+		self.write_override(address, state_override).await
+	}
+
+	async fn set_storage_at(&self, address: H160, slot: U256, value: H256) -> RpcResult<bool> {
+		let slot = H256(slot.to_big_endian());
+		let state_override =
+			StateOverride { state_diff: Some([(slot, value)].into()), ..Default::default() };
+		self.write_override(address, state_override).await?;
+		Ok(true)
+	}
+
+	async fn impersonate_account(&self, address: H160) -> RpcResult<()> {
+		self.write(StateWrite::Impersonate(address, true)).await
+	}
+
+	async fn stop_impersonating_account(&self, address: H160) -> RpcResult<()> {
+		self.write(StateWrite::Impersonate(address, false)).await
+	}
+}
+
+/// Create an internal error with the given message.
+fn internal_error(message: impl Into<String>) -> ErrorObjectOwned {
+	ErrorObjectOwned::owned(ErrorCode::InternalError.code(), message.into(), None::<()>)
+}
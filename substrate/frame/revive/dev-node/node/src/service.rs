@@ -15,14 +15,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::cli::Consensus;
-use futures::FutureExt;
+use crate::{cli::Consensus, rpc::DevState};
+use futures::{FutureExt, StreamExt};
 use polkadot_sdk::{
 	sc_client_api::backend::Backend,
+	sc_consensus_manual_seal::EngineCommand,
 	sc_executor::WasmExecutor,
 	sc_service::{error::Error as ServiceError, Configuration, TaskManager},
 	sc_telemetry::{Telemetry, TelemetryWorker},
-	sc_transaction_pool_api::OffchainTransactionPoolFactory,
+	sc_transaction_pool_api::{OffchainTransactionPoolFactory, TransactionPool},
 	sp_runtime::traits::Block as BlockT,
 	*,
 };
@@ -163,12 +164,24 @@ pub fn new_full<Network: sc_network::NetworkBackend<Block, <Block as BlockT>::Ha
 		);
 	}
 
+	// The dev RPCs seal blocks through the same channel as the block authoring task.
+	let dev_state = DevState::default();
+	let (command_sink, rpc_commands) = futures::channel::mpsc::channel(1024);
+
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
+		let backend = backend.clone();
+		let dev_state = dev_state.clone();
 
 		Box::new(move |_| {
-			let deps = crate::rpc::FullDeps { client: client.clone(), pool: pool.clone() };
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				backend: backend.clone(),
+				dev_state: dev_state.clone(),
+				command_sink: command_sink.clone(),
+			};
 			crate::rpc::create_full(deps).map_err(Into::into)
 		})
 	};
@@ -196,21 +209,35 @@ pub fn new_full<Network: sc_network::NetworkBackend<Block, <Block as BlockT>::Ha
 		telemetry.as_ref().map(|x| x.handle()),
 	);
 
+	let create_inherent_data_providers = move |_, ()| {
+		let dev_state = dev_state.clone();
+		async move { Ok(dev_state.inherent_data_providers()) }
+	};
+
 	match consensus {
 		Consensus::InstantSeal => {
-			let params = sc_consensus_manual_seal::InstantSealParams {
+			// Seal a block for every transaction imported into the pool.
+			let pool_commands = transaction_pool.import_notification_stream().map(|_| {
+				EngineCommand::SealNewBlock {
+					create_empty: false,
+					finalize: false,
+					parent_hash: None,
+					sender: None,
+				}
+			});
+
+			let params = sc_consensus_manual_seal::ManualSealParams {
 				block_import: client.clone(),
 				env: proposer,
 				client,
 				pool: transaction_pool,
 				select_chain,
+				commands_stream: futures::stream::select(pool_commands, rpc_commands),
 				consensus_data_provider: None,
-				create_inherent_data_providers: move |_, ()| async move {
-					Ok(sp_timestamp::InherentDataProvider::from_system_time())
-				},
+				create_inherent_data_providers,
 			};
 
-			let authorship_future = sc_consensus_manual_seal::run_instant_seal(params);
+			let authorship_future = sc_consensus_manual_seal::run_manual_seal(params);
 
 			task_manager.spawn_essential_handle().spawn_blocking(
 				"instant-seal",
@@ -239,11 +266,9 @@ pub fn new_full<Network: sc_network::NetworkBackend<Block, <Block as BlockT>::Ha
 				client,
 				pool: transaction_pool,
 				select_chain,
-				commands_stream: Box::pin(commands_stream),
+				commands_stream: futures::stream::select(commands_stream, rpc_commands),
 				consensus_data_provider: None,
-				create_inherent_data_providers: move |_, ()| async move {
-					Ok(sp_timestamp::InherentDataProvider::from_system_time())
-				},
+				create_inherent_data_providers,
 			};
 			let authorship_future = sc_consensus_manual_seal::run_manual_seal(params);
 
@@ -0,0 +1,124 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A development pallet writing the state of the chain directly.
+//!
+//! The writes are requested through the dev RPCs of the node, such as `anvil_setBalance`, and
+//! applied by an inherent of the next block. This pallet must never be part of a production
+//! runtime.
+
+pub use pallet::*;
+
+/// The log target of this pallet.
+const LOG_TARGET: &str = "runtime::revive-dev";
+
+#[polkadot_sdk::frame_support::pallet(dev_mode)]
+pub mod pallet {
+	use super::LOG_TARGET;
+	use alloc::vec::Vec;
+	use codec::{Decode, DecodeWithMemTracking, Encode};
+	use polkadot_sdk::{
+		frame_support::{inherent::MakeFatalError, pallet_prelude::*},
+		frame_system::pallet_prelude::*,
+		pallet_revive::{evm::StateOverride, BalanceOf, MomentOf, H160, H256, U256},
+		sp_runtime::traits::Bounded,
+		*,
+	};
+
+	/// The identifier of the inherent data holding the pending [`StateWrite`]s.
+	pub const INHERENT_IDENTIFIER: InherentIdentifier = *b"revivdev";
+
+	/// A write to the state of the chain.
+	#[derive(
+		Clone, PartialEq, Eq, Encode, Decode, DecodeWithMemTracking, TypeInfo, RuntimeDebug,
+	)]
+	pub enum StateWrite {
+		/// Override the state of an account.
+		Override(H160, StateOverride),
+		/// Start or stop accepting transactions sent on behalf of an account.
+		Impersonate(H160, bool),
+	}
+
+	#[pallet::config]
+	pub trait Config: polkadot_sdk::frame_system::Config + pallet_revive::Config {}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// The accounts whose Ethereum transactions are accepted without a valid signature.
+	///
+	/// See [`pallet_revive::evm::TransactionSigned::impersonated_signer`].
+	#[pallet::storage]
+	pub type Impersonated<T: Config> = StorageMap<_, Identity, H160, ()>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T>
+	where
+		BalanceOf<T>: Into<U256> + TryFrom<U256> + Bounded,
+		MomentOf<T>: Into<U256>,
+		T::Hash: IsType<H256>,
+	{
+		/// Apply the given state writes.
+		///
+		/// Writes that fail are skipped, as the block could not be built otherwise.
+		#[pallet::call_index(0)]
+		#[pallet::weight((Weight::zero(), DispatchClass::Mandatory))]
+		pub fn apply_state_writes(origin: OriginFor<T>, writes: Vec<StateWrite>) -> DispatchResult {
+			ensure_none(origin)?;
+			for write in writes {
+				match write {
+					StateWrite::Override(address, state_override) => {
+						let overrides = [(address, state_override)].into_iter().collect();
+						if let Err(err) =
+							pallet_revive::Pallet::<T>::apply_state_overrides(overrides)
+						{
+							log::warn!(
+								target: LOG_TARGET,
+								"Failed to write the state of {address:?}: {err:?}"
+							);
+						}
+					},
+					StateWrite::Impersonate(address, true) =>
+						Impersonated::<T>::insert(address, ()),
+					StateWrite::Impersonate(address, false) => Impersonated::<T>::remove(address),
+				}
+			}
+			Ok(())
+		}
+	}
+
+	#[pallet::inherent]
+	impl<T: Config> ProvideInherent for Pallet<T>
+	where
+		BalanceOf<T>: Into<U256> + TryFrom<U256> + Bounded,
+		MomentOf<T>: Into<U256>,
+		T::Hash: IsType<H256>,
+	{
+		type Call = Call<T>;
+		type Error = MakeFatalError<()>;
+		const INHERENT_IDENTIFIER: InherentIdentifier = INHERENT_IDENTIFIER;
+
+		fn create_inherent(data: &InherentData) -> Option<Self::Call> {
+			let writes: Vec<StateWrite> = data.get_data(&INHERENT_IDENTIFIER).ok().flatten()?;
+			(!writes.is_empty()).then_some(Call::apply_state_writes { writes })
+		}
+
+		fn is_inherent(call: &Self::Call) -> bool {
+			matches!(call, Call::apply_state_writes { .. })
+		}
+	}
+}
@@ -30,7 +30,10 @@ use frame_support::weights::{
 	Weight,
 };
 use frame_system::limits::BlockWeights;
-use pallet_revive::{evm::runtime::EthExtra, AccountId32Mapper};
+use pallet_revive::{
+	evm::{runtime::EthExtra, TransactionSigned},
+	AccountId32Mapper, H160,
+};
 use pallet_transaction_payment::{FeeDetails, RuntimeDispatchInfo};
 use polkadot_sdk::{
 	polkadot_sdk_frame::{
@@ -46,6 +49,8 @@ pub use polkadot_sdk::{
 	polkadot_sdk_frame::runtime::types_common::OpaqueBlock,
 };
 
+pub mod dev;
+
 pub mod currency {
 	use super::Balance;
 	pub const MILLICENTS: Balance = 1_000_000_000;
@@ -183,6 +188,13 @@ impl EthExtra for EthExtraImpl {
 			frame_system::WeightReclaim::<Runtime>::new(),
 		)
 	}
+
+	fn recover_signer(tx: &TransactionSigned) -> Result<H160, ()> {
+		match tx.impersonated_signer() {
+			Some(address) if dev::Impersonated::<Runtime>::contains_key(address) => Ok(address),
+			_ => tx.recover_eth_address(),
+		}
+	}
 }
 
 pub type UncheckedExtrinsic =
@@ -238,6 +250,14 @@ mod runtime {
 	/// Provides the ability to execute Smart Contracts.
 	#[runtime::pallet_index(5)]
 	pub type Revive = pallet_revive::Pallet<Runtime>;
+
+	/// Provides the ability to write the state of the chain through the dev RPCs.
+	#[runtime::pallet_index(6)]
+	pub type Dev = dev::Pallet<Runtime>;
+
+	/// Provides the ability to run multi-block migrations.
+	#[runtime::pallet_index(7)]
+	pub type MultiBlockMigrations = pallet_migrations::Pallet<Runtime>;
 }
 
 /// We assume that ~10% of the block weight is consumed by `on_initialize` handlers.
@@ -281,6 +301,7 @@ impl frame_system::Config for Runtime {
 	type Hash = Hash;
 	type Nonce = Nonce;
 	type AccountData = pallet_balances::AccountData<<Runtime as pallet_balances::Config>::Balance>;
+	type MultiBlockMigrator = MultiBlockMigrations;
 }
 
 parameter_types! {
@@ -299,6 +320,12 @@ impl pallet_balances::Config for Runtime {
 #[derive_impl(pallet_sudo::config_preludes::TestDefaultConfig)]
 impl pallet_sudo::Config for Runtime {}
 
+// Implements the types required for the migrations pallet.
+#[derive_impl(pallet_migrations::config_preludes::TestDefaultConfig)]
+impl pallet_migrations::Config for Runtime {
+	type Migrations = pallet_revive::migrations::v2::Migration<Runtime>;
+}
+
 // Implements the types required for the sudo pallet.
 #[derive_impl(pallet_timestamp::config_preludes::TestDefaultConfig)]
 impl pallet_timestamp::Config for Runtime {}
@@ -331,6 +358,9 @@ impl pallet_revive::Config for Runtime {
 	type Time = Timestamp;
 }
 
+// Implements the types required for the dev pallet.
+impl dev::Config for Runtime {}
+
 pallet_revive::impl_runtime_apis_plus_revive!(
 	Runtime,
 	Executive,
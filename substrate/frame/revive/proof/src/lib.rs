@@ -0,0 +1,507 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Verification of `pallet-revive` account and storage proofs.
+//!
+//! These are the proofs returned by the `eth_getProof` method of the revive eth-rpc server. Unlike
+//! Ethereum, the accounts and the storage of the contracts are not kept in a single Merkle Patricia
+//! trie:
+//!
+//! - The account proof proves the `AccountInfoOf` entry of the address in the main trie. For a
+//!   contract, or an account delegating its code to a contract, it also proves the root of the
+//!   child trie holding its storage, which is stored in the main trie under the prefixed key of the
+//!   child trie.
+//! - The same proof also proves the `frame_system::Account` entry of the account, holding its nonce
+//!   and native balance, see [`verify_system_account_proof`].
+//! - Each storage proof proves a storage slot in the child trie of the contract, against the root
+//!   returned by [`verify_account_proof`].
+//!
+//! Both proofs are lists of trie nodes, as returned by `state_getReadProof` and
+//! `state_getChildReadProof`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use codec::{Decode, DecodeAll};
+use sp_core::{
+	hashing::{blake2_256, twox_128},
+	Hasher, H160, H256,
+};
+use sp_storage::ChildInfo;
+use sp_trie::{empty_child_trie_root, read_trie_value, LayoutV1, StorageProof};
+
+/// The name of the storage item holding the account infos in `pallet-revive`.
+const ACCOUNT_INFO_OF: &[u8] = b"AccountInfoOf";
+
+/// An error returned when verifying a proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+	/// The proof does not contain the trie nodes needed to read the value.
+	IncompleteProof,
+	/// The proven `AccountInfo` could not be decoded.
+	InvalidAccountInfo,
+	/// The proven child trie root could not be decoded.
+	InvalidStorageRoot,
+	/// The proven `frame_system::AccountInfo` could not be decoded.
+	InvalidSystemAccount,
+}
+
+/// An account, as proven by [`verify_account_proof`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Account<Hash> {
+	/// An externally owned account.
+	Eoa,
+	/// A contract account.
+	Contract {
+		/// The hash of the code of the contract.
+		code_hash: H256,
+		/// The root of the child trie of the contract.
+		storage_root: Hash,
+	},
+	/// An externally owned account delegating its code to a contract, see
+	/// [EIP-7702](https://eips.ethereum.org/EIPS/eip-7702).
+	Delegated {
+		/// The address of the contract the account delegates its code to.
+		///
+		/// The zero address means that the delegation was cleared.
+		target: H160,
+		/// The root of the child trie holding the storage of the account.
+		storage_root: Hash,
+	},
+}
+
+/// The `frame_system::Account` entry of an account, as proven by
+/// [`verify_system_account_proof`].
+///
+/// These are the leading fields of a SCALE encoded `frame_system::AccountInfo` holding a
+/// `pallet_balances::AccountData`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Decode)]
+pub struct SystemAccount<Nonce, Balance> {
+	/// The number of transactions sent by the account.
+	pub nonce: Nonce,
+	/// The number of other modules that depend on the account.
+	pub consumers: u32,
+	/// The number of other modules that allow the account to exist.
+	pub providers: u32,
+	/// The number of modules that allow the account to exist for their own purposes.
+	pub sufficients: u32,
+	/// The free balance of the account.
+	pub free: Balance,
+	/// The balance of the account that is reserved or on hold.
+	pub reserved: Balance,
+	/// The balance of the account that can not be transferred.
+	pub frozen: Balance,
+}
+
+/// The leading fields of a SCALE encoded `pallet_revive::AccountInfo`.
+///
+/// Only the fields needed to verify the storage of a contract are decoded.
+#[derive(Decode)]
+enum AccountInfoPrefix {
+	Contract { trie_id: Vec<u8>, code_hash: H256 },
+	Eoa,
+	Delegated { target: H160, trie_id: Vec<u8> },
+}
+
+/// A SCALE encoded `pallet_revive::AccountInfo`.
+///
+/// This mirrors the type of the pallet, which can not be used without a runtime. `Balance` is the
+/// balance type of the runtime.
+#[derive(Decode)]
+struct AccountInfo<Balance> {
+	#[allow(dead_code)]
+	account_type: AccountType<Balance>,
+	dust: u32,
+}
+
+/// A SCALE encoded `pallet_revive::AccountType`.
+#[derive(Decode)]
+#[allow(dead_code)]
+enum AccountType<Balance> {
+	Contract(ContractInfo<Balance>),
+	Eoa,
+	Delegated { target: H160, contract_info: ContractInfo<Balance> },
+}
+
+/// A SCALE encoded `pallet_revive::ContractInfo`.
+#[derive(Decode)]
+#[allow(dead_code)]
+struct ContractInfo<Balance> {
+	trie_id: Vec<u8>,
+	code_hash: H256,
+	storage_bytes: u32,
+	storage_items: u32,
+	storage_byte_deposit: Balance,
+	storage_item_deposit: Balance,
+	storage_base_deposit: Balance,
+	immutable_data_len: u32,
+}
+
+/// Returns the `AccountInfoOf` storage key of `address`.
+///
+/// `pallet_name` is the name of `pallet-revive` in the runtime, e.g. `b"Revive"`.
+pub fn account_info_key(pallet_name: &[u8], address: &H160) -> Vec<u8> {
+	[&twox_128(pallet_name)[..], &twox_128(ACCOUNT_INFO_OF)[..], address.as_bytes()].concat()
+}
+
+/// Returns the key of a storage slot in the child trie of a contract.
+pub fn storage_slot_key(slot: &[u8; 32]) -> [u8; 32] {
+	blake2_256(slot)
+}
+
+/// Read the value of `key` from a proof against the given trie `root`.
+///
+/// Returns `Ok(None)` if the proof shows that the key does not exist.
+pub fn read_proof_value<H: Hasher>(
+	root: &H::Out,
+	key: &[u8],
+	proof: impl IntoIterator<Item = Vec<u8>>,
+) -> Result<Option<Vec<u8>>, Error> {
+	let db = StorageProof::new(proof).into_memory_db::<H>();
+	read_trie_value::<LayoutV1<H>, _>(&db, root, key, None, None)
+		.map_err(|_| Error::IncompleteProof)
+}
+
+/// Verify the account proof of an address against the `state_root` of a block.
+///
+/// `account_info_key` is the `AccountInfoOf` storage key of the address, see
+/// [`account_info_key`]. Returns `Ok(None)` if the address has no `AccountInfoOf` entry.
+pub fn verify_account_proof<H: Hasher>(
+	state_root: &H::Out,
+	account_info_key: &[u8],
+	proof: impl IntoIterator<Item = Vec<u8>>,
+) -> Result<Option<Account<H::Out>>, Error> {
+	let db = StorageProof::new(proof).into_memory_db::<H>();
+	let read = |key: &[u8]| {
+		read_trie_value::<LayoutV1<H>, _>(&db, state_root, key, None, None)
+			.map_err(|_| Error::IncompleteProof)
+	};
+
+	let Some(account_info) = read(account_info_key)? else {
+		return Ok(None);
+	};
+
+	let prefix =
+		AccountInfoPrefix::decode(&mut &account_info[..]).map_err(|_| Error::InvalidAccountInfo)?;
+	let trie_id = match &prefix {
+		AccountInfoPrefix::Eoa => return Ok(Some(Account::Eoa)),
+		AccountInfoPrefix::Contract { trie_id, .. } |
+		AccountInfoPrefix::Delegated { trie_id, .. } => trie_id,
+	};
+
+	// The root of an empty child trie is not stored in the main trie.
+	let child_trie_key = ChildInfo::new_default(trie_id).prefixed_storage_key();
+	let storage_root = match read(child_trie_key.as_slice())? {
+		None => empty_child_trie_root::<LayoutV1<H>>(),
+		Some(root) => {
+			let mut storage_root = H::Out::default();
+			if root.len() != storage_root.as_ref().len() {
+				return Err(Error::InvalidStorageRoot);
+			}
+			storage_root.as_mut().copy_from_slice(&root);
+			storage_root
+		},
+	};
+
+	Ok(Some(match prefix {
+		AccountInfoPrefix::Contract { code_hash, .. } =>
+			Account::Contract { code_hash, storage_root },
+		AccountInfoPrefix::Delegated { target, .. } => Account::Delegated { target, storage_root },
+		AccountInfoPrefix::Eoa => Account::Eoa,
+	}))
+}
+
+/// Verify the proof of the dust of an address against the `state_root` of a block.
+///
+/// The dust is the balance of the account that can not be represented in the native currency, see
+/// `pallet_revive::AccountInfo`. `account_info_key` is the `AccountInfoOf` storage key of the
+/// address and `Balance` the balance type of the runtime. Returns `Ok(0)` if the address has no
+/// `AccountInfoOf` entry.
+pub fn verify_dust_proof<H: Hasher, Balance: Decode>(
+	state_root: &H::Out,
+	account_info_key: &[u8],
+	proof: impl IntoIterator<Item = Vec<u8>>,
+) -> Result<u32, Error> {
+	let Some(account_info) = read_proof_value::<H>(state_root, account_info_key, proof)? else {
+		return Ok(0);
+	};
+
+	AccountInfo::<Balance>::decode_all(&mut &account_info[..])
+		.map(|info| info.dust)
+		.map_err(|_| Error::InvalidAccountInfo)
+}
+
+/// Verify the proof of the `frame_system::Account` entry of an account against the `state_root` of
+/// a block.
+///
+/// `system_account_key` is the storage key of the entry. Returns `Ok(None)` if the account does
+/// not exist.
+pub fn verify_system_account_proof<H: Hasher, Nonce: Decode, Balance: Decode>(
+	state_root: &H::Out,
+	system_account_key: &[u8],
+	proof: impl IntoIterator<Item = Vec<u8>>,
+) -> Result<Option<SystemAccount<Nonce, Balance>>, Error> {
+	read_proof_value::<H>(state_root, system_account_key, proof)?
+		.map(|account| {
+			SystemAccount::decode(&mut &account[..]).map_err(|_| Error::InvalidSystemAccount)
+		})
+		.transpose()
+}
+
+/// Verify the storage proof of `slot` against the `storage_root` of a contract.
+///
+/// Returns `Ok(None)` if the slot is empty.
+pub fn verify_storage_proof<H: Hasher>(
+	storage_root: &H::Out,
+	slot: &[u8; 32],
+	proof: impl IntoIterator<Item = Vec<u8>>,
+) -> Result<Option<Vec<u8>>, Error> {
+	read_proof_value::<H>(storage_root, &storage_slot_key(slot), proof)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use sp_core::{storage::StateVersion, Blake2Hasher};
+	use sp_state_machine::{
+		prove_child_read_on_trie_backend, prove_read_on_trie_backend, InMemoryBackend,
+	};
+
+	const PALLET: &[u8] = b"Revive";
+
+	fn contract_info(trie_id: &[u8], code_hash: H256, dust: u32) -> Vec<u8> {
+		// `AccountType::Contract` variant, followed by the `ContractInfo` fields and the dust.
+		let mut info = vec![0u8];
+		info.extend(trie_id.to_vec().encode());
+		info.extend(code_hash.encode());
+		info.extend((1u32, 2u32, 3u128, 4u128, 5u128, 6u32).encode());
+		info.extend(dust.encode());
+		info
+	}
+
+	fn delegated_info(target: H160, trie_id: &[u8]) -> Vec<u8> {
+		// `AccountType::Delegated` variant, followed by the target and the `ContractInfo` fields.
+		let mut info = vec![2u8];
+		info.extend(target.encode());
+		info.extend(trie_id.to_vec().encode());
+		// The remaining fields are not decoded by the verifier.
+		info.extend([0u8; 48]);
+		info
+	}
+
+	#[test]
+	fn verify_contract_proof_works() {
+		let contract = H160::repeat_byte(1);
+		let eoa = H160::repeat_byte(2);
+		let code_hash = H256::repeat_byte(3);
+		let trie_id = b"trie_id".to_vec();
+		let child_info = ChildInfo::new_default(&trie_id);
+		let slot = [4u8; 32];
+
+		let backend = InMemoryBackend::<Blake2Hasher>::from((
+			vec![
+				(
+					None,
+					vec![
+						(
+							account_info_key(PALLET, &contract),
+							Some(contract_info(&trie_id, code_hash, 0)),
+						),
+						(account_info_key(PALLET, &eoa), Some(vec![1u8])),
+					],
+				),
+				(
+					Some(child_info.clone()),
+					vec![(storage_slot_key(&slot).to_vec(), Some(b"value".to_vec()))],
+				),
+			],
+			StateVersion::V1,
+		));
+		let state_root = *backend.root();
+
+		let keys = [
+			account_info_key(PALLET, &contract),
+			child_info.prefixed_storage_key().into_inner(),
+			account_info_key(PALLET, &eoa),
+		];
+		let proof = prove_read_on_trie_backend(&backend, &keys)
+			.unwrap()
+			.into_iter_nodes()
+			.collect::<Vec<_>>();
+
+		let Some(Account::Contract { code_hash: proven_code_hash, storage_root }) =
+			verify_account_proof::<Blake2Hasher>(&state_root, &keys[0], proof.clone()).unwrap()
+		else {
+			panic!("contract should be proven");
+		};
+		assert_eq!(proven_code_hash, code_hash);
+		assert_eq!(
+			verify_account_proof::<Blake2Hasher>(&state_root, &keys[2], proof),
+			Ok(Some(Account::Eoa))
+		);
+
+		// Proving the contract requires the child trie root.
+		let proof = prove_read_on_trie_backend(&backend, &keys[..1]).unwrap().into_iter_nodes();
+		assert_eq!(
+			verify_account_proof::<Blake2Hasher>(&state_root, &keys[0], proof),
+			Err(Error::IncompleteProof)
+		);
+
+		let storage_proof =
+			prove_child_read_on_trie_backend(&backend, &child_info, [storage_slot_key(&slot)])
+				.unwrap()
+				.into_iter_nodes()
+				.collect::<Vec<_>>();
+		assert_eq!(
+			verify_storage_proof::<Blake2Hasher>(&storage_root, &slot, storage_proof.clone()),
+			Ok(Some(b"value".to_vec()))
+		);
+		assert_eq!(
+			verify_storage_proof::<Blake2Hasher>(&storage_root, &[5u8; 32], storage_proof.clone()),
+			Ok(None)
+		);
+		// The proof does not contain the nodes of other tries.
+		assert_eq!(
+			verify_storage_proof::<Blake2Hasher>(&H256::repeat_byte(9), &slot, storage_proof),
+			Err(Error::IncompleteProof)
+		);
+	}
+
+	#[test]
+	fn verify_delegated_proof_works() {
+		let authority = H160::repeat_byte(1);
+		let target = H160::repeat_byte(2);
+		let trie_id = b"trie_id".to_vec();
+		let child_info = ChildInfo::new_default(&trie_id);
+		let slot = [4u8; 32];
+
+		let backend = InMemoryBackend::<Blake2Hasher>::from((
+			vec![
+				(
+					None,
+					vec![(
+						account_info_key(PALLET, &authority),
+						Some(delegated_info(target, &trie_id)),
+					)],
+				),
+				(
+					Some(child_info.clone()),
+					vec![(storage_slot_key(&slot).to_vec(), Some(b"value".to_vec()))],
+				),
+			],
+			StateVersion::V1,
+		));
+		let state_root = *backend.root();
+
+		let keys =
+			[account_info_key(PALLET, &authority), child_info.prefixed_storage_key().into_inner()];
+		let proof = prove_read_on_trie_backend(&backend, &keys).unwrap().into_iter_nodes();
+		let Some(Account::Delegated { target: proven_target, storage_root }) =
+			verify_account_proof::<Blake2Hasher>(&state_root, &keys[0], proof).unwrap()
+		else {
+			panic!("delegated account should be proven");
+		};
+		assert_eq!(proven_target, target);
+
+		let storage_proof =
+			prove_child_read_on_trie_backend(&backend, &child_info, [storage_slot_key(&slot)])
+				.unwrap()
+				.into_iter_nodes();
+		assert_eq!(
+			verify_storage_proof::<Blake2Hasher>(&storage_root, &slot, storage_proof),
+			Ok(Some(b"value".to_vec()))
+		);
+	}
+
+	#[test]
+	fn verify_system_account_and_dust_proof_works() {
+		let eoa = H160::repeat_byte(1);
+		let missing = H160::repeat_byte(2);
+		let contract = H160::repeat_byte(3);
+		let invalid = H160::repeat_byte(4);
+		let system_account_key = b"system_account".to_vec();
+		// The `frame_system::AccountInfo` fields, followed by the `AccountData` ones.
+		let system_account = (7u32, 1u32, 1u32, 0u32, 100u128, 10u128, 20u128, 0u128).encode();
+		// `AccountType::Eoa` variant, followed by the dust.
+		let eoa_info = [vec![1u8], 42u32.encode()].concat();
+
+		let backend = InMemoryBackend::<Blake2Hasher>::from((
+			vec![(
+				None,
+				vec![
+					(system_account_key.clone(), Some(system_account)),
+					(account_info_key(PALLET, &eoa), Some(eoa_info)),
+					(
+						account_info_key(PALLET, &contract),
+						Some(contract_info(b"trie_id", H256::repeat_byte(4), 7)),
+					),
+					// The account type is missing.
+					(account_info_key(PALLET, &invalid), Some(42u32.encode())),
+				],
+			)],
+			StateVersion::V1,
+		));
+		let state_root = *backend.root();
+
+		let keys = [
+			system_account_key,
+			account_info_key(PALLET, &eoa),
+			account_info_key(PALLET, &missing),
+			account_info_key(PALLET, &contract),
+			account_info_key(PALLET, &invalid),
+		];
+		let proof = prove_read_on_trie_backend(&backend, &keys)
+			.unwrap()
+			.into_iter_nodes()
+			.collect::<Vec<_>>();
+
+		assert_eq!(
+			verify_system_account_proof::<Blake2Hasher, u32, u128>(
+				&state_root,
+				&keys[0],
+				proof.clone()
+			),
+			Ok(Some(SystemAccount {
+				nonce: 7,
+				consumers: 1,
+				providers: 1,
+				sufficients: 0,
+				free: 100,
+				reserved: 10,
+				frozen: 20,
+			}))
+		);
+		let verify_dust =
+			|key: &[u8], proof| verify_dust_proof::<Blake2Hasher, u128>(&state_root, key, proof);
+		assert_eq!(verify_dust(&keys[1], proof.clone()), Ok(42));
+		assert_eq!(verify_dust(&keys[2], proof.clone()), Ok(0));
+		assert_eq!(verify_dust(&keys[3], proof.clone()), Ok(7));
+		assert_eq!(verify_dust(&keys[4], proof), Err(Error::InvalidAccountInfo));
+
+		// Each entry can not be read without its trie nodes.
+		let proof = prove_read_on_trie_backend(&backend, &keys[1..2]).unwrap().into_iter_nodes();
+		assert_eq!(
+			verify_system_account_proof::<Blake2Hasher, u32, u128>(&state_root, &keys[0], proof),
+			Err(Error::IncompleteProof)
+		);
+		let proof = prove_read_on_trie_backend(&backend, &keys[..1]).unwrap().into_iter_nodes();
+		assert_eq!(verify_dust(&keys[1], proof.collect()), Err(Error::IncompleteProof));
+	}
+}
@@ -0,0 +1,24 @@
+CREATE TABLE IF NOT EXISTS traces (
+	block_hash BLOB NOT NULL,
+	block_number INTEGER NOT NULL,
+	transaction_index INTEGER NOT NULL,
+	trace_index INTEGER NOT NULL,
+	from_address BLOB NOT NULL,
+	to_address BLOB NOT NULL,
+	trace TEXT NOT NULL,
+	PRIMARY KEY (block_hash, transaction_index, trace_index)
+);
+
+CREATE INDEX IF NOT EXISTS idx_traces_from_address_block_number ON traces (
+	from_address,
+	block_number
+);
+
+CREATE INDEX IF NOT EXISTS idx_traces_to_address_block_number ON traces (
+	to_address,
+	block_number
+);
+
+CREATE INDEX IF NOT EXISTS idx_traces_block_number ON traces (
+	block_number
+);
@@ -14,11 +14,26 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+mod bundler_apis;
+pub use bundler_apis::*;
+
 mod debug_apis;
 pub use debug_apis::*;
 
+mod dev_apis;
+pub use dev_apis::*;
+
 mod execution_apis;
 pub use execution_apis::*;
 
 mod health_api;
 pub use health_api::*;
+
+mod pubsub_apis;
+pub use pubsub_apis::*;
+
+mod trace_apis;
+pub use trace_apis::*;
+
+mod txpool_apis;
+pub use txpool_apis::*;
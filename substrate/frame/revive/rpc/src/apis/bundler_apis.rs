@@ -0,0 +1,105 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::*;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+
+/// ERC-4337 bundler JSON-RPC apis.
+#[rpc(server, client)]
+pub trait BundlerRpc {
+	/// Submits a user operation to the mempool of the bundler, and returns its hash.
+	///
+	/// ## References
+	///
+	/// - <https://eips.ethereum.org/EIPS/eip-4337#rpc-methods-eth-namespace>
+	#[method(name = "eth_sendUserOperation")]
+	async fn send_user_operation(
+		&self,
+		user_operation: UserOperation,
+		entry_point: H160,
+	) -> RpcResult<H256>;
+
+	/// Estimates the gas limits of a user operation.
+	///
+	/// ## References
+	///
+	/// - <https://eips.ethereum.org/EIPS/eip-4337#rpc-methods-eth-namespace>
+	#[method(name = "eth_estimateUserOperationGas")]
+	async fn estimate_user_operation_gas(
+		&self,
+		user_operation: UserOperation,
+		entry_point: H160,
+	) -> RpcResult<UserOperationGasEstimate>;
+
+	/// Returns the receipt of a user operation, if it was included.
+	///
+	/// ## References
+	///
+	/// - <https://eips.ethereum.org/EIPS/eip-4337#rpc-methods-eth-namespace>
+	#[method(name = "eth_getUserOperationReceipt")]
+	async fn get_user_operation_receipt(
+		&self,
+		user_operation_hash: H256,
+	) -> RpcResult<Option<UserOperationReceipt>>;
+
+	/// Returns the `EntryPoint` contracts supported by the bundler.
+	///
+	/// ## References
+	///
+	/// - <https://eips.ethereum.org/EIPS/eip-4337#rpc-methods-eth-namespace>
+	#[method(name = "eth_supportedEntryPoints")]
+	async fn supported_entry_points(&self) -> RpcResult<Vec<H160>>;
+}
+
+pub struct BundlerRpcServerImpl {
+	bundler: Bundler,
+}
+
+impl BundlerRpcServerImpl {
+	pub fn new(bundler: Bundler) -> Self {
+		Self { bundler }
+	}
+}
+
+#[async_trait]
+impl BundlerRpcServer for BundlerRpcServerImpl {
+	async fn send_user_operation(
+		&self,
+		user_operation: UserOperation,
+		entry_point: H160,
+	) -> RpcResult<H256> {
+		Ok(self.bundler.send_user_operation(user_operation, entry_point).await?)
+	}
+
+	async fn estimate_user_operation_gas(
+		&self,
+		user_operation: UserOperation,
+		entry_point: H160,
+	) -> RpcResult<UserOperationGasEstimate> {
+		Ok(self.bundler.estimate_user_operation_gas(user_operation, entry_point).await?)
+	}
+
+	async fn get_user_operation_receipt(
+		&self,
+		user_operation_hash: H256,
+	) -> RpcResult<Option<UserOperationReceipt>> {
+		Ok(self.bundler.user_operation_receipt(user_operation_hash).await?)
+	}
+
+	async fn supported_entry_points(&self) -> RpcResult<Vec<H160>> {
+		Ok(vec![self.bundler.entry_point()])
+	}
+}
@@ -0,0 +1,147 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{client::SubstrateBlockNumber, *};
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use std::{collections::BTreeMap, sync::Arc};
+use subxt::ext::subxt_rpcs::rpc_params;
+use tokio::sync::Mutex;
+
+/// Foundry and Hardhat style JSON-RPC apis controlling a development chain.
+///
+/// The calls are forwarded to the node, which must be a `revive-dev-node`.
+///
+/// ## References
+///
+/// - <https://book.getfoundry.sh/reference/anvil/#custom-methods>
+#[rpc(server, client)]
+pub trait DevRpc {
+	/// Seals a new block.
+	#[method(name = "evm_mine")]
+	async fn mine(&self) -> RpcResult<String>;
+
+	/// Shifts the timestamp of the next blocks by `seconds`, returning the total time shift.
+	#[method(name = "evm_increaseTime")]
+	async fn increase_time(&self, seconds: u64) -> RpcResult<u64>;
+
+	/// Takes a snapshot of the chain, returning its id.
+	#[method(name = "evm_snapshot")]
+	async fn snapshot(&self) -> RpcResult<U256>;
+
+	/// Reverts the chain to the snapshot with the given id.
+	///
+	/// The receipts, logs and traces of the reverted blocks are removed.
+	#[method(name = "evm_revert")]
+	async fn revert(&self, id: U256) -> RpcResult<bool>;
+
+	/// Sets the balance of an account.
+	#[method(name = "anvil_setBalance")]
+	async fn set_balance(&self, address: H160, balance: U256) -> RpcResult<()>;
+
+	/// Sets the code of an account.
+	#[method(name = "anvil_setCode")]
+	async fn set_code(&self, address: H160, code: Bytes) -> RpcResult<()>;
+
+	/// Sets a storage slot of a contract.
+	#[method(name = "anvil_setStorageAt")]
+	async fn set_storage_at(&self, address: H160, slot: U256, value: H256) -> RpcResult<bool>;
+
+	/// Sends the transactions of an account without signing them, see
+	/// `eth_sendTransaction`.
+	#[method(name = "anvil_impersonateAccount")]
+	async fn impersonate_account(&self, address: H160) -> RpcResult<()>;
+
+	/// Stops impersonating an account.
+	#[method(name = "anvil_stopImpersonatingAccount")]
+	async fn stop_impersonating_account(&self, address: H160) -> RpcResult<()>;
+}
+
+#[derive(Clone)]
+pub struct DevRpcServerImpl {
+	client: client::Client,
+	/// The best block number of the snapshots taken, by id.
+	snapshots: Arc<Mutex<BTreeMap<U256, SubstrateBlockNumber>>>,
+}
+
+impl DevRpcServerImpl {
+	pub fn new(client: client::Client) -> Self {
+		Self { client, snapshots: Default::default() }
+	}
+}
+
+#[async_trait]
+impl DevRpcServer for DevRpcServerImpl {
+	async fn mine(&self) -> RpcResult<String> {
+		Ok(self.client.dev_request("evm_mine", rpc_params![]).await?)
+	}
+
+	async fn increase_time(&self, seconds: u64) -> RpcResult<u64> {
+		Ok(self.client.dev_request("evm_increaseTime", rpc_params![seconds]).await?)
+	}
+
+	async fn snapshot(&self) -> RpcResult<U256> {
+		let mut snapshots = self.snapshots.lock().await;
+		let id: U256 = self.client.dev_request("evm_snapshot", rpc_params![]).await?;
+		snapshots.insert(id, self.client.best_block_number().await?);
+		Ok(id)
+	}
+
+	async fn revert(&self, id: U256) -> RpcResult<bool> {
+		let mut snapshots = self.snapshots.lock().await;
+		let reverted_blocks = match snapshots.get(&id) {
+			Some(block_number) => self.client.best_block_hashes_after(*block_number).await?,
+			None => {
+				log::warn!(target: LOG_TARGET, "Reverting to unknown snapshot {id:?}");
+				vec![]
+			},
+		};
+
+		let reverted: bool = self.client.dev_request("evm_revert", rpc_params![id]).await?;
+		if reverted {
+			snapshots.split_off(&id);
+			self.client.remove_reverted_blocks(&reverted_blocks).await?;
+		}
+		Ok(reverted)
+	}
+
+	async fn set_balance(&self, address: H160, balance: U256) -> RpcResult<()> {
+		Ok(self
+			.client
+			.dev_request("anvil_setBalance", rpc_params![address, balance])
+			.await?)
+	}
+
+	async fn set_code(&self, address: H160, code: Bytes) -> RpcResult<()> {
+		Ok(self.client.dev_request("anvil_setCode", rpc_params![address, code]).await?)
+	}
+
+	async fn set_storage_at(&self, address: H160, slot: U256, value: H256) -> RpcResult<bool> {
+		let params = rpc_params![address, slot, value];
+		Ok(self.client.dev_request("anvil_setStorageAt", params).await?)
+	}
+
+	async fn impersonate_account(&self, address: H160) -> RpcResult<()> {
+		Ok(self
+			.client
+			.dev_request("anvil_impersonateAccount", rpc_params![address])
+			.await?)
+	}
+
+	async fn stop_impersonating_account(&self, address: H160) -> RpcResult<()> {
+		let params = rpc_params![address];
+		Ok(self.client.dev_request("anvil_stopImpersonatingAccount", params).await?)
+	}
+}
@@ -32,17 +32,29 @@ pub trait EthRpc {
 	async fn block_number(&self) -> RpcResult<U256>;
 
 	/// Executes a new message call immediately without creating a transaction on the block chain.
+	///
+	/// The debug messages emitted by the called contracts are returned along with the return data.
 	#[method(name = "eth_call")]
 	async fn call(
 		&self,
 		transaction: GenericTransaction,
 		block: Option<BlockNumberOrTagOrHash>,
-	) -> RpcResult<Bytes>;
+		state_overrides: Option<StateOverrides>,
+	) -> RpcResult<CallResult>;
 
 	/// Returns the chain ID of the current network.
 	#[method(name = "eth_chainId")]
 	async fn chain_id(&self) -> RpcResult<U256>;
 
+	/// Generates an access list for a transaction, along with the gas used when sending the
+	/// transaction with this access list.
+	#[method(name = "eth_createAccessList")]
+	async fn create_access_list(
+		&self,
+		transaction: GenericTransaction,
+		block: Option<BlockNumberOrTagOrHash>,
+	) -> RpcResult<AccessListResult>;
+
 	/// Generates and returns an estimate of how much gas is necessary to allow the transaction to
 	/// complete.
 	#[method(name = "eth_estimateGas")]
@@ -50,6 +62,7 @@ pub trait EthRpc {
 		&self,
 		transaction: GenericTransaction,
 		block: Option<BlockNumberOrTag>,
+		state_overrides: Option<StateOverrides>,
 	) -> RpcResult<U256>;
 
 	/// Returns the current price per gas in wei.
@@ -77,6 +90,13 @@ pub trait EthRpc {
 		hydrated_transactions: bool,
 	) -> RpcResult<Option<Block>>;
 
+	/// Returns the receipts of all transactions in a block.
+	#[method(name = "eth_getBlockReceipts")]
+	async fn get_block_receipts(
+		&self,
+		block: BlockNumberOrTagOrHash,
+	) -> RpcResult<Option<Vec<ReceiptInfo>>>;
+
 	/// Returns the number of transactions in a block from a block matching the given block hash.
 	#[method(name = "eth_getBlockTransactionCountByHash")]
 	async fn get_block_transaction_count_by_hash(
@@ -95,10 +115,28 @@ pub trait EthRpc {
 	#[method(name = "eth_getCode")]
 	async fn get_code(&self, address: Address, block: BlockNumberOrTagOrHash) -> RpcResult<Bytes>;
 
+	/// Polling method for a filter, which returns an array of items which occurred since last
+	/// poll.
+	#[method(name = "eth_getFilterChanges")]
+	async fn get_filter_changes(&self, filter_id: U256) -> RpcResult<FilterResults>;
+
+	/// Returns an array of all logs matching filter with given id.
+	#[method(name = "eth_getFilterLogs")]
+	async fn get_filter_logs(&self, filter_id: U256) -> RpcResult<FilterResults>;
+
 	/// Returns an array of all logs matching filter with given id.
 	#[method(name = "eth_getLogs")]
 	async fn get_logs(&self, filter: Option<Filter>) -> RpcResult<FilterResults>;
 
+	/// Returns the merkle proof for a given account and optionally some storage keys.
+	#[method(name = "eth_getProof")]
+	async fn get_proof(
+		&self,
+		address: Address,
+		storage_keys: Vec<U256>,
+		block: BlockNumberOrTagOrHash,
+	) -> RpcResult<AccountProof>;
+
 	/// Returns the value from a storage position at a given address.
 	#[method(name = "eth_getStorageAt")]
 	async fn get_storage_at(
@@ -150,6 +188,18 @@ pub trait EthRpc {
 	#[method(name = "eth_maxPriorityFeePerGas")]
 	async fn max_priority_fee_per_gas(&self) -> RpcResult<U256>;
 
+	/// Creates a filter in the node, to notify when a new block arrives.
+	#[method(name = "eth_newBlockFilter")]
+	async fn new_block_filter(&self) -> RpcResult<U256>;
+
+	/// Creates a filter object, based on filter options, to notify when the state changes (logs).
+	#[method(name = "eth_newFilter")]
+	async fn new_filter(&self, filter: Filter) -> RpcResult<U256>;
+
+	/// Creates a filter in the node, to notify when new pending transactions arrive.
+	#[method(name = "eth_newPendingTransactionFilter")]
+	async fn new_pending_transaction_filter(&self) -> RpcResult<U256>;
+
 	/// Submits a raw transaction. For EIP-4844 transactions, the raw form must be the network form.
 	/// This means it includes the blobs, KZG commitments, and KZG proofs.
 	#[method(name = "eth_sendRawTransaction")]
@@ -159,10 +209,23 @@ pub trait EthRpc {
 	#[method(name = "eth_sendTransaction")]
 	async fn send_transaction(&self, transaction: GenericTransaction) -> RpcResult<H256>;
 
+	/// Executes a sequence of calls over several simulated blocks, on top of the given block and
+	/// with optional state and block overrides.
+	#[method(name = "eth_simulateV1")]
+	async fn simulate_v1(
+		&self,
+		payload: SimulatePayload,
+		block: Option<BlockNumberOrTagOrHash>,
+	) -> RpcResult<Vec<SimulatedBlock>>;
+
 	/// Returns an object with data about the sync status or false.
 	#[method(name = "eth_syncing")]
 	async fn syncing(&self) -> RpcResult<SyncingStatus>;
 
+	/// Uninstalls a filter with given id.
+	#[method(name = "eth_uninstallFilter")]
+	async fn uninstall_filter(&self, filter_id: U256) -> RpcResult<bool>;
+
 	/// Returns true when the client is actively listening for network connections, otherwise false
 	#[method(name = "net_listening")]
 	async fn net_listening(&self) -> RpcResult<bool>;
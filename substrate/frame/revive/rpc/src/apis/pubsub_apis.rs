@@ -0,0 +1,141 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Publish/subscribe JSON-RPC methods.
+
+use crate::*;
+use jsonrpsee::{
+	core::SubscriptionResult, proc_macros::rpc, PendingSubscriptionSink, SubscriptionMessage,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
+
+/// The kind of events to subscribe to.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SubscriptionKind {
+	/// New best block headers, including the ones added by a re-org.
+	NewHeads,
+	/// Logs included in new best blocks, matching the given filter.
+	Logs,
+	/// Hashes of the transactions submitted through this server.
+	NewPendingTransactions,
+}
+
+/// An item sent to the subscribers.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum SubscriptionItem {
+	/// A new best block.
+	Header(Box<Block>),
+	/// A log matching the subscription filter.
+	Log(Box<Log>),
+	/// The hash of a pending transaction.
+	TransactionHash(H256),
+}
+
+/// Ethereum publish/subscribe JSON-RPC apis.
+#[rpc(server, client)]
+pub trait EthPubSubRpc {
+	/// Subscribe to new heads, logs or pending transactions.
+	///
+	/// ## References
+	///
+	/// - <https://geth.ethereum.org/docs/interacting-with-geth/rpc/pubsub>
+	#[subscription(
+		name = "eth_subscribe" => "eth_subscription",
+		unsubscribe = "eth_unsubscribe",
+		item = SubscriptionItem
+	)]
+	async fn eth_subscribe(
+		&self,
+		kind: SubscriptionKind,
+		filter: Option<Filter>,
+	) -> SubscriptionResult;
+}
+
+pub struct EthPubSubRpcServerImpl {
+	client: client::Client,
+}
+
+impl EthPubSubRpcServerImpl {
+	pub fn new(client: client::Client) -> Self {
+		Self { client }
+	}
+}
+
+#[async_trait]
+impl EthPubSubRpcServer for EthPubSubRpcServerImpl {
+	async fn eth_subscribe(
+		&self,
+		pending: PendingSubscriptionSink,
+		kind: SubscriptionKind,
+		filter: Option<Filter>,
+	) -> SubscriptionResult {
+		if filter.is_some() && kind != SubscriptionKind::Logs {
+			pending.reject(EthRpcError::InvalidSubscriptionParams).await;
+			return Ok(());
+		}
+
+		let filter = filter.unwrap_or_default();
+		let mut events = self.client.subscribe_chain_events();
+		let sink = pending.accept().await?;
+
+		loop {
+			let event = tokio::select! {
+				_ = sink.closed() => break,
+				event = events.recv() => event,
+			};
+
+			let event = match event {
+				Ok(event) => event,
+				Err(RecvError::Lagged(skipped)) => {
+					log::warn!(
+						target: LOG_TARGET,
+						"Subscriber {:?} lagging behind, {skipped} events skipped",
+						sink.subscription_id()
+					);
+					continue;
+				},
+				Err(RecvError::Closed) => break,
+			};
+
+			let items = match (kind, &*event) {
+				(SubscriptionKind::NewHeads, ChainEvent::NewHead(block)) =>
+					vec![SubscriptionItem::Header(block.clone())],
+				(SubscriptionKind::Logs, ChainEvent::Logs(logs)) => logs
+					.iter()
+					.filter(|log| log_matches(&filter, log))
+					.map(|log| SubscriptionItem::Log(Box::new(log.clone())))
+					.collect(),
+				(
+					SubscriptionKind::NewPendingTransactions,
+					ChainEvent::NewPendingTransaction(hash),
+				) => vec![SubscriptionItem::TransactionHash(*hash)],
+				_ => continue,
+			};
+
+			for item in items {
+				let msg = SubscriptionMessage::from_json(&item)?;
+				if sink.send(msg).await.is_err() {
+					return Ok(());
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
@@ -0,0 +1,93 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::*;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+
+/// Parity-style trace JSON-RPC apis.
+#[rpc(server, client)]
+pub trait TraceRpc {
+	/// Returns the flat traces of all the transactions of a block.
+	///
+	/// ## References
+	///
+	/// - <https://openethereum.github.io/JSONRPC-trace-module#trace_block>
+	#[method(name = "trace_block")]
+	async fn trace_block(&self, block: BlockNumberOrTag) -> RpcResult<Vec<FlatTrace>>;
+
+	/// Returns the flat traces matching the given filter.
+	///
+	/// Traces are only available for the blocks indexed while trace indexing is enabled.
+	///
+	/// ## References
+	///
+	/// - <https://openethereum.github.io/JSONRPC-trace-module#trace_filter>
+	#[method(name = "trace_filter")]
+	async fn trace_filter(&self, filter: TraceFilter) -> RpcResult<Vec<FlatTrace>>;
+
+	/// Replays a transaction, and returns the requested traces.
+	///
+	/// Only the `trace` type is supported.
+	///
+	/// ## References
+	///
+	/// - <https://openethereum.github.io/JSONRPC-trace-module#trace_replaytransaction>
+	#[method(name = "trace_replayTransaction")]
+	async fn replay_transaction(
+		&self,
+		transaction_hash: H256,
+		trace_types: Vec<TraceResultType>,
+	) -> RpcResult<TraceResults>;
+}
+
+pub struct TraceRpcServerImpl {
+	client: client::Client,
+}
+
+impl TraceRpcServerImpl {
+	pub fn new(client: client::Client) -> Self {
+		Self { client }
+	}
+}
+
+#[async_trait]
+impl TraceRpcServer for TraceRpcServerImpl {
+	async fn trace_block(&self, block: BlockNumberOrTag) -> RpcResult<Vec<FlatTrace>> {
+		Ok(self.client.flat_trace_block_by_number(block).await?)
+	}
+
+	async fn trace_filter(&self, filter: TraceFilter) -> RpcResult<Vec<FlatTrace>> {
+		Ok(self.client.trace_filter(filter).await?)
+	}
+
+	async fn replay_transaction(
+		&self,
+		transaction_hash: H256,
+		trace_types: Vec<TraceResultType>,
+	) -> RpcResult<TraceResults> {
+		if let Some(trace_type) =
+			trace_types.iter().find(|trace_type| **trace_type != TraceResultType::Trace)
+		{
+			return Err(EthRpcError::TraceTypeNotSupported(*trace_type).into());
+		}
+
+		let mut results = self.client.replay_transaction(transaction_hash).await?;
+		if trace_types.is_empty() {
+			results.trace = None;
+		}
+		Ok(results)
+	}
+}
@@ -0,0 +1,72 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::*;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+
+/// Transaction pool JSON-RPC apis.
+#[rpc(server, client)]
+pub trait TxPoolRpc {
+	/// Returns the pending and queued transactions of the pool, grouped by sender and nonce.
+	///
+	/// ## References
+	///
+	/// - <https://geth.ethereum.org/docs/interacting-with-geth/rpc/ns-txpool#txpool-content>
+	#[method(name = "txpool_content")]
+	async fn content(&self) -> RpcResult<TxPoolContent>;
+
+	/// Returns a summary of the pending and queued transactions of the pool, grouped by sender
+	/// and nonce.
+	///
+	/// ## References
+	///
+	/// - <https://geth.ethereum.org/docs/interacting-with-geth/rpc/ns-txpool#txpool-inspect>
+	#[method(name = "txpool_inspect")]
+	async fn inspect(&self) -> RpcResult<TxPoolInspect>;
+
+	/// Returns the number of pending and queued transactions of the pool.
+	///
+	/// ## References
+	///
+	/// - <https://geth.ethereum.org/docs/interacting-with-geth/rpc/ns-txpool#txpool-status>
+	#[method(name = "txpool_status")]
+	async fn status(&self) -> RpcResult<TxPoolStatus>;
+}
+
+pub struct TxPoolRpcServerImpl {
+	client: client::Client,
+}
+
+impl TxPoolRpcServerImpl {
+	pub fn new(client: client::Client) -> Self {
+		Self { client }
+	}
+}
+
+#[async_trait]
+impl TxPoolRpcServer for TxPoolRpcServerImpl {
+	async fn content(&self) -> RpcResult<TxPoolContent> {
+		Ok(self.client.txpool_content().await?)
+	}
+
+	async fn inspect(&self) -> RpcResult<TxPoolInspect> {
+		Ok(self.client.txpool_content().await?.inspect())
+	}
+
+	async fn status(&self) -> RpcResult<TxPoolStatus> {
+		Ok(self.client.txpool_content().await?.status())
+	}
+}
@@ -0,0 +1,1103 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! An [ERC-4337](https://eips.ethereum.org/EIPS/eip-4337) bundler.
+//!
+//! User operations are validated by dry-running `handleOps` on the `EntryPoint` contract, kept in
+//! a local mempool, and bundled into a `handleOps` transaction submitted with `eth_transact` on
+//! every new best block.
+//!
+//! The [ERC-7562](https://eips.ethereum.org/EIPS/eip-7562) opcode rules are checked on the gas
+//! profile of the validation: the account, factory and paymaster may not access the environment
+//! of the block, and only the factory may instantiate contracts. The storage access rules are not
+//! enforced.
+//!
+//! Only the v0.7 `EntryPoint` ABI is supported.
+use crate::{
+	client::{Client, ClientError},
+	subxt_client, ChainEvent, LOG_TARGET,
+};
+use alloy_core::{
+	primitives::{Address, Bytes as AlloyBytes, B256, U256 as AlloyU256},
+	sol,
+	sol_types::{SolCall, SolError, SolEvent, SolValue},
+};
+use jsonrpsee::types::ErrorObjectOwned;
+use pallet_revive::{
+	evm::{
+		Account, BlockTag, Bytes, GasProfilerConfig, GenericTransaction, Log, ReceiptInfo, Trace,
+	},
+	EthTransactError,
+};
+use serde::{Deserialize, Serialize};
+use sp_core::{keccak_256, H160, H256, U256};
+use std::{
+	collections::{HashMap, HashSet, VecDeque},
+	sync::Arc,
+};
+use thiserror::Error;
+use tokio::sync::{broadcast::error::RecvError, Mutex};
+
+sol! {
+	/// A user operation, as passed to the `EntryPoint`.
+	struct PackedUserOperation {
+		address sender;
+		uint256 nonce;
+		bytes initCode;
+		bytes callData;
+		bytes32 accountGasLimits;
+		uint256 preVerificationGas;
+		bytes32 gasFees;
+		bytes paymasterAndData;
+		bytes signature;
+	}
+
+	/// The subset of the v0.7 `EntryPoint` interface used by the bundler.
+	interface IEntryPoint {
+		function handleOps(PackedUserOperation[] calldata ops, address payable beneficiary) external;
+
+		event UserOperationEvent(
+			bytes32 indexed userOpHash,
+			address indexed sender,
+			address indexed paymaster,
+			uint256 nonce,
+			bool success,
+			uint256 actualGasCost,
+			uint256 actualGasUsed
+		);
+
+		event UserOperationRevertReason(
+			bytes32 indexed userOpHash,
+			address indexed sender,
+			uint256 nonce,
+			bytes revertReason
+		);
+
+		error FailedOp(uint256 opIndex, string reason);
+
+		error FailedOpWithRevert(uint256 opIndex, string reason, bytes inner);
+	}
+}
+
+/// The maximum number of user operations in a bundle.
+const MAX_BUNDLE_SIZE: usize = 16;
+
+/// The maximum number of user operations in the mempool.
+const MAX_POOL_SIZE: usize = 4096;
+
+/// The number of included user operations whose bundle is remembered for receipt lookups.
+const MAX_INCLUDED: usize = 4096;
+
+/// The number of user operations rejected in a bundle that are remembered as banned.
+const MAX_BANNED: usize = 4096;
+
+/// The fee increase, in percent, required to replace a user operation with the same sender and
+/// nonce.
+const REPLACEMENT_FEE_BUMP_PERCENT: u64 = 10;
+
+/// The gas limits used when estimating the gas of a user operation.
+const ESTIMATION_GAS_LIMIT: u64 = 10_000_000;
+
+/// The costs a contract may not charge while validating a user operation.
+///
+/// They access the environment of the block, so an operation can pass the simulation and fail in
+/// the bundle. See the [ERC-7562 opcode rules](https://eips.ethereum.org/EIPS/eip-7562#opcode-rules).
+const BANNED_VALIDATION_COSTS: &[&str] = &[
+	"Origin",
+	"CallerIsOrigin",
+	"GasPrice",
+	"GasLimit",
+	"BaseFee",
+	"BlockHash",
+	"BlockNumber",
+	"BlockAuthor",
+	"Now",
+	"Balance",
+	"BalanceOf",
+	"WeightToFee",
+	"Terminate",
+];
+
+/// The calldata and overhead costs of a bundle, used to compute the `preVerificationGas`.
+mod pre_verification_gas {
+	/// The transaction overhead, fully charged to each user operation.
+	pub const FIXED: u64 = 21_000;
+	/// The overhead of a user operation in a bundle.
+	pub const PER_USER_OP: u64 = 18_300;
+	/// The overhead per word of an encoded user operation.
+	pub const PER_USER_OP_WORD: u64 = 4;
+	/// The cost of a zero calldata byte.
+	pub const ZERO_BYTE: u64 = 4;
+	/// The cost of a non-zero calldata byte.
+	pub const NON_ZERO_BYTE: u64 = 16;
+}
+
+/// JSON-RPC error code of invalid user operation fields.
+///
+/// See <https://eips.ethereum.org/EIPS/eip-7769#error-codes>.
+const INVALID_FIELDS_CODE: i32 = -32602;
+
+/// JSON-RPC error code of a user operation rejected by the `EntryPoint`.
+const REJECTED_BY_ENTRY_POINT_CODE: i32 = -32500;
+
+/// JSON-RPC error code of a user operation violating the opcode validation rules.
+const OPCODE_VALIDATION_CODE: i32 = -32502;
+
+/// A user operation, as sent by RPC clients.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct UserOperation {
+	/// The account making the operation.
+	pub sender: H160,
+	/// The anti-replay nonce of the account.
+	pub nonce: U256,
+	/// The factory deploying the account, if it is not deployed yet.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub factory: Option<H160>,
+	/// The data passed to the factory.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub factory_data: Option<Bytes>,
+	/// The data passed to the account.
+	pub call_data: Bytes,
+	/// The gas allocated to the execution of the call.
+	pub call_gas_limit: U256,
+	/// The gas allocated to the validation of the operation.
+	pub verification_gas_limit: U256,
+	/// The gas paid for the overhead of the bundle.
+	pub pre_verification_gas: U256,
+	/// The maximum fee per gas, see [EIP-1559](https://eips.ethereum.org/EIPS/eip-1559).
+	pub max_fee_per_gas: U256,
+	/// The maximum priority fee per gas.
+	pub max_priority_fee_per_gas: U256,
+	/// The paymaster paying for the operation, if any.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub paymaster: Option<H160>,
+	/// The gas allocated to the validation of the paymaster.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub paymaster_verification_gas_limit: Option<U256>,
+	/// The gas allocated to the post-operation of the paymaster.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub paymaster_post_op_gas_limit: Option<U256>,
+	/// The data passed to the paymaster.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub paymaster_data: Option<Bytes>,
+	/// The signature validated by the account.
+	pub signature: Bytes,
+}
+
+/// The gas estimation of a user operation.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperationGasEstimate {
+	/// The gas paid for the overhead of the bundle.
+	pub pre_verification_gas: U256,
+	/// The gas needed to validate the operation.
+	pub verification_gas_limit: U256,
+	/// The gas needed to execute the call.
+	pub call_gas_limit: U256,
+	/// The gas needed to validate the paymaster, if any.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub paymaster_verification_gas_limit: Option<U256>,
+}
+
+/// The receipt of an included user operation.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperationReceipt {
+	/// The hash of the user operation.
+	pub user_op_hash: H256,
+	/// The `EntryPoint` that executed the operation.
+	pub entry_point: H160,
+	/// The account making the operation.
+	pub sender: H160,
+	/// The nonce of the operation.
+	pub nonce: U256,
+	/// The paymaster that paid for the operation, zero if none.
+	pub paymaster: H160,
+	/// The cost of the operation, paid by the account or the paymaster.
+	pub actual_gas_cost: U256,
+	/// The gas used by the operation.
+	pub actual_gas_used: U256,
+	/// Whether the call of the operation succeeded.
+	pub success: bool,
+	/// The revert reason of the call, if it failed.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub reason: Option<Bytes>,
+	/// The logs emitted by the operation.
+	pub logs: Vec<Log>,
+	/// The receipt of the bundle transaction.
+	pub receipt: ReceiptInfo,
+}
+
+/// The error type of the bundler.
+#[derive(Error, Debug)]
+pub enum BundlerError {
+	/// A [`ClientError`] wrapper error.
+	#[error("Client error: {0}")]
+	ClientError(#[from] ClientError),
+	/// The user operation was sent to an `EntryPoint` the bundler doesn't submit to.
+	#[error("Unsupported entry point {0:?}")]
+	UnsupportedEntryPoint(H160),
+	/// A field of the user operation is invalid.
+	#[error("Invalid user operation field: {0}")]
+	InvalidField(&'static str),
+	/// The `preVerificationGas` doesn't cover the overhead of the operation.
+	#[error("preVerificationGas too low, expected at least {0}")]
+	PreVerificationGasTooLow(U256),
+	/// The `EntryPoint` rejected the user operation.
+	#[error("{0}")]
+	Rejected(String),
+	/// The `EntryPoint` rejected the user operation at `index` of a bundle.
+	#[error("User operation {0} of the bundle rejected: {1}")]
+	RejectedInBundle(usize, String),
+	/// The validation of the user operation uses a forbidden cost.
+	#[error("User operation violates the validation rules: {0}")]
+	ValidationRule(String),
+	/// The user operation was rejected in a bundle before.
+	#[error("User operation banned")]
+	Banned,
+	/// The user operation is already in the mempool.
+	#[error("User operation already known")]
+	AlreadyKnown,
+	/// An operation with the same sender and nonce and higher fees is already in the mempool.
+	#[error("Replacement user operation underpriced")]
+	ReplacementUnderpriced,
+	/// The mempool is full.
+	#[error("User operation mempool is full")]
+	MempoolFull,
+	/// The bundle transaction couldn't be built.
+	#[error("Invalid bundle transaction")]
+	InvalidBundleTransaction,
+}
+
+impl From<BundlerError> for ErrorObjectOwned {
+	fn from(value: BundlerError) -> Self {
+		match value {
+			BundlerError::ClientError(err) => Self::from(err),
+			BundlerError::Rejected(_) |
+			BundlerError::RejectedInBundle(..) |
+			BundlerError::Banned =>
+				Self::owned::<String>(REJECTED_BY_ENTRY_POINT_CODE, value.to_string(), None),
+			BundlerError::ValidationRule(_) =>
+				Self::owned::<String>(OPCODE_VALIDATION_CODE, value.to_string(), None),
+			_ => Self::owned::<String>(INVALID_FIELDS_CODE, value.to_string(), None),
+		}
+	}
+}
+
+impl UserOperation {
+	/// Pack the operation into the layout expected by the `EntryPoint`.
+	pub fn pack(&self) -> PackedUserOperation {
+		let init_code = match self.factory {
+			Some(factory) => [factory.as_bytes(), bytes(&self.factory_data)].concat(),
+			None => Vec::new(),
+		};
+		let mut paymaster_and_data = Vec::new();
+		if let Some(paymaster) = self.paymaster {
+			let verification_gas_limit = self.paymaster_verification_gas_limit.unwrap_or_default();
+			let post_op_gas_limit = self.paymaster_post_op_gas_limit.unwrap_or_default();
+			paymaster_and_data.extend_from_slice(paymaster.as_bytes());
+			paymaster_and_data.extend_from_slice(&verification_gas_limit.low_u128().to_be_bytes());
+			paymaster_and_data.extend_from_slice(&post_op_gas_limit.low_u128().to_be_bytes());
+			paymaster_and_data.extend_from_slice(bytes(&self.paymaster_data));
+		}
+
+		PackedUserOperation {
+			sender: Address::from(self.sender.0),
+			nonce: to_alloy_u256(self.nonce),
+			initCode: init_code.into(),
+			callData: AlloyBytes::from(self.call_data.0.clone()),
+			accountGasLimits: pack_u128_pair(self.verification_gas_limit, self.call_gas_limit),
+			preVerificationGas: to_alloy_u256(self.pre_verification_gas),
+			gasFees: pack_u128_pair(self.max_priority_fee_per_gas, self.max_fee_per_gas),
+			paymasterAndData: paymaster_and_data.into(),
+			signature: AlloyBytes::from(self.signature.0.clone()),
+		}
+	}
+
+	/// The hash identifying the operation, as computed by `EntryPoint.getUserOpHash`.
+	pub fn hash(&self, entry_point: H160, chain_id: u64) -> H256 {
+		let packed = self.pack();
+		let encoded = (
+			packed.sender,
+			packed.nonce,
+			B256::from(keccak_256(&packed.initCode)),
+			B256::from(keccak_256(&packed.callData)),
+			packed.accountGasLimits,
+			packed.preVerificationGas,
+			packed.gasFees,
+			B256::from(keccak_256(&packed.paymasterAndData)),
+		)
+			.abi_encode();
+		let encoded = (
+			B256::from(keccak_256(&encoded)),
+			Address::from(entry_point.0),
+			AlloyU256::from(chain_id),
+		)
+			.abi_encode();
+		H256(keccak_256(&encoded))
+	}
+
+	/// The `preVerificationGas` needed to pay for the calldata and overhead of the operation.
+	pub fn required_pre_verification_gas(&self) -> U256 {
+		use pre_verification_gas::*;
+		let encoded = self.pack().abi_encode();
+		let calldata: u64 = encoded
+			.iter()
+			.map(|byte| if *byte == 0 { ZERO_BYTE } else { NON_ZERO_BYTE })
+			.sum();
+		let words = encoded.len().div_ceil(32) as u64;
+		(calldata + FIXED + PER_USER_OP + PER_USER_OP_WORD * words).into()
+	}
+
+	/// Check that the fields of the operation can be packed.
+	pub fn validate(&self) -> Result<(), BundlerError> {
+		let u128_fields = [
+			(self.call_gas_limit, "callGasLimit"),
+			(self.verification_gas_limit, "verificationGasLimit"),
+			(self.max_fee_per_gas, "maxFeePerGas"),
+			(self.max_priority_fee_per_gas, "maxPriorityFeePerGas"),
+			(
+				self.paymaster_verification_gas_limit.unwrap_or_default(),
+				"paymasterVerificationGasLimit",
+			),
+			(self.paymaster_post_op_gas_limit.unwrap_or_default(), "paymasterPostOpGasLimit"),
+		];
+		for (value, field) in u128_fields {
+			if value > U256::from(u128::MAX) {
+				return Err(BundlerError::InvalidField(field));
+			}
+		}
+
+		if self.max_priority_fee_per_gas > self.max_fee_per_gas {
+			return Err(BundlerError::InvalidField("maxPriorityFeePerGas"));
+		}
+		if self.factory.is_none() && self.factory_data.is_some() {
+			return Err(BundlerError::InvalidField("factoryData"));
+		}
+		if self.paymaster.is_none() &&
+			(self.paymaster_verification_gas_limit.is_some() ||
+				self.paymaster_post_op_gas_limit.is_some() ||
+				self.paymaster_data.is_some())
+		{
+			return Err(BundlerError::InvalidField("paymaster"));
+		}
+		Ok(())
+	}
+}
+
+/// The user operations waiting to be bundled, and the bundles of the included ones.
+#[derive(Default)]
+pub struct UserOperationPool {
+	/// The pending operations, by hash.
+	pending: HashMap<H256, UserOperation>,
+	/// The hash of the bundle transaction of the included operations.
+	included: HashMap<H256, H256>,
+	/// The included operations, oldest first.
+	included_order: VecDeque<H256>,
+	/// The operations rejected in a bundle.
+	banned: HashSet<H256>,
+	/// The banned operations, oldest first.
+	banned_order: VecDeque<H256>,
+}
+
+impl UserOperationPool {
+	/// Add an operation to the pool.
+	///
+	/// An operation with the same sender and nonce is replaced if the new one bumps both fees by
+	/// at least [`REPLACEMENT_FEE_BUMP_PERCENT`].
+	pub fn add(&mut self, hash: H256, op: UserOperation) -> Result<(), BundlerError> {
+		if self.pending.contains_key(&hash) {
+			return Err(BundlerError::AlreadyKnown);
+		}
+		if self.banned.contains(&hash) {
+			return Err(BundlerError::Banned);
+		}
+
+		let replaced = self
+			.pending
+			.iter()
+			.find(|(_, pending)| pending.sender == op.sender && pending.nonce == op.nonce)
+			.map(|(hash, pending)| (*hash, is_fee_bump(pending, &op)));
+		match replaced {
+			Some((_, false)) => return Err(BundlerError::ReplacementUnderpriced),
+			Some((replaced, true)) => {
+				self.pending.remove(&replaced);
+			},
+			None if self.pending.len() >= MAX_POOL_SIZE => return Err(BundlerError::MempoolFull),
+			None => {},
+		}
+
+		self.pending.insert(hash, op);
+		Ok(())
+	}
+
+	/// Remove and return the operations of the next bundle.
+	///
+	/// The operations paying the highest priority fee come first. Only the operation with the
+	/// lowest nonce of each sender is included, as the following ones can't be validated before
+	/// it is executed.
+	pub fn take_bundle(&mut self) -> Vec<(H256, UserOperation)> {
+		let mut candidates: Vec<_> = self.pending.iter().collect();
+		candidates.sort_by(|(_, a), (_, b)| {
+			b.max_priority_fee_per_gas
+				.cmp(&a.max_priority_fee_per_gas)
+				.then(a.nonce.cmp(&b.nonce))
+		});
+
+		let mut bundle: Vec<H256> = Vec::new();
+		let mut senders = Vec::new();
+		for (hash, op) in candidates {
+			let lowest_nonce = !self
+				.pending
+				.values()
+				.any(|other| other.sender == op.sender && other.nonce < op.nonce);
+			if lowest_nonce && !senders.contains(&op.sender) {
+				senders.push(op.sender);
+				bundle.push(*hash);
+			}
+			if bundle.len() == MAX_BUNDLE_SIZE {
+				break;
+			}
+		}
+
+		bundle
+			.into_iter()
+			.filter_map(|hash| self.pending.remove(&hash).map(|op| (hash, op)))
+			.collect()
+	}
+
+	/// Return the operations of a bundle that couldn't be submitted to the pool.
+	///
+	/// The operation at `rejected`, if any, made the `EntryPoint` reject the whole bundle. It is
+	/// banned instead, so that it can't block the following bundles.
+	pub fn return_bundle(&mut self, bundle: Vec<(H256, UserOperation)>, rejected: Option<usize>) {
+		for (index, (hash, op)) in bundle.into_iter().enumerate() {
+			if Some(index) == rejected {
+				self.ban(hash);
+			} else {
+				let _ = self.add(hash, op);
+			}
+		}
+	}
+
+	/// Ban the operation `hash` from the pool.
+	fn ban(&mut self, hash: H256) {
+		if self.banned.insert(hash) {
+			self.banned_order.push_back(hash);
+		}
+		while self.banned_order.len() > MAX_BANNED {
+			if let Some(hash) = self.banned_order.pop_front() {
+				self.banned.remove(&hash);
+			}
+		}
+	}
+
+	/// Record that the given operations were submitted in the bundle transaction `tx_hash`.
+	pub fn included(&mut self, hashes: impl IntoIterator<Item = H256>, tx_hash: H256) {
+		for hash in hashes {
+			self.included.insert(hash, tx_hash);
+			self.included_order.push_back(hash);
+		}
+		while self.included_order.len() > MAX_INCLUDED {
+			if let Some(hash) = self.included_order.pop_front() {
+				self.included.remove(&hash);
+			}
+		}
+	}
+
+	/// The hash of the bundle transaction that included the operation `hash`.
+	pub fn bundle_of(&self, hash: &H256) -> Option<H256> {
+		self.included.get(hash).copied()
+	}
+}
+
+/// A bundler submitting user operations to an `EntryPoint` contract.
+#[derive(Clone)]
+pub struct Bundler {
+	/// The client used to interact with the substrate node.
+	client: Client,
+	/// The `EntryPoint` the operations are submitted to.
+	entry_point: H160,
+	/// The account signing the bundle transactions, and receiving their fees.
+	signer: Arc<Account>,
+	/// The user operation mempool.
+	pool: Arc<Mutex<UserOperationPool>>,
+}
+
+impl Bundler {
+	/// Creates a new [`Bundler`].
+	pub fn new(client: Client, entry_point: H160, signer: Account) -> Self {
+		Self { client, entry_point, signer: Arc::new(signer), pool: Default::default() }
+	}
+
+	/// The `EntryPoint` the operations are submitted to.
+	pub fn entry_point(&self) -> H160 {
+		self.entry_point
+	}
+
+	/// Validate a user operation and add it to the mempool.
+	pub async fn send_user_operation(
+		&self,
+		op: UserOperation,
+		entry_point: H160,
+	) -> Result<H256, BundlerError> {
+		self.ensure_entry_point(entry_point)?;
+		op.validate()?;
+
+		let required = op.required_pre_verification_gas();
+		if op.pre_verification_gas < required {
+			return Err(BundlerError::PreVerificationGasTooLow(required));
+		}
+
+		self.simulate(vec![op.pack()]).await?;
+		self.check_validation_rules(&op).await?;
+		let hash = op.hash(self.entry_point, self.client.chain_id());
+		self.pool.lock().await.add(hash, op)?;
+		log::debug!(target: LOG_TARGET, "Added user operation {hash:?} to the mempool");
+		Ok(hash)
+	}
+
+	/// Estimate the gas limits of a user operation.
+	///
+	/// The operation is simulated with generous gas limits and no fees. The gas used by the whole
+	/// `handleOps` call is returned for both the validation and the execution, which is a
+	/// conservative upper bound of each of them.
+	pub async fn estimate_user_operation_gas(
+		&self,
+		mut op: UserOperation,
+		entry_point: H160,
+	) -> Result<UserOperationGasEstimate, BundlerError> {
+		self.ensure_entry_point(entry_point)?;
+
+		op.verification_gas_limit = ESTIMATION_GAS_LIMIT.into();
+		op.call_gas_limit = ESTIMATION_GAS_LIMIT.into();
+		op.max_fee_per_gas = U256::zero();
+		op.max_priority_fee_per_gas = U256::zero();
+		if op.paymaster.is_some() {
+			op.paymaster_verification_gas_limit = Some(ESTIMATION_GAS_LIMIT.into());
+			op.paymaster_post_op_gas_limit = Some(ESTIMATION_GAS_LIMIT.into());
+		}
+		op.validate()?;
+		op.pre_verification_gas = op.required_pre_verification_gas();
+
+		let gas = self.simulate(vec![op.pack()]).await?;
+		Ok(UserOperationGasEstimate {
+			pre_verification_gas: op.pre_verification_gas,
+			verification_gas_limit: gas,
+			call_gas_limit: gas,
+			paymaster_verification_gas_limit: op.paymaster.map(|_| gas),
+		})
+	}
+
+	/// Get the receipt of a user operation submitted through this bundler.
+	pub async fn user_operation_receipt(
+		&self,
+		hash: H256,
+	) -> Result<Option<UserOperationReceipt>, BundlerError> {
+		let Some(tx_hash) = self.pool.lock().await.bundle_of(&hash) else { return Ok(None) };
+		let Some(receipt) = self.client.receipt(&tx_hash).await else { return Ok(None) };
+		Ok(user_operation_receipt(hash, self.entry_point, receipt))
+	}
+
+	/// Submit a bundle on every new best block, until the chain event stream is closed.
+	pub async fn run(self) {
+		let mut events = self.client.subscribe_chain_events();
+		loop {
+			match events.recv().await {
+				Ok(event) if matches!(*event, ChainEvent::NewHead(_)) =>
+					if let Err(err) = self.bundle().await {
+						log::warn!(target: LOG_TARGET, "Failed to submit bundle: {err:?}");
+					},
+				Ok(_) | Err(RecvError::Lagged(_)) => {},
+				Err(RecvError::Closed) => break,
+			}
+		}
+	}
+
+	/// Submit the next bundle of the mempool, if any.
+	///
+	/// Operations that fail the simulation are dropped, and the operation the `EntryPoint` rejects
+	/// in the bundle is banned. Returns the hash of the bundle transaction.
+	pub async fn bundle(&self) -> Result<Option<H256>, BundlerError> {
+		let candidates = self.pool.lock().await.take_bundle();
+
+		let mut bundle = Vec::new();
+		for (hash, op) in candidates {
+			match self.simulate(vec![op.pack()]).await {
+				Ok(_) => bundle.push((hash, op)),
+				Err(err) => {
+					log::debug!(target: LOG_TARGET, "Dropping user operation {hash:?}: {err:?}")
+				},
+			}
+		}
+		if bundle.is_empty() {
+			return Ok(None);
+		}
+
+		let ops = bundle.iter().map(|(_, op)| op.pack()).collect();
+		match self.submit(ops).await {
+			Ok(tx_hash) => {
+				log::debug!(
+					target: LOG_TARGET,
+					"Submitted bundle {tx_hash:?} with {} user operations",
+					bundle.len()
+				);
+				self.pool
+					.lock()
+					.await
+					.included(bundle.into_iter().map(|(hash, _)| hash), tx_hash);
+				Ok(Some(tx_hash))
+			},
+			Err(err) => {
+				let rejected = match &err {
+					BundlerError::RejectedInBundle(index, reason) => {
+						log::debug!(
+							target: LOG_TARGET,
+							"Banning user operation {:?}: {reason}",
+							bundle.get(*index).map(|(hash, _)| hash)
+						);
+						Some(*index)
+					},
+					_ => None,
+				};
+				self.pool.lock().await.return_bundle(bundle, rejected);
+				Err(err)
+			},
+		}
+	}
+
+	/// Ensure that `entry_point` is the `EntryPoint` of the bundler.
+	fn ensure_entry_point(&self, entry_point: H160) -> Result<(), BundlerError> {
+		if entry_point != self.entry_point {
+			return Err(BundlerError::UnsupportedEntryPoint(entry_point));
+		}
+		Ok(())
+	}
+
+	/// Build the transaction executing `ops` on the `EntryPoint`.
+	fn handle_ops_transaction(&self, ops: Vec<PackedUserOperation>) -> GenericTransaction {
+		let beneficiary = Address::from(self.signer.address().0);
+		let input = IEntryPoint::handleOpsCall { ops, beneficiary }.abi_encode();
+		GenericTransaction {
+			from: Some(self.signer.address()),
+			to: Some(self.entry_point),
+			input: input.into(),
+			..Default::default()
+		}
+	}
+
+	/// Dry run `handleOps` with the given operations and return the gas it uses.
+	async fn simulate(&self, ops: Vec<PackedUserOperation>) -> Result<U256, BundlerError> {
+		let hash = self.client.block_hash_for_tag(BlockTag::Latest.into()).await?;
+		let runtime_api = self.client.runtime_api(hash);
+		match runtime_api.dry_run(self.handle_ops_transaction(ops), None).await {
+			Ok(info) => Ok(info.eth_gas),
+			Err(ClientError::TransactError(EthTransactError::Data(data))) =>
+				Err(decode_failed_op(&data)
+					.map(|(_, reason)| BundlerError::Rejected(reason))
+					.unwrap_or(ClientError::TransactError(EthTransactError::Data(data)).into())),
+			Err(err) => Err(err.into()),
+		}
+	}
+
+	/// Check the validation of a user operation against the opcode rules.
+	async fn check_validation_rules(&self, op: &UserOperation) -> Result<(), BundlerError> {
+		let config = GasProfilerConfig { disable_storage: true, disable_stacks: false };
+		let trace = self
+			.client
+			.trace_call(
+				self.handle_ops_transaction(vec![op.pack()]),
+				BlockTag::Latest.into(),
+				config.into(),
+			)
+			.await?;
+		match trace {
+			Trace::GasProfiler(trace) =>
+				check_validation_stacks(&trace.stacks, self.entry_point, op.factory),
+			_ => Ok(()),
+		}
+	}
+
+	/// Sign and submit a `handleOps` transaction with the given operations.
+	async fn submit(&self, ops: Vec<PackedUserOperation>) -> Result<H256, BundlerError> {
+		let hash = self.client.block_hash_for_tag(BlockTag::Latest.into()).await?;
+		let runtime_api = self.client.runtime_api(hash);
+
+		let mut transaction = self.handle_ops_transaction(ops);
+		let gas = match runtime_api.dry_run(transaction.clone(), None).await {
+			Ok(info) => info.eth_gas,
+			Err(ClientError::TransactError(EthTransactError::Data(data))) =>
+				return Err(decode_failed_op(&data)
+					.map(|(index, reason)| BundlerError::RejectedInBundle(index, reason))
+					.unwrap_or(ClientError::TransactError(EthTransactError::Data(data)).into())),
+			Err(err) => return Err(err.into()),
+		};
+		transaction.gas = Some(gas);
+		transaction.gas_price = Some(runtime_api.gas_price().await?);
+		transaction.nonce =
+			Some(self.pending_nonce(runtime_api.nonce(self.signer.address()).await?).await?);
+		transaction.chain_id = Some(self.client.chain_id().into());
+
+		let tx = transaction
+			.try_into_unsigned()
+			.map_err(|_| BundlerError::InvalidBundleTransaction)?;
+		let payload = self.signer.sign_transaction(tx).signed_payload();
+		let tx_hash = H256(keccak_256(&payload));
+		let call = subxt_client::tx().revive().eth_transact(payload);
+		self.client.submit(call).await?;
+		self.client.new_pending_transaction(tx_hash);
+		Ok(tx_hash)
+	}
+
+	/// The nonce of the next bundle transaction of the signer.
+	///
+	/// The bundles still in the transaction pool aren't reflected by the nonce of the latest
+	/// block, so the nonce following the last pending one is used instead.
+	async fn pending_nonce(&self, latest: U256) -> Result<U256, BundlerError> {
+		let txpool = self.client.txpool_content().await?;
+		let pending = txpool
+			.pending
+			.get(&self.signer.address())
+			.and_then(|txs| txs.keys().next_back())
+			.map(|nonce| U256::from(*nonce) + 1);
+		Ok(pending.map_or(latest, |pending| pending.max(latest)))
+	}
+}
+
+/// Extract the receipt of the user operation `hash` from the receipt of its bundle.
+///
+/// The logs of the operation are the ones emitted after the `UserOperationEvent` of the previous
+/// operation of the bundle.
+fn user_operation_receipt(
+	hash: H256,
+	entry_point: H160,
+	receipt: ReceiptInfo,
+) -> Option<UserOperationReceipt> {
+	let is_entry_point_event = |log: &Log, signature: B256| {
+		log.address == entry_point && log.topics.first() == Some(&H256(signature.0))
+	};
+
+	let mut start = 0;
+	for (index, log) in receipt.logs.iter().enumerate() {
+		if !is_entry_point_event(log, IEntryPoint::UserOperationEvent::SIGNATURE_HASH) {
+			continue;
+		}
+		if log.topics.get(1) != Some(&hash) {
+			start = index + 1;
+			continue;
+		}
+
+		let event = IEntryPoint::UserOperationEvent::decode_raw_log(
+			log.topics.iter().map(|topic| B256::from(topic.0)),
+			bytes(&log.data),
+		)
+		.ok()?;
+		let logs = receipt.logs[start..index].to_vec();
+		let reason = logs
+			.iter()
+			.filter(|log| {
+				is_entry_point_event(log, IEntryPoint::UserOperationRevertReason::SIGNATURE_HASH)
+			})
+			.find_map(|log| {
+				IEntryPoint::UserOperationRevertReason::decode_raw_log(
+					log.topics.iter().map(|topic| B256::from(topic.0)),
+					bytes(&log.data),
+				)
+				.ok()
+			})
+			.map(|event| Bytes(event.revertReason.to_vec()));
+
+		return Some(UserOperationReceipt {
+			user_op_hash: hash,
+			entry_point,
+			sender: H160::from_slice(event.sender.as_slice()),
+			nonce: from_alloy_u256(event.nonce),
+			paymaster: H160::from_slice(event.paymaster.as_slice()),
+			actual_gas_cost: from_alloy_u256(event.actualGasCost),
+			actual_gas_used: from_alloy_u256(event.actualGasUsed),
+			success: event.success,
+			reason,
+			logs,
+			receipt,
+		});
+	}
+	None
+}
+
+/// Decode the index of the rejected operation and the reason of a `FailedOp` or
+/// `FailedOpWithRevert` error of the `EntryPoint`.
+fn decode_failed_op(data: &[u8]) -> Option<(usize, String)> {
+	let index = |index: AlloyU256| index.saturating_to::<usize>();
+	if let Ok(err) = IEntryPoint::FailedOp::abi_decode(data) {
+		return Some((index(err.opIndex), err.reason));
+	}
+	IEntryPoint::FailedOpWithRevert::abi_decode(data)
+		.ok()
+		.map(|err| (index(err.opIndex), format!("{} {}", err.reason, err.inner)))
+}
+
+/// Check the folded call stacks of a `handleOps` call with a single operation against the opcode
+/// rules.
+///
+/// The frames called by the `EntryPoint` validate the operation, except for the `EntryPoint`
+/// calling itself to execute it. Only the frames of the factory may instantiate contracts.
+fn check_validation_stacks(
+	stacks: &[String],
+	entry_point: H160,
+	factory: Option<H160>,
+) -> Result<(), BundlerError> {
+	let entry_point = format!("{entry_point:?}");
+	let factory = factory.map(|factory| format!("{factory:?}"));
+	for line in stacks {
+		let Some((path, _gas)) = line.rsplit_once(' ') else { continue };
+		let mut frames: Vec<&str> = path.split(';').collect();
+		let Some(cost) = frames.pop() else { continue };
+		if frames.len() < 2 || frames[1] == entry_point {
+			continue;
+		}
+
+		let contract = frames.last().copied().unwrap_or_default();
+		if BANNED_VALIDATION_COSTS.contains(&cost) {
+			return Err(BundlerError::ValidationRule(format!("{contract} uses {cost}")));
+		}
+		let in_factory = factory.as_deref().is_some_and(|factory| frames.contains(&factory));
+		if cost == "Instantiate" && !in_factory {
+			return Err(BundlerError::ValidationRule(format!("{contract} uses {cost}")));
+		}
+	}
+	Ok(())
+}
+
+/// Whether `new` bumps both fees of `old` by at least [`REPLACEMENT_FEE_BUMP_PERCENT`].
+fn is_fee_bump(old: &UserOperation, new: &UserOperation) -> bool {
+	let bumped = |fee: U256| fee.saturating_mul((100 + REPLACEMENT_FEE_BUMP_PERCENT).into()) / 100;
+	new.max_fee_per_gas >= bumped(old.max_fee_per_gas) &&
+		new.max_priority_fee_per_gas >= bumped(old.max_priority_fee_per_gas)
+}
+
+/// The bytes of an optional [`Bytes`].
+fn bytes(data: &Option<Bytes>) -> &[u8] {
+	data.as_ref().map(|data| &data.0[..]).unwrap_or_default()
+}
+
+/// Pack two `u128` values into a word, `high` first.
+fn pack_u128_pair(high: U256, low: U256) -> B256 {
+	let mut word = [0u8; 32];
+	word[..16].copy_from_slice(&high.low_u128().to_be_bytes());
+	word[16..].copy_from_slice(&low.low_u128().to_be_bytes());
+	B256::from(word)
+}
+
+fn to_alloy_u256(value: U256) -> AlloyU256 {
+	AlloyU256::from_be_bytes(value.to_big_endian())
+}
+
+fn from_alloy_u256(value: AlloyU256) -> U256 {
+	U256::from_big_endian(&value.to_be_bytes::<32>())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+
+	fn user_op(sender: u8, nonce: u64, priority_fee: u64) -> UserOperation {
+		UserOperation {
+			sender: H160::repeat_byte(sender),
+			nonce: nonce.into(),
+			max_fee_per_gas: (priority_fee * 2).into(),
+			max_priority_fee_per_gas: priority_fee.into(),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn pack_works() {
+		let op = UserOperation {
+			factory: Some(H160::repeat_byte(1)),
+			factory_data: Some(Bytes(vec![2, 3])),
+			verification_gas_limit: 1.into(),
+			call_gas_limit: 2.into(),
+			paymaster: Some(H160::repeat_byte(4)),
+			paymaster_verification_gas_limit: Some(5.into()),
+			paymaster_post_op_gas_limit: Some(6.into()),
+			paymaster_data: Some(Bytes(vec![7])),
+			..user_op(8, 0, 3)
+		};
+		let packed = op.pack();
+
+		assert_eq!(packed.initCode.to_vec(), [&[1u8; 20][..], &[2, 3]].concat());
+		let mut account_gas_limits = [0u8; 32];
+		account_gas_limits[15] = 1;
+		account_gas_limits[31] = 2;
+		assert_eq!(packed.accountGasLimits, B256::from(account_gas_limits));
+		let mut gas_fees = [0u8; 32];
+		gas_fees[15] = 3;
+		gas_fees[31] = 6;
+		assert_eq!(packed.gasFees, B256::from(gas_fees));
+
+		let mut paymaster_and_data = vec![4u8; 20];
+		paymaster_and_data.extend_from_slice(&5u128.to_be_bytes());
+		paymaster_and_data.extend_from_slice(&6u128.to_be_bytes());
+		paymaster_and_data.push(7);
+		assert_eq!(packed.paymasterAndData.to_vec(), paymaster_and_data);
+	}
+
+	#[test]
+	fn hash_depends_on_entry_point_and_chain() {
+		let op = user_op(1, 0, 1);
+		let hash = op.hash(H160::repeat_byte(1), 1);
+		assert_eq!(hash, op.hash(H160::repeat_byte(1), 1));
+		assert_ne!(hash, op.hash(H160::repeat_byte(2), 1));
+		assert_ne!(hash, op.hash(H160::repeat_byte(1), 2));
+		assert_ne!(hash, user_op(1, 1, 1).hash(H160::repeat_byte(1), 1));
+	}
+
+	#[test]
+	fn validate_works() {
+		assert!(user_op(1, 0, 1).validate().is_ok());
+
+		let op = UserOperation { call_gas_limit: U256::MAX, ..user_op(1, 0, 1) };
+		assert!(matches!(op.validate(), Err(BundlerError::InvalidField("callGasLimit"))));
+
+		let op = UserOperation { paymaster_data: Some(Bytes(vec![1])), ..user_op(1, 0, 1) };
+		assert!(matches!(op.validate(), Err(BundlerError::InvalidField("paymaster"))));
+
+		let op = UserOperation { max_fee_per_gas: 0.into(), ..user_op(1, 0, 1) };
+		assert!(matches!(op.validate(), Err(BundlerError::InvalidField("maxPriorityFeePerGas"))));
+	}
+
+	#[test]
+	fn pool_replacement_requires_fee_bump() {
+		let mut pool = UserOperationPool::default();
+		pool.add(H256::repeat_byte(1), user_op(1, 0, 100)).unwrap();
+		assert!(matches!(
+			pool.add(H256::repeat_byte(1), user_op(1, 0, 100)),
+			Err(BundlerError::AlreadyKnown)
+		));
+		assert!(matches!(
+			pool.add(H256::repeat_byte(2), user_op(1, 0, 105)),
+			Err(BundlerError::ReplacementUnderpriced)
+		));
+
+		pool.add(H256::repeat_byte(3), user_op(1, 0, 110)).unwrap();
+		assert_eq!(pool.take_bundle(), vec![(H256::repeat_byte(3), user_op(1, 0, 110))]);
+	}
+
+	#[test]
+	fn take_bundle_orders_by_priority_fee_and_nonce() {
+		let mut pool = UserOperationPool::default();
+		pool.add(H256::repeat_byte(1), user_op(1, 0, 1)).unwrap();
+		pool.add(H256::repeat_byte(2), user_op(1, 1, 10)).unwrap();
+		pool.add(H256::repeat_byte(3), user_op(2, 0, 5)).unwrap();
+
+		let hashes = |bundle: Vec<(H256, UserOperation)>| {
+			bundle.into_iter().map(|(hash, _)| hash).collect::<Vec<_>>()
+		};
+		assert_eq!(hashes(pool.take_bundle()), vec![H256::repeat_byte(3), H256::repeat_byte(1)]);
+		assert_eq!(hashes(pool.take_bundle()), vec![H256::repeat_byte(2)]);
+		assert!(pool.take_bundle().is_empty());
+	}
+
+	#[test]
+	fn rejected_op_of_bundle_is_banned() {
+		let mut pool = UserOperationPool::default();
+		pool.add(H256::repeat_byte(1), user_op(1, 0, 3)).unwrap();
+		pool.add(H256::repeat_byte(2), user_op(2, 0, 2)).unwrap();
+		pool.add(H256::repeat_byte(3), user_op(3, 0, 1)).unwrap();
+		let bundle = pool.take_bundle();
+
+		let data = IEntryPoint::FailedOp { opIndex: AlloyU256::from(1), reason: "AA23".into() }
+			.abi_encode();
+		let (index, reason) = decode_failed_op(&data).unwrap();
+		assert_eq!((index, reason.as_str()), (1, "AA23"));
+		pool.return_bundle(bundle, Some(index));
+
+		let hashes: Vec<_> = pool.take_bundle().into_iter().map(|(hash, _)| hash).collect();
+		assert_eq!(hashes, vec![H256::repeat_byte(1), H256::repeat_byte(3)]);
+		assert!(matches!(
+			pool.add(H256::repeat_byte(2), user_op(2, 0, 2)),
+			Err(BundlerError::Banned)
+		));
+	}
+
+	#[test]
+	fn validation_stacks_are_checked() {
+		let entry_point = H160::repeat_byte(0xee);
+		let sender = H160::repeat_byte(1);
+		let factory = H160::repeat_byte(2);
+		let stack = |frames: &[H160], cost: &str| {
+			let path: Vec<_> = frames.iter().map(|frame| format!("{frame:?}")).collect();
+			vec![format!("{};{cost} 100", path.join(";"))]
+		};
+		let check = |stacks: Vec<String>| check_validation_stacks(&stacks, entry_point, None);
+
+		assert!(check(stack(&[entry_point], "Now")).is_ok());
+		assert!(check(stack(&[entry_point, sender], "GetStorage")).is_ok());
+		assert!(check(stack(&[entry_point, entry_point, sender], "Now")).is_ok());
+		assert!(matches!(
+			check(stack(&[entry_point, sender], "Now")),
+			Err(BundlerError::ValidationRule(_))
+		));
+		assert!(matches!(
+			check(stack(&[entry_point, sender], "Instantiate")),
+			Err(BundlerError::ValidationRule(_))
+		));
+
+		let stacks = stack(&[entry_point, H160::repeat_byte(3), factory], "Instantiate");
+		assert!(check_validation_stacks(&stacks, entry_point, Some(factory)).is_ok());
+	}
+
+	#[test]
+	fn receipt_is_extracted_from_bundle_logs() {
+		let entry_point = H160::repeat_byte(0xee);
+		let hash = H256::repeat_byte(1);
+		let sender = H160::repeat_byte(2);
+		let event = |op_hash: H256, nonce: u64| {
+			let event = IEntryPoint::UserOperationEvent {
+				userOpHash: B256::from(op_hash.0),
+				sender: Address::from(sender.0),
+				paymaster: Address::ZERO,
+				nonce: AlloyU256::from(nonce),
+				success: true,
+				actualGasCost: AlloyU256::from(100),
+				actualGasUsed: AlloyU256::from(10),
+			};
+			let log = event.encode_log_data();
+			Log {
+				address: entry_point,
+				topics: log.topics().iter().map(|topic| H256(topic.0)).collect(),
+				data: Some(Bytes(log.data.to_vec())),
+				..Default::default()
+			}
+		};
+		let account_log = Log { address: sender, ..Default::default() };
+		let receipt = ReceiptInfo {
+			logs: vec![event(H256::repeat_byte(9), 0), account_log.clone(), event(hash, 1)],
+			..Default::default()
+		};
+
+		let op_receipt = user_operation_receipt(hash, entry_point, receipt.clone()).unwrap();
+		assert_eq!(op_receipt.nonce, 1.into());
+		assert_eq!(op_receipt.sender, sender);
+		assert_eq!(op_receipt.actual_gas_cost, 100.into());
+		assert_eq!(op_receipt.actual_gas_used, 10.into());
+		assert!(op_receipt.success);
+		assert_eq!(op_receipt.logs, vec![account_log]);
+		assert!(user_operation_receipt(H256::repeat_byte(3), entry_point, receipt).is_none());
+	}
+}
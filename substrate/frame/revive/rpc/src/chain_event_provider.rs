@@ -0,0 +1,262 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{client::SubstrateBlockNumber, AddressOrAddresses, FilterTopic};
+use pallet_revive::evm::{Block, Filter, Log};
+use sp_core::H256;
+use std::{collections::BTreeMap, sync::Arc};
+use tokio::sync::{broadcast, Mutex};
+
+/// The number of best blocks kept to detect re-orgs.
+const HISTORY_SIZE: usize = 256;
+
+/// The capacity of the event channel, before slow subscribers start lagging.
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// An event emitted to the subscribers.
+#[derive(Debug, Clone)]
+pub enum ChainEvent {
+	/// A new best block.
+	NewHead(Box<Block>),
+	/// Logs emitted, or removed by a re-org.
+	Logs(Vec<Log>),
+	/// A new transaction was submitted to the pool.
+	NewPendingTransaction(H256),
+}
+
+/// A best block reported to the subscribers.
+#[derive(Debug, Clone)]
+struct ReportedBlock {
+	hash: H256,
+	logs: Vec<Log>,
+}
+
+/// Broadcasts new heads, logs and pending transactions to the subscribers.
+#[derive(Clone)]
+pub struct ChainEventProvider {
+	/// The event sender.
+	sender: broadcast::Sender<Arc<ChainEvent>>,
+	/// The best blocks reported so far, indexed by block number.
+	reported_blocks: Arc<Mutex<BTreeMap<SubstrateBlockNumber, ReportedBlock>>>,
+}
+
+impl Default for ChainEventProvider {
+	fn default() -> Self {
+		let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+		Self { sender, reported_blocks: Default::default() }
+	}
+}
+
+impl ChainEventProvider {
+	/// Subscribe to the chain events.
+	pub fn subscribe(&self) -> broadcast::Receiver<Arc<ChainEvent>> {
+		self.sender.subscribe()
+	}
+
+	/// Return the hash of the best block reported at the given height, if any.
+	pub async fn reported_hash(&self, block_number: SubstrateBlockNumber) -> Option<H256> {
+		self.reported_blocks.lock().await.get(&block_number).map(|block| block.hash)
+	}
+
+	/// Report a new best block with the logs it emitted.
+	///
+	/// Any block previously reported at the same height or above is retracted, and its logs are
+	/// sent again with `removed: true`.
+	pub async fn new_best_block(&self, head: Block, mut logs: Vec<Log>) {
+		let Ok(block_number) = SubstrateBlockNumber::try_from(head.number) else { return };
+		let mut reported_blocks = self.reported_blocks.lock().await;
+		if reported_blocks.get(&block_number).is_some_and(|block| block.hash == head.hash) {
+			return
+		}
+
+		let retracted = reported_blocks.split_off(&block_number);
+		let removed_logs = retracted
+			.into_values()
+			.rev()
+			.flat_map(|block| block.logs)
+			.map(|log| Log { removed: Some(true), ..log })
+			.collect::<Vec<_>>();
+
+		if !removed_logs.is_empty() {
+			log::debug!(
+				target: "eth-rpc::subscription",
+				"Re-org at block #{block_number}, removing {} logs",
+				removed_logs.len()
+			);
+			self.send(ChainEvent::Logs(removed_logs));
+		}
+
+		logs.iter_mut().for_each(|log| log.removed = Some(false));
+		if reported_blocks.len() >= HISTORY_SIZE {
+			reported_blocks.pop_first();
+		}
+		reported_blocks.insert(block_number, ReportedBlock { hash: head.hash, logs: logs.clone() });
+
+		self.send(ChainEvent::NewHead(Box::new(head)));
+		if !logs.is_empty() {
+			self.send(ChainEvent::Logs(logs));
+		}
+	}
+
+	/// Report a transaction submitted to the pool.
+	pub fn new_pending_transaction(&self, hash: H256) {
+		self.send(ChainEvent::NewPendingTransaction(hash));
+	}
+
+	fn send(&self, event: ChainEvent) {
+		// An error only means that there are no subscribers.
+		let _ = self.sender.send(Arc::new(event));
+	}
+}
+
+/// Check whether the log matches the address and topics of the filter.
+///
+/// The block range of the filter is ignored, as it does not apply to subscriptions.
+pub fn log_matches(filter: &Filter, log: &Log) -> bool {
+	let address_matches = match &filter.address {
+		None => true,
+		Some(AddressOrAddresses::Address(address)) => &log.address == address,
+		Some(AddressOrAddresses::Addresses(addresses)) =>
+			addresses.is_empty() || addresses.contains(&log.address),
+	};
+
+	let topics_match = || {
+		filter.topics.iter().flatten().enumerate().all(|(i, topic)| match topic {
+			FilterTopic::Single(hash) => log.topics.get(i) == Some(hash),
+			FilterTopic::Multiple(hashes) =>
+				hashes.is_empty() || log.topics.get(i).is_some_and(|t| hashes.contains(t)),
+		})
+	};
+
+	address_matches && topics_match()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pretty_assertions::assert_eq;
+	use sp_core::{H160, U256};
+
+	fn block(number: u32, hash: u8) -> Block {
+		Block { number: number.into(), hash: H256::from([hash; 32]), ..Default::default() }
+	}
+
+	fn log(block: &Block, index: u32) -> Log {
+		Log {
+			block_hash: block.hash,
+			block_number: block.number,
+			log_index: U256::from(index),
+			..Default::default()
+		}
+	}
+
+	async fn next_logs(receiver: &mut broadcast::Receiver<Arc<ChainEvent>>) -> Vec<Log> {
+		loop {
+			if let ChainEvent::Logs(logs) = &*receiver.recv().await.unwrap() {
+				return logs.clone()
+			}
+		}
+	}
+
+	#[tokio::test]
+	async fn new_best_block_emits_head_and_logs() {
+		let provider = ChainEventProvider::default();
+		let mut receiver = provider.subscribe();
+
+		let block1 = block(1, 1);
+		provider.new_best_block(block1.clone(), vec![log(&block1, 0)]).await;
+
+		assert!(
+			matches!(&*receiver.recv().await.unwrap(), ChainEvent::NewHead(b) if **b == block1)
+		);
+		assert_eq!(
+			next_logs(&mut receiver).await,
+			vec![Log { removed: Some(false), ..log(&block1, 0) }]
+		);
+		assert_eq!(provider.reported_hash(1).await, Some(block1.hash));
+
+		// Reporting the same block again is a no-op.
+		provider.new_best_block(block1.clone(), vec![log(&block1, 0)]).await;
+		assert!(receiver.try_recv().is_err());
+	}
+
+	#[tokio::test]
+	async fn reorg_removes_logs() {
+		let provider = ChainEventProvider::default();
+		let (block1, block2, block3) = (block(1, 1), block(2, 2), block(3, 3));
+		provider.new_best_block(block1.clone(), vec![log(&block1, 0)]).await;
+		provider.new_best_block(block2.clone(), vec![log(&block2, 0)]).await;
+		provider
+			.new_best_block(block3.clone(), vec![log(&block3, 0), log(&block3, 1)])
+			.await;
+
+		let mut receiver = provider.subscribe();
+		let fork2 = block(2, 42);
+		provider.new_best_block(fork2.clone(), vec![log(&fork2, 0)]).await;
+
+		assert_eq!(
+			next_logs(&mut receiver).await,
+			vec![
+				Log { removed: Some(true), ..log(&block3, 0) },
+				Log { removed: Some(true), ..log(&block3, 1) },
+				Log { removed: Some(true), ..log(&block2, 0) },
+			]
+		);
+		assert!(matches!(&*receiver.recv().await.unwrap(), ChainEvent::NewHead(b) if **b == fork2));
+		assert_eq!(
+			next_logs(&mut receiver).await,
+			vec![Log { removed: Some(false), ..log(&fork2, 0) }]
+		);
+		assert_eq!(provider.reported_hash(2).await, Some(fork2.hash));
+		assert_eq!(provider.reported_hash(3).await, None);
+	}
+
+	#[test]
+	fn log_matches_works() {
+		let log = Log {
+			address: H160::from([1u8; 20]),
+			topics: vec![H256::from([1u8; 32]), H256::from([2u8; 32])],
+			..Default::default()
+		};
+
+		let matches = |address: Option<AddressOrAddresses>, topics: Option<Vec<FilterTopic>>| {
+			log_matches(&Filter { address, topics, ..Default::default() }, &log)
+		};
+
+		assert!(matches(None, None));
+		assert!(matches(Some(log.address.into()), None));
+		assert!(!matches(Some(H160::from([2u8; 20]).into()), None));
+		assert!(matches(Some(vec![H160::from([2u8; 20]), log.address].into()), None));
+		assert!(matches(None, Some(vec![FilterTopic::Single(log.topics[0])])));
+		assert!(!matches(None, Some(vec![FilterTopic::Single(log.topics[1])])));
+		assert!(matches(
+			None,
+			Some(vec![FilterTopic::Multiple(vec![]), FilterTopic::Single(log.topics[1])])
+		));
+		assert!(matches(
+			None,
+			Some(vec![FilterTopic::Multiple(vec![H256::zero(), log.topics[0]])])
+		));
+		assert!(!matches(
+			None,
+			Some(vec![
+				FilterTopic::Single(log.topics[0]),
+				FilterTopic::Single(log.topics[1]),
+				FilterTopic::Single(log.topics[1]),
+			])
+		));
+	}
+}
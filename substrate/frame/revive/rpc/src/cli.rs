@@ -17,9 +17,11 @@
 //! The Ethereum JSON-RPC server.
 use crate::{
 	client::{connect, Client, SubscriptionType, SubstrateBlockNumber},
-	DebugRpcServer, DebugRpcServerImpl, EthRpcServer, EthRpcServerImpl, ReceiptExtractor,
-	ReceiptProvider, SubxtBlockInfoProvider, SystemHealthRpcServer, SystemHealthRpcServerImpl,
-	LOG_TARGET,
+	Account, Bundler, BundlerRpcServer, BundlerRpcServerImpl, DebugRpcServer, DebugRpcServerImpl,
+	DevRpcServer, DevRpcServerImpl, EthPubSubRpcServer, EthPubSubRpcServerImpl, EthRpcServer,
+	EthRpcServerImpl, ReceiptExtractor, ReceiptProvider, SubxtBlockInfoProvider,
+	SystemHealthRpcServer, SystemHealthRpcServerImpl, TraceRpcServer, TraceRpcServerImpl,
+	TxPoolRpcServer, TxPoolRpcServerImpl, LOG_TARGET,
 };
 use clap::Parser;
 use futures::{pin_mut, FutureExt};
@@ -29,6 +31,7 @@ use sc_service::{
 	config::{PrometheusConfig, RpcConfiguration},
 	start_rpc_servers, TaskManager,
 };
+use sp_core::H160;
 use sqlx::sqlite::SqlitePoolOptions;
 
 // Default port if --prometheus-port is not specified
@@ -65,6 +68,24 @@ pub struct CliCommand {
 	#[clap(long)]
 	pub index_last_n_blocks: Option<SubstrateBlockNumber>,
 
+	/// Index the flat traces of the indexed blocks, so that they can be queried with
+	/// `trace_filter`.
+	///
+	/// Tracing re-executes every indexed block, which is expensive.
+	#[clap(long)]
+	pub index_traces: bool,
+
+	/// Enable the ERC-4337 bundler, submitting user operations to the given `EntryPoint`
+	/// contract.
+	#[clap(long)]
+	pub bundler_entry_point: Option<H160>,
+
+	/// The hex encoded secret key of the account signing the bundle transactions.
+	///
+	/// Defaults to the dev account in `--dev` mode.
+	#[clap(long, env = "BUNDLER_SECRET_KEY")]
+	pub bundler_secret_key: Option<String>,
+
 	#[allow(missing_docs)]
 	#[clap(flatten)]
 	pub shared_params: SharedParams,
@@ -105,6 +126,8 @@ fn build_client(
 	earliest_receipt_block: Option<SubstrateBlockNumber>,
 	node_rpc_url: &str,
 	database_url: &str,
+	index_traces: bool,
+	allow_impersonation: bool,
 	abort_signal: Signals,
 ) -> anyhow::Result<Client> {
 	let fut = async {
@@ -127,7 +150,8 @@ fn build_client(
 
 		let receipt_extractor = ReceiptExtractor::new(
 			api.clone(),
-			earliest_receipt_block).await?;
+			earliest_receipt_block).await?
+			.with_impersonation(allow_impersonation);
 
 		let receipt_provider = ReceiptProvider::new(
 				pool,
@@ -137,8 +161,10 @@ fn build_client(
 			)
 			.await?;
 
-		let client =
-			Client::new(api, rpc_client, rpc, block_provider, receipt_provider).await?;
+		let client = Client::new(api, rpc_client, rpc, block_provider, receipt_provider)
+			.await?
+			.with_trace_indexing(index_traces)
+			.with_impersonation(allow_impersonation);
 
 		Ok(client)
 	}
@@ -162,6 +188,9 @@ pub fn run(cmd: CliCommand) -> anyhow::Result<()> {
 		database_url,
 		earliest_receipt_block,
 		index_last_n_blocks,
+		index_traces,
+		bundler_entry_point,
+		bundler_secret_key,
 		shared_params,
 		..
 	} = cmd;
@@ -204,9 +233,23 @@ pub fn run(cmd: CliCommand) -> anyhow::Result<()> {
 		earliest_receipt_block,
 		&node_rpc_url,
 		&database_url,
+		index_traces,
+		is_dev,
 		tokio_runtime.block_on(async { Signals::capture() })?,
 	)?;
 
+	let bundler = bundler_entry_point
+		.map(|entry_point| {
+			let signer = bundler_signer(bundler_secret_key.as_deref(), is_dev)?;
+			log::info!(
+				target: LOG_TARGET,
+				"📦 Bundling user operations for entry point {entry_point:?} with {:?}",
+				signer.address()
+			);
+			anyhow::Ok(Bundler::new(client.clone(), entry_point, signer))
+		})
+		.transpose()?;
+
 	// Prometheus metrics.
 	if let Some(PrometheusConfig { port, registry }) = prometheus_config.clone() {
 		task_manager.spawn_handle().spawn(
@@ -216,14 +259,20 @@ pub fn run(cmd: CliCommand) -> anyhow::Result<()> {
 		);
 	}
 
+	// The dev apis are shared by the servers, so that they share the snapshots taken.
+	let dev_api = is_dev.then(|| DevRpcServerImpl::new(client.clone()));
 	let rpc_server_handle = start_rpc_servers(
 		&rpc_config,
 		prometheus_registry,
 		tokio_handle,
-		|| rpc_module(is_dev, client.clone()),
+		|| rpc_module(is_dev, client.clone(), bundler.clone(), dev_api.clone()),
 		None,
 	)?;
 
+	if let Some(bundler) = bundler {
+		task_manager.spawn_handle().spawn("bundler", None, bundler.run());
+	}
+
 	task_manager
 		.spawn_essential_handle()
 		.spawn("block-subscription", None, async move {
@@ -248,18 +297,58 @@ pub fn run(cmd: CliCommand) -> anyhow::Result<()> {
 	Ok(())
 }
 
+/// Get the account signing the bundle transactions.
+fn bundler_signer(secret_key: Option<&str>, is_dev: bool) -> anyhow::Result<Account> {
+	let Some(secret_key) = secret_key else {
+		if is_dev {
+			return Ok(Account::default());
+		}
+		anyhow::bail!("--bundler-secret-key is required to enable the bundler");
+	};
+
+	let secret_key: [u8; 32] = hex::decode(secret_key.trim_start_matches("0x"))?
+		.try_into()
+		.map_err(|_| anyhow::anyhow!("The bundler secret key must be 32 bytes long"))?;
+	let keypair = subxt_signer::eth::Keypair::from_secret_key(secret_key)
+		.map_err(|err| anyhow::anyhow!("Invalid bundler secret key: {err:?}"))?;
+	Ok(keypair.into())
+}
+
 /// Create the JSON-RPC module.
-fn rpc_module(is_dev: bool, client: Client) -> Result<RpcModule<()>, sc_service::Error> {
+fn rpc_module(
+	is_dev: bool,
+	client: Client,
+	bundler: Option<Bundler>,
+	dev_api: Option<DevRpcServerImpl>,
+) -> Result<RpcModule<()>, sc_service::Error> {
 	let eth_api = EthRpcServerImpl::new(client.clone())
-		.with_accounts(if is_dev { vec![crate::Account::default()] } else { vec![] })
+		.with_accounts(if is_dev { vec![Account::default()] } else { vec![] })
+		.with_impersonation(is_dev)
 		.into_rpc();
 
 	let health_api = SystemHealthRpcServerImpl::new(client.clone()).into_rpc();
-	let debug_api = DebugRpcServerImpl::new(client).into_rpc();
+	let debug_api = DebugRpcServerImpl::new(client.clone()).into_rpc();
+	let trace_api = TraceRpcServerImpl::new(client.clone()).into_rpc();
+	let txpool_api = TxPoolRpcServerImpl::new(client.clone()).into_rpc();
+	let pubsub_api = EthPubSubRpcServerImpl::new(client.clone()).into_rpc();
 
 	let mut module = RpcModule::new(());
 	module.merge(eth_api).map_err(|e| sc_service::Error::Application(e.into()))?;
 	module.merge(health_api).map_err(|e| sc_service::Error::Application(e.into()))?;
 	module.merge(debug_api).map_err(|e| sc_service::Error::Application(e.into()))?;
+	module.merge(trace_api).map_err(|e| sc_service::Error::Application(e.into()))?;
+	module.merge(txpool_api).map_err(|e| sc_service::Error::Application(e.into()))?;
+	module.merge(pubsub_api).map_err(|e| sc_service::Error::Application(e.into()))?;
+	if let Some(bundler) = bundler {
+		let bundler_api = BundlerRpcServerImpl::new(bundler).into_rpc();
+		module
+			.merge(bundler_api)
+			.map_err(|e| sc_service::Error::Application(e.into()))?;
+	}
+	if let Some(dev_api) = dev_api {
+		module
+			.merge(dev_api.into_rpc())
+			.map_err(|e| sc_service::Error::Application(e.into()))?;
+	}
 	Ok(module)
 }
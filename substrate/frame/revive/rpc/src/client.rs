@@ -25,9 +25,11 @@ use storage_api::StorageApi;
 
 use crate::{
 	subxt_client::{self, revive::calls::types::EthTransact, SrcChainConfig},
-	BlockInfoProvider, BlockTag, FeeHistoryProvider, ReceiptProvider, SubxtBlockInfoProvider,
-	TracerType, TransactionInfo, LOG_TARGET,
+	BlockInfoProvider, BlockTag, ChainEvent, ChainEventProvider, FeeHistoryProvider,
+	FilterProvider, ReceiptProvider, SubxtBlockInfoProvider, TracerType, TransactionInfo,
+	LOG_TARGET,
 };
+use codec::{Compact, Decode};
 use jsonrpsee::{
 	core::traits::ToRpcParams,
 	rpc_params,
@@ -35,15 +37,23 @@ use jsonrpsee::{
 };
 use pallet_revive::{
 	evm::{
-		decode_revert_reason, Block, BlockNumberOrTag, BlockNumberOrTagOrHash, FeeHistoryResult,
-		Filter, GenericTransaction, Log, ReceiptInfo, SyncingProgress, SyncingStatus, Trace,
-		TransactionSigned, TransactionTrace, H256, U256,
+		decode_revert_reason, AccountProof, Block, BlockNumberOrTag, BlockNumberOrTagOrHash, Bytes,
+		CallTracerConfig, FeeHistoryResult, Filter, FilterResults, FlatTrace, GenericTransaction,
+		Log, ReceiptInfo, StorageProof, SyncingProgress, SyncingStatus, Trace, TraceFilter,
+		TraceResults, TransactionSigned, TransactionTrace, TxPoolContent, TxPoolTransaction, H160,
+		H256, U256,
 	},
 	EthTransactError,
 };
-use sp_runtime::traits::Block as BlockT;
+use pallet_revive_proof::{
+	storage_slot_key, verify_account_proof, verify_dust_proof, verify_storage_proof,
+	verify_system_account_proof, Account,
+};
+use sc_rpc_api::state::ReadProof;
+use sp_core::keccak_256;
+use sp_runtime::traits::{BlakeTwo256, Block as BlockT};
 use sp_weights::Weight;
-use std::{ops::Range, sync::Arc, time::Duration};
+use std::{collections::HashMap, ops::Range, sync::Arc, time::Duration};
 use subxt::{
 	backend::{
 		legacy::{rpc_methods::SystemHealth, LegacyRpcMethods},
@@ -52,10 +62,13 @@ use subxt::{
 			RpcClient,
 		},
 	},
+	blocks::StaticExtrinsic,
 	config::Header,
+	ext::subxt_rpcs::rpc_params as subxt_rpc_params,
 	Config, OnlineClient,
 };
 use thiserror::Error;
+use tokio::sync::broadcast;
 
 /// The substrate block type.
 pub type SubstrateBlock = subxt::blocks::Block<SrcChainConfig, OnlineClient<SrcChainConfig>>;
@@ -98,6 +111,9 @@ pub enum ClientError {
 	/// A [`codec::Error`] wrapper error.
 	#[error(transparent)]
 	CodecError(#[from] codec::Error),
+	/// A [`serde_json::Error`] wrapper error.
+	#[error(transparent)]
+	JsonError(#[from] serde_json::Error),
 	/// Transcact call failed.
 	#[error("contract reverted")]
 	TransactError(EthTransactError),
@@ -124,6 +140,24 @@ pub enum ClientError {
 	/// Failed to filter logs.
 	#[error("Failed to filter logs")]
 	LogFilterFailed(#[from] anyhow::Error),
+	/// A storage proof returned by the node is invalid.
+	#[error("invalid storage proof")]
+	InvalidProof,
+	/// The filter was not found, or expired.
+	#[error("filter not found")]
+	FilterNotFound,
+	/// Too many filters are installed.
+	#[error("too many filters installed")]
+	TooManyFilters,
+	/// Failed to filter traces.
+	#[error("Failed to filter traces: {0}")]
+	TraceFilterFailed(anyhow::Error),
+	/// Traces are not indexed.
+	#[error("trace indexing is disabled")]
+	TraceIndexingDisabled,
+	/// State overrides are not supported by the runtime.
+	#[error("state overrides are not supported by the runtime")]
+	StateOverridesNotSupported,
 }
 
 const REVERT_CODE: i32 = 3;
@@ -161,8 +195,12 @@ pub struct Client {
 	receipt_provider: ReceiptProvider,
 	block_provider: SubxtBlockInfoProvider,
 	fee_history_provider: FeeHistoryProvider,
+	chain_event_provider: ChainEventProvider,
+	filter_provider: FilterProvider,
 	chain_id: u64,
 	max_block_weight: Weight,
+	index_traces: bool,
+	allow_impersonation: bool,
 }
 
 /// Fetch the chain ID from the substrate chain.
@@ -179,6 +217,45 @@ async fn max_block_weight(api: &OnlineClient<SrcChainConfig>) -> Result<Weight,
 	Ok(max_block.0)
 }
 
+/// The tracer used to build flat traces.
+fn flat_tracer_config() -> TracerType {
+	CallTracerConfig { with_logs: false, only_top_call: false }.into()
+}
+
+/// Decode the EVM transaction of an encoded `eth_transact` extrinsic.
+///
+/// The sender of impersonated transactions is only trusted when `allow_impersonation` is set.
+fn txpool_transaction(
+	metadata: &subxt::Metadata,
+	extrinsic: &[u8],
+	allow_impersonation: bool,
+) -> Option<TxPoolTransaction> {
+	let mut input = extrinsic;
+	let _length = Compact::<u32>::decode(&mut input).ok()?;
+
+	// `eth_transact` extrinsics are bare extrinsics, of version 4 or 5.
+	let version = u8::decode(&mut input).ok()?;
+	if version != 4 && version != 5 {
+		return None;
+	}
+
+	let pallet = metadata.pallet_by_name(EthTransact::PALLET)?;
+	let call = pallet.call_variant_by_name(EthTransact::CALL)?;
+	let [pallet_index, call_index] = <[u8; 2]>::decode(&mut input).ok()?;
+	if pallet_index != pallet.index() || call_index != call.index {
+		return None;
+	}
+
+	let EthTransact { payload } = EthTransact::decode(&mut input).ok()?;
+	let transaction_signed = TransactionSigned::decode(&payload).ok()?;
+	let from = allow_impersonation
+		.then(|| transaction_signed.impersonated_signer())
+		.flatten()
+		.map_or_else(|| transaction_signed.recover_eth_address(), Ok)
+		.ok()?;
+	Some(TxPoolTransaction { from, hash: H256(keccak_256(&payload)), transaction_signed })
+}
+
 /// Extract the block timestamp.
 async fn extract_block_timestamp(block: &SubstrateBlock) -> Option<u64> {
 	let extrinsics = block.extrinsics().await.ok()?;
@@ -221,6 +298,13 @@ impl Client {
 		let (chain_id, max_block_weight) =
 			tokio::try_join!(chain_id(&api), max_block_weight(&api))?;
 
+		let chain_event_provider = ChainEventProvider::default();
+		let filter_provider = FilterProvider::new(
+			receipt_provider.clone(),
+			block_provider.clone(),
+			chain_event_provider.clone(),
+		);
+
 		Ok(Self {
 			api,
 			rpc_client,
@@ -228,11 +312,30 @@ impl Client {
 			receipt_provider,
 			block_provider,
 			fee_history_provider: FeeHistoryProvider::default(),
+			chain_event_provider,
+			filter_provider,
 			chain_id,
 			max_block_weight,
+			index_traces: false,
+			allow_impersonation: false,
 		})
 	}
 
+	/// Sets whether the flat traces of new blocks are indexed, so that they can be queried with
+	/// [`Self::trace_filter`].
+	pub fn with_trace_indexing(mut self, index_traces: bool) -> Self {
+		self.index_traces = index_traces;
+		self
+	}
+
+	/// Sets whether the sender of impersonated transactions in the transaction pool is trusted.
+	///
+	/// This must only be enabled when connected to a development chain.
+	pub fn with_impersonation(mut self, allow_impersonation: bool) -> Self {
+		self.allow_impersonation = allow_impersonation;
+		self
+	}
+
 	/// Subscribe to past blocks executing the callback for each block in `range`.
 	async fn subscribe_past_blocks<F, Fut>(
 		&self,
@@ -327,17 +430,81 @@ impl Client {
 		self.subscribe_new_blocks(subscription_type, |block| async {
 			let (signed_txs, receipts): (Vec<_>, Vec<_>) =
 				self.receipt_provider.insert_block_receipts(&block).await?.into_iter().unzip();
+			if !receipts.is_empty() {
+				self.index_block_traces(&block).await;
+			}
 
 			let evm_block =
 				self.evm_block_from_receipts(&block, &receipts, signed_txs, false).await;
 			self.block_provider.update_latest(block, subscription_type).await;
 
 			self.fee_history_provider.update_fee_history(&evm_block, &receipts).await;
+
+			if let SubscriptionType::BestBlocks = subscription_type {
+				if let Err(err) = self.notify_new_best_block(evm_block, &receipts).await {
+					log::error!(target: LOG_TARGET, "Failed to notify subscribers: {err:?}");
+				}
+			}
 			Ok(())
 		})
 		.await
 	}
 
+	/// Notify the subscribers of a new best block.
+	///
+	/// Blocks of the new best chain that were never reported, because of a re-org, are reported
+	/// first, so that the logs of the retracted blocks get removed.
+	async fn notify_new_best_block(
+		&self,
+		evm_block: Block,
+		receipts: &[ReceiptInfo],
+	) -> Result<(), ClientError> {
+		let mut enacted = Vec::new();
+		let mut parent_hash = evm_block.parent_hash;
+		let mut parent_number = SubstrateBlockNumber::try_from(evm_block.number)
+			.map_err(|_| ClientError::ConversionFailed)?;
+
+		while let Some(number) = parent_number.checked_sub(1) {
+			parent_number = number;
+			match self.chain_event_provider.reported_hash(number).await {
+				Some(hash) if hash != parent_hash => {
+					let parent = self
+						.block_provider
+						.block_by_hash(&parent_hash)
+						.await?
+						.ok_or(ClientError::BlockNotFound)?;
+					parent_hash = parent.header().parent_hash;
+					enacted.push(parent);
+				},
+				_ => break,
+			}
+		}
+
+		for block in enacted.into_iter().rev() {
+			log::debug!(target: "eth-rpc::subscription", "Re-org, enacting block #{}", block.number());
+			let (signed_txs, receipts): (Vec<_>, Vec<_>) =
+				self.receipt_provider.insert_block_receipts(&block).await?.into_iter().unzip();
+			let evm_block =
+				self.evm_block_from_receipts(&block, &receipts, signed_txs, false).await;
+			let logs = receipts.into_iter().flat_map(|receipt| receipt.logs).collect();
+			self.chain_event_provider.new_best_block(evm_block, logs).await;
+		}
+
+		let logs = receipts.iter().flat_map(|receipt| receipt.logs.iter().cloned()).collect();
+		self.chain_event_provider.new_best_block(evm_block, logs).await;
+		Ok(())
+	}
+
+	/// Subscribe to new heads, logs and pending transactions.
+	pub fn subscribe_chain_events(&self) -> broadcast::Receiver<Arc<ChainEvent>> {
+		self.chain_event_provider.subscribe()
+	}
+
+	/// Notify the subscribers of a transaction submitted to the pool.
+	pub fn new_pending_transaction(&self, hash: H256) {
+		self.chain_event_provider.new_pending_transaction(hash);
+	}
+
 	/// Cache old blocks up to the given block number.
 	pub async fn subscribe_and_cache_blocks(
 		&self,
@@ -347,7 +514,9 @@ impl Client {
 		let range = last.saturating_sub(index_last_n_blocks)..last;
 		log::info!(target: LOG_TARGET, "🗄️ Indexing past blocks in range {range:?}");
 		self.subscribe_past_blocks(range, |block| async move {
-			self.receipt_provider.insert_block_receipts(&block).await?;
+			if !self.receipt_provider.insert_block_receipts(&block).await?.is_empty() {
+				self.index_block_traces(&block).await;
+			}
 			Ok(())
 		})
 		.await?;
@@ -424,6 +593,238 @@ impl Client {
 		Ok(sync_state)
 	}
 
+	/// Get the EVM transactions of the transaction pool, grouped by sender and nonce.
+	///
+	/// The pending transactions are the ready transactions of the substrate pool, and the queued
+	/// ones its future transactions. Queued transactions are only reported by nodes allowing unsafe
+	/// RPC calls, which are required by `author_futureExtrinsics`.
+	pub async fn txpool_content(&self) -> Result<TxPoolContent, ClientError> {
+		let client = RpcClient::new(self.rpc_client.clone());
+		let pending: Vec<Bytes> =
+			client.request("author_pendingExtrinsics", Default::default()).await?;
+		let queued: Vec<Bytes> =
+			match client.request("author_futureExtrinsics", Default::default()).await {
+				Ok(queued) => queued,
+				Err(err) => {
+					log::debug!(target: LOG_TARGET, "Failed to fetch future extrinsics: {err:?}");
+					vec![]
+				},
+			};
+
+		let metadata = self.api.metadata();
+		let decode = |extrinsics: Vec<Bytes>| {
+			extrinsics
+				.into_iter()
+				.filter_map(|extrinsic| {
+					txpool_transaction(&metadata, &extrinsic.0, self.allow_impersonation)
+				})
+				.collect::<Vec<_>>()
+		};
+		Ok(TxPoolContent::new(decode(pending), decode(queued)))
+	}
+
+	/// Call a dev RPC method of the node, such as `evm_mine`.
+	///
+	/// Only development nodes, such as `revive-dev-node`, support these methods.
+	pub async fn dev_request<Res: serde::de::DeserializeOwned>(
+		&self,
+		method: &str,
+		params: subxt::ext::subxt_rpcs::client::RpcParams,
+	) -> Result<Res, ClientError> {
+		let client = RpcClient::new(self.rpc_client.clone());
+		Ok(client.request(method, params).await?)
+	}
+
+	/// Get the number of the best block of the node, which can be ahead of [`Self::latest_block`].
+	pub async fn best_block_number(&self) -> Result<SubstrateBlockNumber, ClientError> {
+		let header = self.rpc.chain_get_header(None).await?.ok_or(ClientError::BlockNotFound)?;
+		Ok(header.number)
+	}
+
+	/// Get the hashes of the best blocks of the node after `block_number`.
+	pub async fn best_block_hashes_after(
+		&self,
+		block_number: SubstrateBlockNumber,
+	) -> Result<Vec<SubstrateBlockHash>, ClientError> {
+		let best_number = self.best_block_number().await?;
+		let mut hashes = Vec::new();
+		for number in block_number.saturating_add(1)..=best_number {
+			if let Some(hash) = self.rpc.chain_get_block_hash(Some(number.into())).await? {
+				hashes.push(hash);
+			}
+		}
+		Ok(hashes)
+	}
+
+	/// Remove the receipts, logs and traces of the blocks reverted by a development node, and
+	/// reset the latest block to the new best block of the node.
+	///
+	/// The node does not notify about reverted blocks, so they have to be removed explicitly.
+	pub async fn remove_reverted_blocks(
+		&self,
+		block_hashes: &[SubstrateBlockHash],
+	) -> Result<(), ClientError> {
+		self.receipt_provider.remove(block_hashes).await?;
+		let best_hash =
+			self.rpc.chain_get_block_hash(None).await?.ok_or(ClientError::BlockNotFound)?;
+		let best_block = self.api.blocks().at(best_hash).await?;
+		self.block_provider
+			.update_latest(best_block, SubscriptionType::BestBlocks)
+			.await;
+		Ok(())
+	}
+
+	/// Get a storage proof of the given keys, in the main trie or in the given child trie.
+	async fn read_proof(
+		&self,
+		block_hash: H256,
+		child_trie: Option<Vec<u8>>,
+		keys: Vec<Vec<u8>>,
+	) -> Result<Vec<Bytes>, ClientError> {
+		let client = RpcClient::new(self.rpc_client.clone());
+		let keys = keys.into_iter().map(Bytes::from).collect::<Vec<_>>();
+		let read_proof: ReadProof<H256> = match child_trie {
+			None =>
+				client
+					.request("state_getReadProof", subxt_rpc_params![keys, block_hash])
+					.await?,
+			Some(child_trie) =>
+				client
+					.request(
+						"state_getChildReadProof",
+						subxt_rpc_params![Bytes::from(child_trie), keys, block_hash],
+					)
+					.await?,
+		};
+		Ok(read_proof.proof.into_iter().map(|node| node.0.into()).collect())
+	}
+
+	/// Get the EIP-1186 proof of the given account and storage slots.
+	///
+	/// The account proof proves the `AccountInfoOf` entry of the account, the `frame_system`
+	/// account holding its nonce and balance, and the root of the contract's child trie. Each
+	/// storage proof proves a slot in the child trie. The returned balance and nonce are read from
+	/// the verified proof. See `pallet-revive-proof` to verify them.
+	pub async fn get_proof(
+		&self,
+		address: H160,
+		storage_keys: Vec<U256>,
+		block: BlockNumberOrTagOrHash,
+	) -> Result<AccountProof, ClientError> {
+		let block = self
+			.block_by_number_or_tag_or_hash(block)
+			.await?
+			.ok_or(ClientError::BlockNotFound)?;
+		let block_hash = block.hash();
+		let keys = self.runtime_api(block_hash).account_proof_keys(address).await?;
+
+		let system_account_key = keys.system_account.clone();
+		let account_info_key = keys.account_info.clone();
+		let main_keys = [keys.system_account, keys.account_info]
+			.into_iter()
+			.chain(keys.child_trie.clone())
+			.collect();
+		let account_proof = self.read_proof(block_hash, None, main_keys).await?;
+
+		let state_root = H256(block.header().state_root.0);
+		let proof_nodes = || account_proof.iter().map(|node| node.0.clone());
+		let invalid_proof = |err: pallet_revive_proof::Error| {
+			log::debug!(target: LOG_TARGET, "Invalid account proof for {address:?}: {err:?}");
+			ClientError::InvalidProof
+		};
+		let account =
+			verify_account_proof::<BlakeTwo256>(&state_root, &account_info_key, proof_nodes())
+				.map_err(invalid_proof)?;
+		let system_account = verify_system_account_proof::<BlakeTwo256, u32, Balance>(
+			&state_root,
+			&system_account_key,
+			proof_nodes(),
+		)
+		.map_err(invalid_proof)?
+		.unwrap_or_default();
+		let dust = verify_dust_proof::<BlakeTwo256, Balance>(
+			&state_root,
+			&account_info_key,
+			proof_nodes(),
+		)
+		.map_err(invalid_proof)?;
+
+		// The balance is the transferable balance of the account, as computed by the runtime: its
+		// free balance that is neither frozen nor needed to keep the account alive, plus its dust.
+		let existential_deposit = self
+			.api
+			.constants()
+			.at(&subxt_client::constants().balances().existential_deposit())?;
+		let native_to_eth_ratio = self
+			.api
+			.constants()
+			.at(&subxt_client::constants().revive().native_to_eth_ratio())?;
+		let untouchable = system_account
+			.frozen
+			.saturating_sub(system_account.reserved)
+			.max(existential_deposit);
+		let balance = U256::from(system_account.free.saturating_sub(untouchable))
+			.saturating_mul(native_to_eth_ratio.into())
+			.saturating_add(dust.into());
+		let nonce = system_account.nonce.into();
+
+		let (code_hash, storage_hash) = match account {
+			Some(Account::Contract { code_hash, storage_root }) => (code_hash, storage_root),
+			// The code of a delegating account is its delegation designator, see EIP-7702.
+			Some(Account::Delegated { target, storage_root }) if !target.is_zero() => {
+				let designator = [&[0xef, 0x01, 0x00][..], target.as_bytes()].concat();
+				(H256(keccak_256(&designator)), storage_root)
+			},
+			Some(Account::Delegated { storage_root, .. }) => (H256(keccak_256(&[])), storage_root),
+			_ => (H256(keccak_256(&[])), H256::zero()),
+		};
+
+		let storage_proof = match keys.child_trie {
+			None => storage_keys
+				.into_iter()
+				.map(|key| StorageProof { key, ..Default::default() })
+				.collect(),
+			Some(child_trie) => {
+				let proofs = storage_keys.into_iter().map(|key| {
+					let child_trie = child_trie.clone();
+					async move {
+						let slot = key.to_big_endian();
+						let proof = self
+							.read_proof(
+								block_hash,
+								Some(child_trie),
+								vec![storage_slot_key(&slot).to_vec()],
+							)
+							.await?;
+						let value = verify_storage_proof::<BlakeTwo256>(
+							&storage_hash,
+							&slot,
+							proof.iter().map(|node| node.0.clone()),
+						)
+						.map_err(|_| ClientError::InvalidProof)?
+						.unwrap_or_default();
+						if value.len() > 32 {
+							return Err(ClientError::ConversionFailed);
+						}
+						let value = U256::from_big_endian(&value);
+						Ok::<_, ClientError>(StorageProof { key, value, proof })
+					}
+				});
+				futures::future::try_join_all(proofs).await?
+			},
+		};
+
+		Ok(AccountProof {
+			address,
+			account_proof,
+			balance,
+			code_hash,
+			nonce,
+			storage_hash,
+			storage_proof,
+		})
+	}
+
 	/// Get the syncing status of the chain.
 	pub async fn syncing(&self) -> Result<SyncingStatus, ClientError> {
 		let health = self.rpc.system_health().await?;
@@ -505,6 +906,20 @@ impl Client {
 		}
 	}
 
+	/// Get a block for the specified number, tag or hash.
+	pub async fn block_by_number_or_tag_or_hash(
+		&self,
+		block: BlockNumberOrTagOrHash,
+	) -> Result<Option<Arc<SubstrateBlock>>, ClientError> {
+		match block {
+			BlockNumberOrTagOrHash::BlockHash(hash) => self.block_by_hash(&hash).await,
+			BlockNumberOrTagOrHash::BlockNumber(n) =>
+				self.block_by_number_or_tag(&BlockNumberOrTag::U256(n)).await,
+			BlockNumberOrTagOrHash::BlockTag(tag) =>
+				self.block_by_number_or_tag(&BlockNumberOrTag::BlockTag(tag)).await,
+		}
+	}
+
 	/// Get a block by hash
 	pub async fn block_by_hash(
 		&self,
@@ -554,17 +969,41 @@ impl Client {
 		}
 
 		let block_hash = self.block_hash_for_tag(at.into()).await?;
+		self.trace_block(block_hash, config).await
+	}
+
+	/// Get the transaction traces for the given block hash.
+	///
+	/// The block does not need to be canonical, so that blocks of non-finalized forks can be
+	/// traced as well.
+	pub async fn trace_block_by_hash(
+		&self,
+		block_hash: H256,
+		config: TracerType,
+	) -> Result<Vec<TransactionTrace>, ClientError> {
+		let block = self.block_by_hash(&block_hash).await?.ok_or(ClientError::BlockNotFound)?;
+		if self
+			.receipt_provider
+			.is_before_earliest_block(&U256::from(block.number()).into())
+		{
+			return Ok(vec![]);
+		}
+
+		self.trace_block(block_hash, config).await
+	}
+
+	/// Trace all the EVM transactions of the given block.
+	async fn trace_block(
+		&self,
+		block_hash: H256,
+		config: TracerType,
+	) -> Result<Vec<TransactionTrace>, ClientError> {
 		let block = self.tracing_block(block_hash).await?;
 		let parent_hash = block.header().parent_hash;
 		let runtime_api = RuntimeApi::new(self.api.runtime_api().at(parent_hash));
 		let traces = runtime_api.trace_block(block, config.clone()).await?;
 
-		let mut hashes = self
-			.receipt_provider
-			.block_transaction_hashes(&block_hash)
-			.await
-			.ok_or(ClientError::EthExtrinsicNotFound)?;
-
+		let mut hashes = self.block_transaction_hashes(&block_hash).await?;
 		let traces = traces.into_iter().filter_map(|(index, trace)| {
 			Some(TransactionTrace { tx_hash: hashes.remove(&(index as usize))?, trace })
 		});
@@ -572,6 +1011,30 @@ impl Client {
 		Ok(traces.collect())
 	}
 
+	/// Get the EVM transaction hashes of the given block, indexed by extrinsic index.
+	///
+	/// Blocks that are not indexed, e.g. blocks of a retracted fork, are read from the chain.
+	async fn block_transaction_hashes(
+		&self,
+		block_hash: &H256,
+	) -> Result<HashMap<usize, H256>, ClientError> {
+		if let Some(hashes) = self
+			.receipt_provider
+			.block_transaction_hashes(block_hash)
+			.await
+			.filter(|hashes| !hashes.is_empty())
+		{
+			return Ok(hashes);
+		}
+
+		let block = self.block_by_hash(block_hash).await?.ok_or(ClientError::BlockNotFound)?;
+		let receipts = self.receipt_provider.receipts_from_block(&block).await?;
+		Ok(receipts
+			.into_iter()
+			.map(|(_, receipt)| (receipt.transaction_index.as_usize(), receipt.transaction_hash))
+			.collect())
+	}
+
 	/// Get the transaction traces for the given transaction.
 	pub async fn trace_transaction(
 		&self,
@@ -603,6 +1066,106 @@ impl Client {
 		runtime_api.trace_call(transaction, config.clone()).await
 	}
 
+	/// Get the flat traces of all the EVM transactions of the given block.
+	pub async fn flat_trace_block(&self, block_hash: H256) -> Result<Vec<FlatTrace>, ClientError> {
+		let block = self.tracing_block(block_hash).await?;
+		let block_number = block.header().number;
+		let parent_hash = block.header().parent_hash;
+		let runtime_api = RuntimeApi::new(self.api.runtime_api().at(parent_hash));
+		let traces = runtime_api.trace_block(block, flat_tracer_config()).await?;
+
+		let mut hashes = self.block_transaction_hashes(&block_hash).await?;
+		let traces = traces.into_iter().filter_map(|(index, trace)| {
+			let Trace::Call(trace) = trace else { return None };
+			let transaction_hash = hashes.remove(&(index as usize))?;
+			let traces =
+				FlatTrace::from_call_trace(trace).into_iter().map(move |trace| FlatTrace {
+					block_hash: Some(block_hash),
+					block_number: Some(block_number.into()),
+					transaction_hash: Some(transaction_hash),
+					transaction_position: Some(index),
+					..trace
+				});
+			Some(traces)
+		});
+
+		Ok(traces.flatten().collect())
+	}
+
+	/// Get the flat traces of the given block.
+	pub async fn flat_trace_block_by_number(
+		&self,
+		at: BlockNumberOrTag,
+	) -> Result<Vec<FlatTrace>, ClientError> {
+		if self.receipt_provider.is_before_earliest_block(&at) {
+			return Ok(vec![]);
+		}
+
+		let block_hash = self.block_hash_for_tag(at.into()).await?;
+		self.flat_trace_block(block_hash).await
+	}
+
+	/// Replay the given transaction, and return its flat traces.
+	pub async fn replay_transaction(
+		&self,
+		transaction_hash: H256,
+	) -> Result<TraceResults, ClientError> {
+		let Trace::Call(trace) =
+			self.trace_transaction(transaction_hash, flat_tracer_config()).await?
+		else {
+			return Err(ClientError::EthExtrinsicNotFound);
+		};
+
+		Ok(TraceResults {
+			output: trace.output.clone(),
+			trace: Some(FlatTrace::from_call_trace(trace)),
+		})
+	}
+
+	/// Get the indexed flat traces matching the given filter.
+	pub async fn trace_filter(&self, filter: TraceFilter) -> Result<Vec<FlatTrace>, ClientError> {
+		if !self.index_traces {
+			return Err(ClientError::TraceIndexingDisabled);
+		}
+
+		self.receipt_provider
+			.traces(filter)
+			.await
+			.map_err(ClientError::TraceFilterFailed)
+	}
+
+	/// Index the flat traces of the given block, if trace indexing is enabled.
+	async fn index_block_traces(&self, block: &SubstrateBlock) {
+		if !self.index_traces {
+			return;
+		}
+
+		let block_number = block.number();
+		let res = match self.flat_trace_block(block.hash()).await {
+			Ok(traces) => self.receipt_provider.insert_traces(block, &traces).await,
+			Err(err) => Err(err),
+		};
+		if let Err(err) = res {
+			log::error!(target: LOG_TARGET, "Failed to index traces of block #{block_number}: {err:?}");
+		}
+	}
+
+	/// Get the receipts of the given block, ordered by transaction index.
+	pub async fn receipts_by_block(
+		&self,
+		block: &SubstrateBlock,
+	) -> Result<Vec<ReceiptInfo>, ClientError> {
+		let mut receipts = self
+			.receipt_provider
+			.receipts_from_block(block)
+			.await?
+			.into_iter()
+			.map(|(_, receipt)| receipt)
+			.collect::<Vec<_>>();
+		receipts.sort_by_key(|receipt| receipt.transaction_index);
+		Ok(receipts)
+	}
+
 	/// Get the EVM block for the given Substrate block.
 	pub async fn evm_block(
 		&self,
@@ -692,6 +1255,36 @@ impl Client {
 		Ok(logs)
 	}
 
+	/// Install a log filter, and return its id.
+	pub async fn new_filter(&self, filter: Filter) -> Result<U256, ClientError> {
+		self.filter_provider.new_filter(filter).await
+	}
+
+	/// Install a new block filter, and return its id.
+	pub async fn new_block_filter(&self) -> Result<U256, ClientError> {
+		self.filter_provider.new_block_filter().await
+	}
+
+	/// Install a new pending transaction filter, and return its id.
+	pub async fn new_pending_transaction_filter(&self) -> Result<U256, ClientError> {
+		self.filter_provider.new_pending_transaction_filter().await
+	}
+
+	/// Get the changes of the given filter since it was last polled.
+	pub async fn filter_changes(&self, id: U256) -> Result<FilterResults, ClientError> {
+		self.filter_provider.filter_changes(id).await
+	}
+
+	/// Get all the logs matching the given log filter.
+	pub async fn filter_logs(&self, id: U256) -> Result<Vec<Log>, ClientError> {
+		self.filter_provider.filter_logs(id).await
+	}
+
+	/// Uninstall the given filter, returning whether it was installed.
+	pub async fn uninstall_filter(&self, id: U256) -> bool {
+		self.filter_provider.uninstall_filter(id).await
+	}
+
 	pub async fn fee_history(
 		&self,
 		block_count: u32,
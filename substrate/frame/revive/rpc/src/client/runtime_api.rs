@@ -20,12 +20,20 @@ use crate::{
 	subxt_client::{self, SrcChainConfig},
 	ClientError, LOG_TARGET,
 };
+use codec::Encode;
 use pallet_revive::{
-	evm::{GenericTransaction, Trace, H160, U256},
-	EthTransactInfo,
+	evm::{
+		AccessListResult, GenericTransaction, SimulateBlock, SimulatedBlock, StateOverrides, Trace,
+		H160, U256,
+	},
+	AccountProofKeys, EthTransactError, EthTransactInfo, EthTransactInfoV1,
 };
+use sp_version::RuntimeVersion;
 use subxt::OnlineClient;
 
+/// The version of the `ReviveApi` that added state overrides and debug messages to `eth_transact`.
+const ETH_TRANSACT_API_VERSION: u32 = 2;
+
 /// A Wrapper around subxt Runtime API
 #[derive(Clone)]
 pub struct RuntimeApi(subxt::runtime_api::RuntimeApi<SrcChainConfig, OnlineClient<SrcChainConfig>>);
@@ -58,20 +66,76 @@ impl RuntimeApi {
 		Ok(result)
 	}
 
-	/// Dry run a transaction and returns the [`EthTransactInfo`] for the transaction.
+	/// Get the storage keys needed to prove the given address.
+	pub async fn account_proof_keys(&self, address: H160) -> Result<AccountProofKeys, ClientError> {
+		let params = address.encode();
+		let keys = self.0.call_raw("ReviveApi_account_proof_keys", Some(&params)).await?;
+		Ok(keys)
+	}
+
+	/// Get the version of the `ReviveApi` implemented by the runtime.
+	pub async fn revive_api_version(&self) -> Result<Option<u32>, ClientError> {
+		let version: RuntimeVersion = self.0.call_raw("Core_version", None).await?;
+		Ok(version.api_version(&sp_crypto_hashing::blake2_64(b"ReviveApi")))
+	}
+
+	/// Dry run a transaction on top of the given state overrides and returns the
+	/// [`EthTransactInfo`] for the transaction.
+	///
+	/// Runtimes implementing a `ReviveApi` older than version 2 are called without state
+	/// overrides, and fail if any are given. They don't return debug messages.
 	pub async fn dry_run(
 		&self,
 		tx: GenericTransaction,
+		state_overrides: Option<StateOverrides>,
 	) -> Result<EthTransactInfo<Balance>, ClientError> {
-		let payload = subxt_client::apis().revive_api().eth_transact(tx.into());
-		let result = self.0.call(payload).await?;
-		match result {
-			Err(err) => {
-				log::debug!(target: LOG_TARGET, "Dry run failed {err:?}");
-				Err(ClientError::TransactError(err.0))
-			},
-			Ok(result) => Ok(result.0),
-		}
+		let result: Result<EthTransactInfo<Balance>, EthTransactError> =
+			match self.revive_api_version().await? {
+				Some(version) if version >= ETH_TRANSACT_API_VERSION => {
+					let params = (tx, state_overrides).encode();
+					self.0.call_raw("ReviveApi_eth_transact", Some(&params)).await?
+				},
+				_ if state_overrides.is_some() =>
+					return Err(ClientError::StateOverridesNotSupported),
+				_ => {
+					let params = tx.encode();
+					let result: Result<EthTransactInfoV1<Balance>, EthTransactError> =
+						self.0.call_raw("ReviveApi_eth_transact", Some(&params)).await?;
+					result.map(Into::into)
+				},
+			};
+		result.map_err(|err| {
+			log::debug!(target: LOG_TARGET, "Dry run failed {err:?}");
+			ClientError::TransactError(err)
+		})
+	}
+
+	/// Dry run a transaction and returns the accounts and storage slots it accesses.
+	pub async fn create_access_list(
+		&self,
+		tx: GenericTransaction,
+	) -> Result<AccessListResult, ClientError> {
+		let params = tx.encode();
+		let result: Result<AccessListResult, EthTransactError> =
+			self.0.call_raw("ReviveApi_create_access_list", Some(&params)).await?;
+		result.map_err(|err| {
+			log::debug!(target: LOG_TARGET, "Create access list failed {err:?}");
+			ClientError::TransactError(err)
+		})
+	}
+
+	/// Dry run the calls of the given blocks, applying the overrides of each block first.
+	pub async fn simulate(
+		&self,
+		blocks: Vec<SimulateBlock>,
+	) -> Result<Vec<SimulatedBlock>, ClientError> {
+		let params = blocks.encode();
+		let result: Result<Vec<SimulatedBlock>, EthTransactError> =
+			self.0.call_raw("ReviveApi_simulate", Some(&params)).await?;
+		result.map_err(|err| {
+			log::debug!(target: LOG_TARGET, "Simulate failed {err:?}");
+			ClientError::TransactError(err)
+		})
 	}
 
 	/// Get the nonce of the given address.
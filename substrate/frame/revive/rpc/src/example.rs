@@ -127,10 +127,11 @@ impl<Client: EthRpcClient + Send + Sync> TransactionBuilder<Client> {
 					..Default::default()
 				},
 				None,
+				None,
 			)
 			.await
 			.with_context(|| "eth_call failed")?;
-		Ok(result.0)
+		Ok(result.into_data().0)
 	}
 
 	/// Send the transaction.
@@ -156,6 +157,7 @@ impl<Client: EthRpcClient + Send + Sync> TransactionBuilder<Client> {
 					..Default::default()
 				},
 				None,
+				None,
 			)
 			.await
 			.with_context(|| "Failed to fetch gas estimate")?;
@@ -0,0 +1,352 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{
+	client::SubstrateBlockNumber, BlockInfoProvider, BlockNumberOrTag, BlockTag, ChainEvent,
+	ChainEventProvider, ClientError, ReceiptProvider, SubxtBlockInfoProvider,
+};
+use pallet_revive::evm::{Filter, FilterResults, Log};
+use sp_core::U256;
+use std::{
+	collections::HashMap,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
+use tokio::{
+	sync::{broadcast, broadcast::error::TryRecvError, Mutex},
+	time::Instant,
+};
+
+/// Filters that are not polled within this duration are uninstalled.
+const FILTER_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// The maximum number of installed filters.
+const MAX_FILTERS: usize = 500;
+
+/// The kind of an installed filter.
+enum FilterKind {
+	/// A log filter, with the next block to query for changes.
+	Logs { filter: Filter, next_block: SubstrateBlockNumber },
+	/// A new block filter.
+	Blocks(broadcast::Receiver<Arc<ChainEvent>>),
+	/// A new pending transaction filter.
+	PendingTransactions(broadcast::Receiver<Arc<ChainEvent>>),
+}
+
+/// A filter installed with `eth_newFilter`, `eth_newBlockFilter` or
+/// `eth_newPendingTransactionFilter`.
+struct InstalledFilter {
+	kind: FilterKind,
+	/// The last time the filter was polled.
+	last_poll: Instant,
+}
+
+/// Manages the filters installed by the clients.
+#[derive(Clone)]
+pub struct FilterProvider<B: BlockInfoProvider = SubxtBlockInfoProvider> {
+	/// The receipt provider used to query logs.
+	receipt_provider: ReceiptProvider<B>,
+	/// The block provider used to get the latest block.
+	block_provider: B,
+	/// The chain event provider used to track new blocks and pending transactions.
+	chain_event_provider: ChainEventProvider,
+	/// The installed filters.
+	filters: Arc<Mutex<HashMap<U256, Arc<Mutex<InstalledFilter>>>>>,
+	/// The id of the next installed filter.
+	next_id: Arc<AtomicU64>,
+}
+
+impl<B: BlockInfoProvider> FilterProvider<B> {
+	/// Create a new `FilterProvider`.
+	pub fn new(
+		receipt_provider: ReceiptProvider<B>,
+		block_provider: B,
+		chain_event_provider: ChainEventProvider,
+	) -> Self {
+		Self {
+			receipt_provider,
+			block_provider,
+			chain_event_provider,
+			filters: Default::default(),
+			next_id: Arc::new(AtomicU64::new(1)),
+		}
+	}
+
+	/// Install a log filter, and return its id.
+	///
+	/// Changes are reported from `from_block` if set, or from the next block otherwise.
+	pub async fn new_filter(&self, filter: Filter) -> Result<U256, ClientError> {
+		if filter.block_hash.is_some() {
+			return Err(ClientError::LogFilterFailed(anyhow::anyhow!(
+				"blockHash is not supported for filters"
+			)));
+		}
+
+		let latest = self.block_provider.latest_block_number().await;
+		let next_block = match &filter.from_block {
+			Some(block) => Self::resolve_block(block, latest)?,
+			None => latest.saturating_add(1),
+		};
+
+		self.install(FilterKind::Logs { filter, next_block }).await
+	}
+
+	/// Install a new block filter, and return its id.
+	pub async fn new_block_filter(&self) -> Result<U256, ClientError> {
+		self.install(FilterKind::Blocks(self.chain_event_provider.subscribe())).await
+	}
+
+	/// Install a new pending transaction filter, and return its id.
+	pub async fn new_pending_transaction_filter(&self) -> Result<U256, ClientError> {
+		let receiver = self.chain_event_provider.subscribe();
+		self.install(FilterKind::PendingTransactions(receiver)).await
+	}
+
+	/// Uninstall the filter, returning whether it was installed.
+	pub async fn uninstall_filter(&self, id: U256) -> bool {
+		self.filters.lock().await.remove(&id).is_some()
+	}
+
+	/// Return the changes since the last poll of the filter.
+	pub async fn filter_changes(&self, id: U256) -> Result<FilterResults, ClientError> {
+		let filter = self.get(id).await?;
+		let mut filter = filter.lock().await;
+
+		let changes = match &mut filter.kind {
+			FilterKind::Logs { filter, next_block } => {
+				let latest = self.block_provider.latest_block_number().await;
+				let to_block = match &filter.to_block {
+					Some(block) => Self::resolve_block(block, latest)?.min(latest),
+					None => latest,
+				};
+
+				if *next_block > to_block {
+					FilterResults::Logs(vec![])
+				} else {
+					let range = Filter {
+						from_block: Some(U256::from(*next_block).into()),
+						to_block: Some(U256::from(to_block).into()),
+						..filter.clone()
+					};
+					let logs = self.logs(range).await?;
+					*next_block = to_block.saturating_add(1);
+					FilterResults::Logs(logs)
+				}
+			},
+			FilterKind::Blocks(receiver) =>
+				FilterResults::Hashes(drain(receiver, |event| match event {
+					ChainEvent::NewHead(block) => Some(block.hash),
+					_ => None,
+				})),
+			FilterKind::PendingTransactions(receiver) =>
+				FilterResults::Hashes(drain(receiver, |event| match event {
+					ChainEvent::NewPendingTransaction(hash) => Some(*hash),
+					_ => None,
+				})),
+		};
+
+		filter.last_poll = Instant::now();
+		Ok(changes)
+	}
+
+	/// Return all the logs matching the log filter.
+	pub async fn filter_logs(&self, id: U256) -> Result<Vec<Log>, ClientError> {
+		let filter = self.get(id).await?;
+		let mut filter = filter.lock().await;
+		filter.last_poll = Instant::now();
+
+		match &filter.kind {
+			FilterKind::Logs { filter, .. } => self.logs(filter.clone()).await,
+			_ => Err(ClientError::FilterNotFound),
+		}
+	}
+
+	/// Insert the filter, uninstalling the expired ones first.
+	async fn install(&self, kind: FilterKind) -> Result<U256, ClientError> {
+		let mut filters = self.filters.lock().await;
+		filters.retain(|_, filter| {
+			filter
+				.try_lock()
+				.map_or(true, |filter| filter.last_poll.elapsed() < FILTER_TIMEOUT)
+		});
+
+		if filters.len() >= MAX_FILTERS {
+			return Err(ClientError::TooManyFilters);
+		}
+
+		let id = U256::from(self.next_id.fetch_add(1, Ordering::Relaxed));
+		let filter = InstalledFilter { kind, last_poll: Instant::now() };
+		filters.insert(id, Arc::new(Mutex::new(filter)));
+		Ok(id)
+	}
+
+	/// Get the filter with the given id, if it did not expire.
+	async fn get(&self, id: U256) -> Result<Arc<Mutex<InstalledFilter>>, ClientError> {
+		let mut filters = self.filters.lock().await;
+		let filter = filters.get(&id).ok_or(ClientError::FilterNotFound)?.clone();
+
+		let expired = filter
+			.try_lock()
+			.is_ok_and(|filter| filter.last_poll.elapsed() >= FILTER_TIMEOUT);
+		if expired {
+			filters.remove(&id);
+			return Err(ClientError::FilterNotFound);
+		}
+
+		Ok(filter)
+	}
+
+	async fn logs(&self, filter: Filter) -> Result<Vec<Log>, ClientError> {
+		self.receipt_provider
+			.logs(Some(filter))
+			.await
+			.map_err(ClientError::LogFilterFailed)
+	}
+
+	/// Resolve the block number of a filter bound.
+	fn resolve_block(
+		block: &BlockNumberOrTag,
+		latest: SubstrateBlockNumber,
+	) -> Result<SubstrateBlockNumber, ClientError> {
+		match block {
+			BlockNumberOrTag::U256(n) => (*n).try_into().map_err(|_| ClientError::ConversionFailed),
+			BlockNumberOrTag::BlockTag(BlockTag::Earliest) => Ok(0),
+			BlockNumberOrTag::BlockTag(_) => Ok(latest),
+		}
+	}
+}
+
+/// Collect the items mapped from the events received since the last call.
+fn drain<T>(
+	receiver: &mut broadcast::Receiver<Arc<ChainEvent>>,
+	f: impl Fn(&ChainEvent) -> Option<T>,
+) -> Vec<T> {
+	let mut items = Vec::new();
+	loop {
+		match receiver.try_recv() {
+			Ok(event) => items.extend(f(&event)),
+			Err(TryRecvError::Lagged(_)) => continue,
+			Err(TryRecvError::Empty | TryRecvError::Closed) => return items,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		test::{MockBlockInfo, MockBlockInfoProvider},
+		ReceiptExtractor,
+	};
+	use pallet_revive::evm::{Block, ReceiptInfo, TransactionSigned};
+	use pretty_assertions::assert_eq;
+	use sp_core::H256;
+	use sqlx::SqlitePool;
+
+	async fn setup_filter_provider(pool: SqlitePool) -> FilterProvider<MockBlockInfoProvider> {
+		let receipt_provider = ReceiptProvider::new(
+			pool,
+			MockBlockInfoProvider {},
+			ReceiptExtractor::new_mock(),
+			None,
+		)
+		.await
+		.unwrap();
+		FilterProvider::new(receipt_provider, MockBlockInfoProvider {}, Default::default())
+	}
+
+	async fn insert_log(provider: &FilterProvider<MockBlockInfoProvider>, number: u32) -> Log {
+		let block = MockBlockInfo { hash: H256::from([number as u8; 32]), number };
+		let log = Log {
+			block_hash: block.hash,
+			block_number: number.into(),
+			transaction_hash: block.hash,
+			..Default::default()
+		};
+		let receipt = ReceiptInfo {
+			logs: vec![log.clone()],
+			transaction_hash: log.transaction_hash,
+			..Default::default()
+		};
+		provider
+			.receipt_provider
+			.insert(&block, &[(TransactionSigned::default(), receipt)])
+			.await
+			.unwrap();
+		log
+	}
+
+	#[sqlx::test]
+	async fn log_filter_changes_works(pool: SqlitePool) -> anyhow::Result<()> {
+		let provider = setup_filter_provider(pool).await;
+		let log1 = insert_log(&provider, 1).await;
+		let log2 = insert_log(&provider, 2).await;
+
+		let id = provider
+			.new_filter(Filter { from_block: Some(U256::from(1).into()), ..Default::default() })
+			.await?;
+
+		// Changes are reported once.
+		assert_eq!(
+			provider.filter_changes(id).await?,
+			FilterResults::Logs(vec![log1.clone(), log2.clone()])
+		);
+		assert_eq!(provider.filter_changes(id).await?, FilterResults::Logs(vec![]));
+
+		// All logs are returned by `filter_logs`.
+		assert_eq!(provider.filter_logs(id).await?, vec![log1, log2]);
+
+		// Without `from_block`, only logs from future blocks are reported.
+		let id = provider.new_filter(Filter::default()).await?;
+		assert_eq!(provider.filter_changes(id).await?, FilterResults::Logs(vec![]));
+		Ok(())
+	}
+
+	#[sqlx::test]
+	async fn block_filter_changes_works(pool: SqlitePool) -> anyhow::Result<()> {
+		let provider = setup_filter_provider(pool).await;
+		let id = provider.new_block_filter().await?;
+
+		let block =
+			Block { number: 1u32.into(), hash: H256::from([1u8; 32]), ..Default::default() };
+		provider.chain_event_provider.new_best_block(block.clone(), vec![]).await;
+		provider.chain_event_provider.new_pending_transaction(H256::from([2u8; 32]));
+
+		assert_eq!(provider.filter_changes(id).await?, FilterResults::Hashes(vec![block.hash]));
+		assert_eq!(provider.filter_changes(id).await?, FilterResults::Hashes(vec![]));
+		assert!(matches!(provider.filter_logs(id).await, Err(ClientError::FilterNotFound)));
+		Ok(())
+	}
+
+	#[sqlx::test]
+	async fn uninstall_and_expire_filter_works(pool: SqlitePool) -> anyhow::Result<()> {
+		let provider = setup_filter_provider(pool).await;
+
+		let id = provider.new_pending_transaction_filter().await?;
+		assert!(provider.uninstall_filter(id).await);
+		assert!(!provider.uninstall_filter(id).await);
+		assert!(matches!(provider.filter_changes(id).await, Err(ClientError::FilterNotFound)));
+
+		let id = provider.new_pending_transaction_filter().await?;
+		provider.get(id).await?.lock().await.last_poll -= FILTER_TIMEOUT;
+		assert!(matches!(provider.filter_changes(id).await, Err(ClientError::FilterNotFound)));
+		assert!(provider.filters.lock().await.is_empty());
+		Ok(())
+	}
+}
@@ -44,9 +44,18 @@ pub use receipt_provider::*;
 mod fee_history_provider;
 pub use fee_history_provider::*;
 
+mod chain_event_provider;
+pub use chain_event_provider::*;
+
+mod filter_provider;
+pub use filter_provider::*;
+
 mod receipt_extractor;
 pub use receipt_extractor::*;
 
+mod bundler;
+pub use bundler::*;
+
 mod apis;
 pub use apis::*;
 
@@ -59,12 +68,15 @@ pub struct EthRpcServerImpl {
 
 	/// The accounts managed by the server.
 	accounts: Vec<Account>,
+
+	/// Whether transactions of unmanaged accounts are sent on their behalf, without a signature.
+	impersonate: bool,
 }
 
 impl EthRpcServerImpl {
 	/// Creates a new [`EthRpcServerImpl`].
 	pub fn new(client: client::Client) -> Self {
-		Self { client, accounts: vec![] }
+		Self { client, accounts: vec![], impersonate: false }
 	}
 
 	/// Sets the accounts managed by the server.
@@ -72,6 +84,14 @@ impl EthRpcServerImpl {
 		self.accounts = accounts;
 		self
 	}
+
+	/// Sends the transactions of the accounts not managed by the server on their behalf.
+	///
+	/// They are only accepted for the accounts impersonated with `anvil_impersonateAccount`.
+	pub fn with_impersonation(mut self, impersonate: bool) -> Self {
+		self.impersonate = impersonate;
+		self
+	}
 }
 
 /// The error type for the EVM RPC server.
@@ -98,6 +118,12 @@ pub enum EthRpcError {
 	/// Received an invalid transaction
 	#[error("Invalid transaction {0:?}")]
 	TransactionTypeNotSupported(Byte),
+	/// Received subscription params for a subscription kind that does not take any.
+	#[error("Invalid subscription params")]
+	InvalidSubscriptionParams,
+	/// Received a trace type that is not supported.
+	#[error("Trace type not supported: {0:?}")]
+	TraceTypeNotSupported(TraceResultType),
 }
 
 // TODO use https://eips.ethereum.org/EIPS/eip-1474#error-codes
@@ -143,10 +169,11 @@ impl EthRpcServer for EthRpcServerImpl {
 		&self,
 		transaction: GenericTransaction,
 		block: Option<BlockNumberOrTag>,
+		state_overrides: Option<StateOverrides>,
 	) -> RpcResult<U256> {
 		let hash = self.client.block_hash_for_tag(block.unwrap_or_default().into()).await?;
 		let runtime_api = self.client.runtime_api(hash);
-		let dry_run = runtime_api.dry_run(transaction).await?;
+		let dry_run = runtime_api.dry_run(transaction, state_overrides).await?;
 		Ok(dry_run.eth_gas)
 	}
 
@@ -154,11 +181,23 @@ impl EthRpcServer for EthRpcServerImpl {
 		&self,
 		transaction: GenericTransaction,
 		block: Option<BlockNumberOrTagOrHash>,
-	) -> RpcResult<Bytes> {
+		state_overrides: Option<StateOverrides>,
+	) -> RpcResult<CallResult> {
 		let hash = self.client.block_hash_for_tag(block.unwrap_or_default()).await?;
 		let runtime_api = self.client.runtime_api(hash);
-		let dry_run = runtime_api.dry_run(transaction).await?;
-		Ok(dry_run.data.into())
+		let dry_run = runtime_api.dry_run(transaction, state_overrides).await?;
+		Ok(CallResult::new(dry_run.data, &dry_run.debug_message))
+	}
+
+	async fn create_access_list(
+		&self,
+		transaction: GenericTransaction,
+		block: Option<BlockNumberOrTagOrHash>,
+	) -> RpcResult<AccessListResult> {
+		let hash = self.client.block_hash_for_tag(block.unwrap_or_default()).await?;
+		let runtime_api = self.client.runtime_api(hash);
+		let result = runtime_api.create_access_list(transaction).await?;
+		Ok(result)
 	}
 
 	async fn send_raw_transaction(&self, transaction: Bytes) -> RpcResult<H256> {
@@ -170,6 +209,7 @@ impl EthRpcServer for EthRpcServerImpl {
 		})?;
 
 		log::debug!(target: LOG_TARGET, "send_raw_transaction hash: {hash:?}");
+		self.client.new_pending_transaction(hash);
 		Ok(hash)
 	}
 
@@ -181,14 +221,13 @@ impl EthRpcServer for EthRpcServerImpl {
 			return Err(EthRpcError::InvalidTransaction.into());
 		};
 
-		let account = self
-			.accounts
-			.iter()
-			.find(|account| account.address() == from)
-			.ok_or(EthRpcError::AccountNotFound(from))?;
+		let account = self.accounts.iter().find(|account| account.address() == from);
+		if account.is_none() && !self.impersonate {
+			return Err(EthRpcError::AccountNotFound(from).into());
+		}
 
 		if transaction.gas.is_none() {
-			transaction.gas = Some(self.estimate_gas(transaction.clone(), None).await?);
+			transaction.gas = Some(self.estimate_gas(transaction.clone(), None, None).await?);
 		}
 
 		if transaction.gas_price.is_none() {
@@ -205,10 +244,24 @@ impl EthRpcServer for EthRpcServerImpl {
 		}
 
 		let tx = transaction.try_into_unsigned().map_err(|_| EthRpcError::InvalidTransaction)?;
-		let payload = account.sign_transaction(tx).signed_payload();
+		let payload = match account {
+			Some(account) => account.sign_transaction(tx).signed_payload(),
+			None => tx.impersonate(from).signed_payload(),
+		};
 		self.send_raw_transaction(Bytes(payload)).await
 	}
 
+	async fn simulate_v1(
+		&self,
+		payload: SimulatePayload,
+		block: Option<BlockNumberOrTagOrHash>,
+	) -> RpcResult<Vec<SimulatedBlock>> {
+		let hash = self.client.block_hash_for_tag(block.unwrap_or_default()).await?;
+		let runtime_api = self.client.runtime_api(hash);
+		let result = runtime_api.simulate(payload.block_state_calls).await?;
+		Ok(result)
+	}
+
 	async fn get_block_by_hash(
 		&self,
 		block_hash: H256,
@@ -266,6 +319,17 @@ impl EthRpcServer for EthRpcServerImpl {
 		Ok(Some(block))
 	}
 
+	async fn get_block_receipts(
+		&self,
+		block: BlockNumberOrTagOrHash,
+	) -> RpcResult<Option<Vec<ReceiptInfo>>> {
+		let Some(block) = self.client.block_by_number_or_tag_or_hash(block).await? else {
+			return Ok(None);
+		};
+		let receipts = self.client.receipts_by_block(&block).await?;
+		Ok(Some(receipts))
+	}
+
 	async fn get_block_transaction_count_by_hash(
 		&self,
 		block_hash: Option<H256>,
@@ -297,6 +361,40 @@ impl EthRpcServer for EthRpcServerImpl {
 		Ok(FilterResults::Logs(logs))
 	}
 
+	async fn new_filter(&self, filter: Filter) -> RpcResult<U256> {
+		Ok(self.client.new_filter(filter).await?)
+	}
+
+	async fn new_block_filter(&self) -> RpcResult<U256> {
+		Ok(self.client.new_block_filter().await?)
+	}
+
+	async fn new_pending_transaction_filter(&self) -> RpcResult<U256> {
+		Ok(self.client.new_pending_transaction_filter().await?)
+	}
+
+	async fn get_filter_changes(&self, filter_id: U256) -> RpcResult<FilterResults> {
+		Ok(self.client.filter_changes(filter_id).await?)
+	}
+
+	async fn get_filter_logs(&self, filter_id: U256) -> RpcResult<FilterResults> {
+		let logs = self.client.filter_logs(filter_id).await?;
+		Ok(FilterResults::Logs(logs))
+	}
+
+	async fn uninstall_filter(&self, filter_id: U256) -> RpcResult<bool> {
+		Ok(self.client.uninstall_filter(filter_id).await)
+	}
+
+	async fn get_proof(
+		&self,
+		address: H160,
+		storage_keys: Vec<U256>,
+		block: BlockNumberOrTagOrHash,
+	) -> RpcResult<AccountProof> {
+		Ok(self.client.get_proof(address, storage_keys, block).await?)
+	}
+
 	async fn get_storage_at(
 		&self,
 		address: H160,
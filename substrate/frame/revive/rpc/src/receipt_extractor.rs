@@ -18,6 +18,7 @@ use crate::{
 	client::{SubstrateBlock, SubstrateBlockNumber},
 	subxt_client::{
 		self,
+		events::EthCallReverted,
 		revive::{calls::types::EthTransact, events::ContractEmitted},
 		system::events::ExtrinsicSuccess,
 		transaction_payment::events::TransactionFeePaid,
@@ -48,6 +49,9 @@ pub struct ReceiptExtractor {
 
 	/// Earliest block number to consider when searching for transaction receipts.
 	earliest_receipt_block: Option<SubstrateBlockNumber>,
+
+	/// Whether impersonated transactions are accepted, only enabled for development chains.
+	allow_impersonation: bool,
 }
 
 /// Fetch the native_to_eth_ratio
@@ -80,7 +84,21 @@ impl ReceiptExtractor {
 			Box::pin(fut) as Pin<Box<_>>
 		});
 
-		Ok(Self { native_to_eth_ratio, fetch_gas_price, earliest_receipt_block })
+		Ok(Self {
+			native_to_eth_ratio,
+			fetch_gas_price,
+			earliest_receipt_block,
+			allow_impersonation: false,
+		})
+	}
+
+	/// Sets whether the sender of impersonated transactions is taken from their impersonation
+	/// marker instead of being recovered from their signature.
+	///
+	/// This must only be enabled when connected to a development chain.
+	pub fn with_impersonation(mut self, allow_impersonation: bool) -> Self {
+		self.allow_impersonation = allow_impersonation;
+		self
 	}
 
 	#[cfg(test)]
@@ -88,7 +106,12 @@ impl ReceiptExtractor {
 		let fetch_gas_price =
 			Arc::new(|_| Box::pin(std::future::ready(Ok(U256::from(1000)))) as Pin<Box<_>>);
 
-		Self { native_to_eth_ratio: 1_000_000, fetch_gas_price, earliest_receipt_block: None }
+		Self {
+			native_to_eth_ratio: 1_000_000,
+			fetch_gas_price,
+			earliest_receipt_block: None,
+			allow_impersonation: false,
+		}
 	}
 
 	/// Extract a [`TransactionSigned`] and a [`ReceiptInfo`] from an extrinsic.
@@ -106,6 +129,12 @@ impl ReceiptExtractor {
 		let success = events.has::<ExtrinsicSuccess>().inspect_err(|err| {
 		log::debug!(target: LOG_TARGET, "Failed to lookup for ExtrinsicSuccess event in block {block_number}: {err:?}")
 	})?;
+		// A call applying an authorization list keeps its authorizations when it reverts, hence
+		// its extrinsic succeeds.
+		let reverted = events.has::<EthCallReverted>().inspect_err(|err| {
+		log::debug!(target: LOG_TARGET, "Failed to lookup for EthCallReverted event in block {block_number}: {err:?}")
+	})?;
+		let success = success && !reverted;
 		let tx_fees = events
 		.find_first::<TransactionFeePaid>()?
 		.ok_or(ClientError::TxFeeNotFound)
@@ -116,10 +145,16 @@ impl ReceiptExtractor {
 
 		let signed_tx =
 			TransactionSigned::decode(&call.payload).map_err(|_| ClientError::TxDecodingFailed)?;
-		let from = signed_tx.recover_eth_address().map_err(|_| {
-			log::error!(target: LOG_TARGET, "Failed to recover eth address from signed tx");
-			ClientError::RecoverEthAddressFailed
-		})?;
+		// Impersonated transactions are only included by development chains allowing them.
+		let from = self
+			.allow_impersonation
+			.then(|| signed_tx.impersonated_signer())
+			.flatten()
+			.map_or_else(|| signed_tx.recover_eth_address(), Ok)
+			.map_err(|_| {
+				log::error!(target: LOG_TARGET, "Failed to recover eth address from signed tx");
+				ClientError::RecoverEthAddressFailed
+			})?;
 
 		let base_gas_price = (self.fetch_gas_price)(block_hash).await?;
 		let tx_info =
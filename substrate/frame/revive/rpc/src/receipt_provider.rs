@@ -19,7 +19,10 @@ use crate::{
 	Address, AddressOrAddresses, BlockInfoProvider, BlockNumberOrTag, BlockTag, Bytes, ClientError,
 	FilterTopic, ReceiptExtractor, SubxtBlockInfoProvider, LOG_TARGET,
 };
-use pallet_revive::evm::{Filter, Log, ReceiptInfo, TransactionSigned};
+use pallet_revive::evm::{
+	Filter, FlatTrace, FlatTraceAction, FlatTraceResult, Log, ReceiptInfo, TraceFilter,
+	TransactionSigned,
+};
 use sp_core::{H256, U256};
 use sqlx::{query, QueryBuilder, Row, Sqlite, SqlitePool};
 use std::{
@@ -28,6 +31,9 @@ use std::{
 };
 use tokio::sync::Mutex;
 
+/// The maximum number of traces returned by [`ReceiptProvider::traces`].
+const MAX_TRACES: u64 = 10_000;
+
 /// ReceiptProvider stores transaction receipts and logs in a SQLite database.
 #[derive(Clone)]
 pub struct ReceiptProvider<B: BlockInfoProvider = SubxtBlockInfoProvider> {
@@ -113,14 +119,19 @@ impl<B: BlockInfoProvider> ReceiptProvider<B> {
 		let sql = format!("DELETE FROM logs WHERE block_hash in ({placeholders})");
 		let mut delete_logs_query = sqlx::query(&sql);
 
+		let sql = format!("DELETE FROM traces WHERE block_hash in ({placeholders})");
+		let mut delete_traces_query = sqlx::query(&sql);
+
 		for block_hash in block_hashes {
 			delete_tx_query = delete_tx_query.bind(block_hash.as_ref());
 			delete_logs_query = delete_logs_query.bind(block_hash.as_ref());
+			delete_traces_query = delete_traces_query.bind(block_hash.as_ref());
 		}
 
 		let delete_transaction_hashes = delete_tx_query.execute(&self.pool);
 		let delete_logs = delete_logs_query.execute(&self.pool);
-		tokio::try_join!(delete_transaction_hashes, delete_logs)?;
+		let delete_traces = delete_traces_query.execute(&self.pool);
+		tokio::try_join!(delete_transaction_hashes, delete_logs, delete_traces)?;
 		Ok(())
 	}
 
@@ -155,7 +166,7 @@ impl<B: BlockInfoProvider> ReceiptProvider<B> {
 	///
 	/// Note: Can be merged into `insert_block_receipts` once <https://github.com/paritytech/subxt/issues/1883> is fixed and subxt let
 	/// us create Mock `SubstrateBlock`
-	async fn insert(
+	pub(crate) async fn insert(
 		&self,
 		block: &impl BlockInfo,
 		receipts: &[(TransactionSigned, ReceiptInfo)],
@@ -390,6 +401,124 @@ impl<B: BlockInfoProvider> ReceiptProvider<B> {
 		Ok(logs)
 	}
 
+	/// Insert the flat traces of a block.
+	///
+	/// The traces are expected to have their block and transaction fields set.
+	pub async fn insert_traces(
+		&self,
+		block: &impl BlockInfo,
+		traces: &[FlatTrace],
+	) -> Result<(), ClientError> {
+		let block_hash = block.hash();
+		let block_number = block.number() as i64;
+
+		let mut trace_index = 0i64;
+		let mut last_transaction_index = None;
+		for trace in traces {
+			let transaction_index = trace.transaction_position.unwrap_or_default();
+			if last_transaction_index != Some(transaction_index) {
+				last_transaction_index = Some(transaction_index);
+				trace_index = 0;
+			}
+
+			let (from, to) = match (&trace.action, &trace.result) {
+				(FlatTraceAction::Call(action), _) => (action.from, action.to),
+				(
+					FlatTraceAction::Create(action),
+					Some(FlatTraceResult::Create { address, .. }),
+				) => (action.from, *address),
+				(FlatTraceAction::Create(action), _) => (action.from, Address::zero()),
+			};
+
+			sqlx::query(
+				r#"
+				INSERT OR REPLACE INTO traces (
+					block_hash,
+					block_number,
+					transaction_index,
+					trace_index,
+					from_address,
+					to_address,
+					trace)
+				VALUES ($1, $2, $3, $4, $5, $6, $7)
+				"#,
+			)
+			.bind(block_hash.as_ref())
+			.bind(block_number)
+			.bind(transaction_index as i64)
+			.bind(trace_index)
+			.bind(from.as_ref())
+			.bind(to.as_ref())
+			.bind(serde_json::to_string(trace)?)
+			.execute(&self.pool)
+			.await?;
+
+			trace_index += 1;
+		}
+		Ok(())
+	}
+
+	/// Get the flat traces that match the given filter, ordered by block number, transaction
+	/// index and position in the call tree.
+	///
+	/// When both `from_address` and `to_address` are set, a trace must match both of them.
+	pub async fn traces(&self, filter: TraceFilter) -> anyhow::Result<Vec<FlatTrace>> {
+		let mut qb = QueryBuilder::<Sqlite>::new("SELECT trace FROM traces WHERE 1=1");
+
+		let latest_block = U256::from(self.block_provider.latest_block_number().await);
+		let as_block_number = |block_param| match block_param {
+			None | Some(BlockNumberOrTag::BlockTag(BlockTag::Latest)) => Ok(latest_block),
+			Some(BlockNumberOrTag::U256(v)) => Ok(v),
+			Some(BlockNumberOrTag::BlockTag(BlockTag::Earliest)) => Ok(U256::zero()),
+			Some(BlockNumberOrTag::BlockTag(tag)) => anyhow::bail!("Unsupported tag: {tag:?}"),
+		};
+
+		let from_block = as_block_number(filter.from_block)?;
+		let to_block = as_block_number(filter.to_block)?;
+		if from_block > to_block {
+			anyhow::bail!("invalid block range params");
+		}
+		if to_block > latest_block {
+			anyhow::bail!("block number exceeds latest block");
+		}
+
+		qb.push(" AND block_number BETWEEN ")
+			.push_bind(from_block.as_u64() as i64)
+			.push(" AND ")
+			.push_bind(to_block.as_u64() as i64);
+
+		for (column, addresses) in
+			[("from_address", filter.from_address), ("to_address", filter.to_address)]
+		{
+			let Some(addresses) = addresses.filter(|addresses| !addresses.is_empty()) else {
+				continue;
+			};
+			qb.push(format_args!(" AND {column} IN ("));
+			let mut separated = qb.separated(", ");
+			for address in addresses {
+				separated.push_bind(address.0.to_vec());
+			}
+			separated.push_unseparated(")");
+		}
+
+		let count = filter.count.unwrap_or(MAX_TRACES).min(MAX_TRACES);
+		qb.push(" ORDER BY block_number, transaction_index, trace_index LIMIT ")
+			.push_bind(count as i64)
+			.push(" OFFSET ")
+			.push_bind(filter.after.unwrap_or_default() as i64);
+
+		let traces = qb
+			.build()
+			.try_map(|row| {
+				let trace: String = row.try_get("trace")?;
+				serde_json::from_str(&trace).map_err(|err| sqlx::Error::Decode(err.into()))
+			})
+			.fetch_all(&self.pool)
+			.await?;
+
+		Ok(traces)
+	}
+
 	/// Get the number of receipts per block.
 	pub async fn receipts_count_per_block(&self, block_hash: &H256) -> Option<usize> {
 		let block_hash = block_hash.as_ref();
@@ -495,7 +624,7 @@ impl<B: BlockInfoProvider> ReceiptProvider<B> {
 mod tests {
 	use super::*;
 	use crate::test::{MockBlockInfo, MockBlockInfoProvider};
-	use pallet_revive::evm::{ReceiptInfo, TransactionSigned};
+	use pallet_revive::evm::{FlatCallAction, FlatTraceType, ReceiptInfo, TransactionSigned};
 	use pretty_assertions::assert_eq;
 	use sp_core::{H160, H256};
 	use sqlx::SqlitePool;
@@ -781,4 +910,75 @@ mod tests {
 		assert_eq!(logs, vec![log1.clone(), log2.clone()]);
 		Ok(())
 	}
+
+	#[sqlx::test]
+	async fn test_query_traces(pool: SqlitePool) -> anyhow::Result<()> {
+		let provider = setup_sqlite_provider(pool).await;
+		let block1 = MockBlockInfo { hash: H256::from([1u8; 32]), number: 1 };
+		let block2 = MockBlockInfo { hash: H256::from([2u8; 32]), number: 2 };
+		let alice = H160::from([1u8; 20]);
+		let bob = H160::from([2u8; 20]);
+		let charlie = H160::from([3u8; 20]);
+
+		let trace = |block: &MockBlockInfo, from, to, trace_address: Vec<u32>| FlatTrace {
+			action: FlatTraceAction::Call(FlatCallAction { from, to, ..Default::default() }),
+			block_hash: Some(block.hash),
+			block_number: Some(block.number.into()),
+			error: None,
+			result: Some(FlatTraceResult::Call {
+				gas_used: U256::zero(),
+				output: Bytes::default(),
+			}),
+			subtraces: 0,
+			trace_address,
+			transaction_hash: Some(H256::default()),
+			transaction_position: Some(0),
+			trace_type: FlatTraceType::Call,
+		};
+		let trace1 = FlatTrace { subtraces: 1, ..trace(&block1, alice, bob, vec![]) };
+		let trace2 = trace(&block1, bob, charlie, vec![0]);
+		let trace3 = trace(&block2, charlie, alice, vec![]);
+
+		provider.insert_traces(&block1, &[trace1.clone(), trace2.clone()]).await?;
+		provider.insert_traces(&block2, &[trace3.clone()]).await?;
+
+		// Empty filter
+		let traces = provider.traces(TraceFilter::default()).await?;
+		assert_eq!(traces, vec![trace3.clone()]);
+
+		// Block range filter
+		let filter = TraceFilter {
+			from_block: Some(BlockNumberOrTag::U256(1.into())),
+			to_block: Some(BlockNumberOrTag::U256(2.into())),
+			..Default::default()
+		};
+		let traces = provider.traces(filter.clone()).await?;
+		assert_eq!(traces, vec![trace1.clone(), trace2.clone(), trace3.clone()]);
+
+		// Address filters
+		let traces = provider
+			.traces(TraceFilter { from_address: Some(vec![bob, charlie]), ..filter.clone() })
+			.await?;
+		assert_eq!(traces, vec![trace2.clone(), trace3.clone()]);
+
+		let traces = provider
+			.traces(TraceFilter {
+				from_address: Some(vec![bob, charlie]),
+				to_address: Some(vec![alice]),
+				..filter.clone()
+			})
+			.await?;
+		assert_eq!(traces, vec![trace3.clone()]);
+
+		// Pagination
+		let traces = provider
+			.traces(TraceFilter { after: Some(1), count: Some(1), ..filter.clone() })
+			.await?;
+		assert_eq!(traces, vec![trace2.clone()]);
+
+		// Traces are removed along with their block
+		provider.remove(&[block1.hash()]).await?;
+		assert_eq!(count(&provider.pool, "traces", None).await, 1);
+		Ok(())
+	}
 }
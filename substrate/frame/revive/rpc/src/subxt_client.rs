@@ -66,3 +66,25 @@ pub use subxt::config::PolkadotConfig as SrcChainConfig;
 )]
 mod src_chain {}
 pub use src_chain::*;
+
+/// Events that are not part of the metadata the client is generated against.
+pub mod events {
+	use subxt::ext::scale_decode;
+
+	/// An `eth_call` applying an authorization list reverted, see
+	/// `pallet_revive::Event::EthCallReverted`.
+	///
+	/// The extrinsic of such a call succeeds, so this event marks the Ethereum transaction as
+	/// failed.
+	#[derive(Debug, scale_decode::DecodeAsType)]
+	#[decode_as_type(crate_path = "scale_decode")]
+	pub struct EthCallReverted {
+		/// The contract that was called.
+		pub contract: subxt::utils::H160,
+	}
+
+	impl subxt::events::StaticEvent for EthCallReverted {
+		const PALLET: &'static str = "Revive";
+		const EVENT: &'static str = "EthCallReverted";
+	}
+}
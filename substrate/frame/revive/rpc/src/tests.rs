@@ -29,7 +29,7 @@ use clap::Parser;
 use jsonrpsee::ws_client::{WsClient, WsClientBuilder};
 use pallet_revive::{
 	create1,
-	evm::{Account, BlockTag, U256},
+	evm::{Account, BlockTag, GenericTransaction, Transaction7702Unsigned, U256},
 };
 use static_init::dynamic;
 use std::{sync::Arc, thread};
@@ -125,6 +125,13 @@ async fn transfer() -> anyhow::Result<()> {
 		"Receipt should have the correct contract address."
 	);
 
+	let block_receipts = client.get_block_receipts(receipt.block_hash.into()).await?;
+	assert_eq!(
+		Some(vec![receipt.clone()]),
+		block_receipts,
+		"Block receipts should contain the transaction receipt."
+	);
+
 	let balance = client.get_balance(ethan.address(), BlockTag::Latest.into()).await?;
 	assert_eq!(
 		Some(value),
@@ -275,3 +282,73 @@ async fn invalid_transaction() -> anyhow::Result<()> {
 
 	Ok(())
 }
+
+#[tokio::test]
+async fn reverted_set_code_transaction_fails() -> anyhow::Result<()> {
+	let _lock = SHARED_RESOURCES.write();
+	let client = Arc::new(SharedResources::client().await);
+	let account = Account::default();
+
+	// The fixture reverts when its input starts with 1.
+	let (bytes, _) = pallet_revive_fixtures::compile_module("ok_trap_revert")?;
+	let input = bytes.into_iter().chain(0u32.to_le_bytes()).collect::<Vec<u8>>();
+	let nonce = client.get_transaction_count(account.address(), BlockTag::Latest.into()).await?;
+	let tx = TransactionBuilder::new(&client).input(input).send().await?;
+	tx.wait_for_receipt().await?;
+	let contract_address = create1(&account.address(), nonce.try_into().unwrap());
+
+	let chain_id = client.chain_id().await?;
+	let gas_price = client.gas_price().await?;
+	let gas = client
+		.estimate_gas(
+			GenericTransaction {
+				from: Some(account.address()),
+				to: Some(contract_address),
+				input: 0u32.to_le_bytes().to_vec().into(),
+				gas_price: Some(gas_price),
+				..Default::default()
+			},
+			None,
+			None,
+		)
+		.await?;
+
+	let authority = Account::from(subxt_signer::eth::dev::ethan());
+	let authority_nonce = client
+		.get_transaction_count(authority.address(), BlockTag::Latest.into())
+		.await?;
+	let authorization = authority.sign_authorization(chain_id, contract_address, authority_nonce);
+	let nonce = client.get_transaction_count(account.address(), BlockTag::Latest.into()).await?;
+	let unsigned_tx = Transaction7702Unsigned {
+		chain_id,
+		nonce,
+		gas: gas.saturating_mul(2u32.into()),
+		gas_price,
+		max_fee_per_gas: gas_price,
+		to: contract_address,
+		input: 1u32.to_le_bytes().to_vec().into(),
+		authorization_list: vec![authorization],
+		..Default::default()
+	};
+	let signed_tx = account.sign_transaction(unsigned_tx.into());
+	let hash = client.send_raw_transaction(signed_tx.signed_payload().into()).await?;
+
+	let receipt = async {
+		for _ in 0..30 {
+			tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+			if let Some(receipt) = client.get_transaction_receipt(hash).await? {
+				return Ok(receipt)
+			}
+		}
+		anyhow::bail!("Timeout, failed to get receipt")
+	}
+	.await?;
+	assert!(!receipt.is_success(), "The reverted call should fail the transaction.");
+
+	// The authorization is kept although the call reverted.
+	let authority_nonce_after = client
+		.get_transaction_count(authority.address(), BlockTag::Latest.into())
+		.await?;
+	assert_eq!(authority_nonce_after - authority_nonce, U256::from(1));
+	Ok(())
+}
@@ -0,0 +1,209 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module tracks the accounts and storage slots accessed during a transaction.
+//!
+//! An access is *cold* the first time an account or storage slot is touched in a transaction and
+//! *warm* afterwards, see [EIP-2929](https://eips.ethereum.org/EIPS/eip-2929). The
+//! [`RuntimeCosts`](crate::RuntimeCosts) of an access carry whether it is cold, warm accesses are
+//! discounted by the storage read they avoid. Transactions can warm accounts and storage slots up
+//! front by shipping an access list, see [EIP-2930](https://eips.ethereum.org/EIPS/eip-2930).
+
+use crate::{evm::AccessListEntry, exec::Key};
+use alloc::{collections::BTreeSet, vec::Vec};
+use core::mem;
+use environmental::environmental;
+use sp_core::H160;
+
+environmental!(pending_access_list: Vec<AccessListEntry>);
+
+/// Execute `f` with the given access list.
+///
+/// The access list is consumed by the first call stack created within `f`.
+pub(crate) fn with_access_list<R>(
+	mut access_list: Vec<AccessListEntry>,
+	f: impl FnOnce() -> R,
+) -> R {
+	pending_access_list::using_once(&mut access_list, f)
+}
+
+/// Take the access list passed to [`with_access_list`], if any.
+pub(crate) fn take_access_list() -> Vec<AccessListEntry> {
+	pending_access_list::with(mem::take).unwrap_or_default()
+}
+
+/// An entry representing a journal change.
+enum JournalEntry {
+	/// An account was accessed for the first time.
+	Account(H160),
+	/// A storage slot was accessed for the first time.
+	Storage(H160, Vec<u8>),
+}
+
+/// The accounts and storage slots that are warm in the current transaction.
+///
+/// Accesses made by a reverted call frame are rolled back, the same way as the state changes of
+/// the frame.
+#[derive(Default)]
+pub struct AccessedState {
+	accounts: BTreeSet<H160>,
+	storage: BTreeSet<(H160, Vec<u8>)>,
+	journal: Vec<JournalEntry>,
+	checkpoints: Vec<usize>,
+}
+
+impl AccessedState {
+	/// Create a new state from the given access list.
+	pub fn from_access_list(access_list: &[AccessListEntry]) -> Self {
+		let mut state = Self::default();
+		for entry in access_list {
+			state.accounts.insert(entry.address);
+			for slot in &entry.storage_keys {
+				state.storage.insert((entry.address, Key::Fix(slot.0).hash()));
+			}
+		}
+		state
+	}
+
+	/// Mark `address` as accessed.
+	///
+	/// Returns `true` if the account was already warm.
+	pub fn access_account(&mut self, address: &H160) -> bool {
+		if !self.accounts.insert(*address) {
+			return true;
+		}
+		if !self.checkpoints.is_empty() {
+			self.journal.push(JournalEntry::Account(*address));
+		}
+		false
+	}
+
+	/// Mark the storage slot `key` of the contract at `address` as accessed.
+	///
+	/// Returns `true` if the storage slot was already warm.
+	pub fn access_storage(&mut self, address: &H160, key: &Key) -> bool {
+		let entry = (*address, key.hash());
+		if self.storage.contains(&entry) {
+			return true;
+		}
+		if !self.checkpoints.is_empty() {
+			self.journal.push(JournalEntry::Storage(entry.0, entry.1.clone()));
+		}
+		self.storage.insert(entry);
+		false
+	}
+
+	/// Start a new nested transaction.
+	///
+	/// This allows to either commit or roll back all accesses that are made after this call.
+	/// For every transaction there must be a matching call to either `rollback_transaction`
+	/// or `commit_transaction`.
+	pub fn start_transaction(&mut self) {
+		self.checkpoints.push(self.journal.len());
+	}
+
+	/// Rollback the last transaction started by `start_transaction`.
+	///
+	/// Any accesses made during that transaction are discarded.
+	///
+	/// # Panics
+	///
+	/// Will panic if there is no open transaction.
+	pub fn rollback_transaction(&mut self) {
+		let checkpoint = self
+			.checkpoints
+			.pop()
+			.expect(
+				"A call to rollback_transaction must be preceded by a corresponding call to start_transaction;
+				the code within this crate makes sure that this is always the case; qed"
+			);
+		for entry in self.journal.drain(checkpoint..) {
+			match entry {
+				JournalEntry::Account(address) => {
+					self.accounts.remove(&address);
+				},
+				JournalEntry::Storage(address, key) => {
+					self.storage.remove(&(address, key));
+				},
+			}
+		}
+	}
+
+	/// Commit the last transaction started by `start_transaction`.
+	///
+	/// Any accesses made during that transaction are committed.
+	///
+	/// # Panics
+	///
+	/// Will panic if there is no open transaction.
+	pub fn commit_transaction(&mut self) {
+		self.checkpoints
+			.pop()
+			.expect(
+				"A call to commit_transaction must be preceded by a corresponding call to start_transaction;
+				the code within this crate makes sure that this is always the case; qed"
+			);
+		if self.checkpoints.is_empty() {
+			self.journal.clear();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::H256;
+
+	#[test]
+	fn access_list_is_warm() {
+		let address = H160::repeat_byte(1);
+		let mut state = AccessedState::from_access_list(&[AccessListEntry {
+			address,
+			storage_keys: vec![H256::repeat_byte(2)],
+		}]);
+
+		assert!(state.access_account(&address));
+		assert!(state.access_storage(&address, &Key::Fix([2; 32])));
+		assert!(!state.access_storage(&address, &Key::Fix([3; 32])));
+		assert!(state.access_storage(&address, &Key::Fix([3; 32])));
+		assert!(!state.access_account(&H160::repeat_byte(2)));
+		assert!(!state.access_storage(&H160::repeat_byte(2), &Key::Fix([2; 32])));
+	}
+
+	#[test]
+	fn rollback_transaction_works() {
+		let address = H160::repeat_byte(1);
+		let mut state = AccessedState::default();
+
+		state.start_transaction();
+		assert!(!state.access_account(&address));
+		state.start_transaction();
+		assert!(!state.access_storage(&address, &Key::Fix([1; 32])));
+		assert!(!state.access_account(&H160::repeat_byte(2)));
+		state.rollback_transaction();
+		assert!(state.access_account(&address));
+		assert!(!state.access_storage(&address, &Key::Fix([1; 32])));
+		state.commit_transaction();
+
+		state.start_transaction();
+		assert!(!state.access_account(&H160::repeat_byte(3)));
+		state.rollback_transaction();
+		assert!(state.access_account(&address));
+		assert!(state.access_storage(&address, &Key::Fix([1; 32])));
+		assert!(!state.access_account(&H160::repeat_byte(3)));
+	}
+}
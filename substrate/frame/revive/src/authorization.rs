@@ -0,0 +1,124 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module applies the authorization list of set-code transactions.
+//!
+//! An authorization lets an externally owned account, the *authority*, delegate its code to a
+//! contract: calls to the authority then execute the code of the contract against the storage of
+//! the authority, see [EIP-7702](https://eips.ethereum.org/EIPS/eip-7702).
+
+use crate::{
+	address::AddressMapper,
+	evm::AuthorizationListEntry,
+	exec::AccountIdOf,
+	storage::{meter::Meter as StorageMeter, AccountInfo},
+	weights::WeightInfo,
+	Config, Pallet, StorageDeposit, LOG_TARGET,
+};
+use frame_support::{
+	storage::with_storage_layer,
+	traits::{fungible::Mutate, tokens::Preservation, Get},
+	weights::Weight,
+};
+use frame_system::Pallet as System;
+use sp_core::U256;
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	SaturatedConversion,
+};
+
+/// The weight of applying an authorization list of `len` entries.
+pub(crate) fn weight<T: Config>(len: u32) -> Weight {
+	T::WeightInfo::apply_authorization().saturating_mul(len.into())
+}
+
+/// Apply the given authorizations, in order.
+///
+/// Invalid authorizations are skipped, as required by EIP-7702. `payer` is the sender of the
+/// transaction, which creates the accounts of the authorities that don't exist yet. What it pays
+/// for them is recorded in the `storage_meter` of the transaction.
+pub(crate) fn apply_authorizations<T: Config>(
+	payer: &AccountIdOf<T>,
+	authorization_list: &[AuthorizationListEntry],
+	storage_meter: &mut StorageMeter<T>,
+) {
+	for authorization in authorization_list {
+		if let Err(reason) =
+			with_storage_layer(|| apply_authorization::<T>(payer, authorization, storage_meter))
+		{
+			log::debug!(target: LOG_TARGET, "Skipping authorization {authorization:?}: {reason}");
+		}
+	}
+}
+
+/// Apply a single authorization.
+///
+/// The authorization is rejected if:
+/// - its chain ID is neither zero nor [`Config::ChainId`],
+/// - its signature is invalid,
+/// - the authority is a contract,
+/// - its nonce does not match the nonce of the authority, which is zero if the authority does not
+///   exist.
+///
+/// Otherwise the code of the authority is delegated to the authorized address, and the nonce of
+/// the authority is incremented. The first delegation of an authority holds a deposit from it.
+///
+/// Just like the account of a new contract, the account of an authority that does not exist is
+/// created by `payer`, which transfers the existential deposit and the deposit of the delegation
+/// to it. The authorization is rejected if the deposit can't be paid or exceeds the storage deposit
+/// limit left in `storage_meter`.
+pub(crate) fn apply_authorization<T: Config>(
+	payer: &AccountIdOf<T>,
+	authorization: &AuthorizationListEntry,
+	storage_meter: &mut StorageMeter<T>,
+) -> Result<(), &'static str> {
+	let chain_id = authorization.chain_id;
+	if !chain_id.is_zero() && chain_id != U256::from(T::ChainId::get()) {
+		return Err("invalid chain id");
+	}
+
+	let authority = authorization.recover_authority().map_err(|_| "invalid signature")?;
+	let account_id = T::AddressMapper::to_account_id(&authority);
+	if AccountInfo::<T>::load_contract(&authority).is_some() {
+		return Err("authority is a contract");
+	}
+
+	let nonce: u64 = System::<T>::account_nonce(&account_id).saturated_into();
+	if authorization.nonce != U256::from(nonce) {
+		return Err("invalid nonce");
+	}
+
+	if !System::<T>::account_exists(&account_id) {
+		let deposit = if authorization.address.is_zero() {
+			Zero::zero()
+		} else {
+			AccountInfo::<T>::delegation_deposit(&authority)
+				.map_err(|_| "failed to compute the delegation deposit")?
+		};
+		let value = Pallet::<T>::min_balance().saturating_add(deposit);
+		T::Currency::transfer(payer, &account_id, value, Preservation::Preserve)
+			.map_err(|_| "sender can't create the authority")?;
+		storage_meter
+			.record_charge(&StorageDeposit::Charge(value))
+			.map_err(|_| "storage deposit limit exhausted")?;
+	}
+
+	AccountInfo::<T>::set_delegation(&authority, authorization.address)
+		.map_err(|_| "failed to set delegation")?;
+	System::<T>::inc_account_nonce(&account_id);
+	Ok(())
+}
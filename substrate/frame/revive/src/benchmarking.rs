@@ -21,12 +21,12 @@
 use crate::{
 	call_builder::{caller_funding, default_deposit_limit, CallSetup, Contract, VmBinaryModule},
 	evm::runtime::GAS_PRICE,
-	exec::{Key, MomentOf, PrecompileExt},
+	exec::{ExportedFunction, Key, MomentOf, PrecompileExt},
 	limits,
 	precompiles::{
 		self, run::builtin as run_builtin_precompile, BenchmarkSystem, BuiltinPrecompile, ISystem,
 	},
-	storage::WriteOutcome,
+	storage::{expiry::Tombstone, WriteOutcome},
 	vm::pvm,
 	Pallet as Contracts, *,
 };
@@ -61,6 +61,25 @@ use sp_runtime::{
 /// but might make the results less precise.
 const API_BENCHMARK_RUNS: u32 = 1600;
 
+/// Enables the contract expiry so that instantiations record the contract activity.
+///
+/// Instantiating a contract is most expensive while the expiry is enabled.
+fn enable_contract_expiry<T: Config>() {
+	<ContractExpiryOf<T>>::put(ContractExpiry { period: 0u32.into(), enabled_at: 0u32.into() });
+}
+
+/// Give the contract at `address` the largest data moved along with its tombstone.
+fn add_tombstone_data<T: Config>(address: &H160) {
+	<ImmutableDataOf<T>>::insert::<_, BoundedVec<_, _>>(
+		address,
+		vec![1u8; limits::IMMUTABLE_BYTES as usize].try_into().unwrap(),
+	);
+	<PendingCodeUpgrades<T>>::insert(
+		address,
+		PendingCodeUpgrade { code_hash: H256::zero(), enact_at: 0u32.into() },
+	);
+}
+
 macro_rules! memory(
 	($($bytes:expr,)*) => {{
 		vec![].iter()$(.chain($bytes.iter()))*.cloned().collect::<Vec<_>>()
@@ -192,6 +211,7 @@ mod benchmarks {
 		c: Linear<0, { 100 * 1024 }>,
 		i: Linear<0, { limits::CALLDATA_BYTES }>,
 	) {
+		enable_contract_expiry::<T>();
 		let input = vec![42u8; i as usize];
 		let salt = [42u8; 32];
 		let value = Pallet::<T>::min_balance();
@@ -234,6 +254,7 @@ mod benchmarks {
 		i: Linear<0, { limits::CALLDATA_BYTES }>,
 		d: Linear<0, 1>,
 	) {
+		enable_contract_expiry::<T>();
 		let input = vec![42u8; i as usize];
 
 		let value = Pallet::<T>::min_balance();
@@ -255,7 +276,7 @@ mod benchmarks {
 		assert!(AccountInfoOf::<T>::get(&deployer).is_none());
 
 		#[extrinsic_call]
-		_(origin, evm_value, Weight::MAX, storage_deposit, code, input);
+		_(origin, evm_value, Weight::MAX, storage_deposit, code, input, vec![]);
 
 		let deposit =
 			T::Currency::balance_on_hold(&HoldReason::StorageDepositReserve.into(), &account_id);
@@ -284,6 +305,7 @@ mod benchmarks {
 	// `s`: Size of e salt in bytes.
 	#[benchmark(pov_mode = Measured)]
 	fn instantiate(i: Linear<0, { limits::CALLDATA_BYTES }>) -> Result<(), BenchmarkError> {
+		enable_contract_expiry::<T>();
 		let input = vec![42u8; i as usize];
 		let salt = [42u8; 32];
 		let value = Pallet::<T>::min_balance();
@@ -383,7 +405,7 @@ mod benchmarks {
 		let before = Pallet::<T>::evm_balance(&instance.address);
 		let storage_deposit = default_deposit_limit::<T>();
 		#[extrinsic_call]
-		_(origin, instance.address, evm_value, Weight::MAX, storage_deposit, data);
+		_(origin, instance.address, evm_value, Weight::MAX, storage_deposit, data, vec![], vec![]);
 		let deposit = T::Currency::balance_on_hold(
 			&HoldReason::StorageDepositReserve.into(),
 			&instance.account_id,
@@ -504,6 +526,60 @@ mod benchmarks {
 		_(origin, Box::new(dispatchable));
 	}
 
+	#[benchmark(pov_mode = Measured)]
+	fn set_contract_expiry() {
+		#[extrinsic_call]
+		_(RawOrigin::Root, Some(1u32.into()));
+		assert!(<ContractExpiryOf<T>>::exists());
+	}
+
+	#[benchmark(pov_mode = Measured)]
+	fn tombstone_contract() -> Result<(), BenchmarkError> {
+		enable_contract_expiry::<T>();
+		let instance =
+			<Contract<T>>::with_caller(whitelisted_caller(), VmBinaryModule::dummy(), vec![])?;
+		add_tombstone_data::<T>(&instance.address);
+		let origin = RawOrigin::Signed(instance.caller.clone());
+		#[extrinsic_call]
+		_(origin, instance.address);
+		assert!(<Tombstones<T>>::contains_key(&instance.address));
+		Ok(())
+	}
+
+	// `n`: Number of storage items of the restored contract.
+	// `b`: Size of the keys and values of the storage items in bytes.
+	#[benchmark(pov_mode = Measured)]
+	fn restore_contract(
+		n: Linear<0, { limits::TOMBSTONE_STORAGE_ITEMS }>,
+		b: Linear<0, { limits::TOMBSTONE_STORAGE_BYTES }>,
+	) -> Result<(), BenchmarkError> {
+		enable_contract_expiry::<T>();
+		let instance =
+			<Contract<T>>::with_caller(whitelisted_caller(), VmBinaryModule::dummy(), vec![])?;
+		let value_len = b.saturating_sub(n.saturating_mul(32)).checked_div(n).unwrap_or(0);
+		let items = (0..n)
+			.map(|i| {
+				let key = sp_io::hashing::blake2_256(&i.to_le_bytes());
+				(key, vec![42u8; value_len as usize])
+			})
+			.collect::<Vec<_>>();
+		instance.store(&items)?;
+		let raw_items = items
+			.into_iter()
+			.map(|(key, value)| (Key::Fix(key).hash(), value))
+			.collect::<Vec<_>>();
+
+		add_tombstone_data::<T>(&instance.address);
+		<Tombstone<T>>::create(&instance.address)?;
+		let origin = RawOrigin::Signed(instance.caller.clone());
+		#[extrinsic_call]
+		_(origin, instance.address, raw_items);
+		assert!(!<Tombstones<T>>::contains_key(&instance.address));
+		assert!(instance.info().is_ok());
+		assert!(<ImmutableDataOf<T>>::contains_key(&instance.address));
+		Ok(())
+	}
+
 	#[benchmark(pov_mode = Measured)]
 	fn noop_host_fn(r: Linear<0, API_BENCHMARK_RUNS>) {
 		let mut setup = CallSetup::<T>::new(VmBinaryModule::noop());
@@ -1839,6 +1915,7 @@ mod benchmarks {
 		d: Linear<0, 1>,
 		i: Linear<0, { limits::CALLDATA_BYTES }>,
 	) -> Result<(), BenchmarkError> {
+		enable_contract_expiry::<T>();
 		let code = VmBinaryModule::dummy();
 		let hash = Contract::<T>::with_index(1, VmBinaryModule::dummy(), vec![])?.info()?.code_hash;
 		let hash_bytes = hash.encode();
@@ -2208,12 +2285,62 @@ mod benchmarks {
 		assert_eq!(&memory[..20], runtime.ext().ecdsa_to_eth_address(&pub_key_bytes).unwrap());
 	}
 
+	// Worst case: the authority does not exist, the caller creates it and funds the deposit of its
+	// first delegation.
+	#[benchmark(pov_mode = Measured)]
+	fn apply_authorization() {
+		let caller = whitelisted_caller();
+		T::Currency::set_balance(&caller, caller_funding::<T>());
+
+		let key_type = sp_core::crypto::KeyTypeId(*b"code");
+		let public = sp_io::crypto::ecdsa_generate(key_type, None);
+		let mut authorization = evm::AuthorizationListEntry {
+			chain_id: T::ChainId::get().into(),
+			address: H160::from_low_u64_be(1),
+			nonce: U256::zero(),
+			..Default::default()
+		};
+		let hash = sp_io::hashing::keccak_256(&authorization.unsigned_payload());
+		let signature = sp_io::crypto::ecdsa_sign_prehashed(key_type, &public, &hash)
+			.expect("key was generated above; qed")
+			.0;
+		authorization.r = U256::from_big_endian(&signature[..32]);
+		authorization.s = U256::from_big_endian(&signature[32..64]);
+		authorization.y_parity = U256::from(signature[64]);
+		let authority = authorization.recover_authority().expect("signature is valid; qed");
+
+		let mut storage_meter =
+			crate::storage::meter::Meter::<T>::new(default_deposit_limit::<T>());
+		let result;
+		#[block]
+		{
+			result = authorization::apply_authorization::<T>(
+				&caller,
+				&authorization,
+				&mut storage_meter,
+			);
+		}
+
+		assert_eq!(result, Ok(()));
+		assert_eq!(
+			Pallet::<T>::code(&authority),
+			AccountInfo::<T>::delegation_designator(&authorization.address)
+		);
+	}
+
+	// Worst case: the contract switches to an announced code hash other than its own.
 	#[benchmark(pov_mode = Measured)]
 	fn seal_set_code_hash() -> Result<(), BenchmarkError> {
-		let code_hash =
-			Contract::<T>::with_index(1, VmBinaryModule::dummy(), vec![])?.info()?.code_hash;
+		let code_hash = Contract::<T>::with_index(1, VmBinaryModule::dummy_unique(1), vec![])?
+			.info()?
+			.code_hash;
 
-		build_runtime!(runtime, memory: [ code_hash.encode(),]);
+		build_runtime!(runtime, contract, memory: [ code_hash.encode(),]);
+		<CodeInfo<T>>::increment_refcount(code_hash)?;
+		PendingCodeUpgrades::<T>::insert(
+			&contract.address,
+			PendingCodeUpgrade { code_hash, enact_at: 0u32.into() },
+		);
 
 		let result;
 		#[block]
@@ -2222,6 +2349,38 @@ mod benchmarks {
 		}
 
 		assert_ok!(result);
+		assert_eq!(PendingCodeUpgrades::<T>::get(&contract.address), None);
+		Ok(())
+	}
+
+	// Worst case: the announcement replaces a pending upgrade to another code hash.
+	#[benchmark(pov_mode = Measured)]
+	fn seal_announce_code_upgrade() -> Result<(), BenchmarkError> {
+		let code_hash = Contract::<T>::with_index(1, VmBinaryModule::dummy_unique(1), vec![])?
+			.info()?
+			.code_hash;
+		let previous_hash = Contract::<T>::with_index(2, VmBinaryModule::dummy_unique(2), vec![])?
+			.info()?
+			.code_hash;
+
+		build_runtime!(runtime, contract, memory: [ code_hash.encode(),]);
+		<CodeInfo<T>>::increment_refcount(previous_hash)?;
+		PendingCodeUpgrades::<T>::insert(
+			&contract.address,
+			PendingCodeUpgrade { code_hash: previous_hash, enact_at: 0u32.into() },
+		);
+
+		let result;
+		#[block]
+		{
+			result = runtime.bench_announce_code_upgrade(memory.as_mut_slice(), 0);
+		}
+
+		assert_ok!(result);
+		assert_eq!(
+			PendingCodeUpgrades::<T>::get(&contract.address).map(|pending| pending.code_hash),
+			Some(code_hash)
+		);
 		Ok(())
 	}
 
@@ -2308,6 +2467,29 @@ mod benchmarks {
 		}
 	}
 
+	// Benchmark the execution of EVM instructions.
+	//
+	// `r`: Number of `JUMPDEST` instructions executed. They are the cheapest instructions as they
+	// cost a single unit of EVM gas, which makes this the worst case weight per unit of gas.
+	#[benchmark(pov_mode = Ignored)]
+	fn evm_opcode(r: Linear<0, { limits::code::EVM_BYTES }>) -> Result<(), BenchmarkError> {
+		let module = ContractBlob::<T>::from_evm_runtime_code(
+			vec![0x5b; r as usize], // JUMPDEST
+			whitelisted_caller(),
+		)?;
+		let mut setup = CallSetup::<T>::default();
+		let (mut ext, _) = setup.ext();
+
+		let result;
+		#[block]
+		{
+			result = module.execute(&mut ext, ExportedFunction::Call, Vec::new());
+		}
+
+		assert!(!result.unwrap().did_revert());
+		Ok(())
+	}
+
 	#[benchmark]
 	fn v1_migration_step() {
 		use crate::migrations::v1;
@@ -2330,6 +2512,32 @@ mod benchmarks {
 		assert_eq!(meter.consumed(), <T as Config>::WeightInfo::v1_migration_step() * 2);
 	}
 
+	#[benchmark]
+	fn v2_migration_step() {
+		use crate::migrations::v2;
+		let code_hash = H256::from([1u8; 32]);
+		let old_code_info = v2::old::CodeInfo::<T> {
+			owner: whitelisted_caller(),
+			deposit: 1000u32.into(),
+			refcount: 1,
+			code_len: 1024,
+			behaviour_version: 0,
+		};
+
+		v2::old::CodeInfoOf::<T>::insert(code_hash, old_code_info);
+		let mut meter = WeightMeter::new();
+
+		#[block]
+		{
+			v2::Migration::<T>::step(None, &mut meter).unwrap();
+		}
+
+		assert_eq!(CodeInfoOf::<T>::get(&code_hash).unwrap().code_type(), BytecodeType::Pvm);
+
+		// uses twice the weight once for migration and then for checking if there is another key.
+		assert_eq!(meter.consumed(), <T as Config>::WeightInfo::v2_migration_step() * 2);
+	}
+
 	impl_benchmark_test_suite!(
 		Contracts,
 		crate::tests::ExtBuilder::default().build(),
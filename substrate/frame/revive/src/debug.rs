@@ -0,0 +1,67 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Collection of the debug messages emitted by contracts.
+//!
+//! Contracts can emit human readable debug messages through
+//! [`pallet_revive_uapi::HostFn::debug_message`]. Those messages are only collected when a
+//! dry-run is executed within [`collect`]. They are then returned by
+//! [`crate::Pallet::bare_call`] and [`crate::Pallet::bare_instantiate`] as part of their
+//! [`crate::ContractResult`]. Additionally, the messages are reported to the active tracer.
+
+use crate::limits;
+use alloc::vec::Vec;
+use environmental::environmental;
+
+environmental!(buffer: Vec<u8>);
+
+/// Collect the debug messages emitted by contracts while executing the given closure.
+///
+/// # Warning
+///
+/// Only meant to be called from off-chain code as the memory used by the buffer is not
+/// accounted for in the memory envelope.
+pub fn collect<R, F: FnOnce() -> R>(f: F) -> R {
+	let mut buffer = Vec::new();
+	buffer::using_once(&mut buffer, f)
+}
+
+/// Whether debug messages are currently collected.
+pub(crate) fn is_enabled() -> bool {
+	buffer::with(|_| ()).is_some()
+}
+
+/// Append a debug message to the buffer, if debug messages are collected.
+///
+/// Messages that would grow the buffer beyond [`limits::DEBUG_BUFFER_BYTES`] are dropped.
+/// Returns `true` if the message was appended.
+pub(crate) fn append(message: &[u8]) -> bool {
+	buffer::with(|buffer| {
+		if buffer.len().saturating_add(message.len()) > limits::DEBUG_BUFFER_BYTES as usize {
+			log::debug!(target: crate::LOG_TARGET, "Debug buffer full, dropping message");
+			return false;
+		}
+		buffer.extend_from_slice(message);
+		true
+	})
+	.unwrap_or(false)
+}
+
+/// Take the debug messages collected so far, leaving the buffer empty.
+pub(crate) fn take() -> Vec<u8> {
+	buffer::with(core::mem::take).unwrap_or_default()
+}
@@ -31,9 +31,20 @@ mod debug_rpc_types;
 pub use debug_rpc_types::*;
 
 mod rpc_types;
+pub use rpc_types::*;
+
 mod rpc_types_gen;
 pub use rpc_types_gen::*;
 
+mod simulate_rpc_types;
+pub use simulate_rpc_types::*;
+
+mod trace_rpc_types;
+pub use trace_rpc_types::*;
+
+mod txpool_rpc_types;
+pub use txpool_rpc_types::*;
+
 #[cfg(feature = "std")]
 mod account;
 
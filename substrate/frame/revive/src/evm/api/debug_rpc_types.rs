@@ -27,7 +27,6 @@ use serde::{
 use sp_core::{H160, H256, U256};
 
 /// The type of tracer to use.
-/// Only "callTracer" is supported for now.
 #[derive(TypeInfo, Debug, Clone, Encode, Decode, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "tracer", content = "tracerConfig", rename_all = "camelCase")]
 pub enum TracerType {
@@ -36,6 +35,12 @@ pub enum TracerType {
 
 	/// A tracer that traces the prestate.
 	PrestateTracer(Option<PrestateTracerConfig>),
+
+	/// A tracer that logs every executed instruction (geth's default "structLogger").
+	StructLogger(Option<StructLoggerConfig>),
+
+	/// A tracer that breaks down the gas charged by host function, contract and storage key.
+	GasProfiler(Option<GasProfilerConfig>),
 }
 
 impl From<CallTracerConfig> for TracerType {
@@ -100,6 +105,51 @@ impl Default for PrestateTracerConfig {
 	}
 }
 
+/// The configuration for the struct logger.
+///
+/// The flags follow the naming of geth's `logger.Config`.
+#[derive(Clone, Debug, Default, Decode, Serialize, Deserialize, Encode, PartialEq, TypeInfo)]
+#[serde(default, rename_all = "camelCase")]
+pub struct StructLoggerConfig {
+	/// Whether to omit the registers ("stack") of each step.
+	pub disable_stack: bool,
+
+	/// Whether to include the stack memory of each step.
+	pub enable_memory: bool,
+
+	/// Whether to omit the storage accessed by `get_storage` / `set_storage` steps.
+	pub disable_storage: bool,
+
+	/// Whether to include the return data of the last sub-call in each step.
+	pub enable_return_data: bool,
+
+	/// The maximum number of steps to record. `0` means no limit.
+	pub limit: u64,
+}
+
+impl From<StructLoggerConfig> for TracerType {
+	fn from(config: StructLoggerConfig) -> Self {
+		TracerType::StructLogger(Some(config))
+	}
+}
+
+/// The configuration for the gas profiler.
+#[derive(Clone, Debug, Default, Decode, Serialize, Deserialize, Encode, PartialEq, TypeInfo)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GasProfilerConfig {
+	/// Whether to omit the breakdown per storage key.
+	pub disable_storage: bool,
+
+	/// Whether to omit the folded stacks used to render flamegraphs.
+	pub disable_stacks: bool,
+}
+
+impl From<GasProfilerConfig> for TracerType {
+	fn from(config: GasProfilerConfig) -> Self {
+		TracerType::GasProfiler(Some(config))
+	}
+}
+
 /// Serialization should support the following JSON format:
 ///
 /// ```json
@@ -137,6 +187,34 @@ fn test_tracer_config_serialization() {
 				timeout: Some(core::time::Duration::from_millis(10)),
 			},
 		),
+		(
+			r#"{"tracer": "structLogger"}"#,
+			TracerConfig { config: TracerType::StructLogger(None), timeout: None },
+		),
+		(
+			r#"{"tracer": "structLogger", "tracerConfig": { "disableStack": true, "enableMemory": true, "limit": 10 }}"#,
+			TracerConfig {
+				config: StructLoggerConfig {
+					disable_stack: true,
+					enable_memory: true,
+					limit: 10,
+					..Default::default()
+				}
+				.into(),
+				timeout: None,
+			},
+		),
+		(
+			r#"{"tracer": "gasProfiler"}"#,
+			TracerConfig { config: TracerType::GasProfiler(None), timeout: None },
+		),
+		(
+			r#"{"tracer": "gasProfiler", "tracerConfig": { "disableStorage": true }}"#,
+			TracerConfig {
+				config: GasProfilerConfig { disable_storage: true, disable_stacks: false }.into(),
+				timeout: None,
+			},
+		),
 	];
 
 	for (json_data, expected) in tracers {
@@ -173,6 +251,10 @@ pub enum Trace {
 	Call(CallTrace),
 	/// A prestate trace.
 	Prestate(PrestateTrace),
+	/// A struct logger trace.
+	StructLogger(StructLoggerTrace),
+	/// A gas profiler trace.
+	GasProfiler(GasProfilerTrace),
 }
 
 /// A prestate Trace
@@ -285,6 +367,9 @@ pub struct CallTrace<Gas = U256> {
 	/// List of logs emitted during the call.
 	#[serde(skip_serializing_if = "Vec::is_empty")]
 	pub logs: Vec<CallLog>,
+	/// List of debug messages emitted during the call.
+	#[serde(rename = "debugMessages", skip_serializing_if = "Vec::is_empty")]
+	pub debug_messages: Vec<String>,
 	/// Amount of value transferred.
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub value: Option<U256>,
@@ -311,6 +396,92 @@ pub struct CallLog {
 	pub position: u32,
 }
 
+/// The result of the struct logger, following the format of geth's `ExecutionResult`.
+#[derive(
+	TypeInfo, Default, Encode, Decode, Serialize, Deserialize, Clone, Debug, Eq, PartialEq,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLoggerTrace<Gas = U256> {
+	/// Amount of gas used by the top-level call.
+	pub gas: Gas,
+	/// Whether the top-level call failed or reverted.
+	pub failed: bool,
+	/// The data returned by the top-level call.
+	pub return_value: Bytes,
+	/// The executed steps.
+	pub struct_logs: Vec<StructLog<Gas>>,
+}
+
+/// A single step recorded by the struct logger.
+#[derive(
+	TypeInfo, Default, Encode, Decode, Serialize, Deserialize, Clone, Debug, Eq, PartialEq,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLog<Gas = U256> {
+	/// The program counter of the instruction.
+	pub pc: u64,
+	/// The name of the instruction, or of the host function called by an `ecalli`.
+	pub op: String,
+	/// Gas left before executing the instruction.
+	pub gas: Gas,
+	/// Gas consumed by the instruction, including any sub-call it made.
+	pub gas_cost: Gas,
+	/// The call depth, starting at 1 for the top-level call.
+	pub depth: u32,
+	/// The values of the registers.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub stack: Option<Vec<U256>>,
+	/// The stack memory, split into 32 byte words.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub memory: Option<Vec<Bytes>>,
+	/// The storage of the current contract accessed so far.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub storage: Option<BTreeMap<Bytes, Bytes>>,
+	/// The return data of the last sub-call.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub return_data: Option<Bytes>,
+	/// The error message if the execution failed at this step.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+/// The result of the gas profiler.
+///
+/// All gas values are net of refunds, e.g. the part of an a priori charge that was refunded
+/// once the actual cost of a host function was known.
+#[derive(
+	TypeInfo, Default, Encode, Decode, Serialize, Deserialize, Clone, Debug, Eq, PartialEq,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct GasProfilerTrace<Gas = U256> {
+	/// The gas charged during the whole execution.
+	pub gas: Gas,
+	/// The gas charged per cost, keyed by the name of the host function cost, `Execution` for
+	/// the instructions of PolkaVM contracts and `EvmGas` for those of EVM contracts.
+	pub costs: BTreeMap<String, GasProfileEntry<Gas>>,
+	/// The gas charged while executing each contract, excluding its sub-calls.
+	pub contracts: BTreeMap<H160, GasProfileEntry<Gas>>,
+	/// The gas charged by the storage host functions, per contract and storage key.
+	#[serde(skip_serializing_if = "BTreeMap::is_empty")]
+	pub storage: BTreeMap<H160, BTreeMap<Bytes, GasProfileEntry<Gas>>>,
+	/// The gas charged per call stack and cost, in the folded format used to render flamegraphs.
+	///
+	/// Each line has the form `0xcaller;0xcallee;Cost gas`.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub stacks: Vec<String>,
+}
+
+/// The gas charged for one entry of the [`GasProfilerTrace`].
+#[derive(
+	TypeInfo, Default, Encode, Decode, Serialize, Deserialize, Clone, Debug, Eq, PartialEq,
+)]
+pub struct GasProfileEntry<Gas = U256> {
+	/// The number of times gas was charged.
+	pub count: u32,
+	/// The total gas charged.
+	pub gas: Gas,
+}
+
 /// A transaction trace
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct TransactionTrace {
@@ -39,6 +39,10 @@ impl TransactionUnsigned {
 				s.append(&tx.r#type.value());
 				s.append(tx);
 			},
+			Transaction7702Unsigned(ref tx) => {
+				s.append(&tx.r#type.value());
+				s.append(tx);
+			},
 			TransactionLegacyUnsigned(ref tx) => {
 				s.append(tx);
 			},
@@ -57,6 +61,7 @@ impl TransactionSigned {
 			Transaction2930Signed(tx) => Transaction2930Unsigned(tx.transaction_2930_unsigned),
 			Transaction1559Signed(tx) => Transaction1559Unsigned(tx.transaction_1559_unsigned),
 			Transaction4844Signed(tx) => Transaction4844Unsigned(tx.transaction_4844_unsigned),
+			Transaction7702Signed(tx) => Transaction7702Unsigned(tx.transaction_7702_unsigned),
 			TransactionLegacySigned(tx) =>
 				TransactionLegacyUnsigned(tx.transaction_legacy_unsigned),
 		}
@@ -79,6 +84,10 @@ impl TransactionSigned {
 				s.append(&tx.transaction_4844_unsigned.r#type.value());
 				s.append(tx);
 			},
+			Transaction7702Signed(ref tx) => {
+				s.append(&tx.transaction_7702_unsigned.r#type.value());
+				s.append(tx);
+			},
 			TransactionLegacySigned(ref tx) => {
 				s.append(tx);
 			},
@@ -96,6 +105,7 @@ impl TransactionSigned {
 			TYPE_EIP2930 => rlp::decode::<Transaction2930Signed>(&data[1..]).map(Into::into),
 			TYPE_EIP1559 => rlp::decode::<Transaction1559Signed>(&data[1..]).map(Into::into),
 			TYPE_EIP4844 => rlp::decode::<Transaction4844Signed>(&data[1..]).map(Into::into),
+			TYPE_EIP7702 => rlp::decode::<Transaction7702Signed>(&data[1..]).map(Into::into),
 			_ => rlp::decode::<TransactionLegacySigned>(data).map(Into::into),
 		}
 	}
@@ -410,6 +420,110 @@ impl Decodable for Transaction4844Signed {
 	}
 }
 
+/// See <https://eips.ethereum.org/EIPS/eip-7702>
+impl AuthorizationListEntry {
+	/// Return the bytes signed by the authority, i.e. `MAGIC || rlp([chain_id, address, nonce])`.
+	pub fn unsigned_payload(&self) -> Vec<u8> {
+		let mut s = rlp::RlpStream::new();
+		s.append(&Self::MAGIC);
+		s.begin_list(3);
+		s.append(&self.chain_id);
+		s.append(&self.address);
+		s.append(&self.nonce);
+		s.out().to_vec()
+	}
+}
+
+/// See <https://eips.ethereum.org/EIPS/eip-7702>
+impl Encodable for AuthorizationListEntry {
+	fn rlp_append(&self, s: &mut rlp::RlpStream) {
+		s.begin_list(6);
+		s.append(&self.chain_id);
+		s.append(&self.address);
+		s.append(&self.nonce);
+		s.append(&self.y_parity);
+		s.append(&self.r);
+		s.append(&self.s);
+	}
+}
+
+impl Decodable for AuthorizationListEntry {
+	fn decode(rlp: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+		Ok(AuthorizationListEntry {
+			chain_id: rlp.val_at(0)?,
+			address: rlp.val_at(1)?,
+			nonce: rlp.val_at(2)?,
+			y_parity: rlp.val_at(3)?,
+			r: rlp.val_at(4)?,
+			s: rlp.val_at(5)?,
+		})
+	}
+}
+
+//See https://eips.ethereum.org/EIPS/eip-7702
+impl Encodable for Transaction7702Unsigned {
+	fn rlp_append(&self, s: &mut rlp::RlpStream) {
+		s.begin_list(10);
+		s.append(&self.chain_id);
+		s.append(&self.nonce);
+		s.append(&self.max_priority_fee_per_gas);
+		s.append(&self.max_fee_per_gas);
+		s.append(&self.gas);
+		s.append(&self.to);
+		s.append(&self.value);
+		s.append(&self.input.0);
+		s.append_list(&self.access_list);
+		s.append_list(&self.authorization_list);
+	}
+}
+
+//See https://eips.ethereum.org/EIPS/eip-7702
+impl Encodable for Transaction7702Signed {
+	fn rlp_append(&self, s: &mut rlp::RlpStream) {
+		let tx = &self.transaction_7702_unsigned;
+		s.begin_list(13);
+		s.append(&tx.chain_id);
+		s.append(&tx.nonce);
+		s.append(&tx.max_priority_fee_per_gas);
+		s.append(&tx.max_fee_per_gas);
+		s.append(&tx.gas);
+		s.append(&tx.to);
+		s.append(&tx.value);
+		s.append(&tx.input.0);
+		s.append_list(&tx.access_list);
+		s.append_list(&tx.authorization_list);
+		s.append(&self.y_parity);
+		s.append(&self.r);
+		s.append(&self.s);
+	}
+}
+
+impl Decodable for Transaction7702Signed {
+	fn decode(rlp: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
+		Ok(Transaction7702Signed {
+			transaction_7702_unsigned: {
+				Transaction7702Unsigned {
+					chain_id: rlp.val_at(0)?,
+					nonce: rlp.val_at(1)?,
+					max_priority_fee_per_gas: rlp.val_at(2)?,
+					max_fee_per_gas: rlp.val_at(3)?,
+					gas: rlp.val_at(4)?,
+					to: rlp.val_at(5)?,
+					value: rlp.val_at(6)?,
+					input: Bytes(rlp.val_at(7)?),
+					access_list: rlp.list_at(8)?,
+					authorization_list: rlp.list_at(9)?,
+					..Default::default()
+				}
+			},
+			y_parity: rlp.val_at(10)?,
+			r: rlp.val_at(11)?,
+			s: rlp.val_at(12)?,
+			..Default::default()
+		})
+	}
+}
+
 /// See <https://eips.ethereum.org/EIPS/eip-155>
 impl Decodable for TransactionLegacySigned {
 	fn decode(rlp: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
@@ -587,4 +701,36 @@ mod test {
 		let payload = Account::default().sign_transaction(tx).signed_payload();
 		assert_eq!(dummy_signed_payload.len(), payload.len());
 	}
+
+	#[test]
+	fn encode_decode_7702_tx_works() {
+		let authority = Account::from(subxt_signer::eth::dev::baltathar());
+		let delegate = H160::from([1u8; 20]);
+		let authorization = authority.sign_authorization(U256::from(1), delegate, U256::from(0));
+		assert_eq!(authorization.recover_authority(), Ok(authority.address()));
+
+		let tx: TransactionUnsigned = Transaction7702Unsigned {
+			chain_id: U256::from(1),
+			nonce: U256::from(2),
+			gas: U256::from(100_000),
+			max_fee_per_gas: U256::from(1_000),
+			max_priority_fee_per_gas: U256::from(10),
+			to: authority.address(),
+			input: Bytes(vec![1, 2, 3]),
+			authorization_list: vec![authorization],
+			..Default::default()
+		}
+		.into();
+
+		let account = Account::default();
+		let raw_tx = account.sign_transaction(tx).signed_payload();
+		assert_eq!(raw_tx[0], TYPE_EIP7702);
+
+		let tx = TransactionSigned::decode(&raw_tx).unwrap();
+		assert_eq!(tx.signed_payload(), raw_tx);
+		assert_eq!(tx.recover_eth_address(), Ok(account.address()));
+
+		let json = serde_json::to_string(&tx).unwrap();
+		assert_eq!(serde_json::from_str::<TransactionSigned>(&json).unwrap(), tx);
+	}
 }
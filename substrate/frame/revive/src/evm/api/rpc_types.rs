@@ -14,11 +14,90 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
-//! Utility impl for the RPC types.
+//! Utility impl for the RPC types, and RPC types that are not generated.
 use super::*;
-use alloc::vec::Vec;
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use codec::{Decode, DecodeWithMemTracking, Encode};
+use scale_info::TypeInfo;
+use serde::{Deserialize, Serialize};
 use sp_core::{H160, U256};
 
+/// The state overrides of `eth_call`, `eth_estimateGas` and `eth_simulateV1`, by address.
+pub type StateOverrides = BTreeMap<H160, StateOverride>;
+
+/// Overrides of the state of an account.
+#[derive(
+	Debug,
+	Default,
+	Clone,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	Serialize,
+	Deserialize,
+	Eq,
+	PartialEq,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct StateOverride {
+	/// The balance of the account.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub balance: Option<U256>,
+	/// The nonce of the account.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub nonce: Option<U256>,
+	/// The PolkaVM code of the account.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub code: Option<Bytes>,
+	/// Replaces the whole storage of the account.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub state: Option<BTreeMap<H256, H256>>,
+	/// Overrides individual storage slots of the account.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub state_diff: Option<BTreeMap<H256, H256>>,
+}
+
+/// The result of `eth_call`.
+///
+/// This is serialized as the plain return data, unless the called contracts emitted debug
+/// messages. The return data is then returned along with the messages.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(untagged)]
+pub enum CallResult {
+	/// The return data of the call.
+	Data(Bytes),
+	/// The return data of the call, along with the debug messages emitted by the contracts.
+	#[serde(rename_all = "camelCase")]
+	WithDebugMessage {
+		/// The return data of the call.
+		data: Bytes,
+		/// The debug messages emitted by the contracts, concatenated.
+		debug_message: String,
+	},
+}
+
+impl CallResult {
+	/// Create a new [`CallResult`] from the return data and the debug messages of a call.
+	pub fn new(data: Vec<u8>, debug_message: &[u8]) -> Self {
+		if debug_message.is_empty() {
+			Self::Data(data.into())
+		} else {
+			Self::WithDebugMessage {
+				data: data.into(),
+				debug_message: String::from_utf8_lossy(debug_message).into_owned(),
+			}
+		}
+	}
+
+	/// Consume the [`CallResult`] and return the return data of the call.
+	pub fn into_data(self) -> Bytes {
+		match self {
+			Self::Data(data) | Self::WithDebugMessage { data, .. } => data,
+		}
+	}
+}
+
 impl From<BlockNumberOrTag> for BlockNumberOrTagOrHash {
 	fn from(b: BlockNumberOrTag) -> Self {
 		match b {
@@ -32,6 +111,7 @@ impl From<TransactionSigned> for TransactionUnsigned {
 	fn from(tx: TransactionSigned) -> Self {
 		use TransactionSigned::*;
 		match tx {
+			Transaction7702Signed(tx) => tx.transaction_7702_unsigned.into(),
 			Transaction4844Signed(tx) => tx.transaction_4844_unsigned.into(),
 			Transaction1559Signed(tx) => tx.transaction_1559_unsigned.into(),
 			Transaction2930Signed(tx) => tx.transaction_2930_unsigned.into(),
@@ -249,6 +329,26 @@ impl GenericTransaction {
 				max_priority_fee_per_gas: Some(tx.max_priority_fee_per_gas),
 				..Default::default()
 			},
+			Transaction7702Unsigned(tx) => GenericTransaction {
+				from,
+				r#type: Some(tx.r#type.as_byte()),
+				chain_id: Some(tx.chain_id),
+				input: tx.input.into(),
+				nonce: Some(tx.nonce),
+				value: Some(tx.value),
+				to: Some(tx.to),
+				gas: Some(tx.gas),
+				gas_price: Some(
+					base_gas_price
+						.saturating_add(tx.max_priority_fee_per_gas)
+						.min(tx.max_fee_per_gas),
+				),
+				access_list: Some(tx.access_list),
+				authorization_list: Some(tx.authorization_list),
+				max_fee_per_gas: Some(tx.max_fee_per_gas),
+				max_priority_fee_per_gas: Some(tx.max_priority_fee_per_gas),
+				..Default::default()
+			},
 			Transaction1559Unsigned(tx) => GenericTransaction {
 				from,
 				r#type: Some(tx.r#type.as_byte()),
@@ -339,6 +439,21 @@ impl GenericTransaction {
 				blob_versioned_hashes: self.blob_versioned_hashes,
 			}
 			.into()),
+			TYPE_EIP7702 => Ok(Transaction7702Unsigned {
+				r#type: TypeEip7702 {},
+				chain_id: self.chain_id.unwrap_or_default(),
+				input: self.input.to_bytes(),
+				nonce: self.nonce.unwrap_or_default(),
+				value: self.value.unwrap_or_default(),
+				to: self.to.ok_or(())?,
+				gas: self.gas.unwrap_or_default(),
+				gas_price: self.max_fee_per_gas.unwrap_or_default(),
+				max_fee_per_gas: self.max_fee_per_gas.unwrap_or_default(),
+				max_priority_fee_per_gas: self.max_priority_fee_per_gas.unwrap_or_default(),
+				access_list: self.access_list.unwrap_or_default(),
+				authorization_list: self.authorization_list.unwrap_or_default(),
+			}
+			.into()),
 			_ => Err(()),
 		}
 	}
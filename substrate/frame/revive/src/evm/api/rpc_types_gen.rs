@@ -17,9 +17,9 @@
 //! Generated JSON-RPC types.
 #![allow(missing_docs)]
 
-use super::{byte::*, TypeEip1559, TypeEip2930, TypeEip4844, TypeLegacy};
+use super::{byte::*, TypeEip1559, TypeEip2930, TypeEip4844, TypeEip7702, TypeLegacy};
 use alloc::vec::Vec;
-use codec::{Decode, Encode};
+use codec::{Decode, DecodeWithMemTracking, Encode};
 use derive_more::{From, TryInto};
 pub use ethereum_types::*;
 use scale_info::TypeInfo;
@@ -73,6 +73,42 @@ fn deserialize_input_or_data<'d, D: Deserializer<'d>>(d: D) -> Result<InputOrDat
     }
 }
 
+/// Access list result
+#[derive(
+	Debug, Default, Clone, Encode, Decode, TypeInfo, Serialize, Deserialize, Eq, PartialEq,
+)]
+pub struct AccessListResult {
+	/// accessList
+	#[serde(rename = "accessList")]
+	pub access_list: AccessList,
+	/// gasUsed
+	#[serde(rename = "gasUsed")]
+	pub gas_used: U256,
+}
+
+/// Account proof
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct AccountProof {
+	/// Address
+	pub address: Address,
+	/// Account proof
+	#[serde(rename = "accountProof")]
+	pub account_proof: Vec<Bytes>,
+	/// Balance
+	pub balance: U256,
+	/// Code hash
+	#[serde(rename = "codeHash")]
+	pub code_hash: H256,
+	/// Nonce
+	pub nonce: U256,
+	/// Storage hash
+	#[serde(rename = "storageHash")]
+	pub storage_hash: H256,
+	/// Storage proofs
+	#[serde(rename = "storageProof")]
+	pub storage_proof: Vec<StorageProof>,
+}
+
 /// Block object
 #[derive(Debug, Default, Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub struct Block {
@@ -262,6 +298,10 @@ pub struct GenericTransaction {
 	/// EIP-2930 access list
 	#[serde(rename = "accessList", skip_serializing_if = "Option::is_none")]
 	pub access_list: Option<AccessList>,
+	/// authorizationList
+	/// EIP-7702 authorization list
+	#[serde(rename = "authorizationList", skip_serializing_if = "Option::is_none")]
+	pub authorization_list: Option<AuthorizationList>,
 	/// blobVersionedHashes
 	/// List of versioned blob hashes associated with the transaction's EIP-4844 data blobs.
 	#[serde(rename = "blobVersionedHashes", default, skip_serializing_if = "Vec::is_empty")]
@@ -381,6 +421,17 @@ pub struct ReceiptInfo {
 	pub r#type: Option<Byte>,
 }
 
+/// Storage proof
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct StorageProof {
+	/// key
+	pub key: U256,
+	/// value
+	pub value: U256,
+	/// proof
+	pub proof: Vec<Bytes>,
+}
+
 /// Syncing status
 #[derive(Debug, Clone, Serialize, Deserialize, From, TryInto, Eq, PartialEq)]
 #[serde(untagged)]
@@ -420,6 +471,7 @@ pub struct TransactionInfo {
 #[derive(Debug, Clone, Serialize, Deserialize, From, TryInto, Eq, PartialEq)]
 #[serde(untagged)]
 pub enum TransactionUnsigned {
+	Transaction7702Unsigned(Transaction7702Unsigned),
 	Transaction4844Unsigned(Transaction4844Unsigned),
 	Transaction1559Unsigned(Transaction1559Unsigned),
 	Transaction2930Unsigned(Transaction2930Unsigned),
@@ -434,6 +486,9 @@ impl Default for TransactionUnsigned {
 /// Access list
 pub type AccessList = Vec<AccessListEntry>;
 
+/// Authorization list
+pub type AuthorizationList = Vec<AuthorizationListEntry>;
+
 /// Address(es)
 #[derive(Debug, Clone, Serialize, Deserialize, From, TryInto, Eq, PartialEq)]
 #[serde(untagged)]
@@ -495,7 +550,9 @@ impl Default for HashesOrTransactionInfos {
 }
 
 /// log
-#[derive(Debug, Default, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(
+	Debug, Default, Clone, Encode, Decode, TypeInfo, Serialize, Deserialize, Eq, PartialEq,
+)]
 pub struct Log {
 	/// address
 	pub address: Address,
@@ -608,6 +665,50 @@ pub struct Transaction2930Unsigned {
 	pub value: U256,
 }
 
+/// EIP-7702 transaction.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Transaction7702Unsigned {
+	/// accessList
+	/// EIP-2930 access list
+	#[serde(rename = "accessList")]
+	pub access_list: AccessList,
+	/// authorizationList
+	/// EIP-7702 authorization list
+	#[serde(rename = "authorizationList")]
+	pub authorization_list: AuthorizationList,
+	/// chainId
+	/// Chain ID that this transaction is valid on.
+	#[serde(rename = "chainId")]
+	pub chain_id: U256,
+	/// gas limit
+	pub gas: U256,
+	/// gas price
+	/// The effective gas price paid by the sender in wei. For transactions not yet included in a
+	/// block, this value should be set equal to the max fee per gas. This field is DEPRECATED,
+	/// please transition to using effectiveGasPrice in the receipt object going forward.
+	#[serde(rename = "gasPrice")]
+	pub gas_price: U256,
+	/// input data
+	pub input: Bytes,
+	/// max fee per gas
+	/// The maximum total fee per gas the sender is willing to pay (includes the network / base fee
+	/// and miner / priority fee) in wei
+	#[serde(rename = "maxFeePerGas")]
+	pub max_fee_per_gas: U256,
+	/// max priority fee per gas
+	/// Maximum fee per gas the sender is willing to pay to miners in wei
+	#[serde(rename = "maxPriorityFeePerGas")]
+	pub max_priority_fee_per_gas: U256,
+	/// nonce
+	pub nonce: U256,
+	/// to address
+	pub to: Address,
+	/// type
+	pub r#type: TypeEip7702,
+	/// value
+	pub value: U256,
+}
+
 /// EIP-4844 transaction.
 #[derive(Debug, Default, Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub struct Transaction4844Unsigned {
@@ -678,6 +779,7 @@ pub struct TransactionLegacyUnsigned {
 #[derive(Debug, Clone, Serialize, Deserialize, From, TryInto, Eq, PartialEq)]
 #[serde(untagged)]
 pub enum TransactionSigned {
+	Transaction7702Signed(Transaction7702Signed),
 	Transaction4844Signed(Transaction4844Signed),
 	Transaction1559Signed(Transaction1559Signed),
 	Transaction2930Signed(Transaction2930Signed),
@@ -705,7 +807,17 @@ pub struct Withdrawal {
 
 /// Access list entry
 #[derive(
-	Debug, Default, Clone, Encode, Decode, TypeInfo, Serialize, Deserialize, Eq, PartialEq,
+	Debug,
+	Default,
+	Clone,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	Serialize,
+	Deserialize,
+	Eq,
+	PartialEq,
 )]
 pub struct AccessListEntry {
 	pub address: Address,
@@ -713,6 +825,42 @@ pub struct AccessListEntry {
 	pub storage_keys: Vec<H256>,
 }
 
+/// Authorization list entry
+/// EIP-7702 authorization of an account to delegate its code to a contract.
+#[derive(
+	Debug,
+	Default,
+	Clone,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	TypeInfo,
+	Serialize,
+	Deserialize,
+	Eq,
+	PartialEq,
+)]
+pub struct AuthorizationListEntry {
+	/// chainId
+	/// Chain ID that this authorization is valid on, or zero for all chains.
+	#[serde(rename = "chainId")]
+	pub chain_id: U256,
+	/// address
+	/// The address of the contract the authority delegates its code to.
+	pub address: Address,
+	/// nonce
+	/// The nonce of the authority.
+	pub nonce: U256,
+	/// yParity
+	/// The parity (0 for even, 1 for odd) of the y-value of the secp256k1 signature.
+	#[serde(rename = "yParity")]
+	pub y_parity: U256,
+	/// r
+	pub r: U256,
+	/// s
+	pub s: U256,
+}
+
 /// Filter Topic List Entry
 #[derive(Debug, Clone, Serialize, Deserialize, From, TryInto, Eq, PartialEq)]
 #[serde(untagged)]
@@ -768,6 +916,26 @@ pub struct Transaction2930Signed {
 	pub y_parity: U256,
 }
 
+/// Signed 7702 Transaction
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Transaction7702Signed {
+	#[serde(flatten)]
+	pub transaction_7702_unsigned: Transaction7702Unsigned,
+	/// r
+	pub r: U256,
+	/// s
+	pub s: U256,
+	/// v
+	/// For backwards compatibility, `v` is optionally provided as an alternative to `yParity`.
+	/// This field is DEPRECATED and all use of it should migrate to `yParity`.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub v: Option<U256>,
+	/// yParity
+	/// The parity (0 for even, 1 for odd) of the y-value of the secp256k1 signature.
+	#[serde(rename = "yParity")]
+	pub y_parity: U256,
+}
+
 /// Signed 4844 Transaction
 #[derive(Debug, Default, Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub struct Transaction4844Signed {
@@ -42,6 +42,8 @@ impl TransactionUnsigned {
 		match tx {
 			TransactionSigned::TransactionLegacySigned(signed) =>
 				Self::TransactionLegacyUnsigned(signed.transaction_legacy_unsigned),
+			TransactionSigned::Transaction7702Signed(signed) =>
+				Self::Transaction7702Unsigned(signed.transaction_7702_unsigned),
 			TransactionSigned::Transaction4844Signed(signed) =>
 				Self::Transaction4844Unsigned(signed.transaction_4844_unsigned),
 			TransactionSigned::Transaction1559Signed(signed) =>
@@ -51,6 +53,17 @@ impl TransactionUnsigned {
 		}
 	}
 
+	/// Create a transaction sent on behalf of `from`, without its signature.
+	///
+	/// The signature encodes `from` and is not a valid ECDSA signature, such transactions are
+	/// only accepted by development runtimes that allow impersonating `from`, see
+	/// [`TransactionSigned::impersonated_signer`].
+	pub fn impersonate(self, from: H160) -> TransactionSigned {
+		let mut signature = [0u8; 65];
+		signature[12..32].copy_from_slice(from.as_bytes());
+		self.with_signature(signature)
+	}
+
 	/// Create a signed transaction from an [`TransactionUnsigned`] and a signature.
 	pub fn with_signature(self, signature: [u8; 65]) -> TransactionSigned {
 		let r = U256::from_big_endian(&signature[..32]);
@@ -77,6 +90,16 @@ impl TransactionUnsigned {
 				}
 				.into(),
 
+			TransactionUnsigned::Transaction7702Unsigned(transaction_7702_unsigned) =>
+				Transaction7702Signed {
+					transaction_7702_unsigned,
+					r,
+					s,
+					v: None,
+					y_parity: U256::from(recovery_id),
+				}
+				.into(),
+
 			TransactionUnsigned::Transaction4844Unsigned(transaction_4844_unsigned) =>
 				Transaction4844Signed {
 					transaction_4844_unsigned,
@@ -108,6 +131,7 @@ impl TransactionSigned {
 		use TransactionSigned::*;
 		let (r, s, v) = match self {
 			TransactionLegacySigned(tx) => (tx.r, tx.s, tx.extract_recovery_id().ok_or(())?),
+			Transaction7702Signed(tx) => (tx.r, tx.s, tx.y_parity.try_into().map_err(|_| ())?),
 			Transaction4844Signed(tx) => (tx.r, tx.s, tx.y_parity.try_into().map_err(|_| ())?),
 			Transaction1559Signed(tx) => (tx.r, tx.s, tx.y_parity.try_into().map_err(|_| ())?),
 			Transaction2930Signed(tx) => (tx.r, tx.s, tx.y_parity.try_into().map_err(|_| ())?),
@@ -119,6 +143,17 @@ impl TransactionSigned {
 		Ok(sig)
 	}
 
+	/// Get the address a transaction created with [`TransactionUnsigned::impersonate`] is sent
+	/// on behalf of.
+	pub fn impersonated_signer(&self) -> Option<H160> {
+		let signature = self.raw_signature().ok()?;
+		let (r, s) = signature[..64].split_at(32);
+		if r[..12].iter().chain(s).chain(&signature[64..]).any(|byte| *byte != 0) {
+			return None;
+		}
+		Some(H160::from_slice(&r[12..]))
+	}
+
 	/// Recover the Ethereum address, from a signed transaction.
 	pub fn recover_eth_address(&self) -> Result<H160, ()> {
 		use TransactionSigned::*;
@@ -129,6 +164,11 @@ impl TransactionSigned {
 				let tx = &tx.transaction_legacy_unsigned;
 				s.append(tx);
 			},
+			Transaction7702Signed(tx) => {
+				let tx = &tx.transaction_7702_unsigned;
+				s.append(&tx.r#type.value());
+				s.append(tx);
+			},
 			Transaction4844Signed(tx) => {
 				let tx = &tx.transaction_4844_unsigned;
 				s.append(&tx.r#type.value());
@@ -156,6 +196,34 @@ impl TransactionSigned {
 	}
 }
 
+impl AuthorizationListEntry {
+	/// The prefix of the payload signed by the authority.
+	/// See https://eips.ethereum.org/EIPS/eip-7702
+	pub const MAGIC: u8 = 0x05;
+
+	/// Recover the Ethereum address of the authority that signed the authorization.
+	///
+	/// Signatures with a high `s` value are rejected, as required by EIP-7702.
+	pub fn recover_authority(&self) -> Result<H160, ()> {
+		const SECP256K1N_HALF: U256 =
+			U256([0xdfe92f46681b20a0, 0x5d576e7357a4501d, 0xffffffffffffffff, 0x7fffffffffffffff]);
+		if self.s > SECP256K1N_HALF {
+			return Err(());
+		}
+
+		let mut signature = [0u8; 65];
+		self.r.write_as_big_endian(signature[0..32].as_mut());
+		self.s.write_as_big_endian(signature[32..64].as_mut());
+		signature[64] = self.y_parity.try_into().map_err(|_| ())?;
+
+		let hash = keccak_256(&self.unsigned_payload());
+		let mut addr = H160::default();
+		let pk = secp256k1_ecdsa_recover(&signature, &hash).map_err(|_| ())?;
+		addr.assign_from_slice(&keccak_256(&pk[..])[12..]);
+		Ok(addr)
+	}
+}
+
 #[test]
 fn sign_and_recover_work() {
 	use crate::evm::TransactionUnsigned;
@@ -185,3 +253,18 @@ fn sign_and_recover_work() {
 		assert_eq!(tx, signed);
 	}
 }
+
+#[test]
+fn impersonate_works() {
+	use crate::evm::{TransactionLegacyUnsigned, TransactionUnsigned};
+	let from = H160::repeat_byte(0x42);
+	let tx: TransactionUnsigned =
+		TransactionLegacyUnsigned { chain_id: Some(U256::from(1)), ..Default::default() }.into();
+
+	let signed = tx.clone().impersonate(from);
+	assert_eq!(signed.impersonated_signer(), Some(from));
+	assert!(signed.recover_eth_address().is_err());
+
+	let signed = Account::default().sign_transaction(tx);
+	assert_eq!(signed.impersonated_signer(), None);
+}
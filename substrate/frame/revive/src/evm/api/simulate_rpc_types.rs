@@ -0,0 +1,131 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types of the `eth_simulateV1` method.
+
+use crate::evm::{Bytes, GenericTransaction, Log, StateOverrides};
+use alloc::{string::String, vec::Vec};
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use serde::{Deserialize, Serialize};
+use sp_core::{H160, U256};
+
+/// The payload of `eth_simulateV1`.
+#[derive(
+	Debug, Default, Clone, Encode, Decode, TypeInfo, Serialize, Deserialize, Eq, PartialEq,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatePayload {
+	/// The blocks to simulate, in order.
+	pub block_state_calls: Vec<SimulateBlock>,
+}
+
+/// A block to simulate, along with the overrides applied before executing its calls.
+#[derive(
+	Debug, Default, Clone, Encode, Decode, TypeInfo, Serialize, Deserialize, Eq, PartialEq,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateBlock {
+	/// Overrides of the block header fields.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub block_overrides: Option<BlockOverrides>,
+	/// Overrides of the account states, applied before the calls of the block.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub state_overrides: Option<StateOverrides>,
+	/// The calls to execute, in order.
+	#[serde(default)]
+	pub calls: Vec<GenericTransaction>,
+}
+
+/// Overrides of the block header fields of a simulated block.
+#[derive(
+	Debug, Default, Clone, Encode, Decode, TypeInfo, Serialize, Deserialize, Eq, PartialEq,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockOverrides {
+	/// The block number.
+	///
+	/// Defaults to the number of the previous block plus one.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub number: Option<U256>,
+	/// The block timestamp, in seconds.
+	///
+	/// Defaults to the timestamp of the previous block plus 12 seconds.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub time: Option<U256>,
+	/// The block author.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub fee_recipient: Option<H160>,
+}
+
+/// A simulated block.
+#[derive(
+	Debug, Default, Clone, Encode, Decode, TypeInfo, Serialize, Deserialize, Eq, PartialEq,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBlock {
+	/// The block number.
+	pub number: U256,
+	/// The block timestamp, in seconds.
+	pub timestamp: U256,
+	/// The total gas used by the calls of the block.
+	pub gas_used: U256,
+	/// The block author.
+	pub miner: H160,
+	/// The results of the calls of the block.
+	pub calls: Vec<SimulateCallResult>,
+}
+
+/// The result of a simulated call.
+#[derive(
+	Debug, Default, Clone, Encode, Decode, TypeInfo, Serialize, Deserialize, Eq, PartialEq,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateCallResult {
+	/// `1` if the call succeeded, `0` otherwise.
+	pub status: U256,
+	/// The data returned by the call.
+	pub return_data: Bytes,
+	/// The gas used by the call.
+	pub gas_used: U256,
+	/// The logs emitted by the call.
+	pub logs: Vec<Log>,
+	/// The error of a failed call.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub error: Option<SimulateCallError>,
+}
+
+/// The error of a failed simulated call.
+#[derive(
+	Debug, Default, Clone, Encode, Decode, TypeInfo, Serialize, Deserialize, Eq, PartialEq,
+)]
+pub struct SimulateCallError {
+	/// The JSON-RPC error code.
+	pub code: i32,
+	/// The error message.
+	pub message: String,
+	/// The revert data of a reverted call.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub data: Option<Bytes>,
+}
+
+impl SimulateCallError {
+	/// The error code of a reverted call.
+	pub const REVERTED: i32 = 3;
+	/// The error code of a call that failed to execute.
+	pub const FAILED: i32 = -32015;
+}
@@ -0,0 +1,376 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types of the Parity-style `trace_*` methods.
+
+use crate::evm::{BlockNumberOrTag, Bytes, CallTrace, CallType};
+use alloc::{string::String, vec, vec::Vec};
+use serde::{Deserialize, Serialize};
+use sp_core::{H160, H256, U256};
+
+/// The error message of a reverted call in a flat trace.
+const REVERTED: &str = "Reverted";
+
+/// A flat trace of a single call frame.
+///
+/// The call frames of a transaction are listed in depth-first order, each frame being located in
+/// the call tree by its `trace_address`.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FlatTrace {
+	/// The action performed by the call frame.
+	pub action: FlatTraceAction,
+	/// The hash of the block containing the transaction.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub block_hash: Option<H256>,
+	/// The number of the block containing the transaction.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub block_number: Option<u64>,
+	/// The error message, if the call frame failed.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+	/// The result of the call frame, `None` if it failed.
+	pub result: Option<FlatTraceResult>,
+	/// The number of sub-calls made by the call frame.
+	pub subtraces: u32,
+	/// The path of the call frame in the call tree, as a list of sub-call indexes.
+	pub trace_address: Vec<u32>,
+	/// The hash of the transaction.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub transaction_hash: Option<H256>,
+	/// The index of the transaction in the block.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub transaction_position: Option<u32>,
+	/// The type of the call frame.
+	#[serde(rename = "type")]
+	pub trace_type: FlatTraceType,
+}
+
+/// The type of a flat trace.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FlatTraceType {
+	/// A call to an existing account.
+	#[default]
+	Call,
+	/// The creation of a contract.
+	Create,
+}
+
+/// The action of a flat trace.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(untagged)]
+pub enum FlatTraceAction {
+	/// A call to an existing account.
+	Call(FlatCallAction),
+	/// The creation of a contract.
+	Create(FlatCreateAction),
+}
+
+/// The type of call of a [`FlatCallAction`].
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FlatCallType {
+	/// A regular call.
+	#[default]
+	Call,
+	/// A read-only call.
+	StaticCall,
+	/// A delegate call.
+	DelegateCall,
+}
+
+/// The action of a call to an existing account.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FlatCallAction {
+	/// The type of call.
+	pub call_type: FlatCallType,
+	/// The address of the caller.
+	pub from: H160,
+	/// The gas provided for the call.
+	pub gas: U256,
+	/// The call input data.
+	pub input: Bytes,
+	/// The address of the callee.
+	pub to: H160,
+	/// The value transferred.
+	pub value: U256,
+}
+
+/// The method used to create a contract.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CreationMethod {
+	/// A create call.
+	#[default]
+	Create,
+	/// A create2 call.
+	Create2,
+}
+
+/// The action of the creation of a contract.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FlatCreateAction {
+	/// The method used to create the contract.
+	pub creation_method: CreationMethod,
+	/// The address of the creator.
+	pub from: H160,
+	/// The gas provided for the creation.
+	pub gas: U256,
+	/// The init code, followed by the constructor input data.
+	pub init: Bytes,
+	/// The value transferred.
+	pub value: U256,
+}
+
+/// The result of a flat trace.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(untagged)]
+pub enum FlatTraceResult {
+	/// The result of the creation of a contract.
+	Create {
+		/// The address of the created contract.
+		address: H160,
+		/// The data returned by the constructor.
+		code: Bytes,
+		/// The gas used.
+		#[serde(rename = "gasUsed")]
+		gas_used: U256,
+	},
+	/// The result of a call.
+	Call {
+		/// The gas used.
+		#[serde(rename = "gasUsed")]
+		gas_used: U256,
+		/// The data returned by the call.
+		output: Bytes,
+	},
+}
+
+impl FlatTrace {
+	/// Flatten the call trace of a transaction into a list of [`FlatTrace`], in depth-first order.
+	///
+	/// The block and transaction fields of the returned traces are left to `None`.
+	pub fn from_call_trace(trace: CallTrace) -> Vec<Self> {
+		let mut traces = Vec::new();
+		let mut stack = vec![(trace, Vec::new())];
+
+		while let Some((trace, trace_address)) = stack.pop() {
+			let CallTrace {
+				from,
+				gas,
+				gas_used,
+				to,
+				input,
+				output,
+				error,
+				calls,
+				value,
+				call_type,
+				..
+			} = trace;
+			let value = value.unwrap_or_default();
+
+			let (action, result, trace_type) = match call_type {
+				CallType::Create | CallType::Create2 => {
+					let creation_method = if call_type == CallType::Create2 {
+						CreationMethod::Create2
+					} else {
+						CreationMethod::Create
+					};
+					let action =
+						FlatCreateAction { creation_method, from, gas, init: input, value };
+					let result = FlatTraceResult::Create { address: to, code: output, gas_used };
+					(FlatTraceAction::Create(action), result, FlatTraceType::Create)
+				},
+				CallType::Call | CallType::StaticCall | CallType::DelegateCall => {
+					let call_type = match call_type {
+						CallType::StaticCall => FlatCallType::StaticCall,
+						CallType::DelegateCall => FlatCallType::DelegateCall,
+						_ => FlatCallType::Call,
+					};
+					let action = FlatCallAction { call_type, from, gas, input, to, value };
+					let result = FlatTraceResult::Call { gas_used, output };
+					(FlatTraceAction::Call(action), result, FlatTraceType::Call)
+				},
+			};
+
+			let error = error.map(|error| match error.as_str() {
+				"execution reverted" => REVERTED.into(),
+				_ => error,
+			});
+
+			// Push the sub-calls in reverse order, so that they are popped in order.
+			let subtraces = calls.len() as u32;
+			for (index, call) in calls.into_iter().enumerate().rev() {
+				let mut address = trace_address.clone();
+				address.push(index as u32);
+				stack.push((call, address));
+			}
+
+			traces.push(FlatTrace {
+				action,
+				block_hash: None,
+				block_number: None,
+				result: if error.is_none() { Some(result) } else { None },
+				error,
+				subtraces,
+				trace_address,
+				transaction_hash: None,
+				transaction_position: None,
+				trace_type,
+			});
+		}
+
+		traces
+	}
+}
+
+/// The filter of `trace_filter`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceFilter {
+	/// The first block of the range, defaults to the latest block.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub from_block: Option<BlockNumberOrTag>,
+	/// The last block of the range, defaults to the latest block.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub to_block: Option<BlockNumberOrTag>,
+	/// Only return the traces of call frames sent by one of these addresses.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub from_address: Option<Vec<H160>>,
+	/// Only return the traces of call frames sent to, or creating, one of these addresses.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub to_address: Option<Vec<H160>>,
+	/// The number of matching traces to skip.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub after: Option<u64>,
+	/// The maximum number of traces to return.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub count: Option<u64>,
+}
+
+/// The type of trace requested by `trace_replayTransaction`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum TraceResultType {
+	/// The flat call traces.
+	Trace,
+	/// The virtual machine execution trace.
+	VmTrace,
+	/// The state difference.
+	StateDiff,
+}
+
+/// The result of `trace_replayTransaction`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceResults {
+	/// The data returned by the transaction.
+	pub output: Bytes,
+	/// The flat call traces of the transaction, if requested.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub trace: Option<Vec<FlatTrace>>,
+}
+
+#[test]
+fn flat_trace_from_call_trace_works() {
+	let alice = H160::from([1u8; 20]);
+	let bob = H160::from([2u8; 20]);
+	let charlie = H160::from([3u8; 20]);
+
+	let trace = CallTrace {
+		from: alice,
+		to: bob,
+		value: Some(U256::from(100)),
+		calls: vec![
+			CallTrace {
+				from: bob,
+				to: charlie,
+				call_type: CallType::Create2,
+				output: Bytes(vec![1, 2, 3]),
+				calls: vec![CallTrace {
+					from: charlie,
+					to: alice,
+					call_type: CallType::StaticCall,
+					error: Some("execution reverted".into()),
+					..Default::default()
+				}],
+				..Default::default()
+			},
+			CallTrace {
+				from: bob,
+				to: alice,
+				call_type: CallType::DelegateCall,
+				..Default::default()
+			},
+		],
+		..Default::default()
+	};
+
+	let traces = FlatTrace::from_call_trace(trace);
+	let summary = traces
+		.iter()
+		.map(|trace| (trace.trace_type, trace.trace_address.clone(), trace.subtraces))
+		.collect::<Vec<_>>();
+	assert_eq!(
+		summary,
+		vec![
+			(FlatTraceType::Call, vec![], 2),
+			(FlatTraceType::Create, vec![0], 1),
+			(FlatTraceType::Call, vec![0, 0], 0),
+			(FlatTraceType::Call, vec![1], 0),
+		]
+	);
+
+	assert_eq!(
+		traces[0].action,
+		FlatTraceAction::Call(FlatCallAction {
+			from: alice,
+			to: bob,
+			value: U256::from(100),
+			..Default::default()
+		})
+	);
+	assert_eq!(
+		traces[1].result,
+		Some(FlatTraceResult::Create {
+			address: charlie,
+			code: Bytes(vec![1, 2, 3]),
+			gas_used: U256::zero()
+		})
+	);
+	assert_eq!(traces[2].error, Some(REVERTED.into()));
+	assert_eq!(traces[2].result, None);
+	assert_eq!(
+		traces[3].action,
+		FlatTraceAction::Call(FlatCallAction {
+			call_type: FlatCallType::DelegateCall,
+			from: bob,
+			to: alice,
+			..Default::default()
+		})
+	);
+
+	let json = serde_json::to_value(&traces[1]).unwrap();
+	assert_eq!(json["type"], "create");
+	assert_eq!(json["action"]["creationMethod"], "create2");
+	assert_eq!(json["traceAddress"], serde_json::json!([0]));
+	assert_eq!(serde_json::from_value::<FlatTrace>(json).unwrap(), traces[1]);
+}
@@ -0,0 +1,167 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Types of the `txpool_*` methods.
+
+use crate::evm::{GenericTransaction, TransactionSigned};
+use alloc::{collections::BTreeMap, format, string::String};
+use serde::{Deserialize, Serialize};
+use sp_core::{H160, H256, U256};
+
+/// A transaction of the transaction pool.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct TxPoolTransaction {
+	/// The sender of the transaction.
+	pub from: H160,
+	/// The hash of the transaction.
+	pub hash: H256,
+	/// The signed transaction.
+	#[serde(flatten)]
+	pub transaction_signed: TransactionSigned,
+}
+
+impl TxPoolTransaction {
+	/// Returns the fields of the transaction.
+	fn fields(&self) -> GenericTransaction {
+		GenericTransaction::from_signed(
+			self.transaction_signed.clone(),
+			U256::zero(),
+			Some(self.from),
+		)
+	}
+
+	/// Returns a one-line summary of the transaction, in the format of geth's `txpool_inspect`.
+	pub fn summary(&self) -> String {
+		let tx = self.fields();
+		let to = match tx.to {
+			Some(to) => format!("{to:?}"),
+			None => "contract creation".into(),
+		};
+		format!(
+			"{to}: {} wei + {} gas × {} wei",
+			tx.value.unwrap_or_default(),
+			tx.gas.unwrap_or_default(),
+			tx.max_fee_per_gas.or(tx.gas_price).unwrap_or_default(),
+		)
+	}
+}
+
+/// The transactions of the transaction pool, grouped by sender and nonce.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct TxPoolContent<T = TxPoolTransaction> {
+	/// The transactions that are ready for inclusion.
+	pub pending: BTreeMap<H160, BTreeMap<u64, T>>,
+	/// The transactions that are not ready for inclusion yet, e.g. because of a nonce gap.
+	pub queued: BTreeMap<H160, BTreeMap<u64, T>>,
+}
+
+impl<T> Default for TxPoolContent<T> {
+	fn default() -> Self {
+		Self { pending: Default::default(), queued: Default::default() }
+	}
+}
+
+/// The summaries of the transactions of the transaction pool, grouped by sender and nonce.
+pub type TxPoolInspect = TxPoolContent<String>;
+
+impl TxPoolContent {
+	/// Group the given pending and queued transactions by sender and nonce.
+	pub fn new(
+		pending: impl IntoIterator<Item = TxPoolTransaction>,
+		queued: impl IntoIterator<Item = TxPoolTransaction>,
+	) -> Self {
+		let group = |txs: &mut BTreeMap<H160, BTreeMap<u64, TxPoolTransaction>>,
+		             tx: TxPoolTransaction| {
+			let nonce = tx.fields().nonce.unwrap_or_default();
+			let nonce = nonce.try_into().unwrap_or(u64::MAX);
+			txs.entry(tx.from).or_default().insert(nonce, tx);
+		};
+
+		let mut content = Self::default();
+		pending.into_iter().for_each(|tx| group(&mut content.pending, tx));
+		queued.into_iter().for_each(|tx| group(&mut content.queued, tx));
+		content
+	}
+
+	/// Returns the number of pending and queued transactions.
+	pub fn status(&self) -> TxPoolStatus {
+		let count = |txs: &BTreeMap<H160, BTreeMap<u64, TxPoolTransaction>>| {
+			U256::from(txs.values().map(|txs| txs.len()).sum::<usize>())
+		};
+		TxPoolStatus { pending: count(&self.pending), queued: count(&self.queued) }
+	}
+
+	/// Returns the summaries of the transactions.
+	pub fn inspect(&self) -> TxPoolInspect {
+		let summaries = |txs: &BTreeMap<H160, BTreeMap<u64, TxPoolTransaction>>| {
+			txs.iter()
+				.map(|(from, txs)| {
+					(*from, txs.iter().map(|(nonce, tx)| (*nonce, tx.summary())).collect())
+				})
+				.collect()
+		};
+		TxPoolInspect { pending: summaries(&self.pending), queued: summaries(&self.queued) }
+	}
+}
+
+/// The number of transactions of the transaction pool.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct TxPoolStatus {
+	/// The number of transactions that are ready for inclusion.
+	pub pending: U256,
+	/// The number of transactions that are not ready for inclusion yet.
+	pub queued: U256,
+}
+
+#[test]
+fn txpool_content_works() {
+	use crate::evm::{TransactionLegacySigned, TransactionLegacyUnsigned};
+
+	let alice = H160::from([1u8; 20]);
+	let bob = H160::from([2u8; 20]);
+	let tx = |from, nonce: u64, to| TxPoolTransaction {
+		from,
+		hash: H256::from_low_u64_be(nonce),
+		transaction_signed: TransactionLegacySigned {
+			transaction_legacy_unsigned: TransactionLegacyUnsigned {
+				nonce: nonce.into(),
+				to,
+				value: U256::from(1),
+				gas: U256::from(21_000),
+				gas_price: U256::from(1_000),
+				..Default::default()
+			},
+			..Default::default()
+		}
+		.into(),
+	};
+
+	let content = TxPoolContent::new(
+		[tx(alice, 0, Some(bob)), tx(alice, 1, None)],
+		[tx(bob, 3, Some(alice))],
+	);
+	assert_eq!(content.pending[&alice].keys().copied().collect::<alloc::vec::Vec<_>>(), [0, 1]);
+	assert_eq!(content.queued[&bob][&3].hash, H256::from_low_u64_be(3));
+	assert_eq!(content.status(), TxPoolStatus { pending: U256::from(2), queued: U256::from(1) });
+
+	let inspect = content.inspect();
+	assert_eq!(inspect.pending[&alice][&0], format!("{bob:?}: 1 wei + 21000 gas × 1000 wei"));
+	assert_eq!(inspect.pending[&alice][&1], "contract creation: 1 wei + 21000 gas × 1000 wei");
+
+	let json = serde_json::to_value(&content).unwrap();
+	assert_eq!(json["queued"][format!("{bob:?}")]["3"]["from"], format!("{bob:?}"));
+}
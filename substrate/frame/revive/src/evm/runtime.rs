@@ -20,6 +20,7 @@ use crate::{
 		api::{GenericTransaction, TransactionSigned},
 		GasEncoder,
 	},
+	vm::BytecodeType,
 	AccountIdOf, AddressMapper, BalanceOf, Config, MomentOf, OnChargeTransactionBalanceOf, Pallet,
 	LOG_TARGET, RUNTIME_PALLETS_ADDR,
 };
@@ -31,7 +32,7 @@ use frame_support::{
 	MAX_EXTRINSIC_DEPTH,
 };
 use scale_info::{StaticTypeInfo, TypeInfo};
-use sp_core::{Get, H256, U256};
+use sp_core::{Get, H160, H256, U256};
 use sp_runtime::{
 	generic::{self, CheckedExtrinsic, ExtrinsicFormat},
 	traits::{
@@ -264,6 +265,14 @@ pub trait EthExtra {
 		tip: BalanceOf<Self::Config>,
 	) -> Self::Extension;
 
+	/// Recover the address of the account sending the Ethereum transaction.
+	///
+	/// Development runtimes can override it to accept transactions sent on behalf of
+	/// impersonated accounts, see [`TransactionSigned::impersonated_signer`].
+	fn recover_signer(tx: &TransactionSigned) -> Result<H160, ()> {
+		tx.recover_eth_address()
+	}
+
 	/// Convert the unsigned [`crate::Call::eth_transact`] into a [`CheckedExtrinsic`].
 	/// and ensure that the fees from the Ethereum transaction correspond to the fees computed from
 	/// the encoded_len, the injected gas_limit and storage_deposit_limit.
@@ -294,14 +303,24 @@ pub trait EthExtra {
 			InvalidTransaction::Call
 		})?;
 
-		let signer_addr = tx.recover_eth_address().map_err(|err| {
+		let signer_addr = Self::recover_signer(&tx).map_err(|err| {
 			log::debug!(target: LOG_TARGET, "Failed to recover signer: {err:?}");
 			InvalidTransaction::BadProof
 		})?;
 
 		let signer = <Self::Config as Config>::AddressMapper::to_fallback_account_id(&signer_addr);
-		let GenericTransaction { nonce, chain_id, to, value, input, gas, gas_price, .. } =
-			GenericTransaction::from_signed(tx, crate::GAS_PRICE.into(), None);
+		let GenericTransaction {
+			nonce,
+			chain_id,
+			to,
+			value,
+			input,
+			gas,
+			gas_price,
+			access_list,
+			authorization_list,
+			..
+		} = GenericTransaction::from_signed(tx, crate::GAS_PRICE.into(), None);
 
 		let Some(gas) = gas else {
 			log::debug!(target: LOG_TARGET, "No gas provided");
@@ -315,6 +334,14 @@ pub trait EthExtra {
 
 		let value = value.unwrap_or_default();
 		let data = input.to_vec();
+		let access_list = access_list.unwrap_or_default();
+
+		// Set-code transactions must carry at least one authorization, see EIP-7702.
+		if authorization_list.as_ref().is_some_and(|list| list.is_empty()) {
+			log::debug!(target: LOG_TARGET, "Empty authorization list");
+			return Err(InvalidTransaction::Call);
+		}
+		let authorization_list = authorization_list.unwrap_or_default();
 
 		let (gas_limit, storage_deposit_limit) =
 			<Self::Config as Config>::EthGasEncoder::decode(gas).ok_or_else(|| {
@@ -338,6 +365,11 @@ pub trait EthExtra {
 					return Err(InvalidTransaction::Call)
 				}
 
+				if !authorization_list.is_empty() {
+					log::debug!(target: LOG_TARGET, "Runtime pallets address cannot be called with authorizations");
+					return Err(InvalidTransaction::Call)
+				}
+
 				call
 			} else {
 				crate::Call::eth_call::<Self::Config> {
@@ -346,14 +378,22 @@ pub trait EthExtra {
 					gas_limit,
 					storage_deposit_limit,
 					data,
+					access_list,
+					authorization_list,
 				}
 				.into()
 			}
 		} else {
-			let blob = match polkavm::ProgramBlob::blob_length(&data) {
-				Some(blob_len) =>
-					blob_len.try_into().ok().and_then(|blob_len| (data.split_at_checked(blob_len))),
-				_ => None,
+			// EVM init code already contains the constructor arguments.
+			let blob = match BytecodeType::of(&data) {
+				BytecodeType::Pvm => match polkavm::ProgramBlob::blob_length(&data) {
+					Some(blob_len) => blob_len
+						.try_into()
+						.ok()
+						.and_then(|blob_len| (data.split_at_checked(blob_len))),
+					_ => None,
+				},
+				BytecodeType::Evm => Some((&data[..], &[][..])),
 			};
 
 			let Some((code, data)) = blob else {
@@ -367,6 +407,7 @@ pub trait EthExtra {
 				storage_deposit_limit,
 				code: code.to_vec(),
 				data: data.to_vec(),
+				access_list,
 			}
 			.into()
 		};
@@ -601,7 +642,9 @@ mod test {
 				value: tx.value.unwrap_or_default().as_u64().into(),
 				data: tx.input.to_vec(),
 				gas_limit,
-				storage_deposit_limit
+				storage_deposit_limit,
+				access_list: vec![],
+				authorization_list: vec![],
 			}
 			.into()
 		);
@@ -623,7 +666,8 @@ mod test {
 				code,
 				data,
 				gas_limit,
-				storage_deposit_limit
+				storage_deposit_limit,
+				access_list: vec![],
 			}
 			.into()
 		);
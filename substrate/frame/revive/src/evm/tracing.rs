@@ -15,18 +15,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 use crate::{
-	evm::{CallTrace, Trace},
+	evm::{CallTrace, GasProfilerTrace, StructLoggerTrace, Trace},
 	tracing::Tracing,
 	BalanceOf, Bounded, Config, MomentOf, Weight,
 };
 use sp_core::{H256, U256};
 
+mod access_list_tracing;
+pub use access_list_tracing::*;
+
 mod call_tracing;
 pub use call_tracing::*;
 
+mod gas_profiler;
+pub use gas_profiler::*;
+
+mod log_tracing;
+pub use log_tracing::*;
+
 mod prestate_tracing;
 pub use prestate_tracing::*;
 
+mod struct_logger;
+pub use struct_logger::*;
+
 /// A composite tracer.
 #[derive(derive_more::From, Debug)]
 pub enum Tracer<T> {
@@ -34,6 +46,10 @@ pub enum Tracer<T> {
 	CallTracer(CallTracer<U256, fn(Weight) -> U256>),
 	/// A tracer that traces the prestate.
 	PrestateTracer(PrestateTracer<T>),
+	/// A tracer that logs every executed instruction.
+	StructLogger(StructLogger<U256, fn(Weight) -> U256>),
+	/// A tracer that breaks down the gas charged by host function, contract and storage key.
+	GasProfiler(GasProfiler<U256, fn(Weight) -> U256>),
 }
 
 impl<T: Config> Tracer<T>
@@ -48,6 +64,8 @@ where
 		match self {
 			Tracer::CallTracer(_) => CallTrace::default().into(),
 			Tracer::PrestateTracer(tracer) => tracer.empty_trace().into(),
+			Tracer::StructLogger(_) => StructLoggerTrace::default().into(),
+			Tracer::GasProfiler(_) => GasProfilerTrace::default().into(),
 		}
 	}
 
@@ -56,6 +74,8 @@ where
 		match self {
 			Tracer::CallTracer(inner) => inner as &mut dyn Tracing,
 			Tracer::PrestateTracer(inner) => inner as &mut dyn Tracing,
+			Tracer::StructLogger(inner) => inner as &mut dyn Tracing,
+			Tracer::GasProfiler(inner) => inner as &mut dyn Tracing,
 		}
 	}
 
@@ -64,6 +84,8 @@ where
 		match self {
 			Tracer::CallTracer(inner) => inner.collect_trace().map(Trace::Call),
 			Tracer::PrestateTracer(inner) => Some(inner.collect_trace().into()),
+			Tracer::StructLogger(inner) => inner.collect_trace().map(Trace::StructLogger),
+			Tracer::GasProfiler(inner) => inner.collect_trace().map(Trace::GasProfiler),
 		}
 	}
 }
@@ -0,0 +1,64 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{evm::AccessListEntry, tracing::Tracing, Key};
+use alloc::{
+	collections::{BTreeMap, BTreeSet},
+	vec::Vec,
+};
+use sp_core::{H160, H256};
+
+/// A tracer that records the accounts and storage slots accessed by a transaction.
+///
+/// The sender and the called (or created) contract are always warm and hence only recorded along
+/// with their accessed storage slots.
+/// Storage slots are only recorded for fixed sized keys, as variable sized keys can't be part of
+/// an access list.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct AccessListTracer {
+	/// The accessed accounts and their accessed storage slots.
+	accessed: BTreeMap<H160, BTreeSet<H256>>,
+}
+
+impl AccessListTracer {
+	/// Create a new [`AccessListTracer`] instance.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Collect the access list of the traced transaction.
+	pub fn collect_access_list(&mut self) -> Vec<AccessListEntry> {
+		core::mem::take(&mut self.accessed)
+			.into_iter()
+			.map(|(address, storage_keys)| AccessListEntry {
+				address,
+				storage_keys: storage_keys.into_iter().collect(),
+			})
+			.collect()
+	}
+}
+
+impl Tracing for AccessListTracer {
+	fn account_accessed(&mut self, addr: &H160) {
+		self.accessed.entry(*addr).or_default();
+	}
+
+	fn storage_accessed(&mut self, addr: &H160, key: &Key) {
+		if let Key::Fix(slot) = key {
+			self.accessed.entry(*addr).or_default().insert(H256(*slot));
+		}
+	}
+}
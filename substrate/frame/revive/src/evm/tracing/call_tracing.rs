@@ -129,6 +129,13 @@ impl<Gas: Default, GasMapper: Fn(Weight) -> Gas> Tracing for CallTracer<Gas, Gas
 		self.traces[current_index].logs.push(log);
 	}
 
+	fn debug_message(&mut self, message: &str) {
+		let current_index = self.current_stack.last().unwrap();
+		if let Some(trace) = self.traces.get_mut(*current_index) {
+			trace.debug_messages.push(message.to_string());
+		}
+	}
+
 	fn exit_child_span(&mut self, output: &ExecReturnValue, gas_used: Weight) {
 		self.code_with_salt = None;
 
@@ -0,0 +1,237 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{
+	evm::{Bytes, GasProfileEntry, GasProfilerConfig, GasProfilerTrace},
+	primitives::ExecReturnValue,
+	tracing::Tracing,
+	DispatchError, Key, Weight,
+};
+use alloc::{collections::BTreeMap, format, string::String, vec::Vec};
+use core::fmt::Display;
+use sp_core::{H160, U256};
+use sp_runtime::{traits::Zero, Saturating};
+
+/// The costs of the host functions accessing a storage slot of the current contract.
+const STORAGE_COSTS: [&str; 5] =
+	["SetStorage", "ClearStorage", "ContainsStorage", "GetStorage", "TakeStorage"];
+
+/// The weight charged for an entry of the profile.
+#[derive(Default, Debug, Clone, PartialEq)]
+struct Entry {
+	/// The number of times weight was charged.
+	count: u32,
+	/// The weight charged, net of refunds.
+	weight: Weight,
+}
+
+impl Entry {
+	fn charge(&mut self, weight: Weight) {
+		self.count.saturating_inc();
+		self.weight.saturating_accrue(weight);
+	}
+
+	fn refund(&mut self, weight: Weight) {
+		self.weight.saturating_reduce(weight);
+	}
+}
+
+/// A call frame tracked by the [`GasProfiler`].
+#[derive(Debug, Clone, PartialEq)]
+struct Frame {
+	/// The address of the executed contract.
+	address: H160,
+	/// The addresses of all frames up to this one, separated by `;`.
+	path: String,
+}
+
+/// A tracer that aggregates the gas charged by host function, contract and storage key.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct GasProfiler<Gas, GasMapper> {
+	/// Map Weight to Gas equivalent.
+	gas_mapper: GasMapper,
+	/// The tracer configuration.
+	config: GasProfilerConfig,
+	/// The weight charged during the whole execution.
+	total: Weight,
+	/// The weight charged per cost.
+	costs: BTreeMap<&'static str, Entry>,
+	/// The weight charged per contract, excluding its sub-calls.
+	contracts: BTreeMap<H160, Entry>,
+	/// The weight charged per contract and storage key.
+	storage: BTreeMap<H160, BTreeMap<Bytes, Entry>>,
+	/// The weight charged per call stack and cost.
+	stacks: BTreeMap<String, BTreeMap<&'static str, Entry>>,
+	/// The stack of active call frames.
+	frames: Vec<Frame>,
+	/// The weight of the last storage cost, until the accessed slot is known.
+	pending_storage: Option<Weight>,
+	/// The storage slot the last storage cost was attributed to.
+	storage_slot: Option<(H160, Bytes)>,
+	_phantom: core::marker::PhantomData<Gas>,
+}
+
+impl<Gas: Default + Display, GasMapper: Fn(Weight) -> Gas> GasProfiler<Gas, GasMapper> {
+	/// Create a new [`GasProfiler`] instance.
+	pub fn new(config: GasProfilerConfig, gas_mapper: GasMapper) -> Self {
+		Self {
+			gas_mapper,
+			config,
+			total: Weight::zero(),
+			costs: BTreeMap::new(),
+			contracts: BTreeMap::new(),
+			storage: BTreeMap::new(),
+			stacks: BTreeMap::new(),
+			frames: Vec::new(),
+			pending_storage: None,
+			storage_slot: None,
+			_phantom: Default::default(),
+		}
+	}
+
+	/// Collect the trace and return it.
+	///
+	/// Returns `None` if no gas was charged.
+	pub fn collect_trace(&mut self) -> Option<GasProfilerTrace<Gas>> {
+		if self.costs.is_empty() {
+			return None
+		}
+
+		let to_entry = |entry: Entry| GasProfileEntry {
+			count: entry.count,
+			gas: (self.gas_mapper)(entry.weight),
+		};
+
+		let mut stacks = Vec::new();
+		for (path, costs) in core::mem::take(&mut self.stacks) {
+			for (cost, entry) in costs.into_iter().filter(|(_, entry)| !entry.weight.is_zero()) {
+				let gas = (self.gas_mapper)(entry.weight);
+				if path.is_empty() {
+					stacks.push(format!("{cost} {gas}"));
+				} else {
+					stacks.push(format!("{path};{cost} {gas}"));
+				}
+			}
+		}
+
+		let trace = GasProfilerTrace {
+			gas: (self.gas_mapper)(core::mem::take(&mut self.total)),
+			costs: core::mem::take(&mut self.costs)
+				.into_iter()
+				.map(|(cost, entry)| (cost.into(), to_entry(entry)))
+				.collect(),
+			contracts: core::mem::take(&mut self.contracts)
+				.into_iter()
+				.map(|(address, entry)| (address, to_entry(entry)))
+				.collect(),
+			storage: core::mem::take(&mut self.storage)
+				.into_iter()
+				.map(|(address, slots)| {
+					(
+						address,
+						slots.into_iter().map(|(key, entry)| (key, to_entry(entry))).collect(),
+					)
+				})
+				.collect(),
+			stacks,
+		};
+
+		self.frames.clear();
+		self.pending_storage = None;
+		self.storage_slot = None;
+		Some(trace)
+	}
+
+	/// Apply `f` to the entries of `cost` in the current frame.
+	fn record(&mut self, cost: &'static str, f: impl Fn(&mut Entry)) {
+		f(self.costs.entry(cost).or_default());
+
+		if let Some(frame) = self.frames.last() {
+			f(self.contracts.entry(frame.address).or_default());
+		}
+
+		if !self.config.disable_stacks {
+			let path = self.frames.last().map(|frame| frame.path.as_str()).unwrap_or_default();
+			match self.stacks.get_mut(path) {
+				Some(costs) => f(costs.entry(cost).or_default()),
+				None => f(self.stacks.entry(path.into()).or_default().entry(cost).or_default()),
+			}
+		}
+	}
+}
+
+impl<Gas: Default + Display, GasMapper: Fn(Weight) -> Gas> Tracing for GasProfiler<Gas, GasMapper> {
+	fn enter_child_span(
+		&mut self,
+		_from: H160,
+		to: H160,
+		_is_delegate_call: bool,
+		_is_read_only: bool,
+		_value: U256,
+		_input: &[u8],
+		_gas: Weight,
+	) {
+		let path = match self.frames.last() {
+			Some(parent) => format!("{};{to:?}", parent.path),
+			None => format!("{to:?}"),
+		};
+		self.frames.push(Frame { address: to, path });
+	}
+
+	fn gas_charged(&mut self, cost: &'static str, weight: Weight) {
+		self.total.saturating_accrue(weight);
+		self.record(cost, |entry| entry.charge(weight));
+
+		self.storage_slot = None;
+		self.pending_storage =
+			(!self.config.disable_storage && STORAGE_COSTS.contains(&cost)).then_some(weight);
+	}
+
+	fn gas_refunded(&mut self, cost: &'static str, weight: Weight) {
+		self.total.saturating_reduce(weight);
+		self.record(cost, |entry| entry.refund(weight));
+
+		if !STORAGE_COSTS.contains(&cost) {
+			return
+		}
+		if let Some((address, key)) = &self.storage_slot {
+			if let Some(entry) = self.storage.get_mut(address).and_then(|slots| slots.get_mut(key))
+			{
+				entry.refund(weight);
+			}
+		}
+	}
+
+	fn storage_touched(&mut self, address: &H160, key: &Key) {
+		let Some(weight) = self.pending_storage.take() else { return };
+		let key: Bytes = key.unhashed().to_vec().into();
+		self.storage
+			.entry(*address)
+			.or_default()
+			.entry(key.clone())
+			.or_default()
+			.charge(weight);
+		self.storage_slot = Some((*address, key));
+	}
+
+	fn exit_child_span(&mut self, _output: &ExecReturnValue, _gas_used: Weight) {
+		self.frames.pop();
+	}
+
+	fn exit_child_span_with_error(&mut self, _error: DispatchError, _gas_used: Weight) {
+		self.frames.pop();
+	}
+}
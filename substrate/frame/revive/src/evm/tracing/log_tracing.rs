@@ -0,0 +1,80 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{evm::Log, primitives::ExecReturnValue, tracing::Tracing, DispatchError, Weight};
+use alloc::vec::Vec;
+use sp_core::{H160, H256, U256};
+
+/// A tracer that records the logs emitted by a transaction.
+///
+/// The logs emitted by a call frame that reverts or fails are discarded. Only the address, topics
+/// and data of the returned logs are set.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct LogTracer {
+	/// The logs emitted so far.
+	logs: Vec<Log>,
+	/// The number of logs emitted before each active call frame was entered.
+	checkpoints: Vec<usize>,
+}
+
+impl LogTracer {
+	/// Create a new [`LogTracer`] instance.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Collect the logs of the traced transaction.
+	pub fn collect_logs(&mut self) -> Vec<Log> {
+		self.checkpoints.clear();
+		core::mem::take(&mut self.logs)
+	}
+}
+
+impl Tracing for LogTracer {
+	fn enter_child_span(
+		&mut self,
+		_from: H160,
+		_to: H160,
+		_is_delegate_call: bool,
+		_is_read_only: bool,
+		_value: U256,
+		_input: &[u8],
+		_gas: Weight,
+	) {
+		self.checkpoints.push(self.logs.len());
+	}
+
+	fn log_event(&mut self, address: H160, topics: &[H256], data: &[u8]) {
+		self.logs.push(Log {
+			address,
+			topics: topics.to_vec(),
+			data: Some(data.to_vec().into()),
+			..Default::default()
+		});
+	}
+
+	fn exit_child_span(&mut self, output: &ExecReturnValue, _gas_left: Weight) {
+		let checkpoint = self.checkpoints.pop().unwrap_or_default();
+		if output.did_revert() {
+			self.logs.truncate(checkpoint);
+		}
+	}
+
+	fn exit_child_span_with_error(&mut self, _error: DispatchError, _gas_left: Weight) {
+		let checkpoint = self.checkpoints.pop().unwrap_or_default();
+		self.logs.truncate(checkpoint);
+	}
+}
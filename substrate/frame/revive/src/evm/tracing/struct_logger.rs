@@ -0,0 +1,216 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{
+	evm::{Bytes, StructLog, StructLoggerConfig, StructLoggerTrace},
+	primitives::ExecReturnValue,
+	tracing::Tracing,
+	DispatchError, Key, Weight,
+};
+use alloc::{collections::BTreeMap, format, string::ToString, vec::Vec};
+use sp_core::{H160, U256};
+use sp_runtime::traits::Zero;
+
+/// The state of a call frame tracked by the [`StructLogger`].
+#[derive(Default, Debug, Clone, PartialEq)]
+struct Frame {
+	/// The gas available to the frame when it was entered.
+	gas: Weight,
+	/// Index of the last step recorded in this frame, with the gas left at that step.
+	last_step: Option<(usize, Weight)>,
+	/// The storage accessed by this frame so far.
+	storage: BTreeMap<Bytes, Bytes>,
+	/// The output of the last sub-call made by this frame.
+	return_data: Vec<u8>,
+}
+
+/// A tracer that records every executed instruction, in the format of geth's "structLogger".
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct StructLogger<Gas, GasMapper> {
+	/// Map Weight to Gas equivalent.
+	gas_mapper: GasMapper,
+	/// The tracer configuration.
+	config: StructLoggerConfig,
+	/// The recorded steps, with their gas values expressed in `Weight`.
+	steps: Vec<(StructLog<Gas>, Weight, Weight)>,
+	/// The stack of active call frames.
+	frames: Vec<Frame>,
+	/// The gas used by the top-level call.
+	gas_used: Weight,
+	/// Whether the top-level call failed.
+	failed: bool,
+	/// The output of the top-level call.
+	return_value: Vec<u8>,
+}
+
+impl<Gas: Default, GasMapper: Fn(Weight) -> Gas> StructLogger<Gas, GasMapper> {
+	/// Create a new [`StructLogger`] instance.
+	pub fn new(config: StructLoggerConfig, gas_mapper: GasMapper) -> Self {
+		Self {
+			gas_mapper,
+			config,
+			steps: Vec::new(),
+			frames: Vec::new(),
+			gas_used: Weight::zero(),
+			failed: false,
+			return_value: Vec::new(),
+		}
+	}
+
+	/// Collect the trace and return it.
+	///
+	/// Returns `None` if no call was traced.
+	pub fn collect_trace(&mut self) -> Option<StructLoggerTrace<Gas>> {
+		if self.steps.is_empty() && self.gas_used.is_zero() && self.return_value.is_empty() {
+			return None
+		}
+
+		let struct_logs = core::mem::take(&mut self.steps)
+			.into_iter()
+			.map(|(mut log, gas, gas_cost)| {
+				log.gas = (self.gas_mapper)(gas);
+				log.gas_cost = (self.gas_mapper)(gas_cost);
+				log
+			})
+			.collect();
+
+		self.frames.clear();
+		Some(StructLoggerTrace {
+			gas: (self.gas_mapper)(core::mem::take(&mut self.gas_used)),
+			failed: core::mem::take(&mut self.failed),
+			return_value: core::mem::take(&mut self.return_value).into(),
+			struct_logs,
+		})
+	}
+
+	/// Whether the configured step limit has been reached.
+	fn limit_reached(&self) -> bool {
+		self.config.limit != 0 && self.steps.len() as u64 >= self.config.limit
+	}
+
+	/// Record the storage of the current frame on its last step.
+	fn record_storage(&mut self, key: &Key, value: Option<&[u8]>) {
+		if self.config.disable_storage {
+			return
+		}
+
+		let Some(frame) = self.frames.last_mut() else { return };
+		frame
+			.storage
+			.insert(key.unhashed().to_vec().into(), value.unwrap_or_default().to_vec().into());
+
+		if let Some((index, _)) = frame.last_step {
+			self.steps[index].0.storage = Some(frame.storage.clone());
+		}
+	}
+
+	/// Leave the current frame, recording the output in the parent frame.
+	///
+	/// The cost of the last step of the frame is the gas used between that step and the exit.
+	fn exit_frame(&mut self, output: &[u8], gas_used: Weight, failed: bool) {
+		if let Some(Frame { gas, last_step: Some((index, gas_left)), .. }) = self.frames.pop() {
+			self.steps[index].2 = gas_left.saturating_sub(gas.saturating_sub(gas_used));
+		}
+		match self.frames.last_mut() {
+			Some(parent) => parent.return_data = output.to_vec(),
+			None => {
+				self.gas_used = gas_used;
+				self.failed = failed;
+				self.return_value = output.to_vec();
+			},
+		}
+	}
+}
+
+impl<Gas: Default, GasMapper: Fn(Weight) -> Gas> Tracing for StructLogger<Gas, GasMapper> {
+	fn is_execution_tracing_enabled(&self) -> bool {
+		true
+	}
+
+	fn enter_child_span(
+		&mut self,
+		_from: H160,
+		_to: H160,
+		_is_delegate_call: bool,
+		_is_read_only: bool,
+		_value: U256,
+		_input: &[u8],
+		gas: Weight,
+	) {
+		self.frames.push(Frame { gas, ..Default::default() });
+	}
+
+	fn enter_opcode(
+		&mut self,
+		pc: u64,
+		opcode: &str,
+		gas_left: Weight,
+		registers: &[u64],
+		memory: &dyn Fn() -> Vec<u8>,
+	) {
+		if self.limit_reached() {
+			return
+		}
+
+		let depth = self.frames.len() as u32;
+		let index = self.steps.len();
+		let Some(frame) = self.frames.last_mut() else { return };
+
+		// The cost of the previous step is only known once the next one starts.
+		if let Some((last_index, last_gas_left)) = frame.last_step.replace((index, gas_left)) {
+			self.steps[last_index].2 = last_gas_left.saturating_sub(gas_left);
+		}
+
+		let log = StructLog {
+			pc,
+			op: opcode.to_uppercase(),
+			depth,
+			stack: (!self.config.disable_stack)
+				.then(|| registers.iter().map(|reg| U256::from(*reg)).collect()),
+			memory: self
+				.config
+				.enable_memory
+				.then(|| memory().chunks(32).map(|word| word.to_vec().into()).collect()),
+			return_data: (self.config.enable_return_data && !frame.return_data.is_empty())
+				.then(|| frame.return_data.clone().into()),
+			..Default::default()
+		};
+		self.steps.push((log, gas_left, Weight::zero()));
+	}
+
+	fn storage_read(&mut self, key: &Key, value: Option<&[u8]>) {
+		self.record_storage(key, value);
+	}
+
+	fn storage_write(&mut self, key: &Key, _old_value: Option<Vec<u8>>, new_value: Option<&[u8]>) {
+		self.record_storage(key, new_value);
+	}
+
+	fn exit_child_span(&mut self, output: &ExecReturnValue, gas_used: Weight) {
+		self.exit_frame(&output.data, gas_used, output.did_revert());
+	}
+
+	fn exit_child_span_with_error(&mut self, error: DispatchError, gas_used: Weight) {
+		if let Some((index, _)) = self.frames.last().and_then(|frame| frame.last_step) {
+			self.steps[index].0.error = Some(match error {
+				DispatchError::Module(sp_runtime::ModuleError { message, .. }) =>
+					message.unwrap_or_default().to_string(),
+				_ => format!("{:?}", error),
+			});
+		}
+		self.exit_frame(&[], gas_used, true);
+	}
+}
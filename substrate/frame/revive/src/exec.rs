@@ -16,29 +16,35 @@
 // limitations under the License.
 
 use crate::{
+	access_list::{self, AccessedState},
 	address::{self, AddressMapper},
+	debug,
 	gas::GasMeter,
 	limits,
 	precompiles::{All as AllPrecompiles, Instance as PrecompileInstance, Precompiles},
 	primitives::{BumpNonce, ExecReturnValue, StorageDeposit},
 	runtime_decl_for_revive_api::{Decode, Encode, RuntimeDebugNoBound, TypeInfo},
-	storage::{self, meter::Diff, AccountIdOrAddress, WriteOutcome},
+	simulate,
+	storage::{self, expiry::ContractActivity, meter::Diff, AccountIdOrAddress, WriteOutcome},
 	tracing::if_tracing,
 	transient_storage::TransientStorage,
-	AccountInfo, AccountInfoOf, BalanceOf, BalanceWithDust, CodeInfo, CodeInfoOf, Config,
-	ContractInfo, Error, Event, ImmutableData, ImmutableDataOf, Pallet as Contracts, RuntimeCosts,
-	LOG_TARGET,
+	vm::BytecodeType,
+	AccountInfo, AccountInfoOf, BalanceOf, BalanceWithDust, Code, CodeInfo, CodeInfoOf, Config,
+	ContractActivityOf, ContractBlob, ContractExpiryOf, ContractInfo, Error, Event, ImmutableData,
+	ImmutableDataOf, Pallet as Contracts, PendingCodeUpgrade, PendingCodeUpgrades, RuntimeCosts,
+	Tombstones, LOG_TARGET,
 };
-use alloc::vec::Vec;
+use alloc::{collections::btree_set::BTreeSet, vec::Vec};
 use core::{fmt::Debug, marker::PhantomData, mem};
 use frame_support::{
 	crypto::ecdsa::ECDSAExt,
 	dispatch::DispatchResult,
+	ensure,
 	storage::{with_transaction, TransactionOutcome},
 	traits::{
 		fungible::{Inspect, Mutate},
 		tokens::{Fortitude, Precision, Preservation},
-		Time,
+		EnsureOrigin, Time,
 	},
 	weights::Weight,
 	Blake2_128Concat, BoundedVec, StorageHasher,
@@ -170,6 +176,15 @@ impl<T: Config> Origin<T> {
 		}
 	}
 
+	/// Returns the account [`Config::UploadOrigin`] allows to upload code for this origin.
+	fn upload_account(&self) -> Result<T::AccountId, DispatchError> {
+		let origin: OriginFor<T> = match self {
+			Self::Root => RawOrigin::Root.into(),
+			Self::Signed(account_id) => RawOrigin::Signed(account_id.clone()).into(),
+		};
+		Ok(T::UploadOrigin::ensure_origin(origin)?)
+	}
+
 	/// Make sure that this origin is mapped.
 	///
 	/// We require an origin to be mapped in order to be used in a `Stack`. Otherwise
@@ -209,8 +224,17 @@ pub trait Ext: PrecompileWithInfoExt {
 	fn own_code_hash(&mut self) -> &H256;
 
 	/// Sets new code hash and immutable data for an existing contract.
+	///
+	/// Requires a matured announcement of `hash` if [`Config::CodeUpgradeDelay`] is not zero.
 	fn set_code_hash(&mut self, hash: H256) -> DispatchResult;
 
+	/// Announce that the executing contract will switch to the code at `hash`.
+	///
+	/// Replaces any previously announced upgrade. Announcing the current code hash of the
+	/// contract cancels the pending upgrade. The announced code is referenced until the upgrade
+	/// is enacted or cancelled.
+	fn announce_code_upgrade(&mut self, hash: H256) -> DispatchResult;
+
 	/// Get the length of the immutable data.
 	///
 	/// This query is free as it does not need to load the immutable data from storage.
@@ -253,6 +277,11 @@ pub trait PrecompileWithInfoExt: PrecompileExt {
 		take_old: bool,
 	) -> Result<WriteOutcome, DispatchError>;
 
+	/// Mark the storage entry of the executing account by the given `key` as accessed.
+	///
+	/// Returns `true` if the entry was already accessed in the current transaction.
+	fn access_storage(&mut self, key: &Key) -> bool;
+
 	/// Charges `diff` from the meter.
 	fn charge_storage(&mut self, diff: &Diff);
 
@@ -261,11 +290,13 @@ pub trait PrecompileWithInfoExt: PrecompileExt {
 	/// Returns the original code size of the called contract.
 	/// The newly created account will be associated with `code`. `value` specifies the amount of
 	/// value transferred from the caller to the newly created account.
+	///
+	/// `code` is either the hash of a stored PolkaVM blob or EVM init code.
 	fn instantiate(
 		&mut self,
 		gas_limit: Weight,
 		deposit_limit: U256,
-		code: H256,
+		code: Code,
 		value: U256,
 		input_data: Vec<u8>,
 		salt: Option<&[u8; 32]>,
@@ -358,6 +389,11 @@ pub trait PrecompileExt: sealing::Sealed {
 	/// The `value_transferred` is already added.
 	fn balance_of(&self, address: &H160) -> U256;
 
+	/// Mark the account at `address` as accessed.
+	///
+	/// Returns `true` if the account was already accessed in the current transaction.
+	fn access_account(&mut self, address: &H160) -> bool;
+
 	/// Returns the value transferred along with this call.
 	fn value_transferred(&self) -> U256;
 
@@ -403,6 +439,16 @@ pub trait PrecompileExt: sealing::Sealed {
 	/// Returns Ethereum address from the ECDSA compressed public key.
 	fn ecdsa_to_eth_address(&self, pk: &[u8; 33]) -> Result<[u8; 20], ()>;
 
+	/// Returns `true` if debug messages are collected or traced.
+	///
+	/// This is always `false` when executing on-chain.
+	fn debug_buffer_enabled(&self) -> bool;
+
+	/// Append a debug message to the debug buffer and report it to the tracer.
+	///
+	/// Returns `true` if the message was collected or traced, see [`Self::debug_buffer_enabled`].
+	fn append_debug_buffer(&mut self, msg: &str) -> bool;
+
 	/// Tests sometimes need to modify and inspect the contract info directly.
 	#[cfg(any(test, feature = "runtime-benchmarks"))]
 	fn contract_info(&mut self) -> &mut ContractInfo<Self::T>;
@@ -453,6 +499,11 @@ pub trait Executable<T: Config>: Sized {
 	/// Charges size base load weight from the gas meter.
 	fn from_storage(code_hash: H256, gas_meter: &mut GasMeter<T>) -> Result<Self, DispatchError>;
 
+	/// Create an executable from EVM init code.
+	///
+	/// The init code is not stored: it is only run once in order to create a contract.
+	fn from_evm_init_code(code: Vec<u8>, owner: AccountIdOf<T>) -> Result<Self, DispatchError>;
+
 	/// Execute the specified exported function and return the result.
 	///
 	/// When the specified function is `Constructor` the executable is stored and its
@@ -478,9 +529,14 @@ pub trait Executable<T: Config>: Sized {
 	/// The code hash of the executable.
 	fn code_hash(&self) -> &H256;
 
+	/// The type of bytecode of the executable.
+	fn bytecode_type(&self) -> BytecodeType {
+		self.code_info().code_type()
+	}
+
 	/// Returns true if the executable is a PVM blob.
 	fn is_pvm(&self) -> bool {
-		self.code().starts_with(&polkavm_common::program::BLOB_MAGIC)
+		self.bytecode_type() == BytecodeType::Pvm
 	}
 }
 
@@ -514,9 +570,16 @@ pub struct Stack<'a, T: Config, E> {
 	first_frame: Frame<T>,
 	/// Transient storage used to store data, which is kept for the duration of a transaction.
 	transient_storage: TransientStorage<T>,
+	/// The accounts and storage slots accessed during the transaction.
+	accessed: AccessedState,
 	/// Whether or not actual transfer of funds should be performed.
 	/// This is set to `true` exclusively when we simulate a call through eth_transact.
 	skip_transfer: bool,
+	/// The contracts touched during the transaction, see [`crate::Pallet::set_contract_expiry`].
+	///
+	/// `None` if the expiry is disabled. Their activity is recorded once the transaction
+	/// succeeded.
+	touched: Option<BTreeSet<H160>>,
 	/// No executable is held by the struct but influences its behaviour.
 	_phantom: PhantomData<E>,
 }
@@ -545,6 +608,9 @@ struct Frame<T: Config> {
 	/// The delegate call info of the currently executing frame which was spawned by
 	/// `delegate_call`.
 	delegate: Option<DelegateInfo<T>>,
+	/// The address of the contract whose code is executed, if the account of the frame delegates
+	/// its code to it, see [EIP-7702](https://eips.ethereum.org/EIPS/eip-7702).
+	delegation: Option<H160>,
 	/// The output of the last executed call frame.
 	last_frame_output: ExecReturnValue,
 }
@@ -721,9 +787,9 @@ impl<T: Config> CachedContract<T> {
 	/// Load the `contract_info` from storage if necessary.
 	fn load(&mut self, account_id: &T::AccountId) {
 		if let CachedContract::Invalidated = self {
-			if let Some(contract) =
-				AccountInfo::<T>::load_contract(&T::AddressMapper::to_address(account_id))
-			{
+			if let Some((contract, _)) = AccountInfo::<T>::load_contract_or_delegated(
+				&T::AddressMapper::to_address(account_id),
+			) {
 				*self = CachedContract::Cached(contract);
 			}
 		}
@@ -888,6 +954,11 @@ where
 		skip_transfer: bool,
 	) -> Result<Option<(Self, ExecutableOrPrecompile<T, E, Self>)>, ExecError> {
 		origin.ensure_mapped()?;
+		let access_list = access_list::take_access_list();
+		gas_meter.charge(RuntimeCosts::AccessList {
+			accounts: access_list.len() as u32,
+			storage_slots: access_list.iter().map(|entry| entry.storage_keys.len() as u32).sum(),
+		})?;
 		let Some((first_frame, executable)) = Self::new_frame(
 			args,
 			value,
@@ -902,16 +973,25 @@ where
 			return Ok(None);
 		};
 
+		// The origin and the called account are always warm.
+		let mut accessed = AccessedState::from_access_list(&access_list);
+		if let Ok(account_id) = origin.account_id() {
+			accessed.access_account(&T::AddressMapper::to_address(account_id));
+		}
+		accessed.access_account(&T::AddressMapper::to_address(&first_frame.account_id));
+
 		let stack = Self {
 			origin,
 			gas_meter,
 			storage_meter,
-			timestamp: T::Time::now(),
+			timestamp: simulate::timestamp::<T>().unwrap_or_else(T::Time::now),
 			block_number: <frame_system::Pallet<T>>::block_number(),
 			first_frame,
 			frames: Default::default(),
 			transient_storage: TransientStorage::new(limits::TRANSIENT_STORAGE_BYTES),
+			accessed,
 			skip_transfer,
+			touched: <ContractExpiryOf<T>>::exists().then(Default::default),
 			_phantom: Default::default(),
 		};
 
@@ -932,6 +1012,8 @@ where
 		read_only: bool,
 		origin_is_caller: bool,
 	) -> Result<Option<(Frame<T>, ExecutableOrPrecompile<T, E, Self>)>, ExecError> {
+		// The contract whose code is executed when the called account delegates its code to it.
+		let mut delegation = None;
 		let (account_id, contract_info, executable, delegate, entry_point) = match frame_args {
 			FrameArgs::Call { dest, cached_info, delegated_call } => {
 				let address = T::AddressMapper::to_address(&dest);
@@ -940,9 +1022,16 @@ where
 				// which contract info to load is unaffected by the fact if this
 				// is a delegate call or not
 				let mut contract = match (cached_info, &precompile) {
+					(Some(info), None) if delegated_call.is_none() => {
+						delegation = AccountInfo::<T>::delegation_target(&address);
+						CachedContract::Cached(info)
+					},
 					(Some(info), _) => CachedContract::Cached(info),
 					(None, None) =>
-						if let Some(info) = AccountInfo::<T>::load_contract(&address) {
+						if let Some((info, target)) =
+							AccountInfo::<T>::load_contract_or_delegated(&address)
+						{
+							delegation = target.filter(|_| delegated_call.is_none());
 							CachedContract::Cached(info)
 						} else {
 							return Ok(None);
@@ -981,6 +1070,13 @@ where
 							instance: precompile,
 							_phantom: Default::default(),
 						}
+					} else if let Some(target) = delegation {
+						// Delegations are not followed recursively: the target must be a contract.
+						let Some(info) = AccountInfo::<T>::load_contract(&target) else {
+							return Ok(None);
+						};
+						let executable = E::from_storage(info.code_hash, gas_meter)?;
+						ExecutableOrPrecompile::Executable(executable)
 					} else {
 						let executable = E::from_storage(
 							contract
@@ -1030,6 +1126,7 @@ where
 
 		let frame = Frame {
 			delegate,
+			delegation,
 			value_transferred,
 			contract_info,
 			account_id,
@@ -1103,6 +1200,11 @@ where
 		let frame = self.top_frame();
 		let entry_point = frame.entry_point;
 		let is_pvm = executable.is_pvm();
+		// The account the init code was authorised for, which owns the runtime code it returns.
+		let init_code_owner = executable
+			.as_executable()
+			.filter(|_| entry_point == ExportedFunction::Constructor && !is_pvm)
+			.map(|executable| executable.code_info().owner().clone());
 
 		if_tracing(|tracer| {
 			tracer.enter_child_span(
@@ -1129,6 +1231,7 @@ where
 		}
 
 		self.transient_storage.start_transaction();
+		self.accessed.start_transaction();
 
 		let do_transaction = || -> ExecResult {
 			let caller = self.caller();
@@ -1167,6 +1270,18 @@ where
 				// Contracts nonce starts at 1
 				<System<T>>::inc_account_nonce(account_id);
 
+				// The activity is only recorded while the expiry is enabled, the origin pays its
+				// storage deposit and is refunded this deposit if the contract expires.
+				if self.touched.is_some() {
+					let address = T::AddressMapper::to_address(account_id);
+					// A tombstoned contract can only be restored.
+					if <Tombstones<T>>::contains_key(&address) {
+						Err(<Error<T>>::DuplicateContract)?;
+					}
+					ContractActivity::<T>::instantiated(&address, (*origin).clone());
+					frame.nested_storage.charge(&ContractActivity::<T>::deposit_diff());
+				}
+
 				if matches!(bump_nonce, BumpNonce::Yes) {
 					// Needs to be incremented before calling into the code so that it is visible
 					// in case of recursion.
@@ -1183,6 +1298,23 @@ where
 				}
 			}
 
+			// Contracts are touched whenever their code is executed so that they do not expire.
+			// The write is charged for once per contract and deferred to the end of the
+			// transaction.
+			if let Some(touched) = self.touched.as_mut().filter(|_| {
+				entry_point == ExportedFunction::Call && executable.as_executable().is_some()
+			}) {
+				let code_address = match (&frame.delegate, frame.delegation) {
+					(Some(delegate), _) => delegate.callee,
+					(None, Some(target)) => target,
+					(None, None) => T::AddressMapper::to_address(account_id),
+				};
+				if !touched.contains(&code_address) {
+					frame.nested_gas.charge(RuntimeCosts::TouchContract)?;
+					touched.insert(code_address);
+				}
+			}
+
 			// Every non delegate call or instantiate also optionally transfers the balance.
 			// If it is a delegate call, then we've already transferred tokens in the
 			// last non-delegate frame.
@@ -1215,12 +1347,12 @@ where
 				}
 			}
 
-			let code_deposit = executable
+			let mut code_deposit = executable
 				.as_executable()
 				.map(|exec| exec.code_info().deposit())
 				.unwrap_or_default();
 
-			let output = match executable {
+			let mut output = match executable {
 				ExecutableOrPrecompile::Executable(executable) =>
 					executable.execute(self, entry_point, input_data),
 				ExecutableOrPrecompile::Precompile { instance, .. } =>
@@ -1242,6 +1374,27 @@ where
 				return Ok(output);
 			}
 
+			// EVM init code returns the runtime code of the new contract, which becomes its code.
+			if let Some(owner) = init_code_owner {
+				let runtime_code = mem::take(&mut output.data);
+				let (code_hash, upload_deposit) =
+					ContractBlob::<T>::from_evm_runtime_code(runtime_code, owner)
+						.and_then(|mut module| {
+							let upload_deposit = module.store_code(self.skip_transfer)?;
+							code_deposit = module.code_info().deposit();
+							Ok((*module.code_hash(), upload_deposit))
+						})
+						.map_err(|error| ExecError { error, origin: ErrorOrigin::Callee })?;
+				<CodeInfo<T>>::increment_refcount(code_hash)?;
+				let frame = top_frame_mut!(self);
+				frame.contract_info().code_hash = code_hash;
+				// The code deposit is held from the origin directly but counts towards the limit.
+				frame
+					.nested_storage
+					.record_charge(&StorageDeposit::Charge(upload_deposit))
+					.map_err(|error| ExecError { error, origin: ErrorOrigin::Callee })?;
+			}
+
 			let frame = self.top_frame_mut();
 
 			// The deposit we charge for a contract depends on the size of the immutable data.
@@ -1309,8 +1462,10 @@ where
 
 		if success {
 			self.transient_storage.commit_transaction();
+			self.accessed.commit_transaction();
 		} else {
 			self.transient_storage.rollback_transaction();
+			self.accessed.rollback_transaction();
 		}
 
 		log::trace!(target: LOG_TARGET, "frame finished with: {output:?}");
@@ -1393,6 +1548,14 @@ where
 					contract.clone(),
 				);
 			}
+			// The origin pays the storage deposits charged during the transaction.
+			let origin = self.origin.account_id().ok();
+			for address in self.touched.take().unwrap_or_default() {
+				let payer = origin.filter(|_| {
+					self.storage_meter.charges_contract(&T::AddressMapper::to_account_id(&address))
+				});
+				ContractActivity::<T>::touch(&address, payer);
+			}
 		}
 	}
 
@@ -1653,6 +1816,9 @@ where
 		if frame.entry_point == ExportedFunction::Constructor {
 			return Err(Error::<T>::TerminatedInConstructor.into());
 		}
+		if frame.delegation.is_some() {
+			return Err(Error::<T>::TerminatedWhileDelegated.into());
+		}
 		let info = frame.terminate();
 		let beneficiary_account = T::AddressMapper::to_account_id(beneficiary);
 		frame.nested_storage.terminate(&info, beneficiary_account);
@@ -1661,6 +1827,10 @@ where
 		let account_address = T::AddressMapper::to_address(&frame.account_id);
 		AccountInfoOf::<T>::remove(&account_address);
 		ImmutableDataOf::<T>::remove(&account_address);
+		if let Some(pending) = PendingCodeUpgrades::<T>::take(&account_address) {
+			<CodeInfo<T>>::decrement_refcount(pending.code_hash)?;
+		}
+		ContractActivityOf::<T>::remove(&account_address);
 		<CodeInfo<T>>::decrement_refcount(info.code_hash)?;
 
 		Ok(())
@@ -1670,22 +1840,34 @@ where
 		&self.top_frame_mut().contract_info().code_hash
 	}
 
-	/// TODO: This should be changed to run the constructor of the supplied `hash`.
+	/// Replaces the code of the executing contract with the code of `hash`.
 	///
-	/// Because the immutable data is attached to a contract and not a code,
-	/// we need to update the immutable data too.
+	/// If [`Config::CodeUpgradeDelay`] is not zero, `hash` must have been announced with
+	/// `announce_code_upgrade` at least that many blocks ago. The pending upgrade is removed and
+	/// its deposit refunded once enacted. Otherwise the code is replaced right away.
 	///
-	/// Otherwise we open a massive footgun:
-	/// If the immutables changed in the new code, the contract will brick.
-	///
-	/// A possible implementation strategy is to add a flag to `FrameArgs::Instantiate`,
-	/// so that `fn run()` will roll back any changes if this flag is set.
-	///
-	/// After running the constructor, the new immutable data is already stored in
-	/// `self.immutable_data` at the address of the (reverted) contract instantiation.
-	///
-	/// The `set_code_hash` contract API stays disabled until this change is implemented.
+	/// The immutable data is attached to the contract and not to its code, so it is kept as is:
+	/// the new code must expect the same immutables or the contract will brick.
 	fn set_code_hash(&mut self, hash: H256) -> DispatchResult {
+		let address = self.address();
+		match PendingCodeUpgrades::<T>::get(&address) {
+			Some(pending) if pending.code_hash == hash => {
+				ensure!(self.block_number >= pending.enact_at, Error::<T>::CodeUpgradeNotDue);
+				PendingCodeUpgrades::<T>::remove(&address);
+				<CodeInfo<T>>::decrement_refcount(hash)?;
+				top_frame_mut!(self)
+					.nested_storage
+					.charge(&PendingCodeUpgrade::<BlockNumberFor<T>>::refund_diff());
+				Contracts::<T>::deposit_event(Event::CodeUpgradeEnacted {
+					contract: address,
+					code_hash: hash,
+				});
+			},
+			_ if !T::CodeUpgradeDelay::get().is_zero() =>
+				return Err(Error::<T>::CodeUpgradeNotAnnounced.into()),
+			_ => {},
+		}
+
 		let frame = top_frame_mut!(self);
 
 		let info = frame.contract_info();
@@ -1707,6 +1889,46 @@ where
 		Ok(())
 	}
 
+	fn announce_code_upgrade(&mut self, hash: H256) -> DispatchResult {
+		let address = self.address();
+		let enact_at = self.block_number.saturating_add(T::CodeUpgradeDelay::get());
+		let frame = top_frame_mut!(self);
+
+		// The announced code is referenced until the upgrade is enacted or cancelled, so that it
+		// cannot be removed in the meantime.
+		let previous = PendingCodeUpgrades::<T>::take(&address);
+		if let Some(previous) = &previous {
+			<CodeInfo<T>>::decrement_refcount(previous.code_hash)?;
+		}
+
+		if hash == frame.contract_info().code_hash {
+			if previous.is_some() {
+				frame
+					.nested_storage
+					.charge(&PendingCodeUpgrade::<BlockNumberFor<T>>::refund_diff());
+				Contracts::<T>::deposit_event(Event::CodeUpgradeCancelled { contract: address });
+			}
+			return Ok(());
+		}
+
+		<CodeInfo<T>>::increment_refcount(hash)?;
+		if previous.is_none() {
+			frame
+				.nested_storage
+				.charge(&PendingCodeUpgrade::<BlockNumberFor<T>>::deposit_diff());
+		}
+		PendingCodeUpgrades::<T>::insert(
+			&address,
+			PendingCodeUpgrade { code_hash: hash, enact_at },
+		);
+		Contracts::<T>::deposit_event(Event::CodeUpgradeAnnounced {
+			contract: address,
+			code_hash: hash,
+			enact_at,
+		});
+		Ok(())
+	}
+
 	fn immutable_data_len(&mut self) -> u32 {
 		self.top_frame_mut().contract_info().immutable_data_len()
 	}
@@ -1717,11 +1939,12 @@ where
 		}
 
 		// Immutable is read from contract code being executed
-		let address = self
-			.top_frame()
+		let frame = self.top_frame();
+		let address = frame
 			.delegate
 			.as_ref()
 			.map(|d| d.callee)
+			.or(frame.delegation)
 			.unwrap_or(T::AddressMapper::to_address(self.account_id()));
 		Ok(<ImmutableDataOf<T>>::get(address).ok_or_else(|| Error::<T>::InvalidImmutableAccess)?)
 	}
@@ -1768,6 +1991,18 @@ where
 		)
 	}
 
+	fn access_storage(&mut self, key: &Key) -> bool {
+		let address = self.address();
+		let warm = self.accessed.access_storage(&address, key);
+		if_tracing(|tracer| {
+			if !warm {
+				tracer.storage_accessed(&address, key);
+			}
+			tracer.storage_touched(&address, key);
+		});
+		warm
+	}
+
 	fn charge_storage(&mut self, diff: &Diff) {
 		self.top_frame_mut().nested_storage.charge(diff)
 	}
@@ -1776,7 +2011,7 @@ where
 		&mut self,
 		gas_limit: Weight,
 		deposit_limit: U256,
-		code_hash: H256,
+		code: Code,
 		value: U256,
 		input_data: Vec<u8>,
 		salt: Option<&[u8; 32]>,
@@ -1785,7 +2020,18 @@ where
 		// This is for example the case when creating the frame fails.
 		*self.last_frame_output_mut() = Default::default();
 
-		let executable = E::from_storage(code_hash, self.gas_meter_mut())?;
+		let executable = match &code {
+			Code::Existing(code_hash) => {
+				let executable = E::from_storage(*code_hash, self.gas_meter_mut())?;
+				// Stored EVM code is runtime code: it has no constructor to run.
+				ensure!(executable.is_pvm(), <Error<T>>::CodeRejected);
+				executable
+			},
+			// The runtime code returned by the init code is stored on behalf of the origin, so it
+			// must be allowed to upload code.
+			Code::Upload(code) =>
+				E::from_evm_init_code(code.clone(), self.origin.upload_account()?)?,
+		};
 		let sender = &self.top_frame().account_id;
 		let executable = self.push_frame(
 			FrameArgs::Instantiate {
@@ -1800,7 +2046,7 @@ where
 			self.is_read_only(),
 		)?;
 		let address = T::AddressMapper::to_address(&self.top_frame().account_id);
-		if_tracing(|t| t.instantiate_code(&crate::Code::Existing(code_hash), salt));
+		if_tracing(|t| t.instantiate_code(&code, salt));
 		self.run(executable.expect(FRAME_ALWAYS_EXISTS_ON_INSTANTIATE), input_data, BumpNonce::Yes)
 			.map(|_| address)
 	}
@@ -1962,6 +2208,12 @@ where
 
 		<AccountInfo<T>>::load_contract(&address)
 			.map(|contract| contract.code_hash)
+			.or_else(|| {
+				<AccountInfo<T>>::delegation_target(address).map(|target| {
+					sp_io::hashing::keccak_256(&AccountInfo::<T>::delegation_designator(&target))
+						.into()
+				})
+			})
 			.unwrap_or_else(|| {
 				if System::<T>::account_exists(&T::AddressMapper::to_account_id(address)) {
 					return EMPTY_CODE_HASH;
@@ -1978,6 +2230,10 @@ where
 		<AccountInfo<T>>::load_contract(&address)
 			.and_then(|contract| CodeInfoOf::<T>::get(contract.code_hash))
 			.map(|info| info.code_len())
+			.or_else(|| {
+				<AccountInfo<T>>::delegation_target(address)
+					.map(|target| AccountInfo::<T>::delegation_designator(&target).len() as u64)
+			})
 			.unwrap_or_default()
 	}
 
@@ -2003,6 +2259,14 @@ where
 		balance
 	}
 
+	fn access_account(&mut self, address: &H160) -> bool {
+		let warm = self.accessed.access_account(address);
+		if !warm {
+			if_tracing(|tracer| tracer.account_accessed(address));
+		}
+		warm
+	}
+
 	fn value_transferred(&self) -> U256 {
 		self.top_frame().value_transferred.into()
 	}
@@ -2067,6 +2331,15 @@ where
 		ECDSAPublic::from(*pk).to_eth_address()
 	}
 
+	fn debug_buffer_enabled(&self) -> bool {
+		debug::is_enabled() || if_tracing(|_| ()).is_some()
+	}
+
+	fn append_debug_buffer(&mut self, msg: &str) -> bool {
+		let traced = if_tracing(|tracer| tracer.debug_message(msg)).is_some();
+		debug::append(msg.as_bytes()) || traced
+	}
+
 	#[cfg(any(test, feature = "runtime-benchmarks"))]
 	fn contract_info(&mut self) -> &mut ContractInfo<Self::T> {
 		self.top_frame_mut().contract_info()
@@ -23,7 +23,7 @@ use crate::{
 	precompiles::Diff,
 	storage::{ContractInfo, WriteOutcome},
 	transient_storage::TransientStorage,
-	Config, ExecReturnValue, ImmutableData,
+	Code, Config, ExecReturnValue, ImmutableData,
 };
 use alloc::vec::Vec;
 use core::marker::PhantomData;
@@ -106,6 +106,10 @@ impl<T: Config> PrecompileExt for MockExt<T> {
 		panic!("MockExt::balance_of")
 	}
 
+	fn access_account(&mut self, _address: &H160) -> bool {
+		panic!("MockExt::access_account")
+	}
+
 	fn value_transferred(&self) -> U256 {
 		panic!("MockExt::value_transferred")
 	}
@@ -166,6 +170,14 @@ impl<T: Config> PrecompileExt for MockExt<T> {
 		panic!("MockExt::ecdsa_to_eth_address")
 	}
 
+	fn debug_buffer_enabled(&self) -> bool {
+		false
+	}
+
+	fn append_debug_buffer(&mut self, _msg: &str) -> bool {
+		false
+	}
+
 	#[cfg(any(test, feature = "runtime-benchmarks"))]
 	fn contract_info(&mut self) -> &mut ContractInfo<Self::T> {
 		panic!("MockExt::contract_info")
@@ -207,13 +219,17 @@ impl<T: Config> PrecompileWithInfoExt for MockExt<T> {
 		panic!("MockExt::set_storage")
 	}
 
+	fn access_storage(&mut self, _key: &Key) -> bool {
+		panic!("MockExt::access_storage")
+	}
+
 	fn charge_storage(&mut self, _diff: &Diff) {}
 
 	fn instantiate(
 		&mut self,
 		_gas_limit: Weight,
 		_deposit_limit: U256,
-		_code: H256,
+		_code: Code,
 		_value: U256,
 		_input_data: Vec<u8>,
 		_salt: Option<&[u8; 32]>,
@@ -245,6 +261,10 @@ impl<T: Config> Ext for MockExt<T> {
 		panic!("MockExt::set_code_hash")
 	}
 
+	fn announce_code_upgrade(&mut self, _hash: H256) -> DispatchResult {
+		panic!("MockExt::announce_code_upgrade")
+	}
+
 	fn immutable_data_len(&mut self) -> u32 {
 		panic!("MockExt::immutable_data_len")
 	}
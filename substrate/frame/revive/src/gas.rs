@@ -15,7 +15,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{exec::ExecError, weights::WeightInfo, Config, Error};
+use crate::{exec::ExecError, tracing::if_tracing, weights::WeightInfo, Config, Error};
 use core::marker::PhantomData;
 use frame_support::{
 	dispatch::{DispatchErrorWithPostInfo, DispatchResultWithPostInfo, PostDispatchInfo},
@@ -27,6 +27,10 @@ use sp_runtime::DispatchError;
 #[cfg(test)]
 use std::{any::Any, fmt::Debug};
 
+/// The name under which the weight of the instructions metered by the executor is reported to
+/// tracers.
+const ENGINE_TOKEN_NAME: &str = "Execution";
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct ChargedAmount(Weight);
 
@@ -131,6 +135,11 @@ pub trait Token<T: Config>: Copy + Clone + TestAuxiliaries {
 	fn influence_lowest_gas_limit(&self) -> bool {
 		true
 	}
+
+	/// The name under which the charged weight is reported to tracers.
+	fn name(&self) -> &'static str {
+		"Other"
+	}
 }
 
 /// A wrapper around a type-erased trait object of what used to be a `Token`.
@@ -216,6 +225,7 @@ impl<T: Config> GasMeter<T> {
 		// It is OK to not charge anything on failure because we always charge _before_ we perform
 		// any action
 		self.gas_left = self.gas_left.checked_sub(&amount).ok_or_else(|| Error::<T>::OutOfGas)?;
+		if_tracing(|tracer| tracer.gas_charged(token.name(), amount));
 		Ok(ChargedAmount(amount))
 	}
 
@@ -229,6 +239,7 @@ impl<T: Config> GasMeter<T> {
 		}
 		let adjustment = charged_amount.0.saturating_sub(token.weight());
 		self.gas_left = self.gas_left.saturating_add(adjustment).min(self.gas_limit);
+		if_tracing(|tracer| tracer.gas_refunded(token.name(), adjustment));
 	}
 
 	/// Hand over the gas metering responsibility from the executor to this meter.
@@ -246,6 +257,7 @@ impl<T: Config> GasMeter<T> {
 		self.gas_left
 			.checked_reduce(weight_consumed)
 			.ok_or_else(|| Error::<T>::OutOfGas)?;
+		if_tracing(|tracer| tracer.gas_charged(ENGINE_TOKEN_NAME, weight_consumed));
 		Ok(RefTimeLeft(self.gas_left.ref_time()))
 	}
 
@@ -280,6 +292,20 @@ impl<T: Config> GasMeter<T> {
 		self.gas_left
 	}
 
+	/// Returns how much gas is left, given the fuel left in the executor.
+	///
+	/// Unlike [`Self::sync_from_executor`] this does not modify the meter. It is used to
+	/// observe the gas while the executor is still in charge of metering.
+	pub fn gas_left_with_engine_fuel(&self, engine_fuel: polkavm::Gas) -> Weight {
+		let fuel = u64::try_from(engine_fuel).unwrap_or_default();
+		let consumed = self
+			.engine_meter
+			.fuel
+			.saturating_sub(fuel)
+			.saturating_mul(EngineMeter::<T>::ref_time_per_fuel());
+		self.gas_left.saturating_sub(Weight::from_parts(consumed, 0))
+	}
+
 	/// The amount of gas in terms of engine gas.
 	pub fn engine_fuel_left(&self) -> Result<polkavm::Gas, DispatchError> {
 		self.engine_meter.fuel.try_into().map_err(|_| <Error<T>>::OutOfGas.into())
@@ -22,7 +22,9 @@
 
 extern crate alloc;
 
+mod access_list;
 mod address;
+mod authorization;
 mod benchmarking;
 mod call_builder;
 mod exec;
@@ -30,12 +32,14 @@ mod gas;
 mod impl_fungibles;
 mod limits;
 mod primitives;
+mod simulate;
 mod storage;
 #[cfg(test)]
 mod tests;
 mod transient_storage;
 mod vm;
 
+pub mod debug;
 pub mod evm;
 pub mod migrations;
 pub mod precompiles;
@@ -45,16 +49,19 @@ pub mod weights;
 
 use crate::{
 	evm::{
-		runtime::GAS_PRICE, CallTracer, GasEncoder, GenericTransaction, PrestateTracer, Trace,
-		Tracer, TracerType, TYPE_EIP1559,
+		runtime::GAS_PRICE, AccessListEntry, AccessListResult, AuthorizationListEntry, CallTracer,
+		GasEncoder, GasProfiler, GenericTransaction, PrestateTracer, SimulateBlock, SimulatedBlock,
+		StateOverrides, StructLogger, Trace, Tracer, TracerType, TYPE_EIP1559,
 	},
 	exec::{AccountIdOf, ExecError, Executable, Stack as ExecStack},
 	gas::GasMeter,
 	storage::{
-		meter::Meter as StorageMeter, AccountInfo, AccountType, ContractInfo, DeletionQueueManager,
+		expiry::{ContractActivity, Tombstone},
+		meter::Meter as StorageMeter,
+		AccountInfo, AccountType, ContractInfo, DeletionQueueManager,
 	},
 	tracing::if_tracing,
-	vm::{CodeInfo, ContractBlob, RuntimeCosts},
+	vm::{BytecodeType, CodeInfo, ContractBlob, RuntimeCosts},
 };
 use alloc::{boxed::Box, format, vec};
 use codec::{Codec, Decode, Encode};
@@ -210,6 +217,18 @@ pub mod pallet {
 		#[pallet::constant]
 		type CodeHashLockupDepositPercent: Get<Perbill>;
 
+		/// The number of blocks a contract has to wait between announcing a code upgrade and
+		/// switching to the new code.
+		///
+		/// When non zero, `set_code_hash` is denied unless the contract announced the new code
+		/// hash through `announce_code_upgrade` at least this many blocks earlier. This gives
+		/// users the time to observe the upgrade, see [`Pallet::pending_code_upgrade`], and to
+		/// exit before it takes effect. A value of zero allows contracts to switch their code
+		/// immediately.
+		#[pallet::constant]
+		#[pallet::no_default_bounds]
+		type CodeUpgradeDelay: Get<BlockNumberFor<Self>>;
+
 		/// Use either valid type is [`address::AccountId32Mapper`] or [`address::H160Mapper`].
 		#[pallet::no_default]
 		type AddressMapper: AddressMapper<Self>;
@@ -283,7 +302,7 @@ pub mod pallet {
 		use super::*;
 		use frame_support::{
 			derive_impl,
-			traits::{ConstBool, ConstU32},
+			traits::{ConstBool, ConstU32, GetDefault},
 		};
 		use frame_system::EnsureSigned;
 		use sp_core::parameter_types;
@@ -333,6 +352,7 @@ pub mod pallet {
 			type RuntimeCall = ();
 			type Precompiles = ();
 			type CodeHashLockupDepositPercent = CodeHashLockupDepositPercent;
+			type CodeUpgradeDelay = GetDefault;
 			type DepositPerByte = DepositPerByte;
 			type DepositPerItem = DepositPerItem;
 			type Time = Self;
@@ -366,6 +386,52 @@ pub mod pallet {
 
 		/// Contract deployed by deployer at the specified address.
 		Instantiated { deployer: H160, contract: H160 },
+
+		/// A contract announced that it will switch to new code.
+		CodeUpgradeAnnounced {
+			/// The contract that announced the upgrade.
+			contract: H160,
+			/// The code hash the contract will switch to.
+			code_hash: H256,
+			/// The first block at which the contract can switch to the new code.
+			enact_at: BlockNumberFor<T>,
+		},
+
+		/// A contract cancelled its pending code upgrade.
+		CodeUpgradeCancelled { contract: H160 },
+
+		/// A contract switched to the code it announced.
+		CodeUpgradeEnacted { contract: H160, code_hash: H256 },
+
+		/// An expired contract was tombstoned.
+		ContractTombstoned {
+			/// The contract that was tombstoned.
+			contract: H160,
+			/// The account the storage deposit of the contract was returned to.
+			depositor: T::AccountId,
+			/// The storage deposit returned to `depositor`.
+			deposit: BalanceOf<T>,
+		},
+
+		/// A tombstoned contract was restored.
+		ContractRestored {
+			/// The contract that was restored.
+			contract: H160,
+			/// The account that paid the storage deposit of the restored contract.
+			restorer: T::AccountId,
+			/// The storage deposit charged from `restorer`.
+			deposit: BalanceOf<T>,
+		},
+
+		/// An `eth_call` applying an authorization list reverted.
+		///
+		/// The authorizations are kept when the call reverts, see
+		/// [EIP-7702](https://eips.ethereum.org/EIPS/eip-7702), so the extrinsic succeeds and this
+		/// event marks the Ethereum transaction as failed.
+		EthCallReverted {
+			/// The contract that was called.
+			contract: H160,
+		},
 	}
 
 	#[pallet::error]
@@ -477,6 +543,37 @@ pub mod pallet {
 		CallDataTooLarge = 0x30,
 		/// The return data exceeds [`limits::CALLDATA_BYTES`].
 		ReturnDataTooLarge = 0x31,
+		/// An account delegating its code to a contract tried to terminate itself.
+		TerminatedWhileDelegated = 0x32,
+		/// An EVM contract jumped to an invalid destination.
+		InvalidJump = 0x33,
+		/// An EVM contract popped more items than available on its stack.
+		StackUnderflow = 0x34,
+		/// An EVM contract pushed more items than allowed on its stack.
+		StackOverflow = 0x35,
+		/// The contract tried to switch to a code hash it did not announce.
+		///
+		/// See [`Config::CodeUpgradeDelay`].
+		CodeUpgradeNotAnnounced = 0x36,
+		/// The contract tried to switch to its announced code before the end of the delay.
+		CodeUpgradeNotDue = 0x37,
+		/// Contracts do not expire as no expiry is set.
+		///
+		/// See [`Pallet::set_contract_expiry`].
+		ContractExpiryDisabled = 0x38,
+		/// The contract was touched within the expiry period or never expires.
+		ContractNotExpired = 0x39,
+		/// No tombstone was found at the specified address.
+		TombstoneNotFound = 0x3A,
+		/// The storage supplied to restore a contract does not match the root of its tombstone.
+		TombstoneRootMismatch = 0x3B,
+		/// The storage of the contract exceeds what can be restored in a single extrinsic.
+		///
+		/// See [`limits::TOMBSTONE_STORAGE_ITEMS`] and [`limits::TOMBSTONE_STORAGE_BYTES`].
+		TombstoneStorageTooLarge = 0x3C,
+		/// The storage deposit of the contract was not only paid by its depositor, or was paid
+		/// while the expiry was disabled, so the contract never expires.
+		ContractDepositShared = 0x3D,
 	}
 
 	/// A reason for the pallet revive placing a hold on funds.
@@ -528,6 +625,29 @@ pub mod pallet {
 	#[pallet::storage]
 	pub(crate) type OriginalAccount<T: Config> = StorageMap<_, Identity, H160, AccountId32>;
 
+	/// The code upgrades announced by contracts that were not enacted yet.
+	///
+	/// See [`Config::CodeUpgradeDelay`].
+	#[pallet::storage]
+	pub(crate) type PendingCodeUpgrades<T: Config> =
+		StorageMap<_, Identity, H160, PendingCodeUpgrade<BlockNumberFor<T>>>;
+
+	/// The expiry of contracts that were not touched for a while.
+	///
+	/// Contracts never expire if this is not set, see [`Pallet::set_contract_expiry`].
+	#[pallet::storage]
+	pub(crate) type ContractExpiryOf<T: Config> =
+		StorageValue<_, ContractExpiry<BlockNumberFor<T>>>;
+
+	/// The depositor of each contract and the last block at which it was touched.
+	#[pallet::storage]
+	pub(crate) type ContractActivityOf<T: Config> =
+		StorageMap<_, Identity, H160, ContractActivity<T>>;
+
+	/// The contracts that expired, see [`Pallet::tombstone_contract`].
+	#[pallet::storage]
+	pub(crate) type Tombstones<T: Config> = StorageMap<_, Identity, H160, Tombstone<T>>;
+
 	#[pallet::genesis_config]
 	#[derive(frame_support::DefaultNoBound)]
 	pub struct GenesisConfig<T: Config> {
@@ -608,11 +728,12 @@ pub mod pallet {
 			.expect("Immutable data size too big");
 
 			// We can use storage to store items using the available block ref_time with the
-			// `set_storage` host function.
+			// `set_storage` host function. Warm accesses are the cheapest ones.
 			let max_storage_size: u32 = ((max_block_ref_time /
 				(<RuntimeCosts as gas::Token<T>>::weight(&RuntimeCosts::SetStorage {
 					new_bytes: max_payload_size,
 					old_bytes: 0,
+					cold: false,
 				})
 				.ref_time()))
 			.saturating_mul(max_payload_size.saturating_add(max_key_size) as u64))
@@ -838,6 +959,9 @@ pub mod pallet {
 		/// via the `CheckNonce` transaction extension. In contrast, [`Self::instantiate_with_code`]
 		/// also bumps the nonce after contract instantiation, since it may be invoked multiple
 		/// times within a batch call transaction.
+		///
+		/// The accounts and storage slots of the `access_list` are warmed up front, see
+		/// [EIP-2930](https://eips.ethereum.org/EIPS/eip-2930).
 		#[pallet::call_index(10)]
 		#[pallet::weight(
 			T::WeightInfo::eth_instantiate_with_code(code.len() as u32, data.len() as u32, Pallet::<T>::has_dust(*value).into())
@@ -850,19 +974,22 @@ pub mod pallet {
 			#[pallet::compact] storage_deposit_limit: BalanceOf<T>,
 			code: Vec<u8>,
 			data: Vec<u8>,
+			access_list: Vec<AccessListEntry>,
 		) -> DispatchResultWithPostInfo {
 			let code_len = code.len() as u32;
 			let data_len = data.len() as u32;
-			let mut output = Self::bare_instantiate(
-				origin,
-				value,
-				gas_limit,
-				DepositLimit::Balance(storage_deposit_limit),
-				Code::Upload(code),
-				data,
-				None,
-				BumpNonce::No,
-			);
+			let mut output = access_list::with_access_list(access_list, || {
+				Self::bare_instantiate(
+					origin,
+					value,
+					gas_limit,
+					DepositLimit::Balance(storage_deposit_limit),
+					Code::Upload(code),
+					data,
+					None,
+					BumpNonce::No,
+				)
+			});
 
 			if let Ok(retval) = &output.result {
 				if retval.result.did_revert() {
@@ -882,8 +1009,19 @@ pub mod pallet {
 
 		/// Same as [`Self::call`], but intended to be dispatched **only**
 		/// by an EVM transaction through the EVM compatibility layer.
+		///
+		/// The accounts and storage slots of the `access_list` are warmed up front, see
+		/// [EIP-2930](https://eips.ethereum.org/EIPS/eip-2930).
+		///
+		/// The authorizations of the `authorization_list` are applied before the call, see
+		/// [EIP-7702](https://eips.ethereum.org/EIPS/eip-7702). They are kept if the call reverts,
+		/// in which case [`Event::EthCallReverted`] is emitted instead of failing the extrinsic.
 		#[pallet::call_index(11)]
-		#[pallet::weight(T::WeightInfo::eth_call(Pallet::<T>::has_dust(*value).into()).saturating_add(*gas_limit))]
+		#[pallet::weight(
+			T::WeightInfo::eth_call(Pallet::<T>::has_dust(*value).into())
+			.saturating_add(authorization::weight::<T>(authorization_list.len() as u32))
+			.saturating_add(*gas_limit)
+		)]
 		pub fn eth_call(
 			origin: OriginFor<T>,
 			dest: H160,
@@ -891,25 +1029,35 @@ pub mod pallet {
 			gas_limit: Weight,
 			#[pallet::compact] storage_deposit_limit: BalanceOf<T>,
 			data: Vec<u8>,
+			access_list: Vec<AccessListEntry>,
+			authorization_list: Vec<AuthorizationListEntry>,
 		) -> DispatchResultWithPostInfo {
-			let mut output = Self::bare_call(
-				origin,
-				dest,
-				value,
-				gas_limit,
-				DepositLimit::Balance(storage_deposit_limit),
-				data,
-			);
+			let mut output = access_list::with_access_list(access_list, || {
+				Self::bare_call_with_authorizations(
+					origin,
+					dest,
+					value,
+					gas_limit,
+					DepositLimit::Balance(storage_deposit_limit),
+					data,
+					&authorization_list,
+				)
+			});
 
 			if let Ok(return_value) = &output.result {
 				if return_value.did_revert() {
-					output.result = Err(<Error<T>>::ContractReverted.into());
+					if authorization_list.is_empty() {
+						output.result = Err(<Error<T>>::ContractReverted.into());
+					} else {
+						Self::deposit_event(Event::EthCallReverted { contract: dest });
+					}
 				}
 			}
 			dispatch_result(
 				output.result,
 				output.gas_consumed,
-				T::WeightInfo::eth_call(Pallet::<T>::has_dust(value).into()),
+				T::WeightInfo::eth_call(Pallet::<T>::has_dust(value).into())
+					.saturating_add(authorization::weight::<T>(authorization_list.len() as u32)),
 			)
 		}
 
@@ -919,6 +1067,10 @@ pub mod pallet {
 		/// and unreserved only when [`Self::remove_code`] is called. The size of the reserve
 		/// depends on the size of the supplied `code`.
 		///
+		/// PolkaVM blobs are told apart from EVM runtime code by their magic prefix, which EVM
+		/// bytecode can't start with. Uploaded EVM code is runtime code: it has no constructor, so
+		/// contracts can't be instantiated from it, but existing contracts can switch to it.
+		///
 		/// # Note
 		///
 		/// Anyone can instantiate a contract from any uploaded code and thus prevent its removal.
@@ -1030,6 +1182,76 @@ pub mod pallet {
 				T::AddressMapper::to_fallback_account_id(&T::AddressMapper::to_address(&origin));
 			call.dispatch(RawOrigin::Signed(unmapped_account).into())
 		}
+
+		/// Privileged function that sets the expiry of contracts that were not touched for a while.
+		///
+		/// Once set, a contract that was not called for `period` blocks can be tombstoned by anyone
+		/// using [`Self::tombstone_contract`]. Passing `None` disables the expiry. Changing the
+		/// period of an enabled expiry does not reset the activity of contracts.
+		#[pallet::call_index(12)]
+		#[pallet::weight(T::WeightInfo::set_contract_expiry())]
+		pub fn set_contract_expiry(
+			origin: OriginFor<T>,
+			period: Option<BlockNumberFor<T>>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			<ContractExpiryOf<T>>::mutate(|expiry| {
+				*expiry = period.map(|period| ContractExpiry {
+					period,
+					enabled_at: expiry
+						.map(|expiry| expiry.enabled_at)
+						.unwrap_or_else(<frame_system::Pallet<T>>::block_number),
+				})
+			});
+			Ok(())
+		}
+
+		/// Tombstone the contract at `contract` if it was not touched during the expiry period.
+		///
+		/// The child trie of the contract is queued for deletion and its whole storage deposit is
+		/// returned to the account that instantiated it. The contract can be restored using
+		/// [`Self::restore_contract`].
+		///
+		/// Contracts that were instantiated before the expiry was last enabled never expire,
+		/// neither do contracts whose storage deposit was partly paid by other accounts or whose
+		/// storage exceeds [`limits::TOMBSTONE_STORAGE_ITEMS`] or
+		/// [`limits::TOMBSTONE_STORAGE_BYTES`].
+		#[pallet::call_index(13)]
+		#[pallet::weight(T::WeightInfo::tombstone_contract())]
+		pub fn tombstone_contract(
+			origin: OriginFor<T>,
+			contract: H160,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			let (depositor, deposit) = <Tombstone<T>>::create(&contract)?;
+			Self::deposit_event(Event::ContractTombstoned { contract, depositor, deposit });
+			// we waive the fee because removing expired contracts is beneficial
+			Ok(Pays::No.into())
+		}
+
+		/// Restore the tombstoned contract at `contract`.
+		///
+		/// `storage` holds the raw key value pairs of the child trie of the contract at the time it
+		/// expired. Their root must match the one recorded in the tombstone. The origin pays the
+		/// storage deposit of the restored contract and is refunded it if the contract expires
+		/// again.
+		#[pallet::call_index(14)]
+		#[pallet::weight(T::WeightInfo::restore_contract(
+			storage.len() as u32,
+			storage.iter().fold(0u32, |bytes, (key, value)| {
+				bytes.saturating_add(key.len().saturating_add(value.len()) as u32)
+			}),
+		))]
+		pub fn restore_contract(
+			origin: OriginFor<T>,
+			contract: H160,
+			storage: Vec<(Vec<u8>, Vec<u8>)>,
+		) -> DispatchResult {
+			let restorer = ensure_signed(origin)?;
+			let deposit = <Tombstone<T>>::restore(&contract, &restorer, storage)?;
+			Self::deposit_event(Event::ContractRestored { contract, restorer, deposit });
+			Ok(())
+		}
 	}
 }
 
@@ -1068,6 +1290,30 @@ where
 		gas_limit: Weight,
 		storage_deposit_limit: DepositLimit<BalanceOf<T>>,
 		data: Vec<u8>,
+	) -> ContractResult<ExecReturnValue, BalanceOf<T>> {
+		Self::bare_call_with_authorizations(
+			origin,
+			dest,
+			evm_value,
+			gas_limit,
+			storage_deposit_limit,
+			data,
+			&[],
+		)
+	}
+
+	/// Same as [`Self::bare_call`], but applies the `authorization_list` before the call.
+	///
+	/// What the origin pays to create the authorities that don't exist yet counts towards the
+	/// `storage_deposit_limit` of the call, see [`authorization::apply_authorizations`].
+	fn bare_call_with_authorizations(
+		origin: OriginFor<T>,
+		dest: H160,
+		evm_value: U256,
+		gas_limit: Weight,
+		storage_deposit_limit: DepositLimit<BalanceOf<T>>,
+		data: Vec<u8>,
+		authorization_list: &[AuthorizationListEntry],
 	) -> ContractResult<ExecReturnValue, BalanceOf<T>> {
 		let mut gas_meter = GasMeter::new(gas_limit);
 		let mut storage_deposit = Default::default();
@@ -1075,6 +1321,13 @@ where
 		let try_call = || {
 			let origin = Origin::from_runtime_origin(origin)?;
 			let mut storage_meter = StorageMeter::new(storage_deposit_limit.limit());
+			if !authorization_list.is_empty() {
+				authorization::apply_authorizations::<T>(
+					origin.account_id()?,
+					authorization_list,
+					&mut storage_meter,
+				);
+			}
 			let result = ExecStack::<T, ContractBlob<T>>::run_call(
 				origin.clone(),
 				dest,
@@ -1097,6 +1350,7 @@ where
 			gas_consumed: gas_meter.gas_consumed(),
 			gas_required: gas_meter.gas_required(),
 			storage_deposit,
+			debug_message: debug::take(),
 		}
 	}
 
@@ -1134,21 +1388,33 @@ where
 			let instantiate_account = T::InstantiateOrigin::ensure_origin(origin.clone())?;
 
 			if_tracing(|t| t.instantiate_code(&code, salt.as_ref()));
-			let (executable, upload_deposit) = match code {
-				Code::Upload(code) if code.starts_with(&polkavm_common::program::BLOB_MAGIC) => {
+			let (executable, upload_deposit, data) = match code {
+				Code::Upload(code) if BytecodeType::of(&code) == BytecodeType::Pvm => {
 					let upload_account = T::UploadOrigin::ensure_origin(origin)?;
-					let (executable, upload_deposit) = Self::try_upload_pvm_code(
+					let (executable, upload_deposit) = Self::try_upload_code(
 						upload_account,
 						code,
 						storage_deposit_limit,
 						unchecked_deposit_limit,
 					)?;
 					storage_deposit_limit.saturating_reduce(upload_deposit);
-					(executable, upload_deposit)
+					(executable, upload_deposit, data)
+				},
+				// The constructor arguments of EVM contracts are appended to their init code.
+				// The runtime code returned by the init code is stored once it ran, on behalf of
+				// the upload account.
+				Code::Upload(code) => {
+					let upload_account = T::UploadOrigin::ensure_origin(origin)?;
+					let init_code = [code, data].concat();
+					let executable = ContractBlob::from_evm_init_code(init_code, upload_account)?;
+					(executable, Default::default(), Vec::new())
+				},
+				Code::Existing(code_hash) => {
+					let executable = ContractBlob::from_storage(code_hash, &mut gas_meter)?;
+					// Stored EVM code is runtime code: it has no constructor to run.
+					ensure!(executable.is_pvm(), <Error<T>>::CodeRejected);
+					(executable, Default::default(), data)
 				},
-				Code::Upload(_code) => return Err(<Error<T>>::CodeRejected.into()),
-				Code::Existing(code_hash) =>
-					(ContractBlob::from_storage(code_hash, &mut gas_meter)?, Default::default()),
 			};
 			let instantiate_origin = Origin::from_account_id(instantiate_account.clone());
 			let mut storage_meter = StorageMeter::new(storage_deposit_limit);
@@ -1176,6 +1442,7 @@ where
 			gas_consumed: gas_meter.gas_consumed(),
 			gas_required: gas_meter.gas_required(),
 			storage_deposit,
+			debug_message: debug::take(),
 		}
 	}
 
@@ -1239,6 +1506,8 @@ where
 		// Convert the value to the native balance type.
 		let value = tx.value.unwrap_or_default();
 		let input = tx.input.clone().to_vec();
+		let access_list = tx.access_list.clone().unwrap_or_default();
+		let authorization_list = tx.authorization_list.clone().unwrap_or_default();
 
 		let extract_error = |err| {
 			if err == Error::<T>::TransferFailed.into() ||
@@ -1284,15 +1553,19 @@ where
 
 					(result, dispatch_call)
 				} else {
-					// Dry run the call.
-					let result = crate::Pallet::<T>::bare_call(
-						T::RuntimeOrigin::signed(origin),
-						dest,
-						value,
-						gas_limit,
-						storage_deposit_limit,
-						input.clone(),
-					);
+					// Dry run the call. The authorizations are applied after the nonce of the
+					// sender was bumped by `prepare_dry_run`, just like when dispatched.
+					let result = access_list::with_access_list(access_list.clone(), || {
+						crate::Pallet::<T>::bare_call_with_authorizations(
+							T::RuntimeOrigin::signed(origin),
+							dest,
+							value,
+							gas_limit,
+							storage_deposit_limit,
+							input.clone(),
+							&authorization_list,
+						)
+					});
 
 					let data = match result.result {
 						Ok(return_value) => {
@@ -1312,6 +1585,7 @@ where
 						storage_deposit: result.storage_deposit.charge_or_zero(),
 						data,
 						eth_gas: Default::default(),
+						debug_message: result.debug_message,
 					};
 
 					let (gas_limit, storage_deposit_limit) = T::EthGasEncoder::as_encoded_values(
@@ -1324,6 +1598,8 @@ where
 						gas_limit,
 						storage_deposit_limit,
 						data: input.clone(),
+						access_list,
+						authorization_list,
 					}
 					.into();
 					(result, dispatch_call)
@@ -1332,8 +1608,9 @@ where
 			// A contract deployment
 			None => {
 				// Extract code and data from the input.
-				let (code, data) = if input.starts_with(&polkavm_common::program::BLOB_MAGIC) {
-					match polkavm::ProgramBlob::blob_length(&input) {
+				// EVM init code already contains the constructor arguments.
+				let (code, data) = match BytecodeType::of(&input) {
+					BytecodeType::Pvm => match polkavm::ProgramBlob::blob_length(&input) {
 						Some(blob_len) => blob_len
 							.try_into()
 							.ok()
@@ -1343,22 +1620,23 @@ where
 							log::debug!(target: LOG_TARGET, "Failed to extract polkavm blob length");
 							(&input[..], &[][..])
 						},
-					}
-				} else {
-					return Err(EthTransactError::Message("Invalid transaction".into()));
+					},
+					BytecodeType::Evm => (&input[..], &[][..]),
 				};
 
 				// Dry run the call.
-				let result = crate::Pallet::<T>::bare_instantiate(
-					T::RuntimeOrigin::signed(origin),
-					value,
-					gas_limit,
-					storage_deposit_limit,
-					Code::Upload(code.to_vec()),
-					data.to_vec(),
-					None,
-					BumpNonce::No,
-				);
+				let result = access_list::with_access_list(access_list.clone(), || {
+					crate::Pallet::<T>::bare_instantiate(
+						T::RuntimeOrigin::signed(origin),
+						value,
+						gas_limit,
+						storage_deposit_limit,
+						Code::Upload(code.to_vec()),
+						data.to_vec(),
+						None,
+						BumpNonce::No,
+					)
+				});
 
 				let returned_data = match result.result {
 					Ok(return_value) => {
@@ -1378,6 +1656,7 @@ where
 					storage_deposit: result.storage_deposit.charge_or_zero(),
 					data: returned_data,
 					eth_gas: Default::default(),
+					debug_message: result.debug_message,
 				};
 
 				// Get the dispatch info of the call.
@@ -1392,6 +1671,7 @@ where
 						storage_deposit_limit,
 						code: code.to_vec(),
 						data: data.to_vec(),
+						access_list,
 					}
 					.into();
 				(result, dispatch_call)
@@ -1494,6 +1774,16 @@ where
 			.into(),
 			TracerType::PrestateTracer(config) =>
 				PrestateTracer::new(config.unwrap_or_default()).into(),
+			TracerType::StructLogger(config) => StructLogger::new(
+				config.unwrap_or_default(),
+				Self::evm_gas_from_weight as fn(Weight) -> U256,
+			)
+			.into(),
+			TracerType::GasProfiler(config) => GasProfiler::new(
+				config.unwrap_or_default(),
+				Self::evm_gas_from_weight as fn(Weight) -> U256,
+			)
+			.into(),
 		}
 	}
 
@@ -1506,15 +1796,14 @@ where
 		storage_deposit_limit: BalanceOf<T>,
 	) -> CodeUploadResult<BalanceOf<T>> {
 		let origin = T::UploadOrigin::ensure_origin(origin)?;
-		let (module, deposit) =
-			Self::try_upload_pvm_code(origin, code, storage_deposit_limit, false)?;
+		let (module, deposit) = Self::try_upload_code(origin, code, storage_deposit_limit, false)?;
 		Ok(CodeUploadReturnValue { code_hash: *module.code_hash(), deposit })
 	}
 
 	/// Query storage of a specified contract under a specified key.
 	pub fn get_storage(address: H160, key: [u8; 32]) -> GetStorageResult {
-		let contract_info =
-			AccountInfo::<T>::load_contract(&address).ok_or(ContractAccessError::DoesntExist)?;
+		let (contract_info, _) = AccountInfo::<T>::load_contract_or_delegated(&address)
+			.ok_or(ContractAccessError::DoesntExist)?;
 
 		let maybe_value = contract_info.read(&Key::from_fixed(key));
 		Ok(maybe_value)
@@ -1533,14 +1822,30 @@ where
 		Ok(maybe_value)
 	}
 
-	/// Uploads new code and returns the Vm binary contract blob and deposit amount collected.
-	fn try_upload_pvm_code(
+	/// Returns the storage keys needed to prove the account at `address`.
+	///
+	/// The storage slots of a contract are stored in its child trie, under the `blake2_256` hash
+	/// of the slot.
+	pub fn account_proof_keys(address: H160) -> AccountProofKeys {
+		let account_id = T::AddressMapper::to_account_id(&address);
+		AccountProofKeys {
+			system_account: frame_system::Account::<T>::hashed_key_for(&account_id),
+			account_info: AccountInfoOf::<T>::hashed_key_for(address),
+			child_trie: AccountInfo::<T>::load_contract_or_delegated(&address).map(
+				|(contract, _)| contract.child_trie_info().prefixed_storage_key().into_inner(),
+			),
+		}
+	}
+
+	/// Uploads new PolkaVM or EVM runtime code and returns the Vm binary contract blob and deposit
+	/// amount collected.
+	fn try_upload_code(
 		origin: T::AccountId,
 		code: Vec<u8>,
 		storage_deposit_limit: BalanceOf<T>,
 		skip_transfer: bool,
 	) -> Result<(ContractBlob<T>, BalanceOf<T>), DispatchError> {
-		let mut module = ContractBlob::from_pvm_code(code, origin)?;
+		let mut module = ContractBlob::from_code(code, origin)?;
 		let deposit = module.store_code(skip_transfer)?;
 		ensure!(storage_deposit_limit >= deposit, <Error<T>>::StorageDepositLimitExhausted);
 		Ok((module, deposit))
@@ -1600,6 +1905,10 @@ impl<T: Config> Pallet<T> {
 	pub fn block_author() -> Option<H160> {
 		use frame_support::traits::FindAuthor;
 
+		if let Some(author) = simulate::block_author() {
+			return Some(author);
+		}
+
 		let digest = <frame_system::Pallet<T>>::digest();
 		let pre_runtime_digests = digest.logs.iter().filter_map(|d| d.as_pre_runtime());
 
@@ -1607,6 +1916,14 @@ impl<T: Config> Pallet<T> {
 		Some(T::AddressMapper::to_address(&account_id))
 	}
 
+	/// Returns the code upgrade announced by the contract at `address`, if any.
+	///
+	/// Wallets can use this to warn users before interacting with a contract whose code is
+	/// about to change.
+	pub fn pending_code_upgrade(address: &H160) -> Option<PendingCodeUpgrade<BlockNumberFor<T>>> {
+		PendingCodeUpgrades::<T>::get(address)
+	}
+
 	/// Returns the code at `address`.
 	///
 	/// This takes pre-compiles into account.
@@ -1615,6 +1932,9 @@ impl<T: Config> Pallet<T> {
 		if let Some(code) = <All<T>>::code(address.as_fixed_bytes()) {
 			return code.into()
 		}
+		if let Some(target) = AccountInfo::<T>::delegation_target(address) {
+			return AccountInfo::<T>::delegation_designator(&target)
+		}
 		AccountInfo::<T>::load_contract(&address)
 			.and_then(|contract| <PristineCode<T>>::get(contract.code_hash))
 			.map(|code| code.into())
@@ -1634,7 +1954,7 @@ environmental!(executing_contract: bool);
 
 sp_api::decl_runtime_apis! {
 	/// The API used to dry-run contract interactions.
-	#[api_version(1)]
+	#[api_version(2)]
 	pub trait ReviveApi<AccountId, Balance, Nonce, BlockNumber> where
 		AccountId: Codec,
 		Balance: Codec,
@@ -1653,6 +1973,19 @@ sp_api::decl_runtime_apis! {
 		/// Returns the nonce of the given `[H160]` address.
 		fn nonce(address: H160) -> Nonce;
 
+		/// Perform a call from a specified account to a given contract.
+		///
+		/// See [`crate::Pallet::bare_call`].
+		#[changed_in(2)]
+		fn call(
+			origin: AccountId,
+			dest: H160,
+			value: Balance,
+			gas_limit: Option<Weight>,
+			storage_deposit_limit: Option<Balance>,
+			input_data: Vec<u8>,
+		) -> ContractResultV1<ExecReturnValue, Balance>;
+
 		/// Perform a call from a specified account to a given contract.
 		///
 		/// See [`crate::Pallet::bare_call`].
@@ -1665,6 +1998,20 @@ sp_api::decl_runtime_apis! {
 			input_data: Vec<u8>,
 		) -> ContractResult<ExecReturnValue, Balance>;
 
+		/// Instantiate a new contract.
+		///
+		/// See `[crate::Pallet::bare_instantiate]`.
+		#[changed_in(2)]
+		fn instantiate(
+			origin: AccountId,
+			value: Balance,
+			gas_limit: Option<Weight>,
+			storage_deposit_limit: Option<Balance>,
+			code: Code,
+			data: Vec<u8>,
+			salt: Option<[u8; 32]>,
+		) -> ContractResultV1<InstantiateReturnValue, Balance>;
+
 		/// Instantiate a new contract.
 		///
 		/// See `[crate::Pallet::bare_instantiate]`.
@@ -1682,7 +2029,16 @@ sp_api::decl_runtime_apis! {
 		/// Perform an Ethereum call.
 		///
 		/// See [`crate::Pallet::dry_run_eth_transact`]
-		fn eth_transact(tx: GenericTransaction) -> Result<EthTransactInfo<Balance>, EthTransactError>;
+		#[changed_in(2)]
+		fn eth_transact(tx: GenericTransaction) -> Result<EthTransactInfoV1<Balance>, EthTransactError>;
+
+		/// Perform an Ethereum call, after applying the given state overrides.
+		///
+		/// See [`crate::Pallet::dry_run_eth_transact`] and [`crate::Pallet::apply_state_overrides`].
+		fn eth_transact(
+			tx: GenericTransaction,
+			state_overrides: Option<StateOverrides>,
+		) -> Result<EthTransactInfo<Balance>, EthTransactError>;
 
 		/// Upload new code without instantiating a contract from it.
 		///
@@ -1713,6 +2069,12 @@ sp_api::decl_runtime_apis! {
 			key: Vec<u8>,
 		) -> GetStorageResult;
 
+		/// Returns the storage keys needed to prove the account at `address`.
+		///
+		/// See eth-rpc `eth_getProof` for usage.
+		#[api_version(2)]
+		fn account_proof_keys(address: H160) -> AccountProofKeys;
+
 		/// Traces the execution of an entire block and returns call traces.
 		///
 		/// This is intended to be called through `state_call` to replay the block from the
@@ -1741,6 +2103,20 @@ sp_api::decl_runtime_apis! {
 		/// See eth-rpc `debug_traceCall` for usage.
 		fn trace_call(tx: GenericTransaction, config: TracerType) -> Result<Trace, EthTransactError>;
 
+		/// Dry run the given call and return the accounts and storage slots it accesses, along with
+		/// the gas used when sending the transaction with this access list.
+		///
+		/// See eth-rpc `eth_createAccessList` for usage.
+		#[api_version(2)]
+		fn create_access_list(tx: GenericTransaction) -> Result<AccessListResult, EthTransactError>;
+
+		/// Dry run the calls of the given blocks in order, applying the state and block overrides
+		/// of each block before its calls.
+		///
+		/// See eth-rpc `eth_simulateV1` for usage.
+		#[api_version(2)]
+		fn simulate(blocks: Vec<SimulateBlock>) -> Result<Vec<SimulatedBlock>, EthTransactError>;
+
 		/// The address of the validator that produced the current block.
 		fn block_author() -> Option<H160>;
 
@@ -1752,6 +2128,12 @@ sp_api::decl_runtime_apis! {
 
 		/// The code at the specified address taking pre-compiles into account.
 		fn code(address: H160) -> Vec<u8>;
+
+		/// The code upgrade announced by the contract at the specified address, if any.
+		///
+		/// See [`crate::Pallet::pending_code_upgrade`].
+		#[api_version(2)]
+		fn pending_code_upgrade(address: H160) -> Option<PendingCodeUpgrade<BlockNumber>>;
 	}
 }
 
@@ -1799,6 +2181,7 @@ macro_rules! impl_runtime_apis_plus_revive {
 
 				fn eth_transact(
 					tx: $crate::evm::GenericTransaction,
+					state_overrides: Option<$crate::evm::StateOverrides>,
 				) -> Result<$crate::EthTransactInfo<Balance>, $crate::EthTransactError> {
 					use $crate::{
 						codec::Encode, evm::runtime::EthExtra, frame_support::traits::Get,
@@ -1826,9 +2209,15 @@ macro_rules! impl_runtime_apis_plus_revive {
 						)
 					};
 
+					if let Some(state_overrides) = state_overrides {
+						$crate::Pallet::<Self>::apply_state_overrides(state_overrides)?;
+					}
+
 					let blockweights: $crate::BlockWeights =
 						<Self as $crate::frame_system::Config>::BlockWeights::get();
-					$crate::Pallet::<Self>::dry_run_eth_transact(tx, blockweights.max_block, tx_fee)
+					$crate::debug::collect(|| {
+						$crate::Pallet::<Self>::dry_run_eth_transact(tx, blockweights.max_block, tx_fee)
+					})
 				}
 
 				fn call(
@@ -1844,14 +2233,16 @@ macro_rules! impl_runtime_apis_plus_revive {
 						<Self as $crate::frame_system::Config>::BlockWeights::get();
 
 					$crate::Pallet::<Self>::prepare_dry_run(&origin);
-					$crate::Pallet::<Self>::bare_call(
-						<Self as $crate::frame_system::Config>::RuntimeOrigin::signed(origin),
-						dest,
-						$crate::Pallet::<Self>::convert_native_to_evm(value),
-						gas_limit.unwrap_or(blockweights.max_block),
-						$crate::DepositLimit::Balance(storage_deposit_limit.unwrap_or(u128::MAX)),
-						input_data,
-					)
+					$crate::debug::collect(|| {
+						$crate::Pallet::<Self>::bare_call(
+							<Self as $crate::frame_system::Config>::RuntimeOrigin::signed(origin),
+							dest,
+							$crate::Pallet::<Self>::convert_native_to_evm(value),
+							gas_limit.unwrap_or(blockweights.max_block),
+							$crate::DepositLimit::Balance(storage_deposit_limit.unwrap_or(u128::MAX)),
+							input_data,
+						)
+					})
 				}
 
 				fn instantiate(
@@ -1868,16 +2259,18 @@ macro_rules! impl_runtime_apis_plus_revive {
 						<Self as $crate::frame_system::Config>::BlockWeights::get();
 
 					$crate::Pallet::<Self>::prepare_dry_run(&origin);
-					$crate::Pallet::<Self>::bare_instantiate(
-						<Self as $crate::frame_system::Config>::RuntimeOrigin::signed(origin),
-						$crate::Pallet::<Self>::convert_native_to_evm(value),
-						gas_limit.unwrap_or(blockweights.max_block),
-						$crate::DepositLimit::Balance(storage_deposit_limit.unwrap_or(u128::MAX)),
-						code,
-						data,
-						salt,
-						$crate::BumpNonce::Yes,
-					)
+					$crate::debug::collect(|| {
+						$crate::Pallet::<Self>::bare_instantiate(
+							<Self as $crate::frame_system::Config>::RuntimeOrigin::signed(origin),
+							$crate::Pallet::<Self>::convert_native_to_evm(value),
+							gas_limit.unwrap_or(blockweights.max_block),
+							$crate::DepositLimit::Balance(storage_deposit_limit.unwrap_or(u128::MAX)),
+							code,
+							data,
+							salt,
+							$crate::BumpNonce::Yes,
+						)
+					})
 				}
 
 				fn upload_code(
@@ -1905,6 +2298,10 @@ macro_rules! impl_runtime_apis_plus_revive {
 					$crate::Pallet::<Self>::get_storage(address, key)
 				}
 
+				fn account_proof_keys(address: $crate::H160) -> $crate::AccountProofKeys {
+					$crate::Pallet::<Self>::account_proof_keys(address)
+				}
+
 				fn trace_block(
 					block: Block,
 					tracer_type: $crate::evm::TracerType,
@@ -1960,7 +2357,7 @@ macro_rules! impl_runtime_apis_plus_revive {
 
 					t.watch_address(&tx.from.unwrap_or_default());
 					t.watch_address(&$crate::Pallet::<Self>::block_author().unwrap_or_default());
-					let result = trace(t, || Self::eth_transact(tx));
+					let result = trace(t, || Self::eth_transact(tx, None));
 
 					if let Some(trace) = tracer.collect_trace() {
 						Ok(trace)
@@ -1971,6 +2368,39 @@ macro_rules! impl_runtime_apis_plus_revive {
 					}
 				}
 
+				fn create_access_list(
+					tx: $crate::evm::GenericTransaction,
+				) -> Result<$crate::evm::AccessListResult, $crate::EthTransactError> {
+					use $crate::{
+						frame_support::storage::{
+							transactional::with_transaction_opaque_err, TransactionOutcome,
+						},
+						tracing::trace,
+					};
+					let mut tracer = $crate::evm::AccessListTracer::new();
+
+					// Discard the state changes of the traced dry run, so that the dry run with the
+					// access list starts from the same state.
+					with_transaction_opaque_err(|| {
+						TransactionOutcome::Rollback(trace(&mut tracer, || Self::eth_transact(tx.clone(), None)))
+					})
+					.map_err(|_| $crate::EthTransactError::Message("Transaction limit reached".into()))??;
+
+					let access_list = tracer.collect_access_list();
+					let info = Self::eth_transact(
+						$crate::evm::GenericTransaction { access_list: Some(access_list.clone()), ..tx },
+						None,
+					)?;
+
+					Ok($crate::evm::AccessListResult { access_list, gas_used: info.eth_gas })
+				}
+
+				fn simulate(
+					blocks: Vec<$crate::evm::SimulateBlock>,
+				) -> Result<Vec<$crate::evm::SimulatedBlock>, $crate::EthTransactError> {
+					$crate::Pallet::<Self>::simulate(blocks, |tx| Self::eth_transact(tx, None))
+				}
+
 				fn runtime_pallets_address() -> $crate::H160 {
 					$crate::RUNTIME_PALLETS_ADDR
 				}
@@ -1978,6 +2408,12 @@ macro_rules! impl_runtime_apis_plus_revive {
 				fn code(address: $crate::H160) -> Vec<u8> {
 					$crate::Pallet::<Self>::code(&address)
 				}
+
+				fn pending_code_upgrade(
+					address: $crate::H160,
+				) -> Option<$crate::PendingCodeUpgrade<BlockNumber>> {
+					$crate::Pallet::<Self>::pending_code_upgrade(&address)
+				}
 			}
 		}
 	};
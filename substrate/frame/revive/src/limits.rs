@@ -73,6 +73,31 @@ pub const PAGE_SIZE: u32 = 4 * 1024;
 /// Which should always be enough because Solidity allows for 16 local (stack) variables.
 pub const IMMUTABLE_BYTES: u32 = 4 * 1024;
 
+/// The maximum amount of memory an EVM contract can use, in bytes.
+///
+/// This stays well below [`code::BASELINE_MEMORY_LIMIT`] so that an EVM frame never requires more
+/// memory than a PolkaVM frame.
+pub const EVM_MEMORY_BYTES: u32 = 1024 * 1024;
+
+/// The maximum number of items on the stack of an EVM contract.
+pub const EVM_STACK_LIMIT: u32 = 1024;
+
+/// The maximum number of storage items of a contract that can be tombstoned.
+///
+/// A tombstoned contract is restored from its whole storage in a single extrinsic, so contracts
+/// storing more items never expire.
+pub const TOMBSTONE_STORAGE_ITEMS: u32 = 1024;
+
+/// The maximum number of storage bytes of a contract that can be tombstoned.
+///
+/// See [`TOMBSTONE_STORAGE_ITEMS`].
+pub const TOMBSTONE_STORAGE_BYTES: u32 = 128 * 1024;
+
+/// The maximum size of the debug messages collected during a dry-run, in bytes.
+///
+/// This isn't part of [`MEMORY_REQUIRED`] as debug messages are never collected on-chain.
+pub const DEBUG_BUFFER_BYTES: u32 = 2 * 1024 * 1024;
+
 /// Limits that are only enforced on code upload.
 ///
 /// # Note
@@ -92,6 +117,12 @@ pub mod code {
 	/// have a maximum encoded length.
 	pub const BLOB_BYTES: u32 = 1024 * 1024;
 
+	/// The maximum length of EVM runtime code in bytes, see [EIP-170](https://eips.ethereum.org/EIPS/eip-170).
+	pub const EVM_BYTES: u32 = 24 * 1024;
+
+	/// The maximum length of EVM init code in bytes, see [EIP-3860](https://eips.ethereum.org/EIPS/eip-3860).
+	pub const EVM_INIT_BYTES: u32 = 2 * EVM_BYTES;
+
 	/// The maximum amount of memory the interpreter is allowed to use for compilation artifacts.
 	pub const INTERPRETER_CACHE_BYTES: u32 = 1024 * 1024;
 
@@ -20,5 +20,8 @@
 /// Migrations from the old `ContractInfoOf` to the new `AccountInfoOf` storage
 pub mod v1;
 
+/// Migrations from the old `CodeInfoOf` to the one recording the type of the code
+pub mod v2;
+
 /// A unique identifier across all pallets.
 const PALLET_MIGRATIONS_ID: &[u8; 17] = b"pallet-revive-mbm";
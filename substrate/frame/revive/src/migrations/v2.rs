@@ -0,0 +1,180 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Multi-Block Migration v2
+//!
+//! This migrates the old `CodeInfoOf` storage to the new `CodeInfo` recording the type of the
+//! code.
+
+extern crate alloc;
+
+use super::PALLET_MIGRATIONS_ID;
+use crate::{weights::WeightInfo, CodeInfo, CodeInfoOf, Config, H256};
+use frame_support::{
+	migrations::{MigrationId, SteppedMigration, SteppedMigrationError},
+	pallet_prelude::PhantomData,
+	weights::WeightMeter,
+};
+
+#[cfg(feature = "try-runtime")]
+use alloc::collections::btree_map::BTreeMap;
+
+#[cfg(feature = "try-runtime")]
+use alloc::vec::Vec;
+
+/// Module containing the old storage items.
+pub mod old {
+	use super::Config;
+	use crate::{pallet::Pallet, AccountIdOf, BalanceOf, H256};
+	use codec::{Decode, Encode, MaxEncodedLen};
+	use frame_support::{storage_alias, Identity};
+
+	/// The code info before the type of the code was recorded.
+	#[derive(Clone, Encode, Decode, scale_info::TypeInfo, MaxEncodedLen)]
+	#[codec(mel_bound())]
+	#[scale_info(skip_type_params(T))]
+	pub struct CodeInfo<T: Config> {
+		pub owner: AccountIdOf<T>,
+		#[codec(compact)]
+		pub deposit: BalanceOf<T>,
+		#[codec(compact)]
+		pub refcount: u64,
+		pub code_len: u32,
+		pub behaviour_version: u32,
+	}
+
+	#[storage_alias]
+	/// The storage item that is being migrated from.
+	pub type CodeInfoOf<T: Config> = StorageMap<Pallet<T>, Identity, H256, CodeInfo<T>>;
+}
+
+/// Migrates the items of the [`old::CodeInfoOf`] map into [`crate::CodeInfoOf`].
+pub struct Migration<T: Config>(PhantomData<T>);
+
+impl<T: Config> SteppedMigration for Migration<T> {
+	type Cursor = H256;
+	type Identifier = MigrationId<17>;
+
+	fn id() -> Self::Identifier {
+		MigrationId { pallet_id: *PALLET_MIGRATIONS_ID, version_from: 1, version_to: 2 }
+	}
+
+	fn step(
+		mut cursor: Option<Self::Cursor>,
+		meter: &mut WeightMeter,
+	) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+		let required = <T as Config>::WeightInfo::v2_migration_step();
+		if meter.remaining().any_lt(required) {
+			return Err(SteppedMigrationError::InsufficientWeight { required });
+		}
+
+		loop {
+			if meter.try_consume(required).is_err() {
+				break;
+			}
+
+			let mut iter = if let Some(last_key) = cursor {
+				old::CodeInfoOf::<T>::iter_from(old::CodeInfoOf::<T>::hashed_key_for(last_key))
+			} else {
+				old::CodeInfoOf::<T>::iter()
+			};
+
+			if let Some((last_key, value)) = iter.next() {
+				CodeInfoOf::<T>::insert(last_key, CodeInfo::<T>::from(value));
+				cursor = Some(last_key)
+			} else {
+				cursor = None;
+				break
+			}
+		}
+		Ok(cursor)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, frame_support::sp_runtime::TryRuntimeError> {
+		use codec::Encode;
+
+		// Return the state of the storage before the migration.
+		Ok(old::CodeInfoOf::<T>::iter().collect::<BTreeMap<_, _>>().encode())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(prev: Vec<u8>) -> Result<(), frame_support::sp_runtime::TryRuntimeError> {
+		use codec::Decode;
+
+		// Check the state of the storage after the migration.
+		let prev_map = BTreeMap::<H256, old::CodeInfo<T>>::decode(&mut &prev[..])
+			.expect("Failed to decode the previous storage state");
+
+		// Check the len of prev and post are the same.
+		assert_eq!(
+			CodeInfoOf::<T>::iter().count(),
+			prev_map.len(),
+			"Migration failed: the number of items in the storage after the migration is not the same as before"
+		);
+
+		for (key, value) in prev_map {
+			let new_value =
+				CodeInfoOf::<T>::get(&key).expect("Migration failed: code info missing");
+			assert_eq!(
+				(new_value.deposit(), new_value.code_len()),
+				(value.deposit, value.code_len.into()),
+				"Migration failed: the value after the migration is not the same as before"
+			);
+			assert_eq!(new_value.code_type(), crate::BytecodeType::Pvm);
+		}
+
+		Ok(())
+	}
+}
+
+#[test]
+fn migrate_to_v2() {
+	use crate::{
+		test_utils::ALICE,
+		tests::{ExtBuilder, Test},
+		BytecodeType,
+	};
+	ExtBuilder::default().build().execute_with(|| {
+		for i in 0..10u8 {
+			old::CodeInfoOf::<Test>::insert(
+				H256::repeat_byte(i),
+				old::CodeInfo {
+					owner: ALICE,
+					deposit: i.into(),
+					refcount: 1,
+					code_len: i.into(),
+					behaviour_version: 0,
+				},
+			);
+		}
+
+		let mut cursor = None;
+		let mut weight_meter = WeightMeter::new();
+		while let Some(new_cursor) = Migration::<Test>::step(cursor, &mut weight_meter).unwrap() {
+			cursor = Some(new_cursor);
+		}
+
+		assert_eq!(CodeInfoOf::<Test>::iter().count(), 10);
+		for i in 0..10u8 {
+			let code_info = CodeInfoOf::<Test>::get(H256::repeat_byte(i)).unwrap();
+			assert_eq!(code_info.deposit(), i.into());
+			assert_eq!(code_info.code_len(), i.into());
+			assert_eq!(code_info.code_type(), BytecodeType::Pvm);
+		}
+	})
+}
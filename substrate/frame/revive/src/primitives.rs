@@ -17,7 +17,7 @@
 
 //! A crate that hosts a common definitions that are relevant for the pallet-revive.
 
-use crate::{BalanceOf, Config, Error, H160, U256};
+use crate::{storage::meter::Diff, BalanceOf, Config, Error, H160, U256};
 use alloc::{string::String, vec::Vec};
 use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::weights::Weight;
@@ -70,9 +70,8 @@ impl<T: Bounded + Copy> DepositLimit<T> {
 ///
 /// #Note
 ///
-/// It has been extended to include `events` at the end of the struct while not bumping the
-/// `ContractsApi` version. Therefore when SCALE decoding a `ContractResult` its trailing data
-/// should be ignored to avoid any potential compatibility issues.
+/// The `debug_message` field was added in version 2 of the `ReviveApi`. Earlier versions return
+/// a [`ContractResultV1`] instead.
 #[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub struct ContractResult<R, Balance> {
 	/// How much weight was consumed during execution.
@@ -97,9 +96,38 @@ pub struct ContractResult<R, Balance> {
 	pub storage_deposit: StorageDeposit<Balance>,
 	/// The execution result of the vm binary code.
 	pub result: Result<R, DispatchError>,
+	/// The debug messages emitted by the contracts, concatenated.
+	///
+	/// This is only filled when the call is dry-run within [`crate::debug::collect`].
+	pub debug_message: Vec<u8>,
+}
+
+/// The [`ContractResult`] returned by version 1 of the `ReviveApi`, without debug messages.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct ContractResultV1<R, Balance> {
+	/// See [`ContractResult::gas_consumed`].
+	pub gas_consumed: Weight,
+	/// See [`ContractResult::gas_required`].
+	pub gas_required: Weight,
+	/// See [`ContractResult::storage_deposit`].
+	pub storage_deposit: StorageDeposit<Balance>,
+	/// See [`ContractResult::result`].
+	pub result: Result<R, DispatchError>,
+}
+
+impl<R, Balance> From<ContractResultV1<R, Balance>> for ContractResult<R, Balance> {
+	fn from(result: ContractResultV1<R, Balance>) -> Self {
+		let ContractResultV1 { gas_consumed, gas_required, storage_deposit, result } = result;
+		Self { gas_consumed, gas_required, storage_deposit, result, debug_message: Vec::new() }
+	}
 }
 
 /// The result of the execution of a `eth_transact` call.
+///
+/// # Note
+///
+/// The `debug_message` field was added in version 2 of the `ReviveApi`. Earlier versions return
+/// an [`EthTransactInfoV1`] instead.
 #[derive(Clone, Eq, PartialEq, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub struct EthTransactInfo<Balance> {
 	/// The amount of gas that was necessary to execute the transaction.
@@ -110,6 +138,28 @@ pub struct EthTransactInfo<Balance> {
 	pub eth_gas: U256,
 	/// The execution return value.
 	pub data: Vec<u8>,
+	/// The debug messages emitted by the contracts, concatenated.
+	pub debug_message: Vec<u8>,
+}
+
+/// The [`EthTransactInfo`] returned by version 1 of the `ReviveApi`, without debug messages.
+#[derive(Clone, Eq, PartialEq, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct EthTransactInfoV1<Balance> {
+	/// See [`EthTransactInfo::gas_required`].
+	pub gas_required: Weight,
+	/// See [`EthTransactInfo::storage_deposit`].
+	pub storage_deposit: Balance,
+	/// See [`EthTransactInfo::eth_gas`].
+	pub eth_gas: U256,
+	/// See [`EthTransactInfo::data`].
+	pub data: Vec<u8>,
+}
+
+impl<Balance> From<EthTransactInfoV1<Balance>> for EthTransactInfo<Balance> {
+	fn from(info: EthTransactInfoV1<Balance>) -> Self {
+		let EthTransactInfoV1 { gas_required, storage_deposit, eth_gas, data } = info;
+		Self { gas_required, storage_deposit, eth_gas, data, debug_message: Vec::new() }
+	}
 }
 
 /// Error type of a `eth_transact` call.
@@ -196,6 +246,57 @@ pub enum ContractAccessError {
 	KeyDecodingFailed,
 }
 
+/// The storage keys proving an account, see `eth_getProof`.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct AccountProofKeys {
+	/// The key of the `frame_system::Account` entry of the account, holding its nonce and balance.
+	pub system_account: Vec<u8>,
+	/// The key of the [`crate::AccountInfo`] entry of the account.
+	pub account_info: Vec<u8>,
+	/// The prefixed storage key of the contract's child trie, if the account is a contract.
+	///
+	/// The root of the child trie is stored under this key in the main trie.
+	pub child_trie: Option<Vec<u8>>,
+}
+
+/// A code upgrade announced by a contract, see [`crate::Config::CodeUpgradeDelay`].
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, MaxEncodedLen, RuntimeDebug, TypeInfo)]
+pub struct PendingCodeUpgrade<BlockNumber> {
+	/// The code hash the contract will switch to.
+	pub code_hash: sp_core::H256,
+	/// The first block at which the contract can switch to the new code.
+	pub enact_at: BlockNumber,
+}
+
+impl<BlockNumber: MaxEncodedLen> PendingCodeUpgrade<BlockNumber> {
+	/// The storage charged to the contract for announcing an upgrade.
+	pub(crate) fn deposit_diff() -> Diff {
+		Diff { bytes_added: Self::max_encoded_len() as u32, items_added: 1, ..Default::default() }
+	}
+
+	/// The storage refunded to the contract once its upgrade is enacted or cancelled.
+	pub(crate) fn refund_diff() -> Diff {
+		Diff {
+			bytes_removed: Self::max_encoded_len() as u32,
+			items_removed: 1,
+			..Default::default()
+		}
+	}
+}
+
+/// The expiry of contracts that were not touched for a while, see
+/// [`crate::Pallet::set_contract_expiry`].
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, MaxEncodedLen, RuntimeDebug, TypeInfo)]
+pub struct ContractExpiry<BlockNumber> {
+	/// The number of blocks after which a contract that was not touched can be tombstoned.
+	pub period: BlockNumber,
+	/// The block at which the expiry was enabled.
+	///
+	/// Contracts are considered to be touched at this block at the latest, as they are not
+	/// tracked while the expiry is disabled.
+	pub enabled_at: BlockNumber,
+}
+
 /// Output of a contract call or instantiation which ran to completion.
 #[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo, Default)]
 pub struct ExecReturnValue {
@@ -0,0 +1,328 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Simulation of calls on top of overridden state, as done by `eth_call`, `eth_estimateGas` and
+//! `eth_simulateV1`.
+//!
+//! The simulation runs inside of a runtime API call: all the state changes made here, including
+//! the state and block overrides, are discarded once the call returns.
+
+use crate::{
+	address::AddressMapper,
+	evm::{
+		GenericTransaction, Log, LogTracer, SimulateBlock, SimulateCallError, SimulateCallResult,
+		SimulatedBlock, StateOverride, StateOverrides,
+	},
+	exec::{Executable, Key},
+	storage::{AccountInfo, ContractInfo},
+	tracing::trace,
+	vm::{CodeInfo, ContractBlob},
+	AccountInfoOf, BalanceOf, BalanceWithDust, Config, EthTransactError, EthTransactInfo, MomentOf,
+	Pallet, H160, H256, U256,
+};
+use alloc::{format, string::ToString, vec::Vec};
+use environmental::environmental;
+use frame_support::{
+	storage::{child, transactional::with_transaction_opaque_err, TransactionOutcome},
+	traits::{
+		fungible::{Inspect, Mutate},
+		Time,
+	},
+};
+use frame_system::{pallet_prelude::BlockNumberFor, Pallet as System};
+use sp_runtime::{
+	traits::{Bounded, Saturating},
+	SaturatedConversion,
+};
+
+/// The maximum number of blocks that can be simulated at once.
+const MAX_SIMULATED_BLOCKS: usize = 256;
+
+/// The time between two simulated blocks, in seconds, unless overridden.
+const BLOCK_TIME: u64 = 12;
+
+/// The header fields of the block being simulated.
+#[derive(Clone, Copy)]
+struct BlockContext {
+	/// The block timestamp, in milliseconds.
+	timestamp: u64,
+	/// The block author.
+	author: H160,
+}
+
+environmental!(block_context: BlockContext);
+
+/// The timestamp of the block being simulated, if any.
+pub(crate) fn timestamp<T: Config>() -> Option<MomentOf<T>> {
+	block_context::with(|context| context.timestamp.saturated_into())
+}
+
+/// The author of the block being simulated, if any.
+pub(crate) fn block_author() -> Option<H160> {
+	block_context::with(|context| context.author)
+}
+
+impl<T: Config> Pallet<T>
+where
+	BalanceOf<T>: Into<U256> + TryFrom<U256> + Bounded,
+	MomentOf<T>: Into<U256>,
+	T::Hash: frame_support::traits::IsType<H256>,
+{
+	/// Simulate the given blocks on top of the current state.
+	///
+	/// For each block, the state overrides are applied before its calls are executed in order with
+	/// `eth_transact`. The state changes of a block or a call are visible to the following ones,
+	/// including the sender nonce bumped by `eth_transact`. Calls that fail to execute, e.g.
+	/// because the sender has insufficient funds, leave no trace in the state.
+	///
+	/// This function is public because it is called by the runtime API implementation
+	/// (see `impl_runtime_apis_plus_revive`).
+	pub fn simulate(
+		blocks: Vec<SimulateBlock>,
+		eth_transact: impl Fn(
+			GenericTransaction,
+		) -> Result<EthTransactInfo<BalanceOf<T>>, EthTransactError>,
+	) -> Result<Vec<SimulatedBlock>, EthTransactError> {
+		if blocks.len() > MAX_SIMULATED_BLOCKS {
+			return Err(EthTransactError::Message(format!(
+				"too many blocks, the maximum is {MAX_SIMULATED_BLOCKS}"
+			)));
+		}
+
+		let author = Self::block_author().unwrap_or_default();
+		let mut number: U256 = System::<T>::block_number().into();
+		let now: U256 = T::Time::now().into();
+		let mut timestamp = now / U256::from(1000);
+
+		let mut results = Vec::with_capacity(blocks.len());
+		for block in blocks {
+			let overrides = block.block_overrides.unwrap_or_default();
+
+			let next_number =
+				overrides.number.unwrap_or_else(|| number.saturating_add(U256::one()));
+			if next_number <= number {
+				return Err(EthTransactError::Message(format!(
+					"block numbers must be strictly increasing: {next_number} <= {number}"
+				)));
+			}
+			let next_timestamp = overrides
+				.time
+				.unwrap_or_else(|| timestamp.saturating_add(U256::from(BLOCK_TIME)));
+			if next_timestamp <= timestamp {
+				return Err(EthTransactError::Message(format!(
+					"block timestamps must be strictly increasing: {next_timestamp} <= {timestamp}"
+				)));
+			}
+			number = next_number;
+			timestamp = next_timestamp;
+
+			let block_number = BlockNumberFor::<T>::try_from(number)
+				.map_err(|_| EthTransactError::Message("block number overflow".into()))?;
+			System::<T>::set_block_number(block_number);
+
+			Self::apply_state_overrides(block.state_overrides.unwrap_or_default())?;
+
+			let mut context = BlockContext {
+				timestamp: timestamp
+					.saturating_mul(U256::from(1000))
+					.min(U256::from(u64::MAX))
+					.as_u64(),
+				author: overrides.fee_recipient.unwrap_or(author),
+			};
+			let mut log_index = 0u32;
+			let mut gas_used = U256::zero();
+			let mut calls = Vec::with_capacity(block.calls.len());
+
+			for (index, tx) in block.calls.into_iter().enumerate() {
+				let mut tracer = LogTracer::new();
+				let result = block_context::using(&mut context, || {
+					with_transaction_opaque_err(|| {
+						let result = trace(&mut tracer, || eth_transact(tx));
+						if matches!(result, Err(EthTransactError::Message(_))) {
+							TransactionOutcome::Rollback(result)
+						} else {
+							TransactionOutcome::Commit(result)
+						}
+					})
+				})
+				.map_err(|_| EthTransactError::Message("Transaction limit reached".into()))?;
+
+				let logs = tracer
+					.collect_logs()
+					.into_iter()
+					.map(|log| {
+						let log = Log {
+							block_number: number,
+							log_index: log_index.into(),
+							transaction_index: index.into(),
+							..log
+						};
+						log_index += 1;
+						log
+					})
+					.collect();
+
+				let call = match result {
+					Ok(info) => SimulateCallResult {
+						status: U256::one(),
+						return_data: info.data.into(),
+						gas_used: info.eth_gas,
+						logs,
+						error: None,
+					},
+					Err(EthTransactError::Data(data)) => SimulateCallResult {
+						return_data: data.clone().into(),
+						error: Some(SimulateCallError {
+							code: SimulateCallError::REVERTED,
+							message: "execution reverted".to_string(),
+							data: Some(data.into()),
+						}),
+						..Default::default()
+					},
+					Err(EthTransactError::Message(message)) => SimulateCallResult {
+						error: Some(SimulateCallError {
+							code: SimulateCallError::FAILED,
+							message,
+							data: None,
+						}),
+						..Default::default()
+					},
+				};
+				gas_used = gas_used.saturating_add(call.gas_used);
+				calls.push(call);
+			}
+
+			results.push(SimulatedBlock {
+				number,
+				timestamp,
+				gas_used,
+				miner: context.author,
+				calls,
+			});
+		}
+
+		Ok(results)
+	}
+
+	/// Apply the given state overrides, e.g. before dry running a call.
+	///
+	/// This function is public because it is called by the runtime API implementation
+	/// (see `impl_runtime_apis_plus_revive`).
+	pub fn apply_state_overrides(state_overrides: StateOverrides) -> Result<(), EthTransactError> {
+		for (address, state_override) in state_overrides {
+			Self::apply_state_override(&address, state_override)?;
+		}
+		Ok(())
+	}
+
+	/// Apply the given state override to the account at `address`.
+	fn apply_state_override(
+		address: &H160,
+		state_override: StateOverride,
+	) -> Result<(), EthTransactError> {
+		let account_id = T::AddressMapper::to_account_id(address);
+
+		if let Some(code) = state_override.code {
+			let mut module =
+				ContractBlob::<T>::from_code(code.0, account_id.clone()).map_err(|err| {
+					EthTransactError::Message(format!("invalid code override: {err:?}"))
+				})?;
+			module.store_code(true).map_err(|err| {
+				EthTransactError::Message(format!("failed to store code override: {err:?}"))
+			})?;
+			let code_hash = *module.code_hash();
+
+			let contract = match AccountInfo::<T>::load_contract(address) {
+				Some(mut contract) => {
+					let _ = <CodeInfo<T>>::decrement_refcount(contract.code_hash);
+					contract.code_hash = code_hash;
+					contract
+				},
+				None => {
+					// Contracts are expected to hold the existential deposit and a consumer
+					// reference, see `Stack::run`.
+					if !System::<T>::account_exists(&account_id) {
+						T::Currency::set_balance(&account_id, T::Currency::minimum_balance());
+					}
+					let _ = System::<T>::inc_consumers(&account_id);
+					let nonce = System::<T>::account_nonce(&account_id);
+					ContractInfo::<T>::new(address, nonce, code_hash).map_err(|err| {
+						EthTransactError::Message(format!("invalid code override: {err:?}"))
+					})?
+				},
+			};
+			let _ = <CodeInfo<T>>::increment_refcount(code_hash);
+			AccountInfo::<T>::insert_contract(address, contract);
+		}
+
+		if let Some(balance) = state_override.balance {
+			let (value, dust) = BalanceWithDust::<BalanceOf<T>>::from_value::<T>(balance)
+				.map_err(|err| {
+					EthTransactError::Message(format!("invalid balance override: {err:?}"))
+				})?
+				.deconstruct();
+			T::Currency::set_balance(
+				&account_id,
+				T::Currency::minimum_balance().saturating_add(value),
+			);
+			AccountInfoOf::<T>::mutate(address, |account| match account {
+				Some(account) => account.dust = dust,
+				None if dust > 0 => *account = Some(AccountInfo { dust, ..Default::default() }),
+				None => {},
+			});
+		}
+
+		if let Some(nonce) = state_override.nonce {
+			let nonce = u64::try_from(nonce)
+				.map_err(|_| EthTransactError::Message("nonce override overflow".into()))?;
+			frame_system::Account::<T>::mutate(&account_id, |account| {
+				account.nonce = nonce.saturated_into();
+			});
+		}
+
+		if state_override.state.is_none() && state_override.state_diff.is_none() {
+			return Ok(());
+		}
+
+		let Some(contract) = AccountInfo::<T>::load_contract(address) else {
+			return Err(EthTransactError::Message(format!(
+				"storage override of {address:?} requires a contract"
+			)));
+		};
+
+		let slots = match (state_override.state, state_override.state_diff) {
+			(Some(_), Some(_)) =>
+				return Err(EthTransactError::Message(format!(
+					"both state and stateDiff overrides are set for {address:?}"
+				))),
+			(Some(state), None) => {
+				let _ = child::clear_storage(&contract.child_trie_info(), None, None);
+				state
+			},
+			(None, state_diff) => state_diff.unwrap_or_default(),
+		};
+
+		for (slot, value) in slots {
+			let value = (!value.is_zero()).then(|| value.0.to_vec());
+			contract.write(&Key::Fix(slot.0), value, None, false).map_err(|err| {
+				EthTransactError::Message(format!("invalid storage override: {err:?}"))
+			})?;
+		}
+
+		Ok(())
+	}
+}
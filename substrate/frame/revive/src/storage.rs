@@ -17,6 +17,7 @@
 
 //! This module contains routines for accessing and altering a contract related state.
 
+pub mod expiry;
 pub mod meter;
 
 use crate::{
@@ -26,13 +27,14 @@ use crate::{
 	tracing::if_tracing,
 	weights::WeightInfo,
 	AccountInfoOf, BalanceOf, BalanceWithDust, Config, DeletionQueue, DeletionQueueCounter, Error,
-	TrieId, SENTINEL,
+	HoldReason, TrieId, LOG_TARGET, SENTINEL,
 };
 use alloc::vec::Vec;
 use codec::{Decode, Encode, MaxEncodedLen};
 use core::marker::PhantomData;
 use frame_support::{
 	storage::child::{self, ChildInfo},
+	traits::fungible::MutateHold,
 	weights::{Weight, WeightMeter},
 	CloneNoBound, DebugNoBound, DefaultNoBound,
 };
@@ -93,8 +95,24 @@ pub enum AccountType<T: Config> {
 	/// An account that is an externally owned account (EOA).
 	#[default]
 	EOA,
+
+	/// An externally owned account (EOA) that delegates its code to a contract, see
+	/// [EIP-7702](https://eips.ethereum.org/EIPS/eip-7702).
+	///
+	/// Calls to the account execute the code of `target` against the storage of the account,
+	/// which is kept in `contract_info`. A zero `target` means that the delegation was cleared.
+	Delegated {
+		/// The address of the contract the account delegates its code to.
+		target: H160,
+		/// The contract info of the account, holding its storage.
+		contract_info: ContractInfo<T>,
+	},
 }
 
+/// The prefix of the code of an account delegating its code, see
+/// [EIP-7702](https://eips.ethereum.org/EIPS/eip-7702).
+pub const DELEGATION_PREFIX: [u8; 3] = [0xef, 0x01, 0x00];
+
 /// Information for managing an account and its sub trie abstraction.
 /// This is the required info to cache for an account.
 #[derive(Encode, Decode, CloneNoBound, PartialEq, Eq, DebugNoBound, TypeInfo, MaxEncodedLen)]
@@ -176,11 +194,88 @@ impl<T: Config> AccountInfo<T> {
 		Some(contract_info)
 	}
 
+	/// Loads the contract information of a contract, or of an account delegating its code to a
+	/// contract, along with the address of the contract it delegates to.
+	pub fn load_contract_or_delegated(address: &H160) -> Option<(ContractInfo<T>, Option<H160>)> {
+		let Some(info) = <AccountInfoOf<T>>::get(address) else { return None };
+		match info.account_type {
+			AccountType::Contract(contract_info) => Some((contract_info, None)),
+			AccountType::Delegated { target, contract_info } if !target.is_zero() =>
+				Some((contract_info, Some(target))),
+			_ => None,
+		}
+	}
+
+	/// Returns the address of the contract the account at `address` delegates its code to.
+	pub fn delegation_target(address: &H160) -> Option<H160> {
+		Self::load_contract_or_delegated(address).and_then(|(_, target)| target)
+	}
+
+	/// Returns the code of an account delegating its code to `target`.
+	pub fn delegation_designator(target: &H160) -> Vec<u8> {
+		[&DELEGATION_PREFIX[..], target.as_bytes()].concat()
+	}
+
+	/// Delegate the code of the externally owned account at `address` to the contract at
+	/// `target`.
+	///
+	/// Delegating to the zero address clears the delegation, the storage of the account is kept.
+	///
+	/// The first delegation of an account creates the contract info holding its storage. Its
+	/// base deposit is held from the account itself, just like the deposit of a contract is held
+	/// from the account of the contract.
+	pub fn set_delegation(address: &H160, target: H160) -> Result<(), DispatchError> {
+		AccountInfoOf::<T>::try_mutate(address, |account| {
+			let account = account.get_or_insert_with(Default::default);
+			match &mut account.account_type {
+				AccountType::Contract(_) => return Err(Error::<T>::DuplicateContract.into()),
+				AccountType::Delegated { target: current, .. } => *current = target,
+				AccountType::EOA if target.is_zero() => {},
+				AccountType::EOA => {
+					let account_id = T::AddressMapper::to_account_id(address);
+					let (contract_info, deposit) = Self::delegation_contract_info(address)?;
+					T::Currency::hold(&HoldReason::StorageDepositReserve.into(), &account_id, deposit)
+						.map_err(|err| {
+							log::debug!(target: LOG_TARGET, "failed to hold delegation deposit {deposit:?} for {address:?}: {err:?}");
+							<Error<T>>::StorageDepositNotEnoughFunds
+						})?;
+					account.account_type = AccountType::Delegated { target, contract_info };
+				},
+			}
+			Ok(())
+		})
+	}
+
+	/// Returns the deposit held from an account on its first delegation, see
+	/// [`Self::set_delegation`].
+	pub fn delegation_deposit(address: &H160) -> Result<BalanceOf<T>, DispatchError> {
+		Self::delegation_contract_info(address).map(|(_, deposit)| deposit)
+	}
+
+	/// Creates the contract info holding the storage of an account delegating its code, along with
+	/// its base deposit.
+	fn delegation_contract_info(
+		address: &H160,
+	) -> Result<(ContractInfo<T>, BalanceOf<T>), DispatchError> {
+		let account_id = T::AddressMapper::to_account_id(address);
+		let nonce = frame_system::Pallet::<T>::account_nonce(&account_id);
+		let mut contract_info = ContractInfo::new(address, nonce, sp_core::H256::zero())?;
+		let deposit = contract_info.update_base_deposit(Zero::zero());
+		Ok((contract_info, deposit))
+	}
+
 	/// Insert a contract, existing dust if any will be unchanged.
+	///
+	/// The delegation of an account delegating its code is kept, only its contract info is
+	/// updated.
 	pub fn insert_contract(address: &H160, contract: ContractInfo<T>) {
 		AccountInfoOf::<T>::mutate(address, |account| {
 			if let Some(account) = account {
-				account.account_type = contract.clone().into();
+				match &mut account.account_type {
+					AccountType::Delegated { contract_info, .. } =>
+						*contract_info = contract.clone(),
+					account_type => *account_type = contract.clone().into(),
+				}
 			} else {
 				*account = Some(AccountInfo { account_type: contract.clone().into(), dust: 0 });
 			}
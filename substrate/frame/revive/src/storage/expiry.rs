@@ -0,0 +1,271 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Expiry of contracts that were not touched for a while.
+//!
+//! Once enabled through [`crate::Pallet::set_contract_expiry`], a contract that was not touched
+//! for the configured period can be tombstoned by anyone. Its child trie is queued for lazy
+//! deletion and its storage deposit is returned to the account that instantiated it. The tombstone
+//! keeps the root of the child trie so that anyone supplying the storage of the contract at the
+//! time it expired can restore it.
+//!
+//! The storage is restored in a single extrinsic, so only contracts whose storage stays within
+//! [`limits::TOMBSTONE_STORAGE_ITEMS`] and [`limits::TOMBSTONE_STORAGE_BYTES`] expire. The other
+//! payers of a storage deposit are not tracked, so only contracts whose whole deposit was paid by
+//! the account that instantiated them expire.
+
+use crate::{
+	address::AddressMapper,
+	exec::AccountIdOf,
+	limits,
+	storage::{meter::Diff, AccountInfo, AccountType, ContractInfo},
+	AccountInfoOf, BalanceOf, Config, ContractActivityOf, ContractExpiryOf, Error, HoldReason,
+	ImmutableData, ImmutableDataOf, PendingCodeUpgrade, PendingCodeUpgrades, Tombstones,
+};
+use alloc::vec::Vec;
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{
+	ensure,
+	storage::child,
+	traits::{
+		fungible::{InspectHold, MutateHold},
+		tokens::{Fortitude, Precision, Preservation, Restriction},
+		Get,
+	},
+	CloneNoBound, DebugNoBound,
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use scale_info::TypeInfo;
+use sp_core::{H160, H256};
+use sp_runtime::{
+	traits::{Hash, Saturating},
+	DispatchError, StateVersion,
+};
+
+/// The activity of a contract, used to determine whether it expired.
+#[derive(Encode, Decode, CloneNoBound, PartialEq, Eq, DebugNoBound, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(T))]
+pub struct ContractActivity<T: Config> {
+	/// The account that instantiated or restored the contract.
+	///
+	/// The storage deposit of the contract is returned to it when the contract is tombstoned.
+	pub depositor: AccountIdOf<T>,
+	/// The block at which the activity started to be recorded.
+	///
+	/// Deposits are not tracked while the expiry is disabled, so contracts whose activity was
+	/// recorded before the expiry was last enabled never expire.
+	pub recorded_at: BlockNumberFor<T>,
+	/// The last block at which the contract was touched while the expiry was enabled.
+	pub last_touched: BlockNumberFor<T>,
+	/// Whether another account than the depositor paid a part of the storage deposit.
+	///
+	/// Such contracts never expire, as the deposit could only be refunded to the depositor.
+	pub shared_deposit: bool,
+}
+
+/// The remains of a contract that expired.
+#[derive(Encode, Decode, CloneNoBound, PartialEq, Eq, DebugNoBound, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(T))]
+pub struct Tombstone<T: Config> {
+	/// The contract info at the time the contract expired.
+	///
+	/// Its trie is queued for deletion and replaced by a new one once the contract is restored.
+	pub contract_info: ContractInfo<T>,
+	/// The root of the child trie of the contract at the time it expired.
+	pub storage_root: H256,
+	/// The immutable data of the contract, if any.
+	pub immutable_data: Option<ImmutableData>,
+	/// The code upgrade announced by the contract, if any.
+	///
+	/// Its code hash stays referenced until the contract is restored.
+	pub pending_code_upgrade: Option<PendingCodeUpgrade<BlockNumberFor<T>>>,
+}
+
+impl<T: Config> ContractActivity<T> {
+	/// Record the instantiation of the contract at `address` by `depositor`.
+	///
+	/// Contracts instantiated before the activity was recorded never expire, as their depositor
+	/// is unknown.
+	pub fn instantiated(address: &H160, depositor: AccountIdOf<T>) {
+		let now = frame_system::Pallet::<T>::block_number();
+		ContractActivityOf::<T>::insert(
+			address,
+			Self { depositor, recorded_at: now, last_touched: now, shared_deposit: false },
+		);
+	}
+
+	/// The storage charged to the contract for its activity.
+	pub fn deposit_diff() -> Diff {
+		Diff { bytes_added: Self::max_encoded_len() as u32, items_added: 1, ..Default::default() }
+	}
+
+	/// Record that the contract at `address` was touched in the current block.
+	///
+	/// `payer` is the account which paid a storage deposit to the contract while touching it, if
+	/// any.
+	pub fn touch(address: &H160, payer: Option<&AccountIdOf<T>>) {
+		let now = frame_system::Pallet::<T>::block_number();
+		ContractActivityOf::<T>::mutate_extant(address, |activity| {
+			activity.last_touched = now;
+			if payer.is_some_and(|payer| *payer != activity.depositor) {
+				activity.shared_deposit = true;
+			}
+		});
+	}
+}
+
+impl<T: Config> Tombstone<T> {
+	/// Tombstone the contract at `address` if it expired.
+	///
+	/// The whole storage deposit held by the contract is refunded to the depositor, which is the
+	/// only account that paid it. The immutable data and the pending code upgrade of the contract
+	/// are moved to the tombstone.
+	///
+	/// Returns the depositor of the contract and the deposit it was refunded.
+	pub fn create(address: &H160) -> Result<(AccountIdOf<T>, BalanceOf<T>), DispatchError> {
+		let expiry = ContractExpiryOf::<T>::get().ok_or(Error::<T>::ContractExpiryDisabled)?;
+		let contract_info =
+			AccountInfo::<T>::load_contract(address).ok_or(Error::<T>::ContractNotFound)?;
+		let activity =
+			ContractActivityOf::<T>::take(address).ok_or(Error::<T>::ContractNotExpired)?;
+
+		ensure!(
+			!activity.shared_deposit && activity.recorded_at >= expiry.enabled_at,
+			Error::<T>::ContractDepositShared
+		);
+		let now = frame_system::Pallet::<T>::block_number();
+		let last_touched = activity.last_touched.max(expiry.enabled_at);
+		ensure!(now >= last_touched.saturating_add(expiry.period), Error::<T>::ContractNotExpired);
+		ensure!(
+			contract_info.storage_items <= limits::TOMBSTONE_STORAGE_ITEMS &&
+				contract_info.storage_bytes <= limits::TOMBSTONE_STORAGE_BYTES,
+			Error::<T>::TombstoneStorageTooLarge
+		);
+
+		let storage_root = Self::storage_root(&contract_info)?;
+		contract_info.queue_trie_for_deletion();
+
+		// The dust of the account is kept, the account is not a contract anymore.
+		AccountInfoOf::<T>::mutate_exists(address, |account| {
+			*account = account
+				.take()
+				.filter(|account| account.dust != 0)
+				.map(|account| AccountInfo { account_type: AccountType::EOA, ..account });
+		});
+
+		// The code stays referenced by the tombstone so that the contract can be restored. The
+		// account of the contract keeps its consumer and existential deposit for the same reason.
+		let account_id = T::AddressMapper::to_account_id(address);
+		let deposit =
+			T::Currency::balance_on_hold(&HoldReason::StorageDepositReserve.into(), &account_id);
+		let refunded = T::Currency::transfer_on_hold(
+			&HoldReason::StorageDepositReserve.into(),
+			&account_id,
+			&activity.depositor,
+			deposit,
+			Precision::BestEffort,
+			Restriction::Free,
+			Fortitude::Polite,
+		)?;
+
+		Tombstones::<T>::insert(
+			address,
+			Self {
+				contract_info,
+				storage_root,
+				immutable_data: ImmutableDataOf::<T>::take(address),
+				pending_code_upgrade: PendingCodeUpgrades::<T>::take(address),
+			},
+		);
+		Ok((activity.depositor, refunded))
+	}
+
+	/// Restore the tombstoned contract at `address` from its storage at the time it expired.
+	///
+	/// `storage` holds the raw key value pairs of the child trie of the contract, which must
+	/// hash to the root recorded in the tombstone. `restorer` pays the storage deposit of the
+	/// restored contract, which was refunded when it was tombstoned, and becomes its depositor.
+	///
+	/// Returns the storage deposit charged from `restorer`.
+	pub fn restore(
+		address: &H160,
+		restorer: &AccountIdOf<T>,
+		storage: Vec<(Vec<u8>, Vec<u8>)>,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		let Self { mut contract_info, storage_root, immutable_data, pending_code_upgrade } =
+			Tombstones::<T>::take(address).ok_or(Error::<T>::TombstoneNotFound)?;
+		// A contract might have been instantiated at the address while the expiry was disabled.
+		ensure!(!AccountInfo::<T>::is_contract(address), Error::<T>::DuplicateContract);
+		let value_bytes = storage
+			.iter()
+			.fold(0usize, |bytes, (_, value)| bytes.saturating_add(value.len()));
+		ensure!(
+			storage.len() <= limits::TOMBSTONE_STORAGE_ITEMS as usize &&
+				value_bytes <= limits::TOMBSTONE_STORAGE_BYTES as usize,
+			Error::<T>::TombstoneStorageTooLarge
+		);
+
+		// The previous trie might still be in the deletion queue, so a new one is derived from it.
+		contract_info.trie_id = ("bcontract_trie_v1", address, &contract_info.trie_id)
+			.using_encoded(T::Hashing::hash)
+			.as_ref()
+			.to_vec()
+			.try_into()
+			.expect("Runtime uses a reasonable hash size. Hence sizeof(T::Hash) <= 128; qed");
+
+		let child_trie_info = contract_info.child_trie_info();
+		for (key, value) in &storage {
+			child::put_raw(&child_trie_info, key, value);
+		}
+		ensure!(
+			Self::storage_root(&contract_info)? == storage_root,
+			Error::<T>::TombstoneRootMismatch
+		);
+
+		let account_id = T::AddressMapper::to_account_id(address);
+		let held =
+			T::Currency::balance_on_hold(&HoldReason::StorageDepositReserve.into(), &account_id);
+		let deposit = contract_info.total_deposit().saturating_sub(held);
+		T::Currency::transfer_and_hold(
+			&HoldReason::StorageDepositReserve.into(),
+			restorer,
+			&account_id,
+			deposit,
+			Precision::Exact,
+			Preservation::Preserve,
+			Fortitude::Polite,
+		)
+		.map_err(|_| Error::<T>::StorageDepositNotEnoughFunds)?;
+
+		AccountInfo::<T>::insert_contract(address, contract_info);
+		if let Some(immutable_data) = immutable_data {
+			ImmutableDataOf::<T>::insert(address, immutable_data);
+		}
+		if let Some(pending_code_upgrade) = pending_code_upgrade {
+			PendingCodeUpgrades::<T>::insert(address, pending_code_upgrade);
+		}
+		ContractActivity::<T>::instantiated(address, restorer.clone());
+		Ok(deposit)
+	}
+
+	/// Returns the root of the child trie of `contract_info`.
+	fn storage_root(contract_info: &ContractInfo<T>) -> Result<H256, DispatchError> {
+		let version: StateVersion = <T as frame_system::Config>::Version::get().state_version();
+		let root = child::root(&contract_info.child_trie_info(), version);
+		H256::decode(&mut &root[..]).map_err(|_| Error::<T>::DecodingFailed.into())
+	}
+}
@@ -355,6 +355,14 @@ where
 		Self { limit, is_root: true, ..Default::default() }
 	}
 
+	/// Whether a storage deposit is charged to `contract` by this meter.
+	pub fn charges_contract(&self, contract: &T::AccountId) -> bool {
+		self.charges.iter().any(|charge| {
+			charge.contract == *contract &&
+				matches!(charge.amount, Deposit::Charge(amount) if !amount.is_zero())
+		})
+	}
+
 	/// The total amount of deposit that should change hands as result of the execution
 	/// that this meter was passed into. This will also perform all the charges accumulated
 	/// in the whole contract stack.
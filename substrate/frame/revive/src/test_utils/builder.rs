@@ -17,8 +17,10 @@
 
 use super::{deposit_limit, GAS_LIMIT};
 use crate::{
-	address::AddressMapper, AccountIdOf, BalanceOf, BumpNonce, Code, Config, ContractResult,
-	DepositLimit, ExecReturnValue, InstantiateReturnValue, OriginFor, Pallet, Weight, U256,
+	address::AddressMapper,
+	evm::{AccessListEntry, AuthorizationListEntry},
+	AccountIdOf, BalanceOf, BumpNonce, Code, Config, ContractResult, DepositLimit, ExecReturnValue,
+	InstantiateReturnValue, OriginFor, Pallet, Weight, U256,
 };
 use alloc::{vec, vec::Vec};
 use frame_support::pallet_prelude::DispatchResultWithPostInfo;
@@ -242,6 +244,8 @@ builder!(
 		gas_limit: Weight,
 		storage_deposit_limit: BalanceOf<T>,
 		data: Vec<u8>,
+		access_list: Vec<AccessListEntry>,
+		authorization_list: Vec<AuthorizationListEntry>,
 	) -> DispatchResultWithPostInfo;
 
 	/// Create a [`EthCallBuilder`] with default values.
@@ -253,6 +257,8 @@ builder!(
 			gas_limit: GAS_LIMIT,
 			storage_deposit_limit: deposit_limit::<T>(),
 			data: vec![],
+			access_list: vec![],
+			authorization_list: vec![],
 		}
 	}
 );
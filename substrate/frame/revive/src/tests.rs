@@ -15,6 +15,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod evm;
 mod pallet_dummy;
 mod precompiles;
 mod pvm;
@@ -282,6 +283,7 @@ parameter_types! {
 	pub static DepositPerByte: BalanceOf<Test> = 1;
 	pub const DepositPerItem: BalanceOf<Test> = 2;
 	pub const CodeHashLockupDepositPercent: Perbill = Perbill::from_percent(30);
+	pub static CodeUpgradeDelay: crate::BlockNumberFor<Test> = 0;
 	pub static ChainId: u64 = 448;
 }
 
@@ -343,6 +345,7 @@ impl Config for Test {
 	type UploadOrigin = EnsureAccount<Self, UploadAccount>;
 	type InstantiateOrigin = EnsureAccount<Self, InstantiateAccount>;
 	type CodeHashLockupDepositPercent = CodeHashLockupDepositPercent;
+	type CodeUpgradeDelay = CodeUpgradeDelay;
 	type ChainId = ChainId;
 	type FindAuthor = Test;
 	type Precompiles = (precompiles::WithInfo<Self>, precompiles::NoInfo<Self>);
@@ -0,0 +1,285 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The pallet-revive EVM specific integration test suite.
+//!
+//! The contracts are hand assembled EVM bytecode.
+
+use crate::{
+	exec::Key,
+	test_utils::builder::Contract,
+	tests::{builder, test_utils::*, Contracts, ExtBuilder, RuntimeOrigin, Test, UploadAccount, *},
+	vm::BytecodeType,
+	Code, CodeInfoOf, Config, ContractBlob, Error, Pallet, H160,
+};
+use codec::Encode;
+use frame_support::{assert_err, assert_ok, traits::fungible::Mutate};
+use pallet_revive_fixtures::compile_module;
+use pretty_assertions::assert_eq;
+use sp_core::U256;
+use sp_runtime::DispatchError;
+
+/// Runtime code storing the first word of the call data in slot 0 if any call data is supplied.
+/// Returns the word stored in slot 0 otherwise.
+const STORAGE_RUNTIME: [u8; 18] = [
+	0x36, // CALLDATASIZE
+	0x60, 0x0c, // PUSH1 0x0c
+	0x57, // JUMPI
+	0x5f, // PUSH0
+	0x54, // SLOAD
+	0x5f, // PUSH0
+	0x52, // MSTORE
+	0x60, 0x20, // PUSH1 0x20
+	0x5f, // PUSH0
+	0xf3, // RETURN
+	0x5b, // JUMPDEST
+	0x5f, // PUSH0
+	0x35, // CALLDATALOAD
+	0x5f, // PUSH0
+	0x55, // SSTORE
+	0x00, // STOP
+];
+
+/// Runtime code calling the address passed as the first word of the call data with the word `7`
+/// as input. Returns whether the call succeeded.
+const CALLER_RUNTIME: [u8; 21] = [
+	0x60, 0x07, // PUSH1 0x07
+	0x5f, // PUSH0
+	0x52, // MSTORE
+	0x5f, // PUSH0 (return data length)
+	0x5f, // PUSH0 (return data offset)
+	0x60, 0x20, // PUSH1 0x20 (input length)
+	0x5f, // PUSH0 (input offset)
+	0x5f, // PUSH0 (value)
+	0x5f, // PUSH0
+	0x35, // CALLDATALOAD (callee)
+	0x5a, // GAS
+	0xf1, // CALL
+	0x5f, // PUSH0
+	0x52, // MSTORE
+	0x60, 0x20, // PUSH1 0x20
+	0x5f, // PUSH0
+	0xf3, // RETURN
+];
+
+/// Wrap `runtime` into init code returning it.
+fn init_code(runtime: &[u8]) -> Vec<u8> {
+	let len = u8::try_from(runtime.len()).unwrap();
+	let mut code = vec![
+		0x60, len, // PUSH1 len
+		0x60, 0x0a, // PUSH1 0x0a (offset of the runtime code)
+		0x5f, // PUSH0
+		0x39, // CODECOPY
+		0x60, len,  // PUSH1 len
+		0x5f, // PUSH0
+		0xf3, // RETURN
+	];
+	code.extend_from_slice(runtime);
+	code
+}
+
+/// Encode `address` as a word.
+fn address_word(address: &H160) -> Vec<u8> {
+	U256::from_big_endian(address.as_bytes()).to_big_endian().to_vec()
+}
+
+#[test]
+fn instantiate_and_call_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		let Contract { addr, .. } =
+			builder::bare_instantiate(Code::Upload(init_code(&STORAGE_RUNTIME)))
+				.build_and_unwrap_contract();
+
+		// Only the runtime code is stored.
+		assert_eq!(Pallet::<Test>::code(&addr), STORAGE_RUNTIME.to_vec());
+
+		let value = U256::from(42).to_big_endian().to_vec();
+		let result = builder::bare_call(addr).data(value.clone()).build_and_unwrap_result();
+		assert!(!result.did_revert());
+		assert_eq!(get_contract(&addr).read(&Key::Fix([0u8; 32])), Some(value.clone()));
+
+		let result = builder::bare_call(addr).build_and_unwrap_result();
+		assert_eq!(result.data, value);
+	});
+}
+
+#[test]
+fn evm_contract_can_call_evm_contract() {
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		let Contract { addr: callee, .. } =
+			builder::bare_instantiate(Code::Upload(init_code(&STORAGE_RUNTIME)))
+				.build_and_unwrap_contract();
+		let Contract { addr: caller, .. } =
+			builder::bare_instantiate(Code::Upload(init_code(&CALLER_RUNTIME)))
+				.build_and_unwrap_contract();
+
+		let result =
+			builder::bare_call(caller).data(address_word(&callee)).build_and_unwrap_result();
+		assert_eq!(result.data, U256::one().to_big_endian().to_vec());
+		assert_eq!(
+			get_contract(&callee).read(&Key::Fix([0u8; 32])),
+			Some(U256::from(7).to_big_endian().to_vec())
+		);
+	});
+}
+
+#[test]
+fn reverting_constructor_does_not_create_contract() {
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		// PUSH0 PUSH0 REVERT
+		let result = builder::bare_instantiate(Code::Upload(vec![0x5f, 0x5f, 0xfd]))
+			.build_and_unwrap_result();
+		assert!(result.result.did_revert());
+		assert!(get_contract_checked(&result.addr).is_none());
+	});
+}
+
+#[test]
+fn runtime_code_with_reserved_prefix_is_rejected() {
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		let result = builder::bare_instantiate(Code::Upload(init_code(&[0xef]))).build();
+		assert_err!(result.result, <Error<Test>>::CodeRejected);
+	});
+}
+
+#[test]
+fn invalid_jump_traps() {
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		// PUSH1 0x01 JUMP: the destination is not a `JUMPDEST`.
+		let result = builder::bare_instantiate(Code::Upload(vec![0x60, 0x01, 0x56])).build();
+		assert_err!(result.result, <Error<Test>>::InvalidJump);
+	});
+}
+
+#[test]
+fn only_upload_origin_can_instantiate_with_init_code() {
+	UploadAccount::set(Some(ALICE));
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		let _ = <Test as Config>::Currency::set_balance(&BOB, 1_000_000);
+
+		// The runtime code returned by the init code would be stored on behalf of bob.
+		let result = builder::bare_instantiate(Code::Upload(init_code(&STORAGE_RUNTIME)))
+			.origin(RuntimeOrigin::signed(BOB))
+			.build();
+		assert_err!(result.result, DispatchError::BadOrigin);
+
+		// Only alice is allowed to upload contract code.
+		let Contract { addr, .. } =
+			builder::bare_instantiate(Code::Upload(init_code(&STORAGE_RUNTIME)))
+				.build_and_unwrap_contract();
+		assert_eq!(Pallet::<Test>::code(&addr), STORAGE_RUNTIME.to_vec());
+	});
+}
+
+#[test]
+fn evm_runtime_code_can_be_uploaded() {
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		assert_ok!(Contracts::upload_code(
+			RuntimeOrigin::signed(ALICE),
+			STORAGE_RUNTIME.to_vec(),
+			deposit_limit::<Test>(),
+		));
+		let code_hash = sp_core::H256(sp_io::hashing::keccak_256(&STORAGE_RUNTIME));
+		assert_eq!(CodeInfoOf::<Test>::get(code_hash).unwrap().code_type(), BytecodeType::Evm);
+
+		// Runtime code has no constructor to instantiate a contract with.
+		let result = builder::bare_instantiate(Code::Existing(code_hash)).build();
+		assert_err!(result.result, <Error<Test>>::CodeRejected);
+
+		// The reserved prefix is rejected on upload as well.
+		assert_err!(
+			Contracts::upload_code(
+				RuntimeOrigin::signed(ALICE),
+				vec![0xef],
+				deposit_limit::<Test>()
+			),
+			<Error<Test>>::CodeRejected,
+		);
+	});
+}
+
+#[test]
+fn pvm_contract_can_call_evm_contract() {
+	let (code, _) = compile_module("call_and_return").unwrap();
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		let Contract { addr: callee, .. } =
+			builder::bare_instantiate(Code::Upload(init_code(&STORAGE_RUNTIME)))
+				.build_and_unwrap_contract();
+		let Contract { addr: caller, .. } =
+			builder::bare_instantiate(Code::Upload(code)).build_and_unwrap_contract();
+
+		let value = U256::from(42).to_big_endian().to_vec();
+		let input = |data: &[u8]| [(&callee, 0u64).encode(), data.to_vec()].concat();
+		let result = builder::bare_call(caller).data(input(&value)).build_and_unwrap_result();
+		assert!(!result.did_revert());
+		assert_eq!(get_contract(&callee).read(&Key::Fix([0u8; 32])), Some(value.clone()));
+
+		let result = builder::bare_call(caller).data(input(&[])).build_and_unwrap_result();
+		assert_eq!(result.data, value);
+	});
+}
+
+#[test]
+fn evm_contract_can_call_pvm_contract() {
+	let (code, _) = compile_module("return_with_data").unwrap();
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		let Contract { addr: callee, .. } = builder::bare_instantiate(Code::Upload(code))
+			.data(vec![0u8; 4])
+			.build_and_unwrap_contract();
+		let Contract { addr: caller, .. } =
+			builder::bare_instantiate(Code::Upload(init_code(&CALLER_RUNTIME)))
+				.build_and_unwrap_contract();
+
+		// The PolkaVM callee returns successfully as the first bytes of the input are zero.
+		let result =
+			builder::bare_call(caller).data(address_word(&callee)).build_and_unwrap_result();
+		assert_eq!(result.data, U256::one().to_big_endian().to_vec());
+	});
+}
+
+#[test]
+fn code_deposit_counts_towards_deposit_limit() {
+	ExtBuilder::default().build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		let code_deposit =
+			ContractBlob::<Test>::from_evm_runtime_code(STORAGE_RUNTIME.to_vec(), ALICE)
+				.unwrap()
+				.code_info()
+				.deposit();
+
+		let result = builder::bare_instantiate(Code::Upload(init_code(&STORAGE_RUNTIME)))
+			.storage_deposit_limit((code_deposit - 1).into())
+			.build();
+		assert_err!(result.result, <Error<Test>>::StorageDepositLimitExhausted);
+
+		let result = builder::bare_instantiate(Code::Upload(init_code(&STORAGE_RUNTIME))).build();
+		let addr = result.result.unwrap().addr;
+		assert_eq!(
+			result.storage_deposit.charge_or_zero(),
+			code_deposit + get_contract(&addr).total_deposit() + Contracts::min_balance(),
+		);
+	});
+}
@@ -36,12 +36,12 @@ use crate::{
 	},
 	tracing::trace,
 	weights::WeightInfo,
-	AccountInfo, AccountInfoOf, BalanceWithDust, BumpNonce, Code, Config, ContractInfo,
-	DeletionQueueCounter, DepositLimit, Error, EthTransactError, HoldReason, Pallet, PristineCode,
-	StorageDeposit, H160,
+	AccountInfo, AccountInfoOf, BalanceWithDust, BumpNonce, Code, Config, ContractActivityOf,
+	ContractInfo, DeletionQueueCounter, DepositLimit, Error, EthTransactError, HoldReason,
+	ImmutableData, ImmutableDataOf, Pallet, PendingCodeUpgrade, PristineCode, StorageDeposit, H160,
 };
 use assert_matches::assert_matches;
-use codec::Encode;
+use codec::{Encode, MaxEncodedLen};
 use frame_support::{
 	assert_err, assert_err_ignore_postinfo, assert_noop, assert_ok,
 	storage::child,
@@ -530,6 +530,397 @@ fn storage_work() {
 	});
 }
 
+#[test]
+fn account_proof_keys_work() {
+	use pallet_revive_proof::{
+		account_info_key, verify_account_proof, verify_storage_proof, Account,
+	};
+	use sp_runtime::traits::BlakeTwo256;
+	use sp_state_machine::{prove_child_read_on_trie_backend, prove_read_on_trie_backend};
+
+	let (code, code_hash) = compile_module("dummy").unwrap();
+	let slot = [1u8; 32];
+
+	let mut ext = ExtBuilder::default().build();
+	let (addr, keys) = ext.execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		let Contract { addr, .. } =
+			builder::bare_instantiate(Code::Upload(code)).build_and_unwrap_contract();
+		get_contract(&addr)
+			.write(&Key::Fix(slot), Some(vec![42u8]), None, false)
+			.unwrap();
+
+		let keys = Pallet::<Test>::account_proof_keys(addr);
+		assert_eq!(Pallet::<Test>::account_proof_keys(BOB_ADDR).child_trie, None);
+		(addr, keys)
+	});
+
+	let backend = ext.as_backend();
+	let state_root = *backend.root();
+	let account_info = account_info_key(b"Contracts", &addr);
+	assert_eq!(keys.account_info, account_info);
+
+	let keys = [keys.system_account, keys.account_info, keys.child_trie.unwrap()];
+	let proof = prove_read_on_trie_backend(&backend, keys).unwrap().into_iter_nodes();
+	let Some(Account::Contract { code_hash: proven_code_hash, storage_root }) =
+		verify_account_proof::<BlakeTwo256>(&state_root, &account_info, proof).unwrap()
+	else {
+		panic!("contract should be proven");
+	};
+	assert_eq!(proven_code_hash, code_hash);
+
+	let child_info = ext.execute_with(|| get_contract(&addr).child_trie_info());
+	let storage_proof =
+		prove_child_read_on_trie_backend(&backend, &child_info, [blake2_256(&slot)])
+			.unwrap()
+			.into_iter_nodes();
+	assert_eq!(
+		verify_storage_proof::<BlakeTwo256>(&storage_root, &slot, storage_proof),
+		Ok(Some(vec![42u8]))
+	);
+}
+
+#[test]
+fn access_list_tracing_works() {
+	use crate::{evm::*, tracing::trace};
+
+	let (binary, _code_hash) = compile_module("balance_of").unwrap();
+	ExtBuilder::default().existential_deposit(200).build().execute_with(|| {
+		let _ = Balances::set_balance(&ALICE, 1_000_000);
+		let _ = Balances::set_balance(&BOB, 1_000_000);
+
+		let Contract { addr, .. } =
+			builder::bare_instantiate(Code::Upload(binary.to_vec())).build_and_unwrap_contract();
+
+		// The origin and the called contract are always warm.
+		let mut tracer = AccessListTracer::new();
+		trace(&mut tracer, || {
+			builder::bare_call(addr).data(ALICE_ADDR.0.to_vec()).build_and_unwrap_result();
+		});
+		assert_eq!(tracer.collect_access_list(), vec![]);
+
+		trace(&mut tracer, || {
+			builder::bare_call(addr).data(BOB_ADDR.0.to_vec()).build_and_unwrap_result();
+		});
+		let access_list = tracer.collect_access_list();
+		assert_eq!(access_list, vec![AccessListEntry { address: BOB_ADDR, storage_keys: vec![] }]);
+
+		// Accounts of the access list are warm, and hence not traced.
+		trace(&mut tracer, || {
+			assert_ok!(builder::eth_call(addr)
+				.data(BOB_ADDR.0.to_vec())
+				.access_list(access_list)
+				.build());
+		});
+		assert_eq!(tracer.collect_access_list(), vec![]);
+	});
+}
+
+#[test]
+fn set_code_authorization_works() {
+	use crate::evm::Account;
+
+	let (binary, _) = compile_module("store_call").unwrap();
+	ExtBuilder::default().existential_deposit(1).build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		let Contract { addr, .. } =
+			builder::bare_instantiate(Code::Upload(binary)).build_and_unwrap_contract();
+
+		let authority = Account::from(subxt_signer::eth::dev::baltathar());
+		let authority_addr = authority.address();
+		let authority_id = <Test as Config>::AddressMapper::to_account_id(&authority_addr);
+		let _ = <Test as Config>::Currency::set_balance(&authority_id, 1_000_000);
+		let chain_id = U256::from(<Test as Config>::ChainId::get());
+
+		// An authorization with an invalid nonce is skipped.
+		let authorization = authority.sign_authorization(chain_id, addr, U256::one());
+		assert_ok!(builder::eth_call(authority_addr)
+			.authorization_list(vec![authorization])
+			.build());
+		assert_eq!(System::account_nonce(&authority_id), 0);
+		assert_eq!(Pallet::<Test>::code(&authority_addr), Vec::<u8>::new());
+
+		// Calls to the authority execute the code of the contract against its own storage.
+		let authorization = authority.sign_authorization(chain_id, addr, U256::zero());
+		assert_ok!(builder::eth_call(authority_addr)
+			.data(4u32.encode())
+			.authorization_list(vec![authorization])
+			.build());
+		assert_eq!(System::account_nonce(&authority_id), 1);
+		assert_eq!(
+			Pallet::<Test>::code(&authority_addr),
+			AccountInfo::<Test>::delegation_designator(&addr)
+		);
+
+		// The authority pays the base deposit of the contract info holding its storage.
+		let (contract_info, _) =
+			AccountInfo::<Test>::load_contract_or_delegated(&authority_addr).unwrap();
+		assert!(contract_info.storage_base_deposit() > 0);
+		assert_eq!(
+			get_balance_on_hold(&HoldReason::StorageDepositReserve.into(), &authority_id),
+			contract_info.total_deposit(),
+		);
+
+		let mut key = [0u8; 32];
+		key[0] = 1;
+		assert_eq!(Pallet::<Test>::get_storage(authority_addr, key), Ok(Some(vec![0u8; 4])));
+		assert_eq!(Pallet::<Test>::get_storage(addr, key), Ok(None));
+
+		// Delegating to the zero address clears the delegation.
+		let authorization = authority.sign_authorization(U256::zero(), H160::zero(), U256::one());
+		assert_ok!(builder::eth_call(authority_addr)
+			.authorization_list(vec![authorization])
+			.build());
+		assert_eq!(System::account_nonce(&authority_id), 2);
+		assert_eq!(Pallet::<Test>::code(&authority_addr), Vec::<u8>::new());
+
+		// An authority which can't pay the deposit of its first delegation is skipped.
+		let poor = Account::from(subxt_signer::eth::dev::charleth());
+		let poor_id = <Test as Config>::AddressMapper::to_account_id(&poor.address());
+		let _ = <Test as Config>::Currency::set_balance(&poor_id, 1);
+		let authorization = poor.sign_authorization(chain_id, addr, U256::zero());
+		assert_ok!(builder::eth_call(authority_addr)
+			.authorization_list(vec![authorization])
+			.build());
+		assert_eq!(System::account_nonce(&poor_id), 0);
+		assert_eq!(Pallet::<Test>::code(&poor.address()), Vec::<u8>::new());
+
+		// What the sender pays to create an authority counts towards the storage deposit limit.
+		let limited = Account::from(subxt_signer::eth::dev::ethan());
+		let limited_id = <Test as Config>::AddressMapper::to_account_id(&limited.address());
+		let authorization = limited.sign_authorization(chain_id, addr, U256::zero());
+		assert_ok!(builder::eth_call(authority_addr)
+			.storage_deposit_limit(1)
+			.authorization_list(vec![authorization])
+			.build());
+		assert!(!System::account_exists(&limited_id));
+		assert_eq!(Pallet::<Test>::code(&limited.address()), Vec::<u8>::new());
+
+		// An authority which does not exist is created by the sender, and its authorization is
+		// kept even if the call reverts.
+		let (revert, _) = compile_module("ok_trap_revert").unwrap();
+		let Contract { addr: revert_addr, .. } =
+			builder::bare_instantiate(Code::Upload(revert)).build_and_unwrap_contract();
+		let new = Account::from(subxt_signer::eth::dev::dorothy());
+		let new_id = <Test as Config>::AddressMapper::to_account_id(&new.address());
+		assert!(!System::account_exists(&new_id));
+		let authorization = new.sign_authorization(chain_id, addr, U256::zero());
+		assert_ok!(builder::eth_call(revert_addr)
+			.data(1u32.encode())
+			.authorization_list(vec![authorization])
+			.build());
+		System::assert_has_event(RuntimeEvent::Contracts(crate::Event::EthCallReverted {
+			contract: revert_addr,
+		}));
+		assert!(System::account_exists(&new_id));
+		assert_eq!(System::account_nonce(&new_id), 1);
+		assert_eq!(
+			Pallet::<Test>::code(&new.address()),
+			AccountInfo::<Test>::delegation_designator(&addr)
+		);
+	});
+}
+
+#[test]
+fn dry_run_applies_authorization_of_sender() {
+	use crate::evm::*;
+
+	let (binary, _) = compile_module("dummy").unwrap();
+	ExtBuilder::default().existential_deposit(1).build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		let Contract { addr, .. } =
+			builder::bare_instantiate(Code::Upload(binary)).build_and_unwrap_contract();
+
+		let sender = Account::from(subxt_signer::eth::dev::baltathar());
+		let sender_id = <Test as Config>::AddressMapper::to_account_id(&sender.address());
+		let _ = <Test as Config>::Currency::set_balance(&sender_id, 1_000_000);
+		let chain_id = U256::from(<Test as Config>::ChainId::get());
+
+		// The nonce of the sender is bumped before its own authorization is applied.
+		let nonce = System::account_nonce(&sender_id) + 1;
+		let authorization = sender.sign_authorization(chain_id, addr, nonce.into());
+		let tx = GenericTransaction {
+			from: Some(sender.address()),
+			to: Some(addr),
+			authorization_list: Some(vec![authorization]),
+			..Default::default()
+		};
+		assert_ok!(Pallet::<Test>::dry_run_eth_transact(tx, Weight::MAX, |_, _| 0u64));
+		assert_eq!(
+			Pallet::<Test>::code(&sender.address()),
+			AccountInfo::<Test>::delegation_designator(&addr)
+		);
+	});
+}
+
+#[test]
+fn simulate_works() {
+	use crate::evm::*;
+
+	let (block_author, _) = compile_module("block_author").unwrap();
+	let (balance_of, _) = compile_module("balance_of").unwrap();
+	ExtBuilder::default().existential_deposit(1).build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		let contract = H160::repeat_byte(0x11);
+		let author = H160::repeat_byte(0x42);
+		let call = |data: H160| GenericTransaction {
+			from: Some(ALICE_ADDR),
+			to: Some(contract),
+			input: data.0.to_vec().into(),
+			..Default::default()
+		};
+
+		let blocks = vec![
+			// The fixture asserts the input to match the block author.
+			SimulateBlock {
+				block_overrides: Some(BlockOverrides {
+					number: Some(U256::from(10)),
+					fee_recipient: Some(author),
+					..Default::default()
+				}),
+				state_overrides: Some(
+					[(
+						contract,
+						StateOverride { code: Some(block_author.into()), ..Default::default() },
+					)]
+					.into(),
+				),
+				calls: vec![call(author), call(EVE_ADDR)],
+			},
+			// The fixture asserts the balance of the input address to be non zero.
+			SimulateBlock {
+				block_overrides: None,
+				state_overrides: Some(
+					[
+						(
+							contract,
+							StateOverride { code: Some(balance_of.into()), ..Default::default() },
+						),
+						(
+							BOB_ADDR,
+							StateOverride {
+								balance: Some(Pallet::<Test>::convert_native_to_evm(1_000u64)),
+								..Default::default()
+							},
+						),
+					]
+					.into(),
+				),
+				calls: vec![call(BOB_ADDR), call(CHARLIE_ADDR)],
+			},
+		];
+
+		let result = Pallet::<Test>::simulate(blocks, |tx| {
+			Pallet::<Test>::dry_run_eth_transact(tx, Weight::MAX, |_, _| 0u64)
+		})
+		.unwrap();
+
+		assert_eq!(result.len(), 2);
+		assert_eq!(result[0].number, U256::from(10));
+		assert_eq!(result[0].miner, author);
+		assert_eq!(result[1].number, U256::from(11));
+		assert_eq!(result[1].timestamp, result[0].timestamp + U256::from(12));
+
+		let statuses = result
+			.iter()
+			.map(|block| block.calls.iter().map(|call| call.status).collect::<Vec<_>>())
+			.collect::<Vec<_>>();
+		assert_eq!(
+			statuses,
+			vec![vec![U256::one(), U256::zero()], vec![U256::one(), U256::zero()]]
+		);
+		assert_eq!(
+			result[0].calls[1].error.as_ref().map(|err| err.code),
+			Some(SimulateCallError::FAILED)
+		);
+	});
+}
+
+#[test]
+fn simulate_bumps_sender_nonce() {
+	use crate::evm::*;
+
+	let (binary, _) = compile_module("dummy").unwrap();
+	ExtBuilder::default().existential_deposit(1).build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 1_000_000_000);
+		let nonce = System::account_nonce(&ALICE);
+		let deploy = GenericTransaction {
+			from: Some(ALICE_ADDR),
+			input: binary.clone().into(),
+			..Default::default()
+		};
+
+		// Both deployments only succeed if they are made with distinct nonces.
+		let blocks = vec![SimulateBlock {
+			block_overrides: None,
+			state_overrides: None,
+			calls: vec![deploy.clone(), deploy],
+		}];
+
+		let result = Pallet::<Test>::simulate(blocks, |tx| {
+			Pallet::<Test>::dry_run_eth_transact(tx, Weight::MAX, |_, _| 0u64)
+		})
+		.unwrap();
+
+		let statuses = result[0].calls.iter().map(|call| call.status).collect::<Vec<_>>();
+		assert_eq!(statuses, vec![U256::one(), U256::one()]);
+		assert_eq!(System::account_nonce(&ALICE), nonce + 2);
+		for nonce in [nonce, nonce + 1] {
+			let addr = create1(&ALICE_ADDR, nonce);
+			assert!(AccountInfo::<Test>::load_contract(&addr).is_some());
+		}
+	});
+}
+
+#[test]
+fn apply_state_overrides_works() {
+	use crate::evm::*;
+
+	let (binary, _) = compile_module("dummy").unwrap();
+	ExtBuilder::default().existential_deposit(1).build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		let Contract { addr, .. } =
+			builder::bare_instantiate(Code::Upload(binary)).build_and_unwrap_contract();
+
+		let slot = H256::repeat_byte(1);
+		let value = H256::repeat_byte(2);
+		assert_ok!(Pallet::<Test>::apply_state_overrides(
+			[
+				(
+					addr,
+					StateOverride {
+						state_diff: Some([(slot, value)].into()),
+						..Default::default()
+					},
+				),
+				(
+					BOB_ADDR,
+					StateOverride {
+						balance: Some(U256::from(1_000_000_000u64)),
+						nonce: Some(U256::from(42)),
+						..Default::default()
+					},
+				),
+			]
+			.into()
+		));
+
+		assert_eq!(Pallet::<Test>::get_storage(addr, slot.0), Ok(Some(value.0.to_vec())));
+		assert_eq!(Pallet::<Test>::evm_balance(&BOB_ADDR), U256::from(1_000_000_000u64));
+		assert_eq!(
+			System::account_nonce(&<Test as Config>::AddressMapper::to_account_id(&BOB_ADDR)),
+			42
+		);
+
+		// Storage overrides require a contract.
+		assert!(Pallet::<Test>::apply_state_overrides(
+			[(BOB_ADDR, StateOverride { state: Some(Default::default()), ..Default::default() })]
+				.into()
+		)
+		.is_err());
+	});
+}
+
 #[test]
 fn storage_max_value_limit() {
 	let (binary, _code_hash) = compile_module("storage_size").unwrap();
@@ -2505,6 +2896,264 @@ fn set_code_hash() {
 	});
 }
 
+/// The storage deposit charged for a pending code upgrade.
+fn pending_code_upgrade_deposit() -> u64 {
+	let len = PendingCodeUpgrade::<crate::BlockNumberFor<Test>>::max_encoded_len() as u64;
+	len * DepositPerByte::get() + DepositPerItem::get()
+}
+
+#[test]
+fn code_upgrade_delay_is_enforced() {
+	let (binary, _) = compile_module("announce_code_upgrade").unwrap();
+	let (new_binary, new_code_hash) = compile_module("new_set_code_hash_contract").unwrap();
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		CodeUpgradeDelay::set(10);
+
+		let Contract { addr, .. } = builder::bare_instantiate(Code::Upload(binary))
+			.native_value(300_000)
+			.build_and_unwrap_contract();
+		assert_ok!(Contracts::upload_code(
+			RuntimeOrigin::signed(ALICE),
+			new_binary,
+			deposit_limit::<Test>(),
+		));
+
+		// Switching to code that was not announced is denied
+		assert_err!(
+			builder::bare_call(addr).data((1u8, new_code_hash).encode()).build().result,
+			<Error<Test>>::CodeUpgradeNotAnnounced,
+		);
+
+		// Announce the upgrade, which charges its storage and references the new code
+		System::reset_events();
+		let result = builder::bare_call(addr).data((0u8, new_code_hash).encode()).build();
+		assert_ok!(result.result);
+		let pending =
+			PendingCodeUpgrade { code_hash: new_code_hash, enact_at: System::block_number() + 10 };
+		assert_eq!(Pallet::<Test>::pending_code_upgrade(&addr), Some(pending));
+		assert_eq!(result.storage_deposit, StorageDeposit::Charge(pending_code_upgrade_deposit()));
+		assert_refcount!(new_code_hash, 1);
+		assert_noop!(
+			Contracts::remove_code(RuntimeOrigin::signed(ALICE), new_code_hash),
+			<Error<Test>>::CodeInUse,
+		);
+		assert!(System::events().iter().any(|record| record.event ==
+			RuntimeEvent::Contracts(crate::Event::CodeUpgradeAnnounced {
+				contract: addr,
+				code_hash: new_code_hash,
+				enact_at: pending.enact_at,
+			})));
+
+		// The upgrade can't be enacted before the end of the delay
+		System::set_block_number(pending.enact_at - 1);
+		assert_err!(
+			builder::bare_call(addr).data((1u8, new_code_hash).encode()).build().result,
+			<Error<Test>>::CodeUpgradeNotDue,
+		);
+
+		// Enact the upgrade
+		System::set_block_number(pending.enact_at);
+		let result = builder::bare_call(addr)
+			.data((1u8, new_code_hash).encode())
+			.build_and_unwrap_result();
+		assert_return_code!(result, 1);
+		assert_eq!(Pallet::<Test>::pending_code_upgrade(&addr), None);
+		assert_refcount!(new_code_hash, 1);
+		assert!(System::events().iter().any(|record| record.event ==
+			RuntimeEvent::Contracts(crate::Event::CodeUpgradeEnacted {
+				contract: addr,
+				code_hash: new_code_hash,
+			})));
+
+		// The next call uses the new code
+		let result = builder::bare_call(addr).build_and_unwrap_result();
+		assert_return_code!(result, 2);
+	});
+}
+
+#[test]
+fn code_upgrade_can_be_cancelled() {
+	let (binary, code_hash) = compile_module("announce_code_upgrade").unwrap();
+	let (new_binary, new_code_hash) = compile_module("new_set_code_hash_contract").unwrap();
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		CodeUpgradeDelay::set(10);
+
+		let Contract { addr, .. } = builder::bare_instantiate(Code::Upload(binary))
+			.native_value(300_000)
+			.build_and_unwrap_contract();
+		assert_ok!(Contracts::upload_code(
+			RuntimeOrigin::signed(ALICE),
+			new_binary,
+			deposit_limit::<Test>(),
+		));
+
+		builder::bare_call(addr)
+			.data((0u8, new_code_hash).encode())
+			.build_and_unwrap_result();
+		assert!(Pallet::<Test>::pending_code_upgrade(&addr).is_some());
+
+		// Announcing the current code cancels the upgrade and refunds its storage
+		System::reset_events();
+		let result = builder::bare_call(addr).data((0u8, code_hash).encode()).build();
+		assert_ok!(result.result);
+		assert_eq!(Pallet::<Test>::pending_code_upgrade(&addr), None);
+		assert_eq!(result.storage_deposit, StorageDeposit::Refund(pending_code_upgrade_deposit()));
+		assert_refcount!(new_code_hash, 0);
+		assert!(System::events().iter().any(|record| record.event ==
+			RuntimeEvent::Contracts(crate::Event::CodeUpgradeCancelled { contract: addr })));
+
+		System::set_block_number(System::block_number() + 10);
+		assert_err!(
+			builder::bare_call(addr).data((1u8, new_code_hash).encode()).build().result,
+			<Error<Test>>::CodeUpgradeNotAnnounced,
+		);
+	});
+}
+
+#[test]
+fn expired_contract_can_be_tombstoned_and_restored() {
+	let (binary, code_hash) = compile_module("store_deploy").unwrap();
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		let _ = <Test as Config>::Currency::set_balance(&BOB, 1_000_000);
+		let _ = <Test as Config>::Currency::set_balance(&CHARLIE, 1_000_000);
+
+		// Contracts do not expire unless an expiry is set
+		assert_err_ignore_postinfo!(
+			Contracts::tombstone_contract(RuntimeOrigin::signed(BOB), H160::repeat_byte(1)),
+			<Error<Test>>::ContractExpiryDisabled,
+		);
+		assert_ok!(Contracts::set_contract_expiry(RuntimeOrigin::root(), Some(10)));
+
+		let Contract { addr, account_id } = builder::bare_instantiate(Code::Upload(binary))
+			.data(100u32.encode())
+			.build_and_unwrap_contract();
+		let deposit = get_contract(&addr).total_deposit();
+		assert_eq!(
+			get_balance_on_hold(&HoldReason::StorageDepositReserve.into(), &account_id),
+			deposit
+		);
+		let mut key = [0u8; 32];
+		key[0] = 1;
+		let storage = vec![(Key::Fix(key).hash(), vec![0u8; 100])];
+
+		assert_err_ignore_postinfo!(
+			Contracts::tombstone_contract(RuntimeOrigin::signed(BOB), addr),
+			<Error<Test>>::ContractNotExpired,
+		);
+
+		// Calling the contract touches it
+		System::set_block_number(System::block_number() + 5);
+		builder::bare_call(addr).build_and_unwrap_result();
+		System::set_block_number(System::block_number() + 9);
+		assert_err_ignore_postinfo!(
+			Contracts::tombstone_contract(RuntimeOrigin::signed(BOB), addr),
+			<Error<Test>>::ContractNotExpired,
+		);
+
+		// Anyone can tombstone the contract once it expired, the deposit goes to its depositor
+		System::set_block_number(System::block_number() + 1);
+		System::reset_events();
+		let immutable_data: ImmutableData = vec![1u8; 32].try_into().unwrap();
+		ImmutableDataOf::<Test>::insert(addr, immutable_data.clone());
+		let trie = &get_contract(&addr).child_trie_info();
+		let alice_balance = get_balance(&ALICE);
+		assert_ok!(Contracts::tombstone_contract(RuntimeOrigin::signed(BOB), addr));
+		assert_eq!(get_balance(&ALICE), alice_balance + deposit);
+		assert_eq!(get_balance_on_hold(&HoldReason::StorageDepositReserve.into(), &account_id), 0);
+		assert_eq!(get_contract_checked(&addr), None);
+		assert_eq!(ImmutableDataOf::<Test>::get(addr), None);
+		assert_refcount!(code_hash, 1);
+		assert_eq!(
+			System::events().last().unwrap().event,
+			RuntimeEvent::Contracts(crate::Event::ContractTombstoned {
+				contract: addr,
+				depositor: ALICE,
+				deposit,
+			})
+		);
+
+		// The child trie is removed lazily
+		assert!(child::exists(trie, &Key::Fix(key).hash()));
+		Contracts::on_idle(System::block_number(), Weight::MAX);
+		assert!(!child::exists(trie, &Key::Fix(key).hash()));
+
+		// The storage must match the one the contract had when it expired
+		assert_noop!(
+			Contracts::restore_contract(
+				RuntimeOrigin::signed(CHARLIE),
+				addr,
+				vec![(Key::Fix(key).hash(), vec![1u8; 100])],
+			),
+			<Error<Test>>::TombstoneRootMismatch,
+		);
+
+		// The storage is bounded as it is restored in a single extrinsic
+		let too_many_items = (0..=limits::TOMBSTONE_STORAGE_ITEMS)
+			.map(|item| (item.encode(), vec![]))
+			.collect::<Vec<_>>();
+		assert_noop!(
+			Contracts::restore_contract(RuntimeOrigin::signed(CHARLIE), addr, too_many_items),
+			<Error<Test>>::TombstoneStorageTooLarge,
+		);
+
+		let charlie_balance = get_balance(&CHARLIE);
+		assert_ok!(Contracts::restore_contract(RuntimeOrigin::signed(CHARLIE), addr, storage));
+		assert_eq!(get_balance(&CHARLIE), charlie_balance - deposit);
+		assert_eq!(
+			get_balance_on_hold(&HoldReason::StorageDepositReserve.into(), &account_id),
+			deposit
+		);
+		assert_eq!(get_contract(&addr).read(&Key::Fix(key)), Some(vec![0u8; 100]));
+		assert_eq!(get_contract(&addr).code_hash, code_hash);
+		assert_eq!(ImmutableDataOf::<Test>::get(addr), Some(immutable_data));
+		builder::bare_call(addr).build_and_unwrap_result();
+
+		// The restorer is refunded the deposit if the contract expires again
+		System::set_block_number(System::block_number() + 10);
+		assert_ok!(Contracts::tombstone_contract(RuntimeOrigin::signed(BOB), addr));
+		assert_eq!(get_balance(&CHARLIE), charlie_balance);
+	});
+}
+
+#[test]
+fn contract_with_shared_deposit_does_not_expire() {
+	let (binary, _code_hash) = compile_module("store_call").unwrap();
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		let _ = <Test as Config>::Currency::set_balance(&BOB, 1_000_000);
+		assert_ok!(Contracts::set_contract_expiry(RuntimeOrigin::root(), Some(10)));
+
+		let Contract { addr, .. } =
+			builder::bare_instantiate(Code::Upload(binary)).build_and_unwrap_contract();
+
+		// The depositor paying for more storage keeps the contract expirable
+		builder::bare_call(addr).data(10u32.encode()).build_and_unwrap_result();
+		assert!(!ContractActivityOf::<Test>::get(addr).unwrap().shared_deposit);
+
+		// Only the depositor is refunded, so contracts paid for by others never expire
+		let result = builder::bare_call(addr)
+			.origin(RuntimeOrigin::signed(BOB))
+			.data(100u32.encode())
+			.build();
+		assert_ok!(result.result);
+		assert!(matches!(result.storage_deposit, StorageDeposit::Charge(amount) if amount > 0));
+		assert!(ContractActivityOf::<Test>::get(addr).unwrap().shared_deposit);
+
+		System::set_block_number(System::block_number() + 10);
+		assert_err_ignore_postinfo!(
+			Contracts::tombstone_contract(RuntimeOrigin::signed(BOB), addr),
+			<Error<Test>>::ContractDepositShared,
+		);
+	});
+}
+
 #[test]
 fn storage_deposit_limit_is_enforced() {
 	let (binary, _code_hash) = compile_module("store_call").unwrap();
@@ -4009,6 +4658,36 @@ fn tracing_works_for_transfers() {
 	});
 }
 
+#[test]
+fn debug_message_works() {
+	let (code, _) = compile_module("debug_message").unwrap();
+
+	ExtBuilder::default().existential_deposit(1).build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 1_000_000);
+		let Contract { addr, .. } =
+			builder::bare_instantiate(Code::Upload(code)).build_and_unwrap_contract();
+
+		// Debug messages are discarded when not collected.
+		let result = builder::bare_call(addr).build();
+		assert_eq!(result.result.unwrap().data, vec![0u8]);
+		assert!(result.debug_message.is_empty());
+
+		let result = crate::debug::collect(|| builder::bare_call(addr).build());
+		assert_eq!(result.result.unwrap().data, vec![1u8]);
+		assert_eq!(result.debug_message, b"Hello world!\n".to_vec());
+
+		// Debug messages are reported to the tracer.
+		let mut tracer = CallTracer::new(Default::default(), |_| U256::zero());
+		let result = trace(&mut tracer, || builder::bare_call(addr).build());
+		assert_eq!(result.result.unwrap().data, vec![1u8]);
+		assert!(result.debug_message.is_empty());
+		assert_eq!(
+			tracer.collect_trace().unwrap().debug_messages,
+			vec!["Hello world!\n".to_string()]
+		);
+	});
+}
+
 #[test]
 fn call_tracing_works() {
 	use crate::evm::*;
@@ -4226,6 +4905,110 @@ fn create_call_tracing_works() {
 	});
 }
 
+#[test]
+fn struct_logger_tracing_works() {
+	use crate::evm::*;
+	let (code, _code_hash) = compile_module("tracing").unwrap();
+	let (binary_callee, _) = compile_module("tracing_callee").unwrap();
+	ExtBuilder::default().existential_deposit(200).build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 100_000_000);
+
+		let Contract { addr: addr_callee, .. } =
+			builder::bare_instantiate(Code::Upload(binary_callee)).build_and_unwrap_contract();
+
+		let Contract { addr, .. } = builder::bare_instantiate(Code::Upload(code))
+			.evm_value(10_000_000.into())
+			.build_and_unwrap_contract();
+
+		let mut tracer = StructLogger::new(Default::default(), |w: Weight| w.ref_time());
+		trace(&mut tracer, || builder::bare_call(addr).data((3u32, addr_callee).encode()).build());
+		let struct_trace = tracer.collect_trace().unwrap();
+
+		assert!(!struct_trace.failed);
+		assert!(struct_trace.gas > 0);
+		assert!(struct_trace.struct_logs.iter().any(|log| log.depth == 1));
+		assert!(struct_trace.struct_logs.iter().any(|log| log.depth > 1));
+		assert!(struct_trace.struct_logs.iter().any(|log| log.op == "CALL"));
+		assert!(struct_trace
+			.struct_logs
+			.iter()
+			.all(|log| log.stack.is_some() && log.memory.is_none()));
+		// The callees return through a host function, charged to the last step of their frame.
+		assert!(struct_trace
+			.struct_logs
+			.windows(2)
+			.filter(|steps| steps[1].depth < steps[0].depth)
+			.all(|steps| steps[0].gas_cost > 0));
+
+		let config = StructLoggerConfig {
+			disable_stack: true,
+			enable_memory: true,
+			limit: 10,
+			..Default::default()
+		};
+		let mut tracer = StructLogger::new(config, |w: Weight| w.ref_time());
+		trace(&mut tracer, || builder::bare_call(addr).data((3u32, addr_callee).encode()).build());
+		let struct_trace = tracer.collect_trace().unwrap();
+
+		assert_eq!(struct_trace.struct_logs.len(), 10);
+		assert!(struct_trace
+			.struct_logs
+			.iter()
+			.all(|log| log.stack.is_none() && log.memory.is_some()));
+	});
+}
+
+#[test]
+fn gas_profiler_works() {
+	use crate::evm::*;
+	let (code, _) = compile_module("tracing").unwrap();
+	let (callee_code, _) = compile_module("tracing_callee").unwrap();
+	let (storage_code, _) = compile_module("storage").unwrap();
+	ExtBuilder::default().existential_deposit(200).build().execute_with(|| {
+		let _ = <Test as Config>::Currency::set_balance(&ALICE, 100_000_000);
+
+		let Contract { addr: addr_callee, .. } =
+			builder::bare_instantiate(Code::Upload(callee_code)).build_and_unwrap_contract();
+
+		let Contract { addr, .. } = builder::bare_instantiate(Code::Upload(code))
+			.evm_value(10_000_000.into())
+			.build_and_unwrap_contract();
+
+		let mut tracer = GasProfiler::new(Default::default(), |w: Weight| w.ref_time());
+		trace(&mut tracer, || builder::bare_call(addr).data((3u32, addr_callee).encode()).build());
+		let profile = tracer.collect_trace().unwrap();
+
+		assert_eq!(profile.gas, profile.costs.values().map(|entry| entry.gas).sum::<u64>());
+		assert!(profile.costs["Execution"].gas > 0);
+		assert_eq!(profile.costs["DepositEvent"].count, 6);
+		assert!(profile.contracts.contains_key(&addr));
+		assert!(profile.contracts.contains_key(&addr_callee));
+		assert!(profile.storage.is_empty());
+		let callee_stack = format!("{addr:?};{addr_callee:?};");
+		assert!(profile.stacks.iter().any(|line| line.starts_with(&callee_stack)));
+
+		let Contract { addr, .. } =
+			builder::bare_instantiate(Code::Upload(storage_code)).build_and_unwrap_contract();
+
+		let config = GasProfilerConfig { disable_stacks: true, ..Default::default() };
+		let mut tracer = GasProfiler::new(config, |w: Weight| w.ref_time());
+		trace(&mut tracer, || builder::bare_call(addr).build_and_unwrap_result());
+		let profile = tracer.collect_trace().unwrap();
+
+		let storage_costs =
+			["SetStorage", "ClearStorage", "ContainsStorage", "GetStorage", "TakeStorage"];
+		assert_eq!(profile.costs["SetStorage"].count, 4);
+		assert!(profile.stacks.is_empty());
+		assert_eq!(
+			profile.storage[&addr][&Bytes::from(vec![1u8; 32])],
+			GasProfileEntry {
+				count: storage_costs.iter().map(|cost| profile.costs[*cost].count).sum(),
+				gas: storage_costs.iter().map(|cost| profile.costs[*cost].gas).sum(),
+			}
+		);
+	});
+}
+
 #[test]
 fn prestate_tracing_works() {
 	use crate::evm::*;
@@ -35,9 +35,9 @@ pub fn trace<R, F: FnOnce() -> R>(tracer: &mut (dyn Tracing + 'static), f: F) ->
 /// Run the closure when tracing is enabled.
 ///
 /// This is safe to be called from on-chain code as tracing will never be activated
-/// there. Hence the closure is not executed in this case.
-pub(crate) fn if_tracing<F: FnOnce(&mut (dyn Tracing + 'static))>(f: F) {
-	tracer::with(f);
+/// there. Hence the closure is not executed in this case and `None` is returned.
+pub(crate) fn if_tracing<R, F: FnOnce(&mut (dyn Tracing + 'static)) -> R>(f: F) -> Option<R> {
+	tracer::with(f)
 }
 
 /// Defines methods to trace contract interactions.
@@ -67,6 +67,18 @@ pub trait Tracing {
 	/// Called when storage read is called
 	fn storage_read(&mut self, _key: &Key, _value: Option<&[u8]>) {}
 
+	/// Called when an account is accessed for the first time in a transaction.
+	fn account_accessed(&mut self, _addr: &H160) {}
+
+	/// Called when a storage slot of the contract at `addr` is accessed for the first time in a
+	/// transaction.
+	fn storage_accessed(&mut self, _addr: &H160, _key: &Key) {}
+
+	/// Called every time a storage slot of the contract at `addr` is accessed.
+	///
+	/// Unlike [`Self::storage_accessed`] this is also called for slots that are already warm.
+	fn storage_touched(&mut self, _addr: &H160, _key: &Key) {}
+
 	/// Called when storage write is called
 	fn storage_write(
 		&mut self,
@@ -79,9 +91,43 @@ pub trait Tracing {
 	/// Record a log event
 	fn log_event(&mut self, _event: H160, _topics: &[H256], _data: &[u8]) {}
 
+	/// Record a debug message emitted by the contract.
+	fn debug_message(&mut self, _message: &str) {}
+
+	/// Called when `weight` is charged from the gas meter of the current call.
+	///
+	/// `token` is the name of the charged cost, e.g. the name of a host function cost or
+	/// `Execution` for the instructions metered by the executor.
+	fn gas_charged(&mut self, _token: &'static str, _weight: Weight) {}
+
+	/// Called when `weight` previously charged for `token` is refunded to the gas meter.
+	fn gas_refunded(&mut self, _token: &'static str, _weight: Weight) {}
+
 	/// Called after a contract call is executed
 	fn exit_child_span(&mut self, _output: &ExecReturnValue, _gas_left: Weight) {}
 
 	/// Called when a contract call terminates with an error
 	fn exit_child_span_with_error(&mut self, _error: DispatchError, _gas_left: Weight) {}
+
+	/// Whether [`Self::enter_opcode`] should be called for every executed instruction.
+	///
+	/// This forces the interpreter to stop after each instruction and should only be
+	/// enabled by tracers that need it.
+	fn is_execution_tracing_enabled(&self) -> bool {
+		false
+	}
+
+	/// Called before an instruction is executed.
+	///
+	/// `opcode` is the name of the instruction, or the name of the host function in case of
+	/// an `ecalli`. `memory` lazily reads the stack memory of the current frame.
+	fn enter_opcode(
+		&mut self,
+		_pc: u64,
+		_opcode: &str,
+		_gas_left: Weight,
+		_registers: &[u64],
+		_memory: &dyn Fn() -> Vec<u8>,
+	) {
+	}
 }
@@ -0,0 +1,267 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Interpreter for contracts represented in EVM bytecode.
+//!
+//! EVM contracts are executed against the same [`Ext`] as PolkaVM contracts: they share the
+//! storage, the gas meter and the storage meter of the call stack, and they can call and
+//! instantiate each other.
+//!
+//! Instructions interacting with the state are charged with the same [`RuntimeCosts`] as the
+//! corresponding PolkaVM host functions. All other instructions are charged with their EVM gas
+//! cost, see [`RuntimeCosts::EvmGas`].
+
+mod instructions;
+
+use crate::{
+	exec::{ExecResult, Ext},
+	limits,
+	primitives::ExecReturnValue,
+	vm::{BytecodeType, RuntimeCosts, WEIGHT_PER_GAS},
+	AccountIdOf, BalanceOf, CodeVec, Config, ContractBlob, Error, LOG_TARGET,
+};
+use alloc::{vec, vec::Vec};
+use frame_support::weights::Weight;
+use pallet_revive_uapi::ReturnFlags;
+use sp_core::U256;
+use sp_runtime::DispatchError;
+
+/// The first byte of EVM runtime code that is reserved, see
+/// [EIP-3541](https://eips.ethereum.org/EIPS/eip-3541).
+const RESERVED_CODE_PREFIX: u8 = 0xEF;
+
+impl<T: Config> ContractBlob<T>
+where
+	BalanceOf<T>: Into<U256> + TryFrom<U256>,
+{
+	/// Create a code blob from EVM init code.
+	///
+	/// The init code is run once in order to create a contract and is never stored.
+	pub fn from_evm_init_code(code: Vec<u8>, owner: AccountIdOf<T>) -> Result<Self, DispatchError> {
+		if BytecodeType::of(&code) != BytecodeType::Evm {
+			log::debug!(target: LOG_TARGET, "EVM init code can't start with the PolkaVM magic");
+			return Err(<Error<T>>::CodeRejected.into());
+		}
+		let code = Self::enforce_evm_code_len(code, limits::code::EVM_INIT_BYTES)?;
+		Ok(Self::from_validated_code(code, owner, BytecodeType::Evm))
+	}
+
+	/// Create a code blob from EVM runtime code.
+	///
+	/// This is the code returned by the init code of a contract, or supplied as a state override.
+	pub fn from_evm_runtime_code(
+		code: Vec<u8>,
+		owner: AccountIdOf<T>,
+	) -> Result<Self, DispatchError> {
+		if code.first() == Some(&RESERVED_CODE_PREFIX) ||
+			BytecodeType::of(&code) != BytecodeType::Evm
+		{
+			log::debug!(target: LOG_TARGET, "EVM runtime code starts with a reserved prefix");
+			return Err(<Error<T>>::CodeRejected.into());
+		}
+		let code = Self::enforce_evm_code_len(code, limits::code::EVM_BYTES)?;
+		Ok(Self::from_validated_code(code, owner, BytecodeType::Evm))
+	}
+
+	/// Make sure that the EVM code is not longer than `limit`.
+	fn enforce_evm_code_len(code: Vec<u8>, limit: u32) -> Result<CodeVec, DispatchError> {
+		let len = code.len();
+		if len > limit as usize {
+			log::debug!(target: LOG_TARGET, "EVM code too large: {len} limit: {limit}");
+			return Err(<Error<T>>::BlobTooLarge.into());
+		}
+		code.try_into().map_err(|_| <Error<T>>::BlobTooLarge.into())
+	}
+}
+
+/// Run the given EVM bytecode with the given input.
+pub fn call<E: Ext>(code: Vec<u8>, ext: &mut E, input: Vec<u8>) -> ExecResult
+where
+	BalanceOf<E::T>: Into<U256> + TryFrom<U256>,
+{
+	let mut interpreter = Interpreter::new(code, ext, input);
+	let halt = loop {
+		if let Some(halt) = interpreter.step()? {
+			break halt
+		}
+	};
+	Ok(match halt {
+		Halt::Stop => ExecReturnValue { flags: ReturnFlags::empty(), data: Vec::new() },
+		Halt::Return(data) => ExecReturnValue { flags: ReturnFlags::empty(), data },
+		Halt::Revert(data) => ExecReturnValue { flags: ReturnFlags::REVERT, data },
+	})
+}
+
+/// The reason why the execution of an EVM contract stopped without an error.
+enum Halt {
+	/// The contract stopped without returning data, e.g. by executing `STOP`.
+	Stop,
+	/// The contract returned data.
+	Return(Vec<u8>),
+	/// The contract reverted.
+	Revert(Vec<u8>),
+}
+
+/// The state of an executing EVM contract.
+struct Interpreter<'a, E: Ext> {
+	/// The environment of the contract.
+	ext: &'a mut E,
+	/// The bytecode being executed.
+	code: Vec<u8>,
+	/// Whether each offset of the code is a valid jump destination.
+	jump_dests: Vec<bool>,
+	/// The input data of the call.
+	input: Vec<u8>,
+	/// The stack, whose top is the last item.
+	stack: Vec<U256>,
+	/// The memory, whose length is always a multiple of 32 bytes.
+	memory: Vec<u8>,
+	/// The offset of the next instruction.
+	pc: usize,
+}
+
+impl<'a, E: Ext> Interpreter<'a, E> {
+	fn new(code: Vec<u8>, ext: &'a mut E, input: Vec<u8>) -> Self {
+		let jump_dests = jump_dests(&code);
+		Self { ext, code, jump_dests, input, stack: Vec::new(), memory: Vec::new(), pc: 0 }
+	}
+
+	/// Charge the given amount of EVM gas.
+	fn charge_evm_gas(&mut self, gas: u64) -> Result<(), DispatchError> {
+		self.charge_gas(RuntimeCosts::EvmGas(gas))
+	}
+
+	/// Charge the gas meter with the specified token.
+	fn charge_gas(&mut self, costs: RuntimeCosts) -> Result<(), DispatchError> {
+		self.ext.gas_meter_mut().charge(costs).map(|_| ())
+	}
+
+	/// Mark `address` as accessed and return whether the access is cold.
+	fn access_account(&mut self, address: &sp_core::H160) -> bool {
+		!self.ext.access_account(address)
+	}
+
+	/// Pop `N` items from the stack, the first item being the top of the stack.
+	fn pop<const N: usize>(&mut self) -> Result<[U256; N], DispatchError> {
+		let start = self.stack.len().checked_sub(N).ok_or(<Error<E::T>>::StackUnderflow)?;
+		let mut items = [U256::zero(); N];
+		for (item, value) in items.iter_mut().zip(self.stack.drain(start..).rev()) {
+			*item = value;
+		}
+		Ok(items)
+	}
+
+	/// Push an item on the stack.
+	fn push(&mut self, value: U256) -> Result<(), DispatchError> {
+		if self.stack.len() >= limits::EVM_STACK_LIMIT as usize {
+			return Err(<Error<E::T>>::StackOverflow.into());
+		}
+		self.stack.push(value);
+		Ok(())
+	}
+
+	/// Make sure that the memory range of `len` bytes at `offset` is allocated and returns it.
+	///
+	/// The memory expansion is charged with its EVM gas cost.
+	fn memory_range(
+		&mut self,
+		offset: U256,
+		len: U256,
+	) -> Result<core::ops::Range<usize>, DispatchError> {
+		if len.is_zero() {
+			return Ok(0..0);
+		}
+		let end = offset
+			.checked_add(len)
+			.filter(|end| *end <= U256::from(limits::EVM_MEMORY_BYTES))
+			.ok_or(<Error<E::T>>::OutOfBounds)?
+			.as_usize();
+		let words = end.div_ceil(32) as u64;
+		let current_words = (self.memory.len() / 32) as u64;
+		if words > current_words {
+			self.charge_evm_gas(memory_cost(words).saturating_sub(memory_cost(current_words)))?;
+			self.memory.resize(words as usize * 32, 0);
+		}
+		Ok(offset.as_usize()..end)
+	}
+
+	/// Read `len` bytes of memory at `offset`.
+	fn read_memory(&mut self, offset: U256, len: U256) -> Result<Vec<u8>, DispatchError> {
+		let range = self.memory_range(offset, len)?;
+		Ok(self.memory[range].to_vec())
+	}
+
+	/// Copy `len` bytes of `data` at `data_offset` to the memory at `offset`.
+	///
+	/// Bytes past the end of `data` are copied as zeros.
+	fn copy_to_memory(
+		&mut self,
+		offset: U256,
+		data_offset: U256,
+		len: U256,
+		data: &[u8],
+	) -> Result<(), DispatchError> {
+		let range = self.memory_range(offset, len)?;
+		let data_offset = usize::try_from(data_offset).unwrap_or(usize::MAX);
+		let memory = &mut self.memory[range];
+		let data = data.get(data_offset..).unwrap_or_default();
+		let copied = memory.len().min(data.len());
+		memory[..copied].copy_from_slice(&data[..copied]);
+		memory[copied..].fill(0);
+		Ok(())
+	}
+}
+
+/// The EVM gas cost of a memory of `words` words of 32 bytes.
+fn memory_cost(words: u64) -> u64 {
+	words.saturating_mul(3).saturating_add(words.saturating_mul(words) / 512)
+}
+
+/// The EVM gas cost of copying `len` bytes.
+fn copy_cost(len: U256) -> u64 {
+	words(len).saturating_mul(3)
+}
+
+/// The number of words of 32 bytes needed to hold `len` bytes.
+fn words(len: U256) -> u64 {
+	u64::try_from(len).unwrap_or(u64::MAX).div_ceil(32)
+}
+
+/// Convert an amount of EVM gas to the weight passed to a sub call.
+fn gas_to_weight(gas: U256) -> Weight {
+	let gas = u64::try_from(gas).unwrap_or(u64::MAX);
+	Weight::from_parts(gas.saturating_mul(WEIGHT_PER_GAS), u64::MAX)
+}
+
+/// Convert a weight to the amount of EVM gas exposed to contracts.
+fn weight_to_gas(weight: Weight) -> U256 {
+	(weight.ref_time() / WEIGHT_PER_GAS).into()
+}
+
+/// Find the valid jump destinations of `code`: the `JUMPDEST` instructions which are not part of
+/// the immediate data of a `PUSH` instruction.
+fn jump_dests(code: &[u8]) -> Vec<bool> {
+	let mut jump_dests = vec![false; code.len()];
+	let mut pc = 0;
+	while let Some(&opcode) = code.get(pc) {
+		if opcode == instructions::JUMPDEST {
+			jump_dests[pc] = true;
+		}
+		pc += 1 + instructions::immediate_len(opcode);
+	}
+	jump_dests
+}
@@ -0,0 +1,933 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The EVM instruction set, up to and including the Cancun hard fork.
+
+use super::{copy_cost, gas_to_weight, weight_to_gas, Halt, Interpreter};
+use crate::{
+	address::AddressMapper,
+	exec::{ErrorOrigin, ExecError, Ext, Key},
+	limits,
+	precompiles::{All as AllPrecompiles, Precompiles},
+	vm::{CodeLoadToken, RuntimeCosts},
+	BalanceOf, Code, Config, Error, Pallet, GAS_PRICE,
+};
+use alloc::vec::Vec;
+use core::{cmp::Ordering, mem};
+use frame_support::{traits::Get, weights::Weight};
+use pallet_revive_uapi::ReturnFlags;
+use sp_core::{H160, H256, U256, U512};
+use sp_io::hashing::keccak_256;
+use sp_runtime::DispatchError;
+
+pub const STOP: u8 = 0x00;
+pub const ADD: u8 = 0x01;
+pub const MUL: u8 = 0x02;
+pub const SUB: u8 = 0x03;
+pub const DIV: u8 = 0x04;
+pub const SDIV: u8 = 0x05;
+pub const MOD: u8 = 0x06;
+pub const SMOD: u8 = 0x07;
+pub const ADDMOD: u8 = 0x08;
+pub const MULMOD: u8 = 0x09;
+pub const EXP: u8 = 0x0A;
+pub const SIGNEXTEND: u8 = 0x0B;
+pub const LT: u8 = 0x10;
+pub const GT: u8 = 0x11;
+pub const SLT: u8 = 0x12;
+pub const SGT: u8 = 0x13;
+pub const EQ: u8 = 0x14;
+pub const ISZERO: u8 = 0x15;
+pub const AND: u8 = 0x16;
+pub const OR: u8 = 0x17;
+pub const XOR: u8 = 0x18;
+pub const NOT: u8 = 0x19;
+pub const BYTE: u8 = 0x1A;
+pub const SHL: u8 = 0x1B;
+pub const SHR: u8 = 0x1C;
+pub const SAR: u8 = 0x1D;
+pub const KECCAK256: u8 = 0x20;
+pub const ADDRESS: u8 = 0x30;
+pub const BALANCE: u8 = 0x31;
+pub const ORIGIN: u8 = 0x32;
+pub const CALLER: u8 = 0x33;
+pub const CALLVALUE: u8 = 0x34;
+pub const CALLDATALOAD: u8 = 0x35;
+pub const CALLDATASIZE: u8 = 0x36;
+pub const CALLDATACOPY: u8 = 0x37;
+pub const CODESIZE: u8 = 0x38;
+pub const CODECOPY: u8 = 0x39;
+pub const GASPRICE: u8 = 0x3A;
+pub const EXTCODESIZE: u8 = 0x3B;
+pub const EXTCODECOPY: u8 = 0x3C;
+pub const RETURNDATASIZE: u8 = 0x3D;
+pub const RETURNDATACOPY: u8 = 0x3E;
+pub const EXTCODEHASH: u8 = 0x3F;
+pub const BLOCKHASH: u8 = 0x40;
+pub const COINBASE: u8 = 0x41;
+pub const TIMESTAMP: u8 = 0x42;
+pub const NUMBER: u8 = 0x43;
+pub const PREVRANDAO: u8 = 0x44;
+pub const GASLIMIT: u8 = 0x45;
+pub const CHAINID: u8 = 0x46;
+pub const SELFBALANCE: u8 = 0x47;
+pub const BASEFEE: u8 = 0x48;
+pub const BLOBHASH: u8 = 0x49;
+pub const BLOBBASEFEE: u8 = 0x4A;
+pub const POP: u8 = 0x50;
+pub const MLOAD: u8 = 0x51;
+pub const MSTORE: u8 = 0x52;
+pub const MSTORE8: u8 = 0x53;
+pub const SLOAD: u8 = 0x54;
+pub const SSTORE: u8 = 0x55;
+pub const JUMP: u8 = 0x56;
+pub const JUMPI: u8 = 0x57;
+pub const PC: u8 = 0x58;
+pub const MSIZE: u8 = 0x59;
+pub const GAS: u8 = 0x5A;
+pub const JUMPDEST: u8 = 0x5B;
+pub const TLOAD: u8 = 0x5C;
+pub const TSTORE: u8 = 0x5D;
+pub const MCOPY: u8 = 0x5E;
+pub const PUSH0: u8 = 0x5F;
+pub const PUSH1: u8 = 0x60;
+pub const PUSH32: u8 = 0x7F;
+pub const DUP1: u8 = 0x80;
+pub const DUP16: u8 = 0x8F;
+pub const SWAP1: u8 = 0x90;
+pub const SWAP16: u8 = 0x9F;
+pub const LOG0: u8 = 0xA0;
+pub const LOG4: u8 = 0xA4;
+pub const CREATE: u8 = 0xF0;
+pub const CALL: u8 = 0xF1;
+pub const CALLCODE: u8 = 0xF2;
+pub const RETURN: u8 = 0xF3;
+pub const DELEGATECALL: u8 = 0xF4;
+pub const CREATE2: u8 = 0xF5;
+pub const STATICCALL: u8 = 0xFA;
+pub const REVERT: u8 = 0xFD;
+pub const INVALID: u8 = 0xFE;
+pub const SELFDESTRUCT: u8 = 0xFF;
+
+/// EVM gas costs of the instructions that don't interact with the state.
+mod gas {
+	pub const BASE: u64 = 2;
+	pub const VERY_LOW: u64 = 3;
+	pub const LOW: u64 = 5;
+	pub const MID: u64 = 8;
+	pub const HIGH: u64 = 10;
+	pub const JUMPDEST: u64 = 1;
+	pub const EXP: u64 = 10;
+	pub const EXP_BYTE: u64 = 50;
+}
+
+/// The number of bytes of immediate data following `opcode`.
+pub fn immediate_len(opcode: u8) -> usize {
+	match opcode {
+		PUSH1..=PUSH32 => (opcode - PUSH1 + 1) as usize,
+		_ => 0,
+	}
+}
+
+/// The kinds of calls that can be made from an EVM contract.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CallKind {
+	Call,
+	DelegateCall,
+	StaticCall,
+}
+
+impl<'a, E: Ext> Interpreter<'a, E>
+where
+	BalanceOf<E::T>: Into<U256> + TryFrom<U256>,
+{
+	/// Execute the next instruction.
+	///
+	/// Returns `Some` if the execution halted.
+	pub(super) fn step(&mut self) -> Result<Option<Halt>, ExecError> {
+		let Some(&opcode) = self.code.get(self.pc) else { return Ok(Some(Halt::Stop)) };
+		let pc = self.pc;
+		self.pc += 1;
+
+		match opcode {
+			STOP => return Ok(Some(Halt::Stop)),
+			ADD => self.binary_op(gas::VERY_LOW, |a, b| a.overflowing_add(b).0)?,
+			MUL => self.binary_op(gas::LOW, |a, b| a.overflowing_mul(b).0)?,
+			SUB => self.binary_op(gas::VERY_LOW, |a, b| a.overflowing_sub(b).0)?,
+			DIV => self.binary_op(gas::LOW, |a, b| a.checked_div(b).unwrap_or_default())?,
+			SDIV => self.binary_op(gas::LOW, signed_div)?,
+			MOD => self.binary_op(gas::LOW, |a, b| a.checked_rem(b).unwrap_or_default())?,
+			SMOD => self.binary_op(gas::LOW, signed_rem)?,
+			ADDMOD => {
+				self.charge_evm_gas(gas::MID)?;
+				let [a, b, n] = self.pop()?;
+				let result = if n.is_zero() {
+					U256::zero()
+				} else {
+					reduce((U512::from(a) + U512::from(b)) % U512::from(n))
+				};
+				self.push(result)?;
+			},
+			MULMOD => {
+				self.charge_evm_gas(gas::MID)?;
+				let [a, b, n] = self.pop()?;
+				let result =
+					if n.is_zero() { U256::zero() } else { reduce(a.full_mul(b) % U512::from(n)) };
+				self.push(result)?;
+			},
+			EXP => {
+				let [base, exponent] = self.pop()?;
+				let exponent_bytes = (exponent.bits() as u64).div_ceil(8);
+				self.charge_evm_gas(gas::EXP + gas::EXP_BYTE * exponent_bytes)?;
+				self.push(base.overflowing_pow(exponent).0)?;
+			},
+			SIGNEXTEND => self.binary_op(gas::LOW, sign_extend)?,
+			LT => self.binary_op(gas::VERY_LOW, |a, b| bool_to_word(a < b))?,
+			GT => self.binary_op(gas::VERY_LOW, |a, b| bool_to_word(a > b))?,
+			SLT => self.binary_op(gas::VERY_LOW, |a, b| {
+				bool_to_word(signed_cmp(a, b) == Ordering::Less)
+			})?,
+			SGT => self.binary_op(gas::VERY_LOW, |a, b| {
+				bool_to_word(signed_cmp(a, b) == Ordering::Greater)
+			})?,
+			EQ => self.binary_op(gas::VERY_LOW, |a, b| bool_to_word(a == b))?,
+			ISZERO => self.unary_op(gas::VERY_LOW, |a| bool_to_word(a.is_zero()))?,
+			AND => self.binary_op(gas::VERY_LOW, |a, b| a & b)?,
+			OR => self.binary_op(gas::VERY_LOW, |a, b| a | b)?,
+			XOR => self.binary_op(gas::VERY_LOW, |a, b| a ^ b)?,
+			NOT => self.unary_op(gas::VERY_LOW, |a| !a)?,
+			BYTE => self.binary_op(gas::VERY_LOW, |i, x| {
+				if i < U256::from(32) {
+					x.byte(31 - i.as_usize()).into()
+				} else {
+					U256::zero()
+				}
+			})?,
+			SHL => self.binary_op(gas::VERY_LOW, |shift, value| {
+				if shift < U256::from(256) {
+					value << shift.as_usize()
+				} else {
+					U256::zero()
+				}
+			})?,
+			SHR => self.binary_op(gas::VERY_LOW, |shift, value| {
+				if shift < U256::from(256) {
+					value >> shift.as_usize()
+				} else {
+					U256::zero()
+				}
+			})?,
+			SAR => self.binary_op(gas::VERY_LOW, arithmetic_shr)?,
+			KECCAK256 => {
+				let [offset, len] = self.pop()?;
+				let data = self.read_memory(offset, len)?;
+				self.charge_gas(RuntimeCosts::HashKeccak256(data.len() as u32))?;
+				self.push(U256::from_big_endian(&keccak_256(&data)))?;
+			},
+			ADDRESS => {
+				self.charge_gas(RuntimeCosts::Address)?;
+				let address = self.ext.address();
+				self.push(address_to_word(&address))?;
+			},
+			BALANCE => {
+				let [address] = self.pop()?;
+				let address = word_to_address(address);
+				let cold = self.access_account(&address);
+				self.charge_gas(RuntimeCosts::BalanceOf { cold })?;
+				let balance = self.ext.balance_of(&address);
+				self.push(balance)?;
+			},
+			ORIGIN => {
+				self.charge_gas(RuntimeCosts::Origin)?;
+				let origin =
+					<E::T as Config>::AddressMapper::to_address(self.ext.origin().account_id()?);
+				self.push(address_to_word(&origin))?;
+			},
+			CALLER => {
+				self.charge_gas(RuntimeCosts::Caller)?;
+				let caller =
+					<E::T as Config>::AddressMapper::to_address(self.ext.caller().account_id()?);
+				self.push(address_to_word(&caller))?;
+			},
+			CALLVALUE => {
+				self.charge_gas(RuntimeCosts::ValueTransferred)?;
+				let value = self.ext.value_transferred();
+				self.push(value)?;
+			},
+			CALLDATALOAD => {
+				self.charge_evm_gas(gas::VERY_LOW)?;
+				let [offset] = self.pop()?;
+				let mut word = [0u8; 32];
+				if let Some(data) =
+					usize::try_from(offset).ok().and_then(|offset| self.input.get(offset..))
+				{
+					let len = data.len().min(32);
+					word[..len].copy_from_slice(&data[..len]);
+				}
+				self.push(U256::from_big_endian(&word))?;
+			},
+			CALLDATASIZE => {
+				self.charge_evm_gas(gas::BASE)?;
+				self.push(self.input.len().into())?;
+			},
+			CALLDATACOPY => {
+				let [offset, data_offset, len] = self.pop()?;
+				self.charge_evm_gas(gas::VERY_LOW.saturating_add(copy_cost(len)))?;
+				let input = mem::take(&mut self.input);
+				let result = self.copy_to_memory(offset, data_offset, len, &input);
+				self.input = input;
+				result?;
+			},
+			CODESIZE => {
+				self.charge_evm_gas(gas::BASE)?;
+				self.push(self.code.len().into())?;
+			},
+			CODECOPY => {
+				let [offset, code_offset, len] = self.pop()?;
+				self.charge_evm_gas(gas::VERY_LOW.saturating_add(copy_cost(len)))?;
+				let code = mem::take(&mut self.code);
+				let result = self.copy_to_memory(offset, code_offset, len, &code);
+				self.code = code;
+				result?;
+			},
+			GASPRICE => {
+				self.charge_gas(RuntimeCosts::GasPrice)?;
+				self.push(GAS_PRICE.into())?;
+			},
+			EXTCODESIZE => {
+				let [address] = self.pop()?;
+				let address = word_to_address(address);
+				let cold = self.access_account(&address);
+				self.charge_gas(RuntimeCosts::CodeSize { cold })?;
+				let code_size = self.ext.code_size(&address);
+				self.push(code_size.into())?;
+			},
+			EXTCODECOPY => {
+				let [address, offset, code_offset, len] = self.pop()?;
+				let address = word_to_address(address);
+				let cold = self.access_account(&address);
+				self.charge_gas(RuntimeCosts::CodeSize { cold })?;
+				let code_size = self.ext.code_size(&address);
+				self.ext
+					.gas_meter_mut()
+					.charge(CodeLoadToken(code_size.try_into().unwrap_or(u32::MAX)))?;
+				self.charge_evm_gas(gas::VERY_LOW.saturating_add(copy_cost(len)))?;
+				let code = Pallet::<E::T>::code(&address);
+				self.copy_to_memory(offset, code_offset, len, &code)?;
+			},
+			RETURNDATASIZE => {
+				self.charge_gas(RuntimeCosts::ReturnDataSize)?;
+				let len = self.ext.last_frame_output().data.len();
+				self.push(len.into())?;
+			},
+			RETURNDATACOPY => {
+				let [offset, data_offset, len] = self.pop()?;
+				self.charge_evm_gas(gas::VERY_LOW.saturating_add(copy_cost(len)))?;
+				let data = mem::take(&mut self.ext.last_frame_output_mut().data);
+				let in_bounds =
+					data_offset.checked_add(len).is_some_and(|end| end <= U256::from(data.len()));
+				let result = if in_bounds {
+					self.copy_to_memory(offset, data_offset, len, &data)
+				} else {
+					Err(Error::<E::T>::OutOfBounds.into())
+				};
+				self.ext.last_frame_output_mut().data = data;
+				result?;
+			},
+			EXTCODEHASH => {
+				let [address] = self.pop()?;
+				let address = word_to_address(address);
+				let cold = self.access_account(&address);
+				self.charge_gas(RuntimeCosts::CodeHash { cold })?;
+				let code_hash = self.ext.code_hash(&address);
+				self.push(U256::from_big_endian(code_hash.as_bytes()))?;
+			},
+			BLOCKHASH => {
+				self.charge_gas(RuntimeCosts::BlockHash)?;
+				let [block_number] = self.pop()?;
+				let block_hash = self.ext.block_hash(block_number).unwrap_or_default();
+				self.push(U256::from_big_endian(block_hash.as_bytes()))?;
+			},
+			COINBASE => {
+				self.charge_gas(RuntimeCosts::BlockAuthor)?;
+				let block_author = self.ext.block_author().unwrap_or_default();
+				self.push(address_to_word(&block_author))?;
+			},
+			TIMESTAMP => {
+				self.charge_gas(RuntimeCosts::Now)?;
+				let now = self.ext.now();
+				self.push(now)?;
+			},
+			NUMBER => {
+				self.charge_gas(RuntimeCosts::BlockNumber)?;
+				let block_number = self.ext.block_number();
+				self.push(block_number)?;
+			},
+			PREVRANDAO | BLOBBASEFEE => {
+				self.charge_evm_gas(gas::BASE)?;
+				self.push(U256::zero())?;
+			},
+			GASLIMIT => {
+				self.charge_gas(RuntimeCosts::GasLimit)?;
+				let gas_limit =
+					<E::T as frame_system::Config>::BlockWeights::get().max_block.ref_time();
+				self.push(gas_limit.into())?;
+			},
+			CHAINID => {
+				self.charge_evm_gas(gas::BASE)?;
+				self.push(<E::T as Config>::ChainId::get().into())?;
+			},
+			SELFBALANCE => {
+				self.charge_gas(RuntimeCosts::Balance)?;
+				let balance = self.ext.balance();
+				self.push(balance)?;
+			},
+			BASEFEE => {
+				self.charge_gas(RuntimeCosts::BaseFee)?;
+				self.push(U256::zero())?;
+			},
+			BLOBHASH => {
+				self.charge_evm_gas(gas::VERY_LOW)?;
+				let [_index] = self.pop()?;
+				self.push(U256::zero())?;
+			},
+			POP => {
+				self.charge_evm_gas(gas::BASE)?;
+				let [_] = self.pop()?;
+			},
+			MLOAD => {
+				self.charge_evm_gas(gas::VERY_LOW)?;
+				let [offset] = self.pop()?;
+				let range = self.memory_range(offset, 32.into())?;
+				let word = U256::from_big_endian(&self.memory[range]);
+				self.push(word)?;
+			},
+			MSTORE => {
+				self.charge_evm_gas(gas::VERY_LOW)?;
+				let [offset, value] = self.pop()?;
+				let range = self.memory_range(offset, 32.into())?;
+				self.memory[range].copy_from_slice(&value.to_big_endian());
+			},
+			MSTORE8 => {
+				self.charge_evm_gas(gas::VERY_LOW)?;
+				let [offset, value] = self.pop()?;
+				let range = self.memory_range(offset, 1.into())?;
+				self.memory[range.start] = value.byte(0);
+			},
+			SLOAD => {
+				let [key] = self.pop()?;
+				let value = self.load(key, false)?;
+				self.push(value)?;
+			},
+			SSTORE => {
+				let [key, value] = self.pop()?;
+				self.store(key, value, false)?;
+			},
+			JUMP => {
+				self.charge_evm_gas(gas::MID)?;
+				let [dest] = self.pop()?;
+				self.jump(dest)?;
+			},
+			JUMPI => {
+				self.charge_evm_gas(gas::HIGH)?;
+				let [dest, condition] = self.pop()?;
+				if !condition.is_zero() {
+					self.jump(dest)?;
+				}
+			},
+			PC => {
+				self.charge_evm_gas(gas::BASE)?;
+				self.push(pc.into())?;
+			},
+			MSIZE => {
+				self.charge_evm_gas(gas::BASE)?;
+				self.push(self.memory.len().into())?;
+			},
+			GAS => {
+				self.charge_evm_gas(gas::BASE)?;
+				let gas_left = weight_to_gas(self.ext.gas_meter().gas_left());
+				self.push(gas_left)?;
+			},
+			JUMPDEST => self.charge_evm_gas(gas::JUMPDEST)?,
+			TLOAD => {
+				let [key] = self.pop()?;
+				let value = self.load(key, true)?;
+				self.push(value)?;
+			},
+			TSTORE => {
+				let [key, value] = self.pop()?;
+				self.store(key, value, true)?;
+			},
+			MCOPY => {
+				let [offset, source, len] = self.pop()?;
+				self.charge_evm_gas(gas::VERY_LOW.saturating_add(copy_cost(len)))?;
+				let source = self.memory_range(source, len)?;
+				let dest = self.memory_range(offset, len)?;
+				self.memory.copy_within(source, dest.start);
+			},
+			PUSH0 => {
+				self.charge_evm_gas(gas::BASE)?;
+				self.push(U256::zero())?;
+			},
+			PUSH1..=PUSH32 => {
+				self.charge_evm_gas(gas::VERY_LOW)?;
+				let len = immediate_len(opcode);
+				let mut word = [0u8; 32];
+				let data = self.code.get(self.pc..).unwrap_or_default();
+				let available = data.len().min(len);
+				// Immediate data past the end of the code is padded with zeros.
+				word[32 - len..32 - len + available].copy_from_slice(&data[..available]);
+				self.pc += len;
+				self.push(U256::from_big_endian(&word))?;
+			},
+			DUP1..=DUP16 => {
+				self.charge_evm_gas(gas::VERY_LOW)?;
+				let depth = (opcode - DUP1) as usize;
+				let value = self
+					.stack
+					.iter()
+					.rev()
+					.nth(depth)
+					.copied()
+					.ok_or(Error::<E::T>::StackUnderflow)?;
+				self.push(value)?;
+			},
+			SWAP1..=SWAP16 => {
+				self.charge_evm_gas(gas::VERY_LOW)?;
+				let depth = (opcode - SWAP1 + 1) as usize;
+				let top = self.stack.len().checked_sub(1);
+				let other = self.stack.len().checked_sub(depth + 1);
+				let (Some(top), Some(other)) = (top, other) else {
+					return Err(Error::<E::T>::StackUnderflow.into())
+				};
+				self.stack.swap(top, other);
+			},
+			LOG0..=LOG4 => self.log((opcode - LOG0) as usize)?,
+			CREATE => {
+				let [value, offset, len] = self.pop()?;
+				self.create(value, offset, len, None)?;
+			},
+			CREATE2 => {
+				let [value, offset, len, salt] = self.pop()?;
+				self.create(value, offset, len, Some(salt.to_big_endian()))?;
+			},
+			CALL => self.call(CallKind::Call)?,
+			DELEGATECALL => self.call(CallKind::DelegateCall)?,
+			STATICCALL => self.call(CallKind::StaticCall)?,
+			RETURN => {
+				let [offset, len] = self.pop()?;
+				return Ok(Some(Halt::Return(self.read_memory(offset, len)?)));
+			},
+			REVERT => {
+				let [offset, len] = self.pop()?;
+				return Ok(Some(Halt::Revert(self.read_memory(offset, len)?)));
+			},
+			SELFDESTRUCT => {
+				if self.ext.is_read_only() {
+					return Err(Error::<E::T>::StateChangeDenied.into());
+				}
+				self.charge_gas(RuntimeCosts::Terminate)?;
+				let [beneficiary] = self.pop()?;
+				self.ext.terminate(&word_to_address(beneficiary))?;
+				return Ok(Some(Halt::Stop));
+			},
+			// `CALLCODE` is deprecated in favour of `DELEGATECALL` and not supported.
+			CALLCODE | INVALID => return Err(Error::<E::T>::InvalidInstruction.into()),
+			_ => return Err(Error::<E::T>::InvalidInstruction.into()),
+		}
+
+		Ok(None)
+	}
+
+	/// Replace the top of the stack with the result of `op`.
+	fn unary_op(&mut self, gas: u64, op: impl FnOnce(U256) -> U256) -> Result<(), ExecError> {
+		self.charge_evm_gas(gas)?;
+		let [a] = self.pop()?;
+		self.push(op(a))?;
+		Ok(())
+	}
+
+	/// Replace the two items at the top of the stack with the result of `op`.
+	///
+	/// The first argument of `op` is the top of the stack.
+	fn binary_op(
+		&mut self,
+		gas: u64,
+		op: impl FnOnce(U256, U256) -> U256,
+	) -> Result<(), ExecError> {
+		self.charge_evm_gas(gas)?;
+		let [a, b] = self.pop()?;
+		self.push(op(a, b))?;
+		Ok(())
+	}
+
+	/// Continue the execution at `dest`, which needs to be a `JUMPDEST` instruction.
+	fn jump(&mut self, dest: U256) -> Result<(), DispatchError> {
+		let dest = usize::try_from(dest)
+			.ok()
+			.filter(|dest| self.jump_dests.get(*dest).copied().unwrap_or(false))
+			.ok_or(Error::<E::T>::InvalidJump)?;
+		self.pc = dest;
+		Ok(())
+	}
+
+	/// Mark `key` as accessed and return whether the access is cold.
+	///
+	/// Transient storage is never read from the state and hence not subject to warm and cold
+	/// accesses.
+	fn access_storage(&mut self, key: &Key, transient: bool) -> bool {
+		!transient && !self.ext.access_storage(key)
+	}
+
+	/// Load the storage slot `key`, `transient` selecting the transient storage.
+	///
+	/// Slots are shared with PolkaVM contracts using fixed size keys.
+	fn load(&mut self, key: U256, transient: bool) -> Result<U256, ExecError> {
+		let costs = |len, cold| {
+			if transient {
+				RuntimeCosts::GetTransientStorage(len)
+			} else {
+				RuntimeCosts::GetStorage { len, cold }
+			}
+		};
+		let max_value_size = self.ext.max_value_size();
+		// The access is charged as cold until the storage slot is known to be warm.
+		let charged = self.ext.gas_meter_mut().charge(costs(max_value_size, true))?;
+		let key = Key::from_fixed(key.to_big_endian());
+		let cold = self.access_storage(&key, transient);
+		let value = if transient {
+			self.ext.get_transient_storage(&key)
+		} else {
+			self.ext.get_storage(&key)
+		}
+		.unwrap_or_default();
+		self.ext.gas_meter_mut().adjust_gas(charged, costs(value.len() as u32, cold));
+
+		let mut word = [0u8; 32];
+		let len = value.len().min(word.len());
+		word[..len].copy_from_slice(&value[..len]);
+		Ok(U256::from_big_endian(&word))
+	}
+
+	/// Store `value` in the storage slot `key`, `transient` selecting the transient storage.
+	///
+	/// Storing zero clears the slot.
+	fn store(&mut self, key: U256, value: U256, transient: bool) -> Result<(), ExecError> {
+		if self.ext.is_read_only() {
+			return Err(Error::<E::T>::StateChangeDenied.into());
+		}
+
+		let max_size = self.ext.max_value_size();
+		let (costs, value): (fn(u32, bool) -> RuntimeCosts, _) = match (value.is_zero(), transient)
+		{
+			(true, false) => (|len, cold| RuntimeCosts::ClearStorage { len, cold }, None),
+			(true, true) => (|len, _| RuntimeCosts::ClearTransientStorage(len), None),
+			(false, false) => (
+				|old_bytes, cold| RuntimeCosts::SetStorage { new_bytes: 32, old_bytes, cold },
+				Some(value),
+			),
+			(false, true) => (
+				|old_bytes, _| RuntimeCosts::SetTransientStorage { new_bytes: 32, old_bytes },
+				Some(value),
+			),
+		};
+		// The access is charged as cold until the storage slot is known to be warm.
+		let charged = self.ext.gas_meter_mut().charge(costs(max_size, true))?;
+		let key = Key::from_fixed(key.to_big_endian());
+		let cold = self.access_storage(&key, transient);
+
+		let value = value.map(|value| value.to_big_endian().to_vec());
+		let write_outcome = if transient {
+			self.ext.set_transient_storage(&key, value, false)?
+		} else {
+			self.ext.set_storage(&key, value, false)?
+		};
+		self.ext
+			.gas_meter_mut()
+			.adjust_gas(charged, costs(write_outcome.old_len(), cold));
+		Ok(())
+	}
+
+	/// Deposit an event with `num_topic` topics.
+	fn log(&mut self, num_topic: usize) -> Result<(), ExecError> {
+		if self.ext.is_read_only() {
+			return Err(Error::<E::T>::StateChangeDenied.into());
+		}
+
+		let [offset, len] = self.pop()?;
+		let topics = (0..num_topic)
+			.map(|_| self.pop().map(|[topic]| H256::from(topic.to_big_endian())))
+			.collect::<Result<Vec<_>, _>>()?;
+		if len > self.ext.max_value_size().into() {
+			return Err(Error::<E::T>::ValueTooLarge.into());
+		}
+		self.charge_gas(RuntimeCosts::DepositEvent {
+			num_topic: num_topic as u32,
+			len: len.as_u32(),
+		})?;
+
+		let data = self.read_memory(offset, len)?;
+		self.ext.deposit_event(topics, data);
+		Ok(())
+	}
+
+	/// The weight that can be passed to a sub call: all but one 64th of the remaining gas.
+	///
+	/// See [EIP-150](https://eips.ethereum.org/EIPS/eip-150).
+	fn sub_call_weight(&self, gas: Option<U256>) -> Weight {
+		let ref_time_left = self.ext.gas_meter().gas_left().ref_time();
+		let available = Weight::from_parts(ref_time_left - ref_time_left / 64, u64::MAX);
+		gas.map_or(available, |gas| gas_to_weight(gas).min(available))
+	}
+
+	/// Execute one of the call instructions.
+	fn call(&mut self, kind: CallKind) -> Result<(), ExecError> {
+		let [gas, callee] = self.pop()?;
+		let value = if kind == CallKind::Call { self.pop::<1>()?[0] } else { U256::zero() };
+		let [input_offset, input_len, output_offset, output_len] = self.pop()?;
+
+		let callee = word_to_address(callee);
+		let precompile = <AllPrecompiles<E::T>>::get::<E>(&callee.as_fixed_bytes());
+		match &precompile {
+			Some(precompile) if precompile.has_contract_info() =>
+				self.charge_gas(RuntimeCosts::PrecompileWithInfoBase)?,
+			Some(_) => self.charge_gas(RuntimeCosts::PrecompileBase)?,
+			None => {
+				let cold = self.access_account(&callee);
+				self.charge_gas(if kind == CallKind::DelegateCall {
+					RuntimeCosts::DelegateCallBase { cold }
+				} else {
+					RuntimeCosts::CallBase { cold }
+				})?;
+			},
+		};
+
+		// we do check this in exec.rs but we want to error out early
+		if input_len > limits::CALLDATA_BYTES.into() {
+			Err(<Error<E::T>>::CallDataTooLarge)?;
+		}
+		let input_data = self.read_memory(input_offset, input_len)?;
+		if precompile.is_some() {
+			self.charge_gas(RuntimeCosts::PrecompileDecode(input_data.len() as u32))?;
+		} else {
+			self.charge_gas(RuntimeCosts::CopyFromContract(input_data.len() as u32))?;
+		}
+		let output_range = self.memory_range(output_offset, output_len)?;
+
+		let weight = self.sub_call_weight(Some(gas));
+		let call_outcome = match kind {
+			CallKind::Call | CallKind::StaticCall => {
+				let read_only = kind == CallKind::StaticCall;
+				if !value.is_zero() {
+					// If the call value is non-zero and state change is not allowed, issue an
+					// error.
+					if self.ext.is_read_only() {
+						return Err(Error::<E::T>::StateChangeDenied.into());
+					}
+
+					self.charge_gas(RuntimeCosts::CallTransferSurcharge {
+						dust_transfer: Pallet::<E::T>::has_dust(value),
+					})?;
+				}
+				self.ext.call(weight, U256::MAX, &callee, value, input_data, true, read_only)
+			},
+			CallKind::DelegateCall => self.ext.delegate_call(weight, U256::MAX, callee, input_data),
+		};
+
+		let success = match call_outcome {
+			Ok(()) => {
+				let output = &self.ext.last_frame_output().data;
+				let len = output.len().min(output_range.len());
+				self.memory[output_range.start..output_range.start + len]
+					.copy_from_slice(&output[..len]);
+				self.charge_gas(RuntimeCosts::CopyToContract(len as u32))?;
+				!self.ext.last_frame_output().flags.contains(ReturnFlags::REVERT)
+			},
+			Err(err) => {
+				Self::ensure_soft_failure(err)?;
+				*self.ext.last_frame_output_mut() = Default::default();
+				false
+			},
+		};
+		self.push(bool_to_word(success))?;
+		Ok(())
+	}
+
+	/// Execute `CREATE` or `CREATE2` depending on whether a `salt` is supplied.
+	fn create(
+		&mut self,
+		value: U256,
+		offset: U256,
+		len: U256,
+		salt: Option<[u8; 32]>,
+	) -> Result<(), ExecError> {
+		if self.ext.is_read_only() {
+			return Err(Error::<E::T>::StateChangeDenied.into());
+		}
+
+		let init_code = self.read_memory(offset, len)?;
+		self.charge_gas(RuntimeCosts::Instantiate {
+			input_data_len: init_code.len() as u32,
+			balance_transfer: Pallet::<E::T>::has_balance(value),
+			dust_transfer: Pallet::<E::T>::has_dust(value),
+		})?;
+
+		let weight = self.sub_call_weight(None);
+		let address = match self.ext.instantiate(
+			weight,
+			U256::MAX,
+			Code::Upload(init_code),
+			value,
+			Vec::new(),
+			salt.as_ref(),
+		) {
+			Ok(_) if self.ext.last_frame_output().flags.contains(ReturnFlags::REVERT) =>
+				U256::zero(),
+			Ok(address) => address_to_word(&address),
+			Err(err) => {
+				Self::ensure_soft_failure(err)?;
+				*self.ext.last_frame_output_mut() = Default::default();
+				U256::zero()
+			},
+		};
+		self.push(address)?;
+		Ok(())
+	}
+
+	/// Decide whether the error returned from a sub call traps the caller.
+	///
+	/// Mirrors the classification of PolkaVM contracts: errors in the callee as well as failed
+	/// transfers and address collisions fail the sub call, other errors trap the caller.
+	fn ensure_soft_failure(err: ExecError) -> Result<(), DispatchError> {
+		let transfer_failed = Error::<E::T>::TransferFailed.into();
+		let duplicate_contract = Error::<E::T>::DuplicateContract.into();
+		let unsupported_precompile = Error::<E::T>::UnsupportedPrecompileAddress.into();
+
+		match (err.error, err.origin) {
+			(err, _) if err == transfer_failed || err == duplicate_contract => Ok(()),
+			(err, _) if err == unsupported_precompile => Err(err),
+			(_, ErrorOrigin::Callee) => Ok(()),
+			(err, _) => Err(err),
+		}
+	}
+}
+
+/// Convert a boolean to a word.
+fn bool_to_word(value: bool) -> U256 {
+	if value {
+		U256::one()
+	} else {
+		U256::zero()
+	}
+}
+
+/// The address stored in the lower 20 bytes of `word`.
+fn word_to_address(word: U256) -> H160 {
+	H160::from_slice(&word.to_big_endian()[12..])
+}
+
+/// Convert an address to a word.
+fn address_to_word(address: &H160) -> U256 {
+	U256::from_big_endian(address.as_bytes())
+}
+
+/// Truncate the result of a modular operation to a word, which it always fits in.
+fn reduce(value: U512) -> U256 {
+	U256::try_from(value).unwrap_or_default()
+}
+
+/// Whether `value` is negative when interpreted as a two's complement number.
+fn is_negative(value: U256) -> bool {
+	value.bit(255)
+}
+
+/// The two's complement negation of `value`.
+fn negate(value: U256) -> U256 {
+	(!value).overflowing_add(U256::one()).0
+}
+
+/// The absolute value of `value` interpreted as a two's complement number.
+fn abs(value: U256) -> U256 {
+	if is_negative(value) {
+		negate(value)
+	} else {
+		value
+	}
+}
+
+/// Signed division of two's complement numbers, rounding towards zero.
+fn signed_div(a: U256, b: U256) -> U256 {
+	if b.is_zero() {
+		return U256::zero();
+	}
+	let quotient = abs(a) / abs(b);
+	if is_negative(a) != is_negative(b) {
+		negate(quotient)
+	} else {
+		quotient
+	}
+}
+
+/// Signed remainder of two's complement numbers, taking the sign of the dividend.
+fn signed_rem(a: U256, b: U256) -> U256 {
+	if b.is_zero() {
+		return U256::zero();
+	}
+	let remainder = abs(a) % abs(b);
+	if is_negative(a) {
+		negate(remainder)
+	} else {
+		remainder
+	}
+}
+
+/// Compare two's complement numbers.
+fn signed_cmp(a: U256, b: U256) -> Ordering {
+	match (is_negative(a), is_negative(b)) {
+		(true, false) => Ordering::Less,
+		(false, true) => Ordering::Greater,
+		_ => a.cmp(&b),
+	}
+}
+
+/// Extend the sign of the two's complement number stored in the lower `size + 1` bytes of `value`.
+fn sign_extend(size: U256, value: U256) -> U256 {
+	if size >= U256::from(31) {
+		return value;
+	}
+	let sign_bit = size.as_usize() * 8 + 7;
+	let mask = (U256::one() << (sign_bit + 1)) - U256::one();
+	if value.bit(sign_bit) {
+		value | !mask
+	} else {
+		value & mask
+	}
+}
+
+/// Shift `value` to the right by `shift` bits, preserving its sign.
+fn arithmetic_shr(shift: U256, value: U256) -> U256 {
+	let negative = is_negative(value);
+	if shift >= U256::from(256) {
+		return if negative { U256::MAX } else { U256::zero() };
+	}
+	let shift = shift.as_usize();
+	if negative {
+		!((!value) >> shift)
+	} else {
+		value >> shift
+	}
+}
@@ -18,14 +18,17 @@
 //! This module provides a means for executing contracts
 //! represented in vm bytecode.
 
+mod evm;
 pub mod pvm;
 mod runtime_costs;
 
 pub use runtime_costs::RuntimeCosts;
+pub(crate) use runtime_costs::WEIGHT_PER_GAS;
 
 use crate::{
 	exec::{ExecResult, Executable, ExportedFunction, Ext},
 	gas::{GasMeter, Token},
+	storage::meter::Diff,
 	weights::WeightInfo,
 	AccountIdOf, BadOrigin, BalanceOf, CodeInfoOf, CodeVec, Config, Error, HoldReason,
 	PristineCode, Weight, LOG_TARGET,
@@ -38,7 +41,7 @@ use frame_support::{
 	traits::{fungible::MutateHold, tokens::Precision::BestEffort},
 };
 use sp_core::{H256, U256};
-use sp_runtime::DispatchError;
+use sp_runtime::{DispatchError, RuntimeDebug};
 
 /// Validated Vm module ready for execution.
 /// This data structure is immutable once created and stored.
@@ -76,6 +79,8 @@ pub struct CodeInfo<T: Config> {
 	refcount: u64,
 	/// Length of the code in bytes.
 	code_len: u32,
+	/// The type of the code, which is decided when it is uploaded.
+	code_type: BytecodeType,
 	/// The behaviour version that this contract operates under.
 	///
 	/// Whenever any observeable change (with the exception of weights) are made we need
@@ -86,6 +91,34 @@ pub struct CodeInfo<T: Config> {
 	behaviour_version: u32,
 }
 
+/// The type of bytecode a contract code is made of.
+#[derive(
+	Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, scale_info::TypeInfo, MaxEncodedLen,
+)]
+pub enum BytecodeType {
+	/// A PolkaVM blob.
+	Pvm,
+	/// EVM bytecode, run by the EVM interpreter.
+	Evm,
+}
+
+impl BytecodeType {
+	/// Returns the type of the given code.
+	///
+	/// PolkaVM blobs start with a magic prefix. EVM bytecode can't start with it as it would pop
+	/// from an empty stack.
+	///
+	/// This is only used to tell apart newly supplied code. The type of stored code is recorded
+	/// in its [`CodeInfo`].
+	pub fn of(code: &[u8]) -> Self {
+		if code.starts_with(&polkavm_common::program::BLOB_MAGIC) {
+			Self::Pvm
+		} else {
+			Self::Evm
+		}
+	}
+}
+
 impl ExportedFunction {
 	/// The vm export name for the function.
 	fn identifier(&self) -> &str {
@@ -114,6 +147,10 @@ impl<T: Config> Token<T> for CodeLoadToken {
 					.set_proof_size(0),
 			)
 	}
+
+	fn name(&self) -> &'static str {
+		"CodeLoad"
+	}
 }
 
 #[cfg(test)]
@@ -125,6 +162,35 @@ impl<T: Config> ContractBlob<T>
 where
 	BalanceOf<T>: Into<U256> + TryFrom<U256>,
 {
+	/// Create a code blob from code which is either a PolkaVM blob or EVM runtime code.
+	pub fn from_code(code: Vec<u8>, owner: AccountIdOf<T>) -> Result<Self, DispatchError> {
+		match BytecodeType::of(&code) {
+			BytecodeType::Pvm => Self::from_pvm_code(code, owner),
+			BytecodeType::Evm => Self::from_evm_runtime_code(code, owner),
+		}
+	}
+
+	/// Create a code blob from validated code of the given type.
+	///
+	/// The deposit is computed from the length of the code.
+	fn from_validated_code(code: CodeVec, owner: AccountIdOf<T>, code_type: BytecodeType) -> Self {
+		let code_len = code.len() as u32;
+		let bytes_added = code_len.saturating_add(<CodeInfo<T>>::max_encoded_len() as u32);
+		let deposit = Diff { bytes_added, items_added: 2, ..Default::default() }
+			.update_contract::<T>(None)
+			.charge_or_zero();
+		let code_info = CodeInfo {
+			owner,
+			deposit,
+			refcount: 0,
+			code_len,
+			code_type,
+			behaviour_version: Default::default(),
+		};
+		let code_hash = H256(sp_io::hashing::keccak_256(&code));
+		ContractBlob { code, code_info, code_hash }
+	}
+
 	/// Remove the code from storage and refund the deposit to its owner.
 	///
 	/// Applies all necessary checks before removing the code.
@@ -184,6 +250,20 @@ where
 	}
 }
 
+impl<T: Config> From<crate::migrations::v2::old::CodeInfo<T>> for CodeInfo<T> {
+	fn from(old: crate::migrations::v2::old::CodeInfo<T>) -> Self {
+		// Only PolkaVM code could be stored before the type of the code was recorded.
+		CodeInfo {
+			owner: old.owner,
+			deposit: old.deposit,
+			refcount: old.refcount,
+			code_len: old.code_len,
+			code_type: BytecodeType::Pvm,
+			behaviour_version: old.behaviour_version,
+		}
+	}
+}
+
 impl<T: Config> CodeInfo<T> {
 	#[cfg(test)]
 	pub fn new(owner: T::AccountId) -> Self {
@@ -192,6 +272,7 @@ impl<T: Config> CodeInfo<T> {
 			deposit: Default::default(),
 			refcount: 0,
 			code_len: 0,
+			code_type: BytecodeType::Pvm,
 			behaviour_version: Default::default(),
 		}
 	}
@@ -202,6 +283,11 @@ impl<T: Config> CodeInfo<T> {
 		self.refcount
 	}
 
+	/// Returns the account that uploaded the module and holds its deposit.
+	pub fn owner(&self) -> &AccountIdOf<T> {
+		&self.owner
+	}
+
 	/// Returns the deposit of the module.
 	pub fn deposit(&self) -> BalanceOf<T> {
 		self.deposit
@@ -212,6 +298,11 @@ impl<T: Config> CodeInfo<T> {
 		self.code_len.into()
 	}
 
+	/// Returns the type of the code.
+	pub fn code_type(&self) -> BytecodeType {
+		self.code_type
+	}
+
 	/// Returns the number of times the specified contract exists on the call stack. Delegated calls
 	/// Increment the reference count of a stored code by one.
 	///
@@ -265,18 +356,23 @@ where
 		Ok(Self { code, code_info, code_hash })
 	}
 
+	fn from_evm_init_code(code: Vec<u8>, owner: AccountIdOf<T>) -> Result<Self, DispatchError> {
+		Self::from_evm_init_code(code, owner)
+	}
+
 	fn execute<E: Ext<T = T>>(
 		self,
 		ext: &mut E,
 		function: ExportedFunction,
 		input_data: Vec<u8>,
 	) -> ExecResult {
-		if self.is_pvm() {
-			let prepared_call =
-				self.prepare_call(pvm::Runtime::new(ext, input_data), function, 0)?;
-			prepared_call.call()
-		} else {
-			Err(Error::<T>::CodeRejected.into())
+		match self.bytecode_type() {
+			BytecodeType::Pvm => {
+				let prepared_call =
+					self.prepare_call(pvm::Runtime::new(ext, input_data), function, 0)?;
+				prepared_call.call()
+			},
+			BytecodeType::Evm => evm::call(self.code.into_inner(), ext, input_data),
 		}
 	}
 
@@ -29,7 +29,7 @@ use crate::{
 	limits,
 	precompiles::{All as AllPrecompiles, Precompiles},
 	primitives::ExecReturnValue,
-	BalanceOf, Config, Error, Pallet, RuntimeCosts, LOG_TARGET, SENTINEL,
+	BalanceOf, Code, Config, Error, Pallet, RuntimeCosts, LOG_TARGET, SENTINEL,
 };
 use alloc::{vec, vec::Vec};
 use codec::Encode;
@@ -277,10 +277,10 @@ enum CallType {
 }
 
 impl CallType {
-	fn cost(&self) -> RuntimeCosts {
+	fn cost(&self, cold: bool) -> RuntimeCosts {
 		match self {
-			CallType::Call { .. } => RuntimeCosts::CallBase,
-			CallType::DelegateCall => RuntimeCosts::DelegateCallBase,
+			CallType::Call { .. } => RuntimeCosts::CallBase { cold },
+			CallType::DelegateCall => RuntimeCosts::DelegateCallBase { cold },
 		}
 	}
 }
@@ -352,6 +352,19 @@ impl<'a, E: Ext, M: ?Sized + Memory<E::T>> Runtime<'a, E, M> {
 		self.ext.gas_meter_mut().adjust_gas(charged, actual_costs);
 	}
 
+	/// Mark `address` as accessed and return whether the access is cold.
+	fn access_account(&mut self, address: &H160) -> bool {
+		!self.ext.access_account(address)
+	}
+
+	/// Mark `key` as accessed and return whether the access is cold.
+	///
+	/// Transient storage is never read from the state and hence not subject to warm and cold
+	/// accesses.
+	fn access_storage(&mut self, key: &Key, transient: bool) -> bool {
+		!transient && !self.ext.access_storage(key)
+	}
+
 	/// Write the given buffer and its length to the designated locations in sandbox memory and
 	/// charge gas according to the token returned by `create_token`.
 	///
@@ -507,11 +520,11 @@ impl<'a, E: Ext, M: ?Sized + Memory<E::T>> Runtime<'a, E, M> {
 		value: StorageValue,
 	) -> Result<u32, TrapReason> {
 		let transient = Self::is_transient(flags)?;
-		let costs = |new_bytes: u32, old_bytes: u32| {
+		let costs = |new_bytes: u32, old_bytes: u32, cold: bool| {
 			if transient {
 				RuntimeCosts::SetTransientStorage { new_bytes, old_bytes }
 			} else {
-				RuntimeCosts::SetStorage { new_bytes, old_bytes }
+				RuntimeCosts::SetStorage { new_bytes, old_bytes, cold }
 			}
 		};
 
@@ -521,12 +534,14 @@ impl<'a, E: Ext, M: ?Sized + Memory<E::T>> Runtime<'a, E, M> {
 		};
 
 		let max_size = self.ext.max_value_size();
-		let charged = self.charge_gas(costs(value_len, self.ext.max_value_size()))?;
+		// The access is charged as cold until the storage slot is known to be warm.
+		let charged = self.charge_gas(costs(value_len, self.ext.max_value_size(), true))?;
 		if value_len > max_size {
 			return Err(Error::<E::T>::ValueTooLarge.into());
 		}
 
 		let key = self.decode_key(memory, key_ptr, key_len)?;
+		let cold = self.access_storage(&key, transient);
 
 		let value = match value {
 			StorageValue::Memory { ptr, len } => Some(memory.read(ptr, len)?),
@@ -539,7 +554,7 @@ impl<'a, E: Ext, M: ?Sized + Memory<E::T>> Runtime<'a, E, M> {
 			self.ext.set_storage(&key, value, false)?
 		};
 
-		self.adjust_gas(charged, costs(value_len, write_outcome.old_len()));
+		self.adjust_gas(charged, costs(value_len, write_outcome.old_len(), cold));
 		Ok(write_outcome.old_len_with_sentinel())
 	}
 
@@ -551,21 +566,22 @@ impl<'a, E: Ext, M: ?Sized + Memory<E::T>> Runtime<'a, E, M> {
 		key_len: u32,
 	) -> Result<u32, TrapReason> {
 		let transient = Self::is_transient(flags)?;
-		let costs = |len| {
+		let costs = |len, cold| {
 			if transient {
 				RuntimeCosts::ClearTransientStorage(len)
 			} else {
-				RuntimeCosts::ClearStorage(len)
+				RuntimeCosts::ClearStorage { len, cold }
 			}
 		};
-		let charged = self.charge_gas(costs(self.ext.max_value_size()))?;
+		let charged = self.charge_gas(costs(self.ext.max_value_size(), true))?;
 		let key = self.decode_key(memory, key_ptr, key_len)?;
+		let cold = self.access_storage(&key, transient);
 		let outcome = if transient {
 			self.ext.set_transient_storage(&key, None, false)?
 		} else {
 			self.ext.set_storage(&key, None, false)?
 		};
-		self.adjust_gas(charged, costs(outcome.old_len()));
+		self.adjust_gas(charged, costs(outcome.old_len(), cold));
 		Ok(outcome.old_len_with_sentinel())
 	}
 
@@ -579,15 +595,16 @@ impl<'a, E: Ext, M: ?Sized + Memory<E::T>> Runtime<'a, E, M> {
 		read_mode: StorageReadMode,
 	) -> Result<ReturnErrorCode, TrapReason> {
 		let transient = Self::is_transient(flags)?;
-		let costs = |len| {
+		let costs = |len, cold| {
 			if transient {
 				RuntimeCosts::GetTransientStorage(len)
 			} else {
-				RuntimeCosts::GetStorage(len)
+				RuntimeCosts::GetStorage { len, cold }
 			}
 		};
-		let charged = self.charge_gas(costs(self.ext.max_value_size()))?;
+		let charged = self.charge_gas(costs(self.ext.max_value_size(), true))?;
 		let key = self.decode_key(memory, key_ptr, key_len)?;
+		let cold = self.access_storage(&key, transient);
 		let outcome = if transient {
 			self.ext.get_transient_storage(&key)
 		} else {
@@ -595,7 +612,7 @@ impl<'a, E: Ext, M: ?Sized + Memory<E::T>> Runtime<'a, E, M> {
 		};
 
 		if let Some(value) = outcome {
-			self.adjust_gas(charged, costs(value.len() as u32));
+			self.adjust_gas(charged, costs(value.len() as u32, cold));
 
 			match read_mode {
 				StorageReadMode::FixedOutput32 => {
@@ -625,7 +642,7 @@ impl<'a, E: Ext, M: ?Sized + Memory<E::T>> Runtime<'a, E, M> {
 				},
 			}
 		} else {
-			self.adjust_gas(charged, costs(0));
+			self.adjust_gas(charged, costs(0, cold));
 
 			match read_mode {
 				StorageReadMode::FixedOutput32 => {
@@ -651,21 +668,22 @@ impl<'a, E: Ext, M: ?Sized + Memory<E::T>> Runtime<'a, E, M> {
 		key_len: u32,
 	) -> Result<u32, TrapReason> {
 		let transient = Self::is_transient(flags)?;
-		let costs = |len| {
+		let costs = |len, cold| {
 			if transient {
 				RuntimeCosts::ContainsTransientStorage(len)
 			} else {
-				RuntimeCosts::ContainsStorage(len)
+				RuntimeCosts::ContainsStorage { len, cold }
 			}
 		};
-		let charged = self.charge_gas(costs(self.ext.max_value_size()))?;
+		let charged = self.charge_gas(costs(self.ext.max_value_size(), true))?;
 		let key = self.decode_key(memory, key_ptr, key_len)?;
+		let cold = self.access_storage(&key, transient);
 		let outcome = if transient {
 			self.ext.get_transient_storage_size(&key)
 		} else {
 			self.ext.get_storage_size(&key)
 		};
-		self.adjust_gas(charged, costs(outcome.unwrap_or(0)));
+		self.adjust_gas(charged, costs(outcome.unwrap_or(0), cold));
 		Ok(outcome.unwrap_or(SENTINEL))
 	}
 
@@ -679,15 +697,16 @@ impl<'a, E: Ext, M: ?Sized + Memory<E::T>> Runtime<'a, E, M> {
 		out_len_ptr: u32,
 	) -> Result<ReturnErrorCode, TrapReason> {
 		let transient = Self::is_transient(flags)?;
-		let costs = |len| {
+		let costs = |len, cold| {
 			if transient {
 				RuntimeCosts::TakeTransientStorage(len)
 			} else {
-				RuntimeCosts::TakeStorage(len)
+				RuntimeCosts::TakeStorage { len, cold }
 			}
 		};
-		let charged = self.charge_gas(costs(self.ext.max_value_size()))?;
+		let charged = self.charge_gas(costs(self.ext.max_value_size(), true))?;
 		let key = self.decode_key(memory, key_ptr, key_len)?;
+		let cold = self.access_storage(&key, transient);
 		let outcome = if transient {
 			self.ext.set_transient_storage(&key, None, true)?
 		} else {
@@ -695,7 +714,7 @@ impl<'a, E: Ext, M: ?Sized + Memory<E::T>> Runtime<'a, E, M> {
 		};
 
 		if let crate::storage::WriteOutcome::Taken(value) = outcome {
-			self.adjust_gas(charged, costs(value.len() as u32));
+			self.adjust_gas(charged, costs(value.len() as u32, cold));
 			self.write_sandbox_output(
 				memory,
 				out_ptr,
@@ -706,7 +725,7 @@ impl<'a, E: Ext, M: ?Sized + Memory<E::T>> Runtime<'a, E, M> {
 			)?;
 			Ok(ReturnErrorCode::Success)
 		} else {
-			self.adjust_gas(charged, costs(0));
+			self.adjust_gas(charged, costs(0, cold));
 			Ok(ReturnErrorCode::KeyNotFound)
 		}
 	}
@@ -730,7 +749,10 @@ impl<'a, E: Ext, M: ?Sized + Memory<E::T>> Runtime<'a, E, M> {
 			Some(precompile) if precompile.has_contract_info() =>
 				self.charge_gas(RuntimeCosts::PrecompileWithInfoBase)?,
 			Some(_) => self.charge_gas(RuntimeCosts::PrecompileBase)?,
-			None => self.charge_gas(call_type.cost())?,
+			None => {
+				let cold = self.access_account(&callee);
+				self.charge_gas(call_type.cost(cold))?
+			},
 		};
 
 		let deposit_limit = memory.read_u256(deposit_ptr)?;
@@ -872,7 +894,7 @@ impl<'a, E: Ext, M: ?Sized + Memory<E::T>> Runtime<'a, E, M> {
 		match self.ext.instantiate(
 			weight,
 			deposit_limit,
-			code_hash,
+			Code::Existing(code_hash),
 			value,
 			input_data,
 			salt.as_ref(),
@@ -909,6 +931,8 @@ pub struct PreparedCall<'a, E: Ext> {
 	module: polkavm::Module,
 	instance: polkavm::RawInstance,
 	runtime: Runtime<'a, E, polkavm::RawInstance>,
+	/// The parsed program. Only set when execution tracing is enabled.
+	program: Option<polkavm::ProgramBlob>,
 }
 
 impl<'a, E: Ext> PreparedCall<'a, E>
@@ -919,6 +943,9 @@ where
 	pub fn call(mut self) -> ExecResult {
 		let exec_result = loop {
 			let interrupt = self.instance.run();
+			if let Ok(polkavm::InterruptKind::Step) = interrupt {
+				self.trace_step();
+			}
 			if let Some(exec_result) =
 				self.runtime.handle_interrupt(interrupt, &self.module, &mut self.instance)
 			{
@@ -929,6 +956,41 @@ where
 		exec_result
 	}
 
+	/// Report the instruction that is about to be executed to the tracer.
+	fn trace_step(&mut self) {
+		use polkavm::program::{Instruction, ISA64_V1};
+
+		let Some(program) = self.program.as_ref() else { return };
+		let Some(pc) = self.instance.program_counter() else { return };
+		let Some(instruction) = program.instructions_bounded_at(ISA64_V1, pc).next() else {
+			return
+		};
+
+		let imports = self.module.imports();
+		let symbol = match instruction.kind {
+			Instruction::ecalli(idx) => imports.get(idx),
+			_ => None,
+		};
+		let opcode = symbol
+			.as_ref()
+			.and_then(|symbol| core::str::from_utf8(symbol.as_bytes()).ok())
+			.unwrap_or_else(|| instruction.kind.opcode().name());
+
+		let gas_left =
+			self.runtime.ext().gas_meter().gas_left_with_engine_fuel(self.instance.gas());
+		let registers = polkavm::Reg::ALL.map(|reg| self.instance.reg(reg));
+		let instance = &self.instance;
+		let stack_high = self.module.memory_map().stack_address_high();
+		let memory = || {
+			let sp = instance.reg(polkavm::Reg::SP) as u32;
+			instance.read_memory(sp, stack_high.saturating_sub(sp)).unwrap_or_default()
+		};
+
+		crate::tracing::if_tracing(|tracer| {
+			tracer.enter_opcode(pc.0.into(), opcode, gas_left, &registers, &memory)
+		});
+	}
+
 	/// The guest memory address at which the aux data is located.
 	#[cfg(feature = "runtime-benchmarks")]
 	pub fn aux_data_base(&self) -> u32 {
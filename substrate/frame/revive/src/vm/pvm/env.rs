@@ -22,12 +22,11 @@ use crate::{
 	exec::Ext,
 	limits,
 	primitives::ExecReturnValue,
-	storage::meter::Diff,
-	vm::{ExportedFunction, RuntimeCosts},
-	AccountIdOf, BalanceOf, CodeInfo, Config, ContractBlob, Error, Weight, SENTINEL,
+	vm::{BytecodeType, ExportedFunction, RuntimeCosts},
+	AccountIdOf, BalanceOf, Config, ContractBlob, Error, Weight, SENTINEL,
 };
-use alloc::vec::Vec;
-use codec::{Encode, MaxEncodedLen};
+use alloc::{string::String, vec::Vec};
+use codec::Encode;
 use core::mem;
 use frame_support::traits::Get;
 use pallet_revive_proc_macro::define_env;
@@ -61,13 +60,19 @@ impl<T: Config> ContractBlob<T> {
 				interpreter is available on all platforms; qed",
 		);
 
+		let step_tracing =
+			crate::tracing::if_tracing(|t| t.is_execution_tracing_enabled()).unwrap_or(false);
+
 		let mut module_config = polkavm::ModuleConfig::new();
 		module_config.set_page_size(limits::PAGE_SIZE);
 		module_config.set_gas_metering(Some(polkavm::GasMeteringKind::Sync));
 		module_config.set_allow_sbrk(false);
 		module_config.set_aux_data_size(aux_data_size);
-		let module = polkavm::Module::new(&engine, &module_config, self.code.into_inner().into())
-			.map_err(|err| {
+		module_config.set_step_tracing(step_tracing);
+		let code: polkavm::ArcBytes = self.code.into_inner().into();
+		let program =
+			step_tracing.then(|| polkavm::ProgramBlob::parse(code.clone()).ok()).flatten();
+		let module = polkavm::Module::new(&engine, &module_config, code).map_err(|err| {
 			log::debug!(target: LOG_TARGET, "failed to create polkavm module: {err:?}");
 			Error::<T>::CodeRejected
 		})?;
@@ -96,7 +101,7 @@ impl<T: Config> ContractBlob<T> {
 			.map_err(|_| Error::<T>::CodeRejected)?;
 		instance.prepare_call_untyped(entry_program_counter, &[]);
 
-		Ok(PreparedCall { module, instance, runtime })
+		Ok(PreparedCall { module, instance, runtime, program })
 	}
 }
 
@@ -110,21 +115,7 @@ where
 		// the limits later without affecting already deployed code.
 		let available_syscalls = list_syscalls(T::UnsafeUnstableInterface::get());
 		let code = limits::code::enforce::<T>(code, available_syscalls)?;
-
-		let code_len = code.len() as u32;
-		let bytes_added = code_len.saturating_add(<CodeInfo<T>>::max_encoded_len() as u32);
-		let deposit = Diff { bytes_added, items_added: 2, ..Default::default() }
-			.update_contract::<T>(None)
-			.charge_or_zero();
-		let code_info = CodeInfo {
-			owner,
-			deposit,
-			refcount: 0,
-			code_len,
-			behaviour_version: Default::default(),
-		};
-		let code_hash = H256(sp_io::hashing::keccak_256(&code));
-		Ok(ContractBlob { code, code_info, code_hash })
+		Ok(Self::from_validated_code(code, owner, BytecodeType::Pvm))
 	}
 }
 
@@ -515,8 +506,9 @@ pub mod env {
 	/// See [`pallet_revive_uapi::HostFn::code_hash`].
 	#[stable]
 	fn code_hash(&mut self, memory: &mut M, addr_ptr: u32, out_ptr: u32) -> Result<(), TrapReason> {
-		self.charge_gas(RuntimeCosts::CodeHash)?;
 		let address = memory.read_h160(addr_ptr)?;
+		let cold = self.access_account(&address);
+		self.charge_gas(RuntimeCosts::CodeHash { cold })?;
 		Ok(self.write_fixed_sandbox_output(
 			memory,
 			out_ptr,
@@ -530,8 +522,9 @@ pub mod env {
 	/// See [`pallet_revive_uapi::HostFn::code_size`].
 	#[stable]
 	fn code_size(&mut self, memory: &mut M, addr_ptr: u32) -> Result<u64, TrapReason> {
-		self.charge_gas(RuntimeCosts::CodeSize)?;
 		let address = memory.read_h160(addr_ptr)?;
+		let cold = self.access_account(&address);
+		self.charge_gas(RuntimeCosts::CodeSize { cold })?;
 		Ok(self.ext.code_size(&address))
 	}
 
@@ -625,8 +618,9 @@ pub mod env {
 		addr_ptr: u32,
 		out_ptr: u32,
 	) -> Result<(), TrapReason> {
-		self.charge_gas(RuntimeCosts::BalanceOf)?;
 		let address = memory.read_h160(addr_ptr)?;
+		let cold = self.access_account(&address);
+		self.charge_gas(RuntimeCosts::BalanceOf { cold })?;
 		Ok(self.write_fixed_sandbox_output(
 			memory,
 			out_ptr,
@@ -863,6 +857,20 @@ pub mod env {
 		Ok(self.ext.gas_meter().gas_left().ref_time())
 	}
 
+	/// Announce that the contract will replace its code with new code.
+	/// See [`pallet_revive_uapi::HostFn::announce_code_upgrade`].
+	#[mutating]
+	fn announce_code_upgrade(
+		&mut self,
+		memory: &mut M,
+		code_hash_ptr: u32,
+	) -> Result<(), TrapReason> {
+		self.charge_gas(RuntimeCosts::AnnounceCodeUpgrade)?;
+		let code_hash: H256 = memory.read_h256(code_hash_ptr)?;
+		self.ext.announce_code_upgrade(code_hash)?;
+		Ok(())
+	}
+
 	/// Checks whether the caller of the current contract is the origin of the whole call stack.
 	/// See [`pallet_revive_uapi::HostFn::caller_is_origin`].
 	fn caller_is_origin(&mut self, _memory: &mut M) -> Result<u32, TrapReason> {
@@ -902,6 +910,28 @@ pub mod env {
 		self.contains_storage(memory, flags, key_ptr, key_len)
 	}
 
+	/// Emit a human readable debug message.
+	/// See [`pallet_revive_uapi::HostFn::debug_message`].
+	fn debug_message(
+		&mut self,
+		memory: &mut M,
+		str_ptr: u32,
+		str_len: u32,
+	) -> Result<ReturnErrorCode, TrapReason> {
+		self.charge_gas(RuntimeCosts::CopyFromContract(str_len))?;
+		if !self.ext.debug_buffer_enabled() {
+			return Ok(ReturnErrorCode::LoggingDisabled);
+		}
+
+		let message = memory.read(str_ptr, str_len)?;
+		let message = String::from_utf8_lossy(&message);
+		if self.ext.append_debug_buffer(&message) {
+			Ok(ReturnErrorCode::Success)
+		} else {
+			Ok(ReturnErrorCode::LoggingDisabled)
+		}
+	}
+
 	/// Calculates Ethereum address from the ECDSA compressed public key and stores
 	/// See [`pallet_revive_uapi::HostFn::ecdsa_to_eth_address`].
 	fn ecdsa_to_eth_address(
@@ -15,8 +15,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{gas::Token, weights::WeightInfo, Config};
-use frame_support::weights::{constants::WEIGHT_REF_TIME_PER_SECOND, Weight};
+use crate::{gas::Token, storage::expiry::ContractActivity, weights::WeightInfo, Config};
+use codec::MaxEncodedLen;
+use frame_support::{
+	traits::Get,
+	weights::{constants::WEIGHT_REF_TIME_PER_SECOND, Weight},
+};
 
 /// Current approximation of the gas/s consumption considering
 /// EVM execution over compiled WASM (on 4.4Ghz CPU).
@@ -27,7 +31,7 @@ const GAS_PER_SECOND: u64 = 40_000_000;
 /// Approximate ratio of the amount of Weight per Gas.
 /// u64 works for approximations because Weight is a very small unit compared to
 /// gas.
-const WEIGHT_PER_GAS: u64 = WEIGHT_REF_TIME_PER_SECOND / GAS_PER_SECOND;
+pub(crate) const WEIGHT_PER_GAS: u64 = WEIGHT_REF_TIME_PER_SECOND / GAS_PER_SECOND;
 
 #[cfg_attr(test, derive(Debug, PartialEq, Eq))]
 #[derive(Copy, Clone)]
@@ -52,12 +56,12 @@ pub enum RuntimeCosts {
 	ToAccountId,
 	/// Weight of calling `seal_origin`.
 	Origin,
-	/// Weight of calling `seal_code_hash`.
-	CodeHash,
+	/// Weight of calling `seal_code_hash`, `cold` if the account was not accessed before.
+	CodeHash { cold: bool },
 	/// Weight of calling `seal_own_code_hash`.
 	OwnCodeHash,
-	/// Weight of calling `seal_code_size`.
-	CodeSize,
+	/// Weight of calling `seal_code_size`, `cold` if the account was not accessed before.
+	CodeSize { cold: bool },
 	/// Weight of calling `seal_caller_is_origin`.
 	CallerIsOrigin,
 	/// Weight of calling `caller_is_root`.
@@ -70,8 +74,8 @@ pub enum RuntimeCosts {
 	WeightLeft,
 	/// Weight of calling `seal_balance`.
 	Balance,
-	/// Weight of calling `seal_balance_of`.
-	BalanceOf,
+	/// Weight of calling `seal_balance_of`, `cold` if the account was not accessed before.
+	BalanceOf { cold: bool },
 	/// Weight of calling `seal_value_transferred`.
 	ValueTransferred,
 	/// Weight of calling `seal_minimum_balance`.
@@ -96,16 +100,21 @@ pub enum RuntimeCosts {
 	Terminate,
 	/// Weight of calling `seal_deposit_event` with the given number of topics and event size.
 	DepositEvent { num_topic: u32, len: u32 },
-	/// Weight of calling `seal_set_storage` for the given storage item sizes.
-	SetStorage { old_bytes: u32, new_bytes: u32 },
-	/// Weight of calling `seal_clear_storage` per cleared byte.
-	ClearStorage(u32),
-	/// Weight of calling `seal_contains_storage` per byte of the checked item.
-	ContainsStorage(u32),
-	/// Weight of calling `seal_get_storage` with the specified size in storage.
-	GetStorage(u32),
-	/// Weight of calling `seal_take_storage` for the given size.
-	TakeStorage(u32),
+	/// Weight of calling `seal_set_storage` for the given storage item sizes, `cold` if the
+	/// storage slot was not accessed before.
+	SetStorage { old_bytes: u32, new_bytes: u32, cold: bool },
+	/// Weight of calling `seal_clear_storage` per cleared byte, `cold` if the storage slot was
+	/// not accessed before.
+	ClearStorage { len: u32, cold: bool },
+	/// Weight of calling `seal_contains_storage` per byte of the checked item, `cold` if the
+	/// storage slot was not accessed before.
+	ContainsStorage { len: u32, cold: bool },
+	/// Weight of calling `seal_get_storage` with the specified size in storage, `cold` if the
+	/// storage slot was not accessed before.
+	GetStorage { len: u32, cold: bool },
+	/// Weight of calling `seal_take_storage` for the given size, `cold` if the storage slot was
+	/// not accessed before.
+	TakeStorage { len: u32, cold: bool },
 	/// Weight of calling `seal_set_transient_storage` for the given storage item sizes.
 	SetTransientStorage { old_bytes: u32, new_bytes: u32 },
 	/// Weight of calling `seal_clear_transient_storage` per cleared byte.
@@ -116,10 +125,11 @@ pub enum RuntimeCosts {
 	GetTransientStorage(u32),
 	/// Weight of calling `seal_take_transient_storage` for the given size.
 	TakeTransientStorage(u32),
-	/// Base weight of calling `seal_call`.
-	CallBase,
-	/// Weight of calling `seal_delegate_call` for the given input size.
-	DelegateCallBase,
+	/// Base weight of calling `seal_call`, `cold` if the callee was not accessed before.
+	CallBase { cold: bool },
+	/// Weight of calling `seal_delegate_call` for the given input size, `cold` if the callee was
+	/// not accessed before.
+	DelegateCallBase { cold: bool },
 	/// Weight of calling a precompile.
 	PrecompileBase,
 	/// Weight of calling a precompile that has a contract info.
@@ -152,6 +162,8 @@ pub enum RuntimeCosts {
 	Precompile(Weight),
 	/// Weight of calling `seal_set_code_hash`
 	SetCodeHash,
+	/// Weight of calling `seal_announce_code_upgrade`
+	AnnounceCodeUpgrade,
 	/// Weight of calling `ecdsa_to_eth_address`
 	EcdsaToEthAddress,
 	/// Weight of calling `get_immutable_dependency`
@@ -170,6 +182,16 @@ pub enum RuntimeCosts {
 	Blake2F(u32),
 	/// Weight of calling `Modexp` precompile
 	Modexp(u64),
+	/// Weight of executing EVM instructions that cost the given amount of EVM gas.
+	///
+	/// Each unit of gas is charged with the weight of the cheapest instruction, see
+	/// the `evm_opcode` benchmark.
+	EvmGas(u64),
+	/// Weight of warming the accounts and storage slots of an access list up front.
+	AccessList { accounts: u32, storage_slots: u32 },
+	/// Weight of recording that a contract was touched, see
+	/// [`crate::Pallet::set_contract_expiry`].
+	TouchContract,
 }
 
 /// For functions that modify storage, benchmarks are performed with one item in the
@@ -203,6 +225,18 @@ macro_rules! cost_storage {
     };
 }
 
+/// The benchmarks of functions accessing an account or a storage slot include reading it from
+/// the state. An access that is warm does not read from the state again and is discounted by it.
+macro_rules! cost_access {
+	($cold:expr, $weight:expr) => {
+		if $cold {
+			$weight
+		} else {
+			$weight.saturating_sub(T::DbWeight::get().reads(1))
+		}
+	};
+}
+
 macro_rules! cost_args {
 	// cost_args!(name, a, b, c) -> T::WeightInfo::name(a, b, c).saturating_sub(T::WeightInfo::name(0, 0, 0))
 	($name:ident, $( $arg: expr ),+) => {
@@ -221,6 +255,84 @@ impl<T: Config> Token<T> for RuntimeCosts {
 		true
 	}
 
+	fn name(&self) -> &'static str {
+		use self::RuntimeCosts::*;
+		match *self {
+			HostFn => "HostFn",
+			CopyFromContract(_) => "CopyFromContract",
+			CopyToContract(_) => "CopyToContract",
+			CallDataLoad => "CallDataLoad",
+			CallDataCopy(_) => "CallDataCopy",
+			Caller => "Caller",
+			CallDataSize => "CallDataSize",
+			ReturnDataSize => "ReturnDataSize",
+			ToAccountId => "ToAccountId",
+			Origin => "Origin",
+			CodeHash { .. } => "CodeHash",
+			OwnCodeHash => "OwnCodeHash",
+			CodeSize { .. } => "CodeSize",
+			CallerIsOrigin => "CallerIsOrigin",
+			CallerIsRoot => "CallerIsRoot",
+			Address => "Address",
+			RefTimeLeft => "RefTimeLeft",
+			WeightLeft => "WeightLeft",
+			Balance => "Balance",
+			BalanceOf { .. } => "BalanceOf",
+			ValueTransferred => "ValueTransferred",
+			MinimumBalance => "MinimumBalance",
+			BlockNumber => "BlockNumber",
+			BlockHash => "BlockHash",
+			BlockAuthor => "BlockAuthor",
+			GasPrice => "GasPrice",
+			BaseFee => "BaseFee",
+			Now => "Now",
+			GasLimit => "GasLimit",
+			WeightToFee => "WeightToFee",
+			Terminate => "Terminate",
+			DepositEvent { .. } => "DepositEvent",
+			SetStorage { .. } => "SetStorage",
+			ClearStorage { .. } => "ClearStorage",
+			ContainsStorage { .. } => "ContainsStorage",
+			GetStorage { .. } => "GetStorage",
+			TakeStorage { .. } => "TakeStorage",
+			SetTransientStorage { .. } => "SetTransientStorage",
+			ClearTransientStorage(_) => "ClearTransientStorage",
+			ContainsTransientStorage(_) => "ContainsTransientStorage",
+			GetTransientStorage(_) => "GetTransientStorage",
+			TakeTransientStorage(_) => "TakeTransientStorage",
+			CallBase { .. } => "CallBase",
+			DelegateCallBase { .. } => "DelegateCallBase",
+			PrecompileBase => "PrecompileBase",
+			PrecompileWithInfoBase => "PrecompileWithInfoBase",
+			PrecompileDecode(_) => "PrecompileDecode",
+			CallTransferSurcharge { .. } => "CallTransferSurcharge",
+			CallInputCloned(_) => "CallInputCloned",
+			Instantiate { .. } => "Instantiate",
+			Ripemd160(_) => "Ripemd160",
+			HashSha256(_) => "HashSha256",
+			HashKeccak256(_) => "HashKeccak256",
+			HashBlake256(_) => "HashBlake256",
+			HashBlake128(_) => "HashBlake128",
+			EcdsaRecovery => "EcdsaRecovery",
+			Sr25519Verify(_) => "Sr25519Verify",
+			Precompile(_) => "Precompile",
+			SetCodeHash => "SetCodeHash",
+			AnnounceCodeUpgrade => "AnnounceCodeUpgrade",
+			EcdsaToEthAddress => "EcdsaToEthAddress",
+			GetImmutableData(_) => "GetImmutableData",
+			SetImmutableData(_) => "SetImmutableData",
+			Bn128Add => "Bn128Add",
+			Bn128Mul => "Bn128Mul",
+			Bn128Pairing(_) => "Bn128Pairing",
+			Identity(_) => "Identity",
+			Blake2F(_) => "Blake2F",
+			Modexp(_) => "Modexp",
+			EvmGas(_) => "EvmGas",
+			AccessList { .. } => "AccessList",
+			TouchContract => "TouchContract",
+		}
+	}
+
 	fn weight(&self) -> Weight {
 		use self::RuntimeCosts::*;
 		match *self {
@@ -234,8 +346,8 @@ impl<T: Config> Token<T> for RuntimeCosts {
 			Caller => T::WeightInfo::seal_caller(),
 			Origin => T::WeightInfo::seal_origin(),
 			ToAccountId => T::WeightInfo::seal_to_account_id(),
-			CodeHash => T::WeightInfo::seal_code_hash(),
-			CodeSize => T::WeightInfo::seal_code_size(),
+			CodeHash { cold } => cost_access!(cold, T::WeightInfo::seal_code_hash()),
+			CodeSize { cold } => cost_access!(cold, T::WeightInfo::seal_code_size()),
 			OwnCodeHash => T::WeightInfo::seal_own_code_hash(),
 			CallerIsOrigin => T::WeightInfo::seal_caller_is_origin(),
 			CallerIsRoot => T::WeightInfo::seal_caller_is_root(),
@@ -243,7 +355,7 @@ impl<T: Config> Token<T> for RuntimeCosts {
 			RefTimeLeft => T::WeightInfo::seal_ref_time_left(),
 			WeightLeft => T::WeightInfo::seal_weight_left(),
 			Balance => T::WeightInfo::seal_balance(),
-			BalanceOf => T::WeightInfo::seal_balance_of(),
+			BalanceOf { cold } => cost_access!(cold, T::WeightInfo::seal_balance_of()),
 			ValueTransferred => T::WeightInfo::seal_value_transferred(),
 			MinimumBalance => T::WeightInfo::seal_minimum_balance(),
 			BlockNumber => T::WeightInfo::seal_block_number(),
@@ -256,13 +368,17 @@ impl<T: Config> Token<T> for RuntimeCosts {
 			WeightToFee => T::WeightInfo::seal_weight_to_fee(),
 			Terminate => T::WeightInfo::seal_terminate(),
 			DepositEvent { num_topic, len } => T::WeightInfo::seal_deposit_event(num_topic, len),
-			SetStorage { new_bytes, old_bytes } => {
-				cost_storage!(write, seal_set_storage, new_bytes, old_bytes)
+			SetStorage { new_bytes, old_bytes, cold } => {
+				cost_access!(cold, cost_storage!(write, seal_set_storage, new_bytes, old_bytes))
 			},
-			ClearStorage(len) => cost_storage!(write, seal_clear_storage, len),
-			ContainsStorage(len) => cost_storage!(read, seal_contains_storage, len),
-			GetStorage(len) => cost_storage!(read, seal_get_storage, len),
-			TakeStorage(len) => cost_storage!(write, seal_take_storage, len),
+			ClearStorage { len, cold } =>
+				cost_access!(cold, cost_storage!(write, seal_clear_storage, len)),
+			ContainsStorage { len, cold } =>
+				cost_access!(cold, cost_storage!(read, seal_contains_storage, len)),
+			GetStorage { len, cold } =>
+				cost_access!(cold, cost_storage!(read, seal_get_storage, len)),
+			TakeStorage { len, cold } =>
+				cost_access!(cold, cost_storage!(write, seal_take_storage, len)),
 			SetTransientStorage { new_bytes, old_bytes } => {
 				cost_storage!(write_transient, seal_set_transient_storage, new_bytes, old_bytes)
 			},
@@ -278,8 +394,8 @@ impl<T: Config> Token<T> for RuntimeCosts {
 			TakeTransientStorage(len) => {
 				cost_storage!(write_transient, seal_take_transient_storage, len)
 			},
-			CallBase => T::WeightInfo::seal_call(0, 0, 0),
-			DelegateCallBase => T::WeightInfo::seal_delegate_call(),
+			CallBase { cold } => cost_access!(cold, T::WeightInfo::seal_call(0, 0, 0)),
+			DelegateCallBase { cold } => cost_access!(cold, T::WeightInfo::seal_delegate_call()),
 			PrecompileBase => T::WeightInfo::seal_call_precompile(0, 0),
 			PrecompileWithInfoBase => T::WeightInfo::seal_call_precompile(1, 0),
 			PrecompileDecode(len) => cost_args!(seal_call_precompile, 0, len),
@@ -301,6 +417,7 @@ impl<T: Config> Token<T> for RuntimeCosts {
 			Sr25519Verify(len) => T::WeightInfo::seal_sr25519_verify(len),
 			Precompile(weight) => weight,
 			SetCodeHash => T::WeightInfo::seal_set_code_hash(),
+			AnnounceCodeUpgrade => T::WeightInfo::seal_announce_code_upgrade(),
 			EcdsaToEthAddress => T::WeightInfo::seal_ecdsa_to_eth_address(),
 			GetImmutableData(len) => T::WeightInfo::seal_get_immutable_data(len),
 			SetImmutableData(len) => T::WeightInfo::seal_set_immutable_data(len),
@@ -310,6 +427,15 @@ impl<T: Config> Token<T> for RuntimeCosts {
 			Identity(len) => T::WeightInfo::identity(len),
 			Blake2F(rounds) => T::WeightInfo::blake2f(rounds),
 			Modexp(gas) => Weight::from_parts(gas.saturating_mul(WEIGHT_PER_GAS), 0),
+			EvmGas(gas) => T::WeightInfo::evm_opcode(1)
+				.saturating_sub(T::WeightInfo::evm_opcode(0))
+				.saturating_mul(gas),
+			AccessList { accounts, storage_slots } =>
+				T::DbWeight::get().reads(accounts.saturating_add(storage_slots).into()),
+			// The proof contains the key and the value of the activity of the contract.
+			TouchContract => T::DbWeight::get().reads_writes(1, 1).saturating_add(
+				Weight::from_parts(0, (32 + 20 + ContractActivity::<T>::max_encoded_len()) as u64),
+			),
 		}
 	}
 }
@@ -86,6 +86,9 @@ pub trait WeightInfo {
 	fn map_account() -> Weight;
 	fn unmap_account() -> Weight;
 	fn dispatch_as_fallback_account() -> Weight;
+	fn set_contract_expiry() -> Weight;
+	fn tombstone_contract() -> Weight;
+	fn restore_contract(n: u32, b: u32, ) -> Weight;
 	fn noop_host_fn(r: u32, ) -> Weight;
 	fn seal_caller() -> Weight;
 	fn seal_origin() -> Weight;
@@ -156,10 +159,14 @@ pub trait WeightInfo {
 	fn bn128_pairing(n: u32, ) -> Weight;
 	fn blake2f(n: u32, ) -> Weight;
 	fn seal_ecdsa_to_eth_address() -> Weight;
+	fn apply_authorization() -> Weight;
 	fn seal_set_code_hash() -> Weight;
+	fn seal_announce_code_upgrade() -> Weight;
 	fn instr(r: u32, ) -> Weight;
 	fn instr_empty_loop(r: u32, ) -> Weight;
+	fn evm_opcode(r: u32, ) -> Weight;
 	fn v1_migration_step() -> Weight;
+	fn v2_migration_step() -> Weight;
 }
 
 /// Weights for `pallet_revive` using the Substrate node and recommended hardware.
@@ -461,6 +468,78 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 		Weight::from_parts(13_660_000, 3610)
 			.saturating_add(T::DbWeight::get().reads(2_u64))
 	}
+	/// Storage: `Revive::ContractExpiryOf` (r:1 w:1)
+	/// Proof: `Revive::ContractExpiryOf` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `Measured`)
+	fn set_contract_expiry() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `4`
+		//  Estimated: `1493`
+		// Minimum execution time: 4_912_000 picoseconds.
+		Weight::from_parts(5_184_000, 1493)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Revive::ContractExpiryOf` (r:1 w:0)
+	/// Proof: `Revive::ContractExpiryOf` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `Measured`)
+	/// Storage: `Revive::AccountInfoOf` (r:1 w:1)
+	/// Proof: `Revive::AccountInfoOf` (`max_values`: None, `max_size`: Some(247), added: 2722, mode: `Measured`)
+	/// Storage: `Revive::ContractActivityOf` (r:1 w:1)
+	/// Proof: `Revive::ContractActivityOf` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `Measured`)
+	/// Storage: `Revive::DeletionQueueCounter` (r:1 w:1)
+	/// Proof: `Revive::DeletionQueueCounter` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `Measured`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(427), added: 2902, mode: `Measured`)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `Measured`)
+	/// Storage: `Revive::DeletionQueue` (r:0 w:1)
+	/// Proof: `Revive::DeletionQueue` (`max_values`: None, `max_size`: Some(142), added: 2617, mode: `Measured`)
+	/// Storage: `Revive::Tombstones` (r:0 w:1)
+	/// Proof: `Revive::Tombstones` (`max_values`: None, `max_size`: Some(4410), added: 6885, mode: `Measured`)
+	/// Storage: `Revive::ImmutableDataOf` (r:1 w:1)
+	/// Proof: `Revive::ImmutableDataOf` (`max_values`: None, `max_size`: Some(4118), added: 6593, mode: `Measured`)
+	/// Storage: `Revive::PendingCodeUpgrades` (r:1 w:1)
+	/// Proof: `Revive::PendingCodeUpgrades` (`max_values`: None, `max_size`: Some(56), added: 2531, mode: `Measured`)
+	fn tombstone_contract() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1034`
+		//  Estimated: `6593`
+		// Minimum execution time: 71_302_000 picoseconds.
+		Weight::from_parts(73_418_000, 6593)
+			.saturating_add(T::DbWeight::get().reads(9_u64))
+			.saturating_add(T::DbWeight::get().writes(11_u64))
+	}
+	/// Storage: `Revive::Tombstones` (r:1 w:1)
+	/// Proof: `Revive::Tombstones` (`max_values`: None, `max_size`: Some(4410), added: 6885, mode: `Measured`)
+	/// Storage: `Skipped::Metadata` (r:0 w:0)
+	/// Proof: `Skipped::Metadata` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(427), added: 2902, mode: `Measured`)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `Measured`)
+	/// Storage: `Revive::AccountInfoOf` (r:1 w:1)
+	/// Proof: `Revive::AccountInfoOf` (`max_values`: None, `max_size`: Some(247), added: 2722, mode: `Measured`)
+	/// Storage: `Revive::ContractActivityOf` (r:0 w:1)
+	/// Proof: `Revive::ContractActivityOf` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `Measured`)
+	/// Storage: `Revive::ImmutableDataOf` (r:0 w:1)
+	/// Proof: `Revive::ImmutableDataOf` (`max_values`: None, `max_size`: Some(4118), added: 6593, mode: `Measured`)
+	/// Storage: `Revive::PendingCodeUpgrades` (r:0 w:1)
+	/// Proof: `Revive::PendingCodeUpgrades` (`max_values`: None, `max_size`: Some(56), added: 2531, mode: `Measured`)
+	/// The range of component `n` is `[0, 1024]`.
+	/// The range of component `b` is `[0, 458752]`.
+	fn restore_contract(n: u32, b: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1187`
+		//  Estimated: `6885`
+		// Minimum execution time: 68_519_000 picoseconds.
+		Weight::from_parts(70_106_000, 6885)
+			// Standard Error: 4_215
+			.saturating_add(Weight::from_parts(3_982_517, 0).saturating_mul(n.into()))
+			// Standard Error: 9
+			.saturating_add(Weight::from_parts(1_214, 0).saturating_mul(b.into()))
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
 	/// The range of component `r` is `[0, 1600]`.
 	fn noop_host_fn(r: u32, ) -> Weight {
 		// Proof Size summary in bytes:
@@ -1177,16 +1256,46 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 		// Minimum execution time: 13_049_000 picoseconds.
 		Weight::from_parts(13_232_000, 0)
 	}
-	/// Storage: `Revive::CodeInfoOf` (r:1 w:1)
+	/// Storage: `Revive::AccountInfoOf` (r:1 w:1)
+	/// Proof: `Revive::AccountInfoOf` (`max_values`: None, `max_size`: Some(247), added: 2722, mode: `Measured`)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `Measured`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(427), added: 2902, mode: `Measured`)
+	fn apply_authorization() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `180`
+		//  Estimated: `6196`
+		// Minimum execution time: 98_417_000 picoseconds.
+		Weight::from_parts(100_305_000, 6196)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: `Revive::PendingCodeUpgrades` (r:1 w:1)
+	/// Proof: `Revive::PendingCodeUpgrades` (`max_values`: None, `max_size`: Some(56), added: 2531, mode: `Measured`)
+	/// Storage: `Revive::CodeInfoOf` (r:2 w:2)
 	/// Proof: `Revive::CodeInfoOf` (`max_values`: None, `max_size`: Some(96), added: 2571, mode: `Measured`)
 	fn seal_set_code_hash() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `296`
-		//  Estimated: `3761`
-		// Minimum execution time: 10_342_000 picoseconds.
-		Weight::from_parts(10_818_000, 3761)
-			.saturating_add(T::DbWeight::get().reads(1_u64))
-			.saturating_add(T::DbWeight::get().writes(1_u64))
+		//  Measured:  `493`
+		//  Estimated: `6433`
+		// Minimum execution time: 17_604_000 picoseconds.
+		Weight::from_parts(18_211_000, 6433)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: `Revive::PendingCodeUpgrades` (r:1 w:1)
+	/// Proof: `Revive::PendingCodeUpgrades` (`max_values`: None, `max_size`: Some(56), added: 2531, mode: `Measured`)
+	/// Storage: `Revive::CodeInfoOf` (r:2 w:2)
+	/// Proof: `Revive::CodeInfoOf` (`max_values`: None, `max_size`: Some(96), added: 2571, mode: `Measured`)
+	fn seal_announce_code_upgrade() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `474`
+		//  Estimated: `6414`
+		// Minimum execution time: 14_372_000 picoseconds.
+		Weight::from_parts(14_936_000, 6414)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
 	}
 	/// The range of component `r` is `[0, 10000]`.
 	fn instr(r: u32, ) -> Weight {
@@ -1208,6 +1317,16 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			// Standard Error: 13
 			.saturating_add(Weight::from_parts(73_452, 0).saturating_mul(r.into()))
 	}
+	/// The range of component `r` is `[0, 24576]`.
+	fn evm_opcode(r: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 1_024_000 picoseconds.
+		Weight::from_parts(1_187_000, 0)
+			// Standard Error: 11
+			.saturating_add(Weight::from_parts(25_000, 0).saturating_mul(r.into()))
+	}
 	/// Storage: UNKNOWN KEY `0x735f040a5d490f1107ad9c56f5ca00d2060e99e5378e562537cf3bc983e17b91` (r:2 w:1)
 	/// Proof: UNKNOWN KEY `0x735f040a5d490f1107ad9c56f5ca00d2060e99e5378e562537cf3bc983e17b91` (r:2 w:1)
 	/// Storage: `Revive::AccountInfoOf` (r:0 w:1)
@@ -1221,6 +1340,17 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(2_u64))
 			.saturating_add(T::DbWeight::get().writes(2_u64))
 	}
+	/// Storage: `Revive::CodeInfoOf` (r:2 w:1)
+	/// Proof: `Revive::CodeInfoOf` (`max_values`: None, `max_size`: Some(96), added: 2571, mode: `Measured`)
+	fn v2_migration_step() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `265`
+		//  Estimated: `6205`
+		// Minimum execution time: 10_872_000 picoseconds.
+		Weight::from_parts(11_304_000, 6205)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
 }
 
 // For backwards compatibility and tests.
@@ -1521,6 +1651,78 @@ impl WeightInfo for () {
 		Weight::from_parts(13_660_000, 3610)
 			.saturating_add(RocksDbWeight::get().reads(2_u64))
 	}
+	/// Storage: `Revive::ContractExpiryOf` (r:1 w:1)
+	/// Proof: `Revive::ContractExpiryOf` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `Measured`)
+	fn set_contract_expiry() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `4`
+		//  Estimated: `1493`
+		// Minimum execution time: 4_912_000 picoseconds.
+		Weight::from_parts(5_184_000, 1493)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `Revive::ContractExpiryOf` (r:1 w:0)
+	/// Proof: `Revive::ContractExpiryOf` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `Measured`)
+	/// Storage: `Revive::AccountInfoOf` (r:1 w:1)
+	/// Proof: `Revive::AccountInfoOf` (`max_values`: None, `max_size`: Some(247), added: 2722, mode: `Measured`)
+	/// Storage: `Revive::ContractActivityOf` (r:1 w:1)
+	/// Proof: `Revive::ContractActivityOf` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `Measured`)
+	/// Storage: `Revive::DeletionQueueCounter` (r:1 w:1)
+	/// Proof: `Revive::DeletionQueueCounter` (`max_values`: Some(1), `max_size`: Some(8), added: 503, mode: `Measured`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(427), added: 2902, mode: `Measured`)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `Measured`)
+	/// Storage: `Revive::DeletionQueue` (r:0 w:1)
+	/// Proof: `Revive::DeletionQueue` (`max_values`: None, `max_size`: Some(142), added: 2617, mode: `Measured`)
+	/// Storage: `Revive::Tombstones` (r:0 w:1)
+	/// Proof: `Revive::Tombstones` (`max_values`: None, `max_size`: Some(4410), added: 6885, mode: `Measured`)
+	/// Storage: `Revive::ImmutableDataOf` (r:1 w:1)
+	/// Proof: `Revive::ImmutableDataOf` (`max_values`: None, `max_size`: Some(4118), added: 6593, mode: `Measured`)
+	/// Storage: `Revive::PendingCodeUpgrades` (r:1 w:1)
+	/// Proof: `Revive::PendingCodeUpgrades` (`max_values`: None, `max_size`: Some(56), added: 2531, mode: `Measured`)
+	fn tombstone_contract() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1034`
+		//  Estimated: `6593`
+		// Minimum execution time: 71_302_000 picoseconds.
+		Weight::from_parts(73_418_000, 6593)
+			.saturating_add(RocksDbWeight::get().reads(9_u64))
+			.saturating_add(RocksDbWeight::get().writes(11_u64))
+	}
+	/// Storage: `Revive::Tombstones` (r:1 w:1)
+	/// Proof: `Revive::Tombstones` (`max_values`: None, `max_size`: Some(4410), added: 6885, mode: `Measured`)
+	/// Storage: `Skipped::Metadata` (r:0 w:0)
+	/// Proof: `Skipped::Metadata` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(427), added: 2902, mode: `Measured`)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `Measured`)
+	/// Storage: `Revive::AccountInfoOf` (r:1 w:1)
+	/// Proof: `Revive::AccountInfoOf` (`max_values`: None, `max_size`: Some(247), added: 2722, mode: `Measured`)
+	/// Storage: `Revive::ContractActivityOf` (r:0 w:1)
+	/// Proof: `Revive::ContractActivityOf` (`max_values`: None, `max_size`: Some(61), added: 2536, mode: `Measured`)
+	/// Storage: `Revive::ImmutableDataOf` (r:0 w:1)
+	/// Proof: `Revive::ImmutableDataOf` (`max_values`: None, `max_size`: Some(4118), added: 6593, mode: `Measured`)
+	/// Storage: `Revive::PendingCodeUpgrades` (r:0 w:1)
+	/// Proof: `Revive::PendingCodeUpgrades` (`max_values`: None, `max_size`: Some(56), added: 2531, mode: `Measured`)
+	/// The range of component `n` is `[0, 1024]`.
+	/// The range of component `b` is `[0, 458752]`.
+	fn restore_contract(n: u32, b: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1187`
+		//  Estimated: `6885`
+		// Minimum execution time: 68_519_000 picoseconds.
+		Weight::from_parts(70_106_000, 6885)
+			// Standard Error: 4_215
+			.saturating_add(Weight::from_parts(3_982_517, 0).saturating_mul(n.into()))
+			// Standard Error: 9
+			.saturating_add(Weight::from_parts(1_214, 0).saturating_mul(b.into()))
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
 	/// The range of component `r` is `[0, 1600]`.
 	fn noop_host_fn(r: u32, ) -> Weight {
 		// Proof Size summary in bytes:
@@ -2237,16 +2439,46 @@ impl WeightInfo for () {
 		// Minimum execution time: 13_049_000 picoseconds.
 		Weight::from_parts(13_232_000, 0)
 	}
-	/// Storage: `Revive::CodeInfoOf` (r:1 w:1)
+	/// Storage: `Revive::AccountInfoOf` (r:1 w:1)
+	/// Proof: `Revive::AccountInfoOf` (`max_values`: None, `max_size`: Some(247), added: 2722, mode: `Measured`)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `Measured`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(427), added: 2902, mode: `Measured`)
+	fn apply_authorization() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `180`
+		//  Estimated: `6196`
+		// Minimum execution time: 98_417_000 picoseconds.
+		Weight::from_parts(100_305_000, 6196)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: `Revive::PendingCodeUpgrades` (r:1 w:1)
+	/// Proof: `Revive::PendingCodeUpgrades` (`max_values`: None, `max_size`: Some(56), added: 2531, mode: `Measured`)
+	/// Storage: `Revive::CodeInfoOf` (r:2 w:2)
 	/// Proof: `Revive::CodeInfoOf` (`max_values`: None, `max_size`: Some(96), added: 2571, mode: `Measured`)
 	fn seal_set_code_hash() -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `296`
-		//  Estimated: `3761`
-		// Minimum execution time: 10_342_000 picoseconds.
-		Weight::from_parts(10_818_000, 3761)
-			.saturating_add(RocksDbWeight::get().reads(1_u64))
-			.saturating_add(RocksDbWeight::get().writes(1_u64))
+		//  Measured:  `493`
+		//  Estimated: `6433`
+		// Minimum execution time: 17_604_000 picoseconds.
+		Weight::from_parts(18_211_000, 6433)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: `Revive::PendingCodeUpgrades` (r:1 w:1)
+	/// Proof: `Revive::PendingCodeUpgrades` (`max_values`: None, `max_size`: Some(56), added: 2531, mode: `Measured`)
+	/// Storage: `Revive::CodeInfoOf` (r:2 w:2)
+	/// Proof: `Revive::CodeInfoOf` (`max_values`: None, `max_size`: Some(96), added: 2571, mode: `Measured`)
+	fn seal_announce_code_upgrade() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `474`
+		//  Estimated: `6414`
+		// Minimum execution time: 14_372_000 picoseconds.
+		Weight::from_parts(14_936_000, 6414)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
 	}
 	/// The range of component `r` is `[0, 10000]`.
 	fn instr(r: u32, ) -> Weight {
@@ -2268,6 +2500,16 @@ impl WeightInfo for () {
 			// Standard Error: 13
 			.saturating_add(Weight::from_parts(73_452, 0).saturating_mul(r.into()))
 	}
+	/// The range of component `r` is `[0, 24576]`.
+	fn evm_opcode(r: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 1_024_000 picoseconds.
+		Weight::from_parts(1_187_000, 0)
+			// Standard Error: 11
+			.saturating_add(Weight::from_parts(25_000, 0).saturating_mul(r.into()))
+	}
 	/// Storage: UNKNOWN KEY `0x735f040a5d490f1107ad9c56f5ca00d2060e99e5378e562537cf3bc983e17b91` (r:2 w:1)
 	/// Proof: UNKNOWN KEY `0x735f040a5d490f1107ad9c56f5ca00d2060e99e5378e562537cf3bc983e17b91` (r:2 w:1)
 	/// Storage: `Revive::AccountInfoOf` (r:0 w:1)
@@ -2281,4 +2523,15 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(2_u64))
 			.saturating_add(RocksDbWeight::get().writes(2_u64))
 	}
+	/// Storage: `Revive::CodeInfoOf` (r:2 w:1)
+	/// Proof: `Revive::CodeInfoOf` (`max_values`: None, `max_size`: Some(96), added: 2571, mode: `Measured`)
+	fn v2_migration_step() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `265`
+		//  Estimated: `6205`
+		// Minimum execution time: 10_872_000 picoseconds.
+		Weight::from_parts(11_304_000, 6205)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
 }
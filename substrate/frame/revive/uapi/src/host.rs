@@ -433,6 +433,26 @@ pub trait HostFn: private::Sealed {
 	/// - `output`: A reference to the output data buffer to write the block number.
 	fn block_number(output: &mut [u8; 32]);
 
+	/// Announce that the contract will replace its code with the code at `code_hash`.
+	///
+	/// The new code can be switched to with [`set_code_hash()`][`Self::set_code_hash`] once the
+	/// upgrade delay configured by the chain has passed. Announcing again replaces the pending
+	/// upgrade and restarts the delay. Announcing the current code hash of the contract cancels
+	/// the pending upgrade.
+	///
+	/// The pending upgrade is charged to the storage deposit of the contract, and keeps the new
+	/// code from being removed until it is enacted or cancelled.
+	///
+	/// # Parameters
+	///
+	/// - `code_hash`: The hash of the new code.
+	///
+	/// # Panics
+	///
+	/// Panics if there is no code on-chain with the specified hash.
+	#[unstable_hostfn]
+	fn announce_code_upgrade(code_hash: &[u8; 32]);
+
 	/// Stores the block hash of the given block number into the supplied buffer.
 	///
 	/// # Parameters
@@ -512,6 +532,22 @@ pub trait HostFn: private::Sealed {
 	#[unstable_hostfn]
 	fn contains_storage(flags: StorageFlags, key: &[u8]) -> Option<u32>;
 
+	/// Emit a human readable debug message.
+	///
+	/// The message is only collected when the contract is dry-run or traced, and discarded
+	/// otherwise. It is appended as is, so it should end with a newline if the messages are
+	/// expected to be printed line by line.
+	///
+	/// # Parameters
+	///
+	/// - `str`: The UTF-8 encoded message.
+	///
+	/// # Errors
+	///
+	/// - [LoggingDisabled][`crate::ReturnErrorCode::LoggingDisabled`]
+	#[unstable_hostfn]
+	fn debug_message(str: &[u8]) -> Result;
+
 	/// Calculates Ethereum address from the ECDSA compressed public key and stores
 	/// it into the supplied buffer.
 	///
@@ -569,7 +605,9 @@ pub trait HostFn: private::Sealed {
 	///
 	/// # Panics
 	///
-	/// Panics if there is no code on-chain with the specified hash.
+	/// Panics if there is no code on-chain with the specified hash, or if the chain enforces an
+	/// upgrade delay and `code_hash` was not announced through
+	/// [`announce_code_upgrade()`][`Self::announce_code_upgrade`] long enough ago.
 	#[unstable_hostfn]
 	fn set_code_hash(code_hash: &[u8; 32]);
 
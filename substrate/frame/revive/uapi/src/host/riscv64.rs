@@ -100,6 +100,7 @@ mod sys {
 		pub fn own_code_hash(out_ptr: *mut u8);
 		pub fn caller_is_origin() -> ReturnCode;
 		pub fn caller_is_root() -> ReturnCode;
+		pub fn debug_message(str_ptr: *const u8, str_len: u32) -> ReturnCode;
 		pub fn address(out_ptr: *mut u8);
 		pub fn weight_to_fee(ref_time: u64, proof_size: u64, out_ptr: *mut u8);
 		pub fn weight_left(out_ptr: *mut u8, out_len_ptr: *mut u32);
@@ -140,6 +141,7 @@ mod sys {
 			message_ptr: *const u8,
 		) -> ReturnCode;
 		pub fn set_code_hash(code_hash_ptr: *const u8);
+		pub fn announce_code_upgrade(code_hash_ptr: *const u8);
 		pub fn ecdsa_to_eth_address(key_ptr: *const u8, out_ptr: *mut u8) -> ReturnCode;
 		pub fn instantiation_nonce() -> u64;
 		pub fn return_data_size() -> u64;
@@ -445,6 +447,11 @@ impl HostFn for HostFnImpl {
 		unsafe { sys::ref_time_left() }
 	}
 
+	#[unstable_hostfn]
+	fn announce_code_upgrade(code_hash: &[u8; 32]) {
+		unsafe { sys::announce_code_upgrade(code_hash.as_ptr()) }
+	}
+
 	#[unstable_hostfn]
 	fn block_hash(block_number_ptr: &[u8; 32], output: &mut [u8; 32]) {
 		unsafe { sys::block_hash(block_number_ptr.as_ptr(), output.as_mut_ptr()) };
@@ -501,6 +508,12 @@ impl HostFn for HostFnImpl {
 		ret_code.into()
 	}
 
+	#[unstable_hostfn]
+	fn debug_message(str: &[u8]) -> Result {
+		let ret_code = unsafe { sys::debug_message(str.as_ptr(), str.len() as u32) };
+		ret_code.into()
+	}
+
 	#[unstable_hostfn]
 	fn ecdsa_to_eth_address(pubkey: &[u8; 33], output: &mut [u8; 20]) -> Result {
 		let ret_code = unsafe { sys::ecdsa_to_eth_address(pubkey.as_ptr(), output.as_mut_ptr()) };
@@ -109,6 +109,8 @@ define_error_codes! {
 	EcdsaRecoveryFailed = 7,
 	/// sr25519 signature verification failed.
 	Sr25519VerifyFailed = 8,
+	/// Debug messages are neither collected nor traced, the message was discarded.
+	LoggingDisabled = 9,
 	/// Contract instantiation failed because the address already exists.
 	/// Occurs when instantiating the same contract with the same salt more than once.
 	DuplicateContractAddress = 11,
@@ -0,0 +1,20 @@
+// SPDX-License-Identifier: MIT
+// OpenZeppelin Contracts (last updated v5.1.0) (token/ERC20/extensions/IERC20Metadata.sol)
+// https://github.com/OpenZeppelin/openzeppelin-contracts/blob/master/contracts/token/ERC20/extensions/IERC20Metadata.sol
+pragma solidity ^0.8.20;
+
+import {IERC20} from "./IERC20.sol";
+
+///
+/// @dev Interface for the optional metadata functions from the ERC-20 standard.
+///
+interface IERC20Metadata is IERC20 {
+     /// @dev Returns the name of the token.
+    function name() external view returns (string memory);
+
+     /// @dev Returns the symbol of the token.
+    function symbol() external view returns (string memory);
+
+     /// @dev Returns the decimals places of the token.
+    function decimals() external view returns (uint8);
+}
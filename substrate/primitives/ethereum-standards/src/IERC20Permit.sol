@@ -0,0 +1,48 @@
+// SPDX-License-Identifier: MIT
+// OpenZeppelin Contracts (last updated v5.1.0) (token/ERC20/extensions/IERC20Permit.sol)
+// https://github.com/OpenZeppelin/openzeppelin-contracts/blob/master/contracts/token/ERC20/extensions/IERC20Permit.sol
+pragma solidity ^0.8.20;
+
+///
+/// @dev Interface of the ERC-20 Permit extension allowing approvals to be made via signatures,
+/// as defined in https://eips.ethereum.org/EIPS/eip-2612[ERC-2612].
+///
+/// Adds the {permit} method, which can be used to change an account's ERC-20 allowance (see
+/// {IERC20-allowance}) by presenting a message signed by the account. By not relying on
+/// {IERC20-approve}, the token holder account doesn't need to send a transaction, and thus is
+/// not required to hold Ether at all.
+///
+interface IERC20Permit {
+     /// @dev Sets `value` as the allowance of `spender` over ``owner``'s tokens,
+     /// given ``owner``'s signed approval.
+     ///
+     /// Requirements:
+     ///
+     /// - `spender` cannot be the zero address.
+     /// - `deadline` must be a timestamp in the future.
+     /// - `v`, `r` and `s` must be a valid `secp256k1` signature from `owner`
+     /// over the EIP712-formatted function arguments.
+     /// - the signature must use ``owner``'s current nonce (see {nonces}).
+     ///
+     /// Emits an {Approval} event.
+    function permit(
+        address owner,
+        address spender,
+        uint256 value,
+        uint256 deadline,
+        uint8 v,
+        bytes32 r,
+        bytes32 s
+    ) external;
+
+     /// @dev Returns the current nonce for `owner`. This value must be
+     /// included whenever a signature is generated for {permit}.
+     ///
+     /// Every successful call to {permit} increases ``owner``'s nonce by one. This
+     /// prevents a signature from being used multiple times.
+    function nonces(address owner) external view returns (uint256);
+
+     /// @dev Returns the domain separator used in the encoding of the signature for {permit}, as defined by {EIP712}.
+    // solhint-disable-next-line func-name-mixedcase
+    function DOMAIN_SEPARATOR() external view returns (bytes32);
+}
@@ -20,3 +20,5 @@
 #![no_std]
 
 alloy_core::sol!("src/IERC20.sol");
+alloy_core::sol!("src/IERC20Metadata.sol");
+alloy_core::sol!("src/IERC20Permit.sol");
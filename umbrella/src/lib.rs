@@ -632,6 +632,10 @@ pub use pallet_revive_eth_rpc;
 #[cfg(feature = "pallet-revive-proc-macro")]
 pub use pallet_revive_proc_macro;
 
+/// Verification of pallet-revive account and storage proofs.
+#[cfg(feature = "pallet-revive-proof")]
+pub use pallet_revive_proof;
+
 /// Exposes all the host functions that a contract can import.
 #[cfg(feature = "pallet-revive-uapi")]
 pub use pallet_revive_uapi;
@@ -795,6 +799,10 @@ pub use pallet_xcm_bridge_hub;
 #[cfg(feature = "pallet-xcm-bridge-hub-router")]
 pub use pallet_xcm_bridge_hub_router;
 
+/// A pallet capping the XCM messages executed by each origin.
+#[cfg(feature = "pallet-xcm-rate-limiter")]
+pub use pallet_xcm_rate_limiter;
+
 /// Logic which is common to all parachain runtimes.
 #[cfg(feature = "parachains-common")]
 pub use parachains_common;